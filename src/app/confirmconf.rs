@@ -0,0 +1,225 @@
+//! Confirmation levels: parse/write `confirmations.conf`.
+//!
+//! Destructive actions are grouped into a few categories, and each category
+//! asks for one of three levels of confirmation before it runs: none at all, a
+//! Yes/No dialog, or typing the name of the user or group it affects. The
+//! defaults match the dialogs the application has always shown.
+
+use super::PendingAction;
+
+/// How much confirmation an action needs before it runs.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ConfirmLevel {
+    /// Run as soon as it is chosen.
+    None,
+    /// Ask Yes/No first. Choosing the action from a menu or picker already
+    /// counts as the answer, so those add no extra dialog.
+    #[default]
+    YesNo,
+    /// Ask Yes/No, then have the affected name typed out.
+    Typed,
+}
+
+impl ConfirmLevel {
+    /// Parse a `none`/`yesno`/`typed` value (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" => Some(ConfirmLevel::None),
+            "yesno" => Some(ConfirmLevel::YesNo),
+            "typed" => Some(ConfirmLevel::Typed),
+            _ => None,
+        }
+    }
+
+    /// Value written to `confirmations.conf`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConfirmLevel::None => "none",
+            ConfirmLevel::YesNo => "yesno",
+            ConfirmLevel::Typed => "typed",
+        }
+    }
+}
+
+/// Confirmation level per category of destructive action.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Confirmations {
+    /// Deleting a user account.
+    pub delete_user: ConfirmLevel,
+    /// Deleting a group, including moving its primary users away first or
+    /// merging it into another group.
+    pub delete_group: ConfirmLevel,
+    /// Removing users from the sudo group.
+    pub remove_from_sudo: ConfirmLevel,
+    /// Adding users to the sudo group, by a group picker or the wheel
+    /// checkbox of the new user forms. Unlike the other categories, `yesno`
+    /// asks even when the action was chosen from a menu.
+    pub grant_sudo: ConfirmLevel,
+    /// Setting or resetting a password. Unconfirmed by default, since the
+    /// password dialog already asks for it twice.
+    pub set_password: ConfirmLevel,
+}
+
+impl Default for Confirmations {
+    fn default() -> Self {
+        Self {
+            delete_user: ConfirmLevel::YesNo,
+            delete_group: ConfirmLevel::YesNo,
+            remove_from_sudo: ConfirmLevel::YesNo,
+            grant_sudo: ConfirmLevel::YesNo,
+            set_password: ConfirmLevel::None,
+        }
+    }
+}
+
+impl Confirmations {
+    /// Level required before `action` runs, or `None` when it falls in no
+    /// category and keeps whatever dialog it already has.
+    pub fn level_for(&self, action: &PendingAction) -> Option<ConfirmLevel> {
+        let sudo = super::sudo_group_name();
+        match action {
+            PendingAction::DeleteUser { .. } => Some(self.delete_user),
+            PendingAction::DeleteGroup { .. }
+            | PendingAction::ReassignAndDeleteGroup { .. }
+            | PendingAction::MergeGroup { .. } => Some(self.delete_group),
+            PendingAction::RemoveUserFromGroup { groupname, .. }
+            | PendingAction::RemoveMembersFromGroup { groupname, .. }
+                if *groupname == sudo =>
+            {
+                Some(self.remove_from_sudo)
+            }
+            PendingAction::RemoveUserFromGroups { groupnames, .. }
+                if groupnames.contains(&sudo) =>
+            {
+                Some(self.remove_from_sudo)
+            }
+            PendingAction::SetPassword { .. } | PendingAction::ResetPassword { .. } => {
+                Some(self.set_password)
+            }
+            _ if !sudo_grantees(action).is_empty() => Some(self.grant_sudo),
+            _ => None,
+        }
+    }
+
+    /// Load the levels from `path`, or write and return the defaults if it is missing.
+    pub fn load_or_init(path: &str) -> Self {
+        if let Some(cfg) = Self::from_file(path) {
+            return cfg;
+        }
+        let cfg = Self::default();
+        let _ = cfg.write_file(path);
+        cfg
+    }
+
+    /// Load the levels from a `<category> = <level>` file.
+    ///
+    /// Categories missing from the file or with an unknown level keep their
+    /// default.
+    pub fn from_file(path: &str) -> Option<Self> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        let mut cfg = Self::default();
+        for raw in contents.lines() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((lhs, rhs)) = line.split_once('=') else {
+                continue;
+            };
+            let Some(level) = ConfirmLevel::parse(rhs) else {
+                continue;
+            };
+            match lhs.trim() {
+                "delete_user" => cfg.delete_user = level,
+                "delete_group" => cfg.delete_group = level,
+                "remove_from_sudo" => cfg.remove_from_sudo = level,
+                "grant_sudo" => cfg.grant_sudo = level,
+                "set_password" => cfg.set_password = level,
+                _ => {}
+            }
+        }
+        Some(cfg)
+    }
+
+    /// Write the levels to `path`.
+    pub fn write_file(&self, path: &str) -> std::io::Result<()> {
+        let buf = format!(
+            "# usrgrp-manager confirmations\n\
+             # <action> = none|yesno|typed; typed asks for the user or group name after Yes\n\
+             delete_user = {}\n\
+             delete_group = {}\n\
+             remove_from_sudo = {}\n\
+             grant_sudo = {}\n\
+             set_password = {}\n",
+            self.delete_user.as_str(),
+            self.delete_group.as_str(),
+            self.remove_from_sudo.as_str(),
+            self.grant_sudo.as_str(),
+            self.set_password.as_str(),
+        );
+        std::fs::write(path, buf)
+    }
+}
+
+/// Name that has to be typed to confirm `action` at the `typed` level: the
+/// user or group it acts on.
+pub fn confirm_keyword(action: &PendingAction) -> String {
+    match action {
+        PendingAction::DeleteGroup { groupname }
+        | PendingAction::ReassignAndDeleteGroup { groupname, .. }
+        | PendingAction::RemoveMembersFromGroup { groupname, .. }
+        | PendingAction::AddMembersToGroup { groupname, .. } => groupname.clone(),
+        PendingAction::MergeGroup { source, .. } => source.clone(),
+        PendingAction::DeleteUser { username, .. }
+        | PendingAction::RemoveUserFromGroup { username, .. }
+        | PendingAction::RemoveUserFromGroups { username, .. }
+        | PendingAction::AddUserToGroup { username, .. }
+        | PendingAction::AddUserToGroups { username, .. }
+        | PendingAction::CreateUserWithOptions { username, .. }
+        | PendingAction::CloneUser { username, .. }
+        | PendingAction::SetPassword { username, .. }
+        | PendingAction::ResetPassword { username } => username.clone(),
+        _ => "yes".to_string(),
+    }
+}
+
+/// Users `action` would add to the sudo group ([`super::sudo_group_name`]);
+/// empty if it grants no one sudo. The sudo checkbox of the new user form
+/// counts too, as it adds the user to that same group.
+pub fn sudo_grantees(action: &PendingAction) -> Vec<String> {
+    let sudo = super::sudo_group_name();
+    match action {
+        PendingAction::AddUserToGroup {
+            username,
+            groupname,
+        } if *groupname == sudo => vec![username.clone()],
+        PendingAction::AddUserToGroups {
+            username,
+            groupnames,
+        }
+        | PendingAction::CloneUser {
+            username,
+            groupnames,
+            ..
+        } if groupnames.contains(&sudo) => vec![username.clone()],
+        PendingAction::AddMembersToGroup {
+            groupname,
+            usernames,
+        }
+        | PendingAction::CreateGroupWithMembers {
+            groupname,
+            members: usernames,
+        } if *groupname == sudo => usernames.clone(),
+        PendingAction::CreateUserWithOptions {
+            username,
+            add_to_wheel: true,
+            ..
+        } => vec![username.clone()],
+        PendingAction::ImportUsers { rows, .. } => rows
+            .iter()
+            .filter(|row| row.groups.contains(&sudo))
+            .map(|row| row.username.clone())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
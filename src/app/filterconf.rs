@@ -33,6 +33,23 @@ pub struct FiltersConfig {
     pub no_password: bool,
     /// Show only users whose password has expired.
     pub expired: bool,
+    /// Show only users whose primary group is missing from `/etc/group`.
+    pub no_primary_group: bool,
+
+    /// Show only groups without members or primary users.
+    pub groups_empty_only: bool,
+    /// Show only groups with GID < 1000.
+    pub groups_system_only: bool,
+    /// Show only groups with GID >= 1000.
+    pub groups_user_only: bool,
+    /// Show only the sudo group.
+    pub groups_has_sudo_flag: bool,
+    /// Show only groups with members missing from `/etc/passwd`.
+    pub groups_orphan_members: bool,
+
+    /// Start with the `human_only` chip on, whatever the saved chips say.
+    /// Only read at startup; saving the filters keeps the value in the file.
+    pub hide_system_users_default: bool,
 }
 
 impl FiltersConfig {
@@ -62,20 +79,31 @@ impl FiltersConfig {
             locked: app.users_filter_chips.locked,
             no_password: app.users_filter_chips.no_password,
             expired: app.users_filter_chips.expired,
+            no_primary_group: app.users_filter_chips.no_primary_group,
+            groups_empty_only: app.groups_filter_chips.empty_only,
+            groups_system_only: app.groups_filter_chips.system_only,
+            groups_user_only: app.groups_filter_chips.user_only,
+            groups_has_sudo_flag: app.groups_filter_chips.has_sudo_flag,
+            groups_orphan_members: app.groups_filter_chips.orphan_members,
+            hide_system_users_default: false,
         }
     }
 
     /// Save the current filter state from an [`AppState`] to a file.
     ///
     /// This is a convenience method that combines [`from_app`](Self::from_app) and
-    /// [`write_file`](Self::write_file).
+    /// [`write_file`](Self::write_file). `hide_system_users_default` is kept
+    /// as it is in the existing file.
     ///
     /// # Arguments
     ///
     /// * `app` - The application state containing the current filters.
     /// * `path` - The path where the configuration will be written.
     pub fn save_from_app(app: &AppState, path: &str) -> std::io::Result<()> {
-        Self::from_app(app).write_file(path)
+        let mut cfg = Self::from_app(app);
+        cfg.hide_system_users_default =
+            Self::from_file(path).is_some_and(|existing| existing.hide_system_users_default);
+        cfg.write_file(path)
     }
 
     /// Load filters from a file, or create defaults if the file doesn't exist.
@@ -160,6 +188,13 @@ impl FiltersConfig {
                 "locked" => cfg.locked = parse_bool(rhs),
                 "no_password" => cfg.no_password = parse_bool(rhs),
                 "expired" => cfg.expired = parse_bool(rhs),
+                "no_primary_group" => cfg.no_primary_group = parse_bool(rhs),
+                "groups_empty_only" => cfg.groups_empty_only = parse_bool(rhs),
+                "groups_system_only" => cfg.groups_system_only = parse_bool(rhs),
+                "groups_user_only" => cfg.groups_user_only = parse_bool(rhs),
+                "groups_has_sudo_flag" => cfg.groups_has_sudo_flag = parse_bool(rhs),
+                "groups_orphan_members" => cfg.groups_orphan_members = parse_bool(rhs),
+                "hide_system_users_default" => cfg.hide_system_users_default = parse_bool(rhs),
                 _ => {}
             }
         }
@@ -182,7 +217,10 @@ impl FiltersConfig {
         buf.push_str("# Users filter: None|OnlyUserIds|OnlySystemIds\n");
         buf.push_str("users_filter = None\n");
         buf.push_str("# Groups filter: None|OnlyUserGids|OnlySystemGids\n");
-        buf.push_str("groups_filter = None\n\n");
+        buf.push_str("groups_filter = None\n");
+        buf.push_str(
+            "# hide_system_users_default = true always starts with only human users shown\n\n",
+        );
 
         let mut kv = |k: &str, v: bool| {
             let _ = writeln!(&mut buf, "{} = {}", k, if v { "true" } else { "false" });
@@ -194,6 +232,13 @@ impl FiltersConfig {
         kv("locked", self.locked);
         kv("no_password", self.no_password);
         kv("expired", self.expired);
+        kv("no_primary_group", self.no_primary_group);
+        kv("groups_empty_only", self.groups_empty_only);
+        kv("groups_system_only", self.groups_system_only);
+        kv("groups_user_only", self.groups_user_only);
+        kv("groups_has_sudo_flag", self.groups_has_sudo_flag);
+        kv("groups_orphan_members", self.groups_orphan_members);
+        kv("hide_system_users_default", self.hide_system_users_default);
 
         std::fs::write(path, buf)
     }
@@ -215,6 +260,16 @@ impl FiltersConfig {
         app.users_filter_chips.locked = self.locked;
         app.users_filter_chips.no_password = self.no_password;
         app.users_filter_chips.expired = self.expired;
+        app.users_filter_chips.no_primary_group = self.no_primary_group;
+        app.groups_filter_chips.empty_only = self.groups_empty_only;
+        app.groups_filter_chips.system_only = self.groups_system_only;
+        app.groups_filter_chips.user_only = self.groups_user_only;
+        app.groups_filter_chips.has_sudo_flag = self.groups_has_sudo_flag;
+        app.groups_filter_chips.orphan_members = self.groups_orphan_members;
+        if self.hide_system_users_default {
+            app.users_filter_chips.human_only = true;
+            app.users_filter_chips.system_only = false;
+        }
     }
 }
 
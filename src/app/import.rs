@@ -0,0 +1,148 @@
+//! Batch user import: parse a CSV of new accounts for [`PendingAction::ImportUsers`].
+//!
+//! Each line is `username,fullname,shell,groups,create_home`. Only the
+//! username is required; `groups` is separated by `;` or spaces, and
+//! `create_home` (`yes`/`no`, `true`/`false`, `1`/`0`) defaults to yes. Fields
+//! may be double-quoted to contain commas. Blank lines, `#` comments and a
+//! first line starting with `username` (a header) are ignored. Rows that
+//! cannot be created are reported and skipped, so one bad line does not stop
+//! the rest.
+//!
+//! [`PendingAction::ImportUsers`]: super::PendingAction::ImportUsers
+
+use crate::sys::{SystemGroup, SystemUser};
+
+/// A validated account to create from one CSV line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportRow {
+    pub username: String,
+    /// GECOS field; left unset when empty.
+    pub full_name: String,
+    /// Login shell; the `useradd` default when empty.
+    pub shell: String,
+    /// Supplementary groups, all of which exist.
+    pub groups: Vec<String>,
+    pub create_home: bool,
+}
+
+/// Longest username `useradd` accepts.
+const MAX_USERNAME_LEN: usize = 32;
+
+/// Whether `name` is a portable username: a lowercase letter or `_`, then
+/// lowercase letters, digits, `_` or `-`, optionally ending in `$`.
+pub fn valid_username(name: &str) -> bool {
+    let body = name.strip_suffix('$').unwrap_or(name);
+    let mut chars = body.chars();
+    name.len() <= MAX_USERNAME_LEN
+        && chars
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+}
+
+/// Split one CSV line into trimmed fields, honouring double quotes (`""`
+/// inside quotes is a literal quote).
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// Parse `contents` into the rows to create and a `line N: reason` note for
+/// each line that was skipped.
+///
+/// Rows are checked against the existing `users` and `groups` and against
+/// earlier rows, so a name is only created once.
+pub fn parse_csv(
+    contents: &str,
+    users: &[SystemUser],
+    groups: &[SystemGroup],
+) -> (Vec<ImportRow>, Vec<String>) {
+    let mut rows: Vec<ImportRow> = Vec::new();
+    let mut skipped = Vec::new();
+    let mut first = true;
+    for (idx, raw) in contents.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields = split_fields(line);
+        let field = |i: usize| fields.get(i).map(String::as_str).unwrap_or("");
+        if std::mem::take(&mut first) && field(0).eq_ignore_ascii_case("username") {
+            continue;
+        }
+        let row = parse_row(&fields).and_then(|row| {
+            if users.iter().any(|u| u.name == row.username)
+                || rows.iter().any(|r| r.username == row.username)
+            {
+                return Err(format!("user '{}' already exists", row.username));
+            }
+            match row
+                .groups
+                .iter()
+                .find(|g| !groups.iter().any(|group| group.name == **g))
+            {
+                Some(g) => Err(format!("group '{}' does not exist", g)),
+                None => Ok(row),
+            }
+        });
+        match row {
+            Ok(row) => rows.push(row),
+            Err(reason) => skipped.push(format!("line {}: {}", idx + 1, reason)),
+        }
+    }
+    (rows, skipped)
+}
+
+/// Check the fields of one line on their own.
+fn parse_row(fields: &[String]) -> Result<ImportRow, String> {
+    let field = |i: usize| fields.get(i).map(String::as_str).unwrap_or("");
+    if fields.len() > 5 {
+        return Err(format!("{} fields, expected at most 5", fields.len()));
+    }
+    let username = field(0);
+    if username.is_empty() {
+        return Err("missing username".to_string());
+    }
+    if !valid_username(username) {
+        return Err(format!("invalid username '{}'", username));
+    }
+    let full_name = field(1);
+    if full_name.contains(':') {
+        return Err("full name contains ':'".to_string());
+    }
+    let shell = field(2);
+    if !shell.is_empty() && !shell.starts_with('/') {
+        return Err(format!("shell '{}' is not an absolute path", shell));
+    }
+    let create_home = match field(4).to_ascii_lowercase().as_str() {
+        "" | "yes" | "y" | "true" | "1" => true,
+        "no" | "n" | "false" | "0" => false,
+        other => return Err(format!("create_home '{}' is not yes or no", other)),
+    };
+    Ok(ImportRow {
+        username: username.to_string(),
+        full_name: full_name.to_string(),
+        shell: shell.to_string(),
+        groups: field(3)
+            .split(|c: char| c == ';' || c.is_whitespace())
+            .filter(|g| !g.is_empty())
+            .map(str::to_string)
+            .collect(),
+        create_home,
+    })
+}
@@ -7,6 +7,7 @@
 //! - Exporting the current keymap back to a file for reference or customization
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::{BTreeMap, BTreeSet};
 
 /// Semantic keyboard actions that can be bound to key combinations.
 ///
@@ -35,12 +36,52 @@ pub enum KeyAction {
     ToggleGroupsFocus,
     /// Toggle the visibility of the keybindings panel on the right.
     ToggleKeybindsPane,
+    /// Toggle alphabetical ordering of the group members list.
+    ToggleMemberSort,
+    /// Cycle UID/GID display between decimal, hex and octal.
+    CycleIdRadix,
+    /// Turn the filesystem-backed fields of the details panes on or off.
+    ToggleLiveStats,
+    /// Switch the Member of pane between `/etc/group` and `id -Gn`.
+    ToggleMembershipSource,
+    /// Switch between the three-column layout and the table alone.
+    ToggleLayout,
+    /// Switch the user details between the full and the compact view.
+    ToggleDetailVerbosity,
+    /// Re-read all users and groups from the system.
+    Reload,
+    /// Copy the shell command for an action on the current selection.
+    CopyCommand,
+    /// Jump from a Member of entry to that group on the Groups tab, and back.
+    JumpToGroup,
+    /// Show the details of the selected item in a popup.
+    ShowDetails,
+    /// Write a readable list of the current keybindings to the config directory.
+    ExportKeybinds,
+    /// List the actions performed in this session.
+    ShowHistory,
+    /// Write the theme, keybindings and filters to their config files.
+    SaveConfig,
+    /// Create users from a CSV file.
+    Import,
+    /// Narrow the main table in favour of the details column.
+    ShrinkTablePane,
+    /// Widen the main table at the expense of the details column.
+    GrowTablePane,
+    /// Widen the keybindings panel at the expense of the details column.
+    GrowKeybindsPane,
+    /// Narrow the keybindings panel in favour of the details column.
+    ShrinkKeybindsPane,
     /// Open an action menu for the selected item (user or group).
     EnterAction,
     /// Move up in the current list.
     MoveUp,
     /// Move down in the current list.
     MoveDown,
+    /// Jump to the first entry of the current list.
+    GoTop,
+    /// Jump to the last entry of the current list.
+    GoBottom,
     /// Move to the previous page of results.
     PageUp,
     /// Move to the next page of results.
@@ -53,6 +94,15 @@ pub enum KeyAction {
     Ignore,
 }
 
+/// Keys per action label, as built by [`Keymap::sections`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BindingSections {
+    /// Application commands such as quit, search and reload.
+    pub general: BTreeMap<&'static str, BTreeSet<String>>,
+    /// Movement within lists and pages.
+    pub navigation: BTreeMap<&'static str, BTreeSet<String>>,
+}
+
 /// Manages keybinding configuration and key-to-action resolution.
 ///
 /// The keymap uses a canonical mapping from `(KeyModifiers, KeyCode)` pairs to [`KeyAction`]s.
@@ -107,10 +157,42 @@ impl Keymap {
         bindings.insert((M::SHIFT, Char('k')), KeyAction::ToggleKeybindsPane);
         bindings.insert((M::SHIFT, Char('K')), KeyAction::ToggleKeybindsPane);
         bindings.insert((M::NONE, Char('K')), KeyAction::ToggleKeybindsPane);
+        bindings.insert((M::NONE, Char('s')), KeyAction::ToggleMemberSort);
+        bindings.insert((M::NONE, Char('x')), KeyAction::CycleIdRadix);
+        bindings.insert((M::NONE, Char('L')), KeyAction::ToggleLiveStats);
+        bindings.insert((M::SHIFT, Char('L')), KeyAction::ToggleLiveStats);
+        bindings.insert((M::NONE, Char('M')), KeyAction::ToggleMembershipSource);
+        bindings.insert((M::SHIFT, Char('M')), KeyAction::ToggleMembershipSource);
+        bindings.insert((M::NONE, Char('z')), KeyAction::ToggleLayout);
+        bindings.insert((M::NONE, Char('v')), KeyAction::ToggleDetailVerbosity);
+        bindings.insert((M::CONTROL, Char('r')), KeyAction::Reload);
+        bindings.insert((M::NONE, Char('y')), KeyAction::CopyCommand);
+        bindings.insert((M::NONE, Char('g')), KeyAction::JumpToGroup);
+        bindings.insert((M::NONE, Char('i')), KeyAction::ShowDetails);
+        bindings.insert((M::NONE, Char('E')), KeyAction::ExportKeybinds);
+        bindings.insert((M::SHIFT, Char('E')), KeyAction::ExportKeybinds);
+        bindings.insert((M::NONE, Char('H')), KeyAction::ShowHistory);
+        bindings.insert((M::SHIFT, Char('H')), KeyAction::ShowHistory);
+        bindings.insert((M::CONTROL, Char('s')), KeyAction::SaveConfig);
+        bindings.insert((M::NONE, Char('I')), KeyAction::Import);
+        bindings.insert((M::SHIFT, Char('I')), KeyAction::Import);
+        // Column resizing; '<', '>', '{' and '}' arrive with SHIFT on most terminals
+        for mods in [M::NONE, M::SHIFT] {
+            bindings.insert((mods, Char('<')), KeyAction::ShrinkTablePane);
+            bindings.insert((mods, Char('>')), KeyAction::GrowTablePane);
+            bindings.insert((mods, Char('{')), KeyAction::GrowKeybindsPane);
+            bindings.insert((mods, Char('}')), KeyAction::ShrinkKeybindsPane);
+        }
 
         // Page keys
         bindings.insert((M::NONE, PageUp), KeyAction::PageUp);
         bindings.insert((M::NONE, PageDown), KeyAction::PageDown);
+        // `g` already jumps to the selected Member of group, so only `G` is
+        // vim-style here; Home goes to the top instead of `gg`
+        bindings.insert((M::NONE, Home), KeyAction::GoTop);
+        bindings.insert((M::NONE, End), KeyAction::GoBottom);
+        bindings.insert((M::NONE, Char('G')), KeyAction::GoBottom);
+        bindings.insert((M::SHIFT, Char('G')), KeyAction::GoBottom);
 
         Self { bindings }
     }
@@ -197,8 +279,8 @@ impl Keymap {
         let mut buf = String::new();
         buf.push_str("# usrgrp-manager keybindings\n");
         buf.push_str("# Format: <Action> = <KeySpec>\n");
-        buf.push_str("# KeySpec examples: q, Ctrl+q, Enter, Esc, Tab, BackTab, Up, Down, Left, Right, PageUp, PageDown, Delete, /, n, f, j, k, h, l\n");
-        buf.push_str("# Actions: Quit, OpenFilterMenu, StartSearch, NewUser, DeleteSelection, SwitchTab, ToggleUsersFocus, ToggleGroupsFocus, ToggleKeybindsPane, EnterAction, MoveUp, MoveDown, MoveLeftPage, MoveRightPage, PageUp, PageDown, Ignore\n\n");
+        buf.push_str("# KeySpec examples: q, Ctrl+q, Enter, Esc, Tab, BackTab, Up, Down, Left, Right, PageUp, PageDown, Home, End, Delete, /, n, f, j, k, h, l\n");
+        buf.push_str("# Actions: Quit, OpenFilterMenu, StartSearch, NewUser, DeleteSelection, SwitchTab, ToggleUsersFocus, ToggleGroupsFocus, ToggleKeybindsPane, ToggleMemberSort, CycleIdRadix, ToggleLiveStats, ToggleMembershipSource, ToggleLayout, ToggleDetailVerbosity, Reload, CopyCommand, JumpToGroup, ShowDetails, ExportKeybinds, ShowHistory, SaveConfig, Import, ShrinkTablePane, GrowTablePane, GrowKeybindsPane, ShrinkKeybindsPane, EnterAction, MoveUp, MoveDown, MoveLeftPage, MoveRightPage, PageUp, PageDown, GoTop, GoBottom, Ignore\n\n");
         buf.push_str("# Additional: OpenHelp (mapped to '?')\n\n");
 
         // Emit a stable, readable subset of current bindings
@@ -211,6 +293,24 @@ impl Keymap {
             ("Tab", KeyAction::SwitchTab),
             ("BackTab", KeyAction::ToggleUsersFocus),
             ("?", KeyAction::OpenHelp),
+            ("s", KeyAction::ToggleMemberSort),
+            ("x", KeyAction::CycleIdRadix),
+            ("L", KeyAction::ToggleLiveStats),
+            ("M", KeyAction::ToggleMembershipSource),
+            ("z", KeyAction::ToggleLayout),
+            ("v", KeyAction::ToggleDetailVerbosity),
+            ("Ctrl+r", KeyAction::Reload),
+            ("y", KeyAction::CopyCommand),
+            ("g", KeyAction::JumpToGroup),
+            ("i", KeyAction::ShowDetails),
+            ("E", KeyAction::ExportKeybinds),
+            ("H", KeyAction::ShowHistory),
+            ("Ctrl+s", KeyAction::SaveConfig),
+            ("I", KeyAction::Import),
+            ("<", KeyAction::ShrinkTablePane),
+            (">", KeyAction::GrowTablePane),
+            ("{", KeyAction::GrowKeybindsPane),
+            ("}", KeyAction::ShrinkKeybindsPane),
             ("Enter", KeyAction::EnterAction),
             ("Up", KeyAction::MoveUp),
             ("Down", KeyAction::MoveDown),
@@ -222,12 +322,38 @@ impl Keymap {
             ("l", KeyAction::MoveRightPage),
             ("PageUp", KeyAction::PageUp),
             ("PageDown", KeyAction::PageDown),
+            ("Home", KeyAction::GoTop),
+            ("End", KeyAction::GoBottom),
+            ("G", KeyAction::GoBottom),
             ("Delete", KeyAction::DeleteSelection),
         ];
         for (k, a) in dump {
             let _ = writeln!(&mut buf, "{} = {}", format_action(a), k);
         }
 
+        // Bindings loaded from a file on top of the defaults above
+        let defaults = Self::new_defaults();
+        let mut custom: Vec<String> = self
+            .bindings
+            .iter()
+            .filter(|(key, action)| defaults.bindings.get(key) != Some(action))
+            .map(|((mods, code), action)| {
+                format!(
+                    "{} = {}",
+                    format_action(*action),
+                    Self::format_key(*mods, *code)
+                )
+            })
+            .collect();
+        if !custom.is_empty() {
+            custom.sort();
+            custom.dedup();
+            buf.push_str("\n# Custom\n");
+            for line in custom {
+                let _ = writeln!(&mut buf, "{}", line);
+            }
+        }
+
         std::fs::write(path, buf)
     }
 
@@ -260,6 +386,147 @@ impl Keymap {
         self.bindings.iter().map(|(k, v)| (*k, *v)).collect()
     }
 
+    /// Bindings grouped and labelled the way the keybindings panel lists them.
+    ///
+    /// Actions the panel shows in its contextual or tab sections instead are
+    /// left out.
+    pub fn sections(&self) -> BindingSections {
+        let mut general: BTreeMap<&'static str, BTreeSet<String>> = BTreeMap::new();
+        let mut navigation: BTreeMap<&'static str, BTreeSet<String>> = BTreeMap::new();
+
+        for ((mods, code), action) in self.all_bindings().into_iter() {
+            // Normalize display for certain combos
+            let key = match code {
+                KeyCode::BackTab => "Shift+Tab".to_string(),
+                KeyCode::Tab if mods.contains(KeyModifiers::SHIFT) => "Shift+Tab".to_string(),
+                _ => Self::format_key(mods, code),
+            };
+
+            match action {
+                // General app commands
+                KeyAction::Quit => {
+                    general.entry("Quit").or_default().insert(key);
+                }
+                KeyAction::SwitchTab => {
+                    general.entry("Switch tab").or_default().insert(key);
+                }
+                KeyAction::OpenFilterMenu => {
+                    general.entry("Open filter menu").or_default().insert(key);
+                }
+                KeyAction::StartSearch => {
+                    general.entry("Search").or_default().insert(key);
+                }
+                KeyAction::DeleteSelection => {
+                    if !crate::app::READ_ONLY {
+                        general.entry("Delete selection").or_default().insert(key);
+                    }
+                }
+                KeyAction::CycleIdRadix => {
+                    general
+                        .entry("UID/GID dec/hex/oct")
+                        .or_default()
+                        .insert(key);
+                }
+                KeyAction::ToggleLiveStats => {
+                    general.entry("Live detail stats").or_default().insert(key);
+                }
+                KeyAction::ToggleMembershipSource => {
+                    general
+                        .entry("Member of: files/id")
+                        .or_default()
+                        .insert(key);
+                }
+                KeyAction::ToggleLayout => {
+                    general.entry("Compact layout").or_default().insert(key);
+                }
+                KeyAction::ToggleDetailVerbosity => {
+                    general.entry("Compact details").or_default().insert(key);
+                }
+                KeyAction::Reload => {
+                    general.entry("Reload from disk").or_default().insert(key);
+                }
+                KeyAction::CopyCommand => {
+                    general.entry("Copy shell command").or_default().insert(key);
+                }
+                KeyAction::ShowDetails => {
+                    general.entry("Details popup").or_default().insert(key);
+                }
+                KeyAction::ExportKeybinds => {
+                    general.entry("Export keybindings").or_default().insert(key);
+                }
+                KeyAction::ShowHistory => {
+                    general.entry("Action history").or_default().insert(key);
+                }
+                KeyAction::SaveConfig => {
+                    general.entry("Save config").or_default().insert(key);
+                }
+                KeyAction::Import => {
+                    if !crate::app::READ_ONLY {
+                        general.entry("Import users (CSV)").or_default().insert(key);
+                    }
+                }
+
+                // Navigation
+                KeyAction::MoveUp => {
+                    navigation.entry("Move up").or_default().insert(key);
+                }
+                KeyAction::MoveDown => {
+                    navigation.entry("Move down").or_default().insert(key);
+                }
+                KeyAction::MoveLeftPage => {
+                    navigation.entry("Move left").or_default().insert(key);
+                }
+                KeyAction::MoveRightPage => {
+                    navigation.entry("Move right").or_default().insert(key);
+                }
+                KeyAction::PageUp => {
+                    navigation.entry("Page up").or_default().insert(key);
+                }
+                KeyAction::PageDown => {
+                    navigation.entry("Page down").or_default().insert(key);
+                }
+                KeyAction::GoTop => {
+                    navigation.entry("Go to top").or_default().insert(key);
+                }
+                KeyAction::GoBottom => {
+                    navigation.entry("Go to bottom").or_default().insert(key);
+                }
+
+                // Shown in contextual/tab sections below; skip in general list
+                KeyAction::EnterAction
+                | KeyAction::ToggleUsersFocus
+                | KeyAction::ToggleGroupsFocus
+                | KeyAction::ToggleKeybindsPane
+                | KeyAction::ToggleMemberSort
+                | KeyAction::JumpToGroup
+                | KeyAction::ShrinkTablePane
+                | KeyAction::GrowTablePane
+                | KeyAction::GrowKeybindsPane
+                | KeyAction::ShrinkKeybindsPane
+                | KeyAction::OpenHelp
+                | KeyAction::NewUser
+                | KeyAction::Ignore => {}
+            }
+        }
+        BindingSections {
+            general,
+            navigation,
+        }
+    }
+
+    /// Human-readable key bound to `action`, if any (the first in sorted order
+    /// when several keys map to it).
+    pub fn key_for(&self, action: KeyAction) -> Option<String> {
+        let mut keys: Vec<String> = self
+            .bindings
+            .iter()
+            .filter(|(_, a)| **a == action)
+            .map(|((m, c), _)| Self::format_key(*m, *c))
+            .collect();
+        keys.sort();
+        keys.into_iter().next()
+    }
+
     /// Format a key (modifiers + code) into a human-readable spec like "Ctrl+q", "BackTab".
     ///
     /// This method is used to display key combinations in a user-friendly format.
@@ -286,6 +553,8 @@ impl Keymap {
             Right => "Right".to_string(),
             PageUp => "PageUp".to_string(),
             PageDown => "PageDown".to_string(),
+            Home => "Home".to_string(),
+            End => "End".to_string(),
             Char('/') => "/".to_string(),
             Char(c) => c.to_string(),
             _ => format!("{:?}", code),
@@ -330,6 +599,8 @@ fn parse_key(spec: &str) -> Option<(KeyModifiers, KeyCode)> {
         "Right" => Right,
         "PageUp" => PageUp,
         "PageDown" => PageDown,
+        "Home" => Home,
+        "End" => End,
         _ => {
             let chars: Vec<char> = rest.chars().collect();
             if chars.len() == 1 {
@@ -354,6 +625,24 @@ fn parse_action(s: &str) -> Option<KeyAction> {
         "ToggleUsersFocus" => Some(KeyAction::ToggleUsersFocus),
         "ToggleGroupsFocus" => Some(KeyAction::ToggleGroupsFocus),
         "ToggleKeybindsPane" => Some(KeyAction::ToggleKeybindsPane),
+        "ToggleMemberSort" => Some(KeyAction::ToggleMemberSort),
+        "CycleIdRadix" => Some(KeyAction::CycleIdRadix),
+        "ToggleLiveStats" => Some(KeyAction::ToggleLiveStats),
+        "ToggleMembershipSource" => Some(KeyAction::ToggleMembershipSource),
+        "ToggleLayout" => Some(KeyAction::ToggleLayout),
+        "ToggleDetailVerbosity" => Some(KeyAction::ToggleDetailVerbosity),
+        "Reload" => Some(KeyAction::Reload),
+        "CopyCommand" => Some(KeyAction::CopyCommand),
+        "JumpToGroup" => Some(KeyAction::JumpToGroup),
+        "ShowDetails" => Some(KeyAction::ShowDetails),
+        "ExportKeybinds" => Some(KeyAction::ExportKeybinds),
+        "ShowHistory" => Some(KeyAction::ShowHistory),
+        "SaveConfig" => Some(KeyAction::SaveConfig),
+        "Import" => Some(KeyAction::Import),
+        "ShrinkTablePane" => Some(KeyAction::ShrinkTablePane),
+        "GrowTablePane" => Some(KeyAction::GrowTablePane),
+        "GrowKeybindsPane" => Some(KeyAction::GrowKeybindsPane),
+        "ShrinkKeybindsPane" => Some(KeyAction::ShrinkKeybindsPane),
         "EnterAction" => Some(KeyAction::EnterAction),
         "MoveUp" => Some(KeyAction::MoveUp),
         "MoveDown" => Some(KeyAction::MoveDown),
@@ -361,11 +650,38 @@ fn parse_action(s: &str) -> Option<KeyAction> {
         "MoveRightPage" => Some(KeyAction::MoveRightPage),
         "PageUp" => Some(KeyAction::PageUp),
         "PageDown" => Some(KeyAction::PageDown),
+        "GoTop" => Some(KeyAction::GoTop),
+        "GoBottom" => Some(KeyAction::GoBottom),
         "Ignore" => Some(KeyAction::Ignore),
         _ => None,
     }
 }
 
+/// Write the keybindings of `keymap` to `path` as a plain-text reference,
+/// grouped like the keybindings panel.
+///
+/// Unlike `keybinds.conf` the file is not read back; it lists every key bound
+/// to each action under the panel's labels.
+pub fn export_keybinds(keymap: &Keymap, path: &str) -> std::io::Result<()> {
+    use std::fmt::Write as _;
+    let sections = keymap.sections();
+    let mut buf = String::from(
+        "usrgrp-manager keybindings\n\
+         (reference only; change bindings in keybinds.conf)\n",
+    );
+    for (title, entries) in [
+        ("General", &sections.general),
+        ("Navigation", &sections.navigation),
+    ] {
+        let _ = writeln!(&mut buf, "\n{title}:");
+        for (label, keys) in entries {
+            let keys = keys.iter().cloned().collect::<Vec<_>>().join(", ");
+            let _ = writeln!(&mut buf, "  {label}: {keys}");
+        }
+    }
+    std::fs::write(path, buf)
+}
+
 pub fn format_action(a: KeyAction) -> &'static str {
     match a {
         KeyAction::Quit => "Quit",
@@ -378,6 +694,24 @@ pub fn format_action(a: KeyAction) -> &'static str {
         KeyAction::ToggleUsersFocus => "ToggleUsersFocus",
         KeyAction::ToggleGroupsFocus => "ToggleGroupsFocus",
         KeyAction::ToggleKeybindsPane => "ToggleKeybindsPane",
+        KeyAction::ToggleMemberSort => "ToggleMemberSort",
+        KeyAction::CycleIdRadix => "CycleIdRadix",
+        KeyAction::ToggleLiveStats => "ToggleLiveStats",
+        KeyAction::ToggleMembershipSource => "ToggleMembershipSource",
+        KeyAction::ToggleLayout => "ToggleLayout",
+        KeyAction::ToggleDetailVerbosity => "ToggleDetailVerbosity",
+        KeyAction::Reload => "Reload",
+        KeyAction::CopyCommand => "CopyCommand",
+        KeyAction::JumpToGroup => "JumpToGroup",
+        KeyAction::ShowDetails => "ShowDetails",
+        KeyAction::ExportKeybinds => "ExportKeybinds",
+        KeyAction::ShowHistory => "ShowHistory",
+        KeyAction::SaveConfig => "SaveConfig",
+        KeyAction::Import => "Import",
+        KeyAction::ShrinkTablePane => "ShrinkTablePane",
+        KeyAction::GrowTablePane => "GrowTablePane",
+        KeyAction::GrowKeybindsPane => "GrowKeybindsPane",
+        KeyAction::ShrinkKeybindsPane => "ShrinkKeybindsPane",
         KeyAction::EnterAction => "EnterAction",
         KeyAction::MoveUp => "MoveUp",
         KeyAction::MoveDown => "MoveDown",
@@ -385,6 +719,8 @@ pub fn format_action(a: KeyAction) -> &'static str {
         KeyAction::MoveRightPage => "MoveRightPage",
         KeyAction::PageUp => "PageUp",
         KeyAction::PageDown => "PageDown",
+        KeyAction::GoTop => "GoTop",
+        KeyAction::GoBottom => "GoBottom",
         KeyAction::Ignore => "Ignore",
     }
 }
@@ -3,15 +3,23 @@
 //! Defines enums and structs that model the TUI state, as well as helpers
 //! to construct defaults and to run the application loop (re-exported as `run`).
 //!
+pub mod clipboard;
+pub mod confirmconf;
 pub mod filterconf;
+pub mod import;
 pub mod keymap;
+pub mod privconf;
+pub mod termcolor;
+pub mod textinput;
+pub mod uiconf;
 pub mod update;
 
 use ratatui::style::Color;
 use ratatui::widgets::TableState;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crate::sys;
+use std::collections::{BTreeSet, VecDeque};
 use std::path::PathBuf;
 
 /// Top-level active tab in the UI.
@@ -239,6 +247,23 @@ impl Theme {
         std::fs::write(path, buf)
     }
 
+    /// This theme with every color mapped to what a terminal with `caps` can show.
+    pub fn downgraded(self, caps: termcolor::ColorCaps) -> Self {
+        let d = |c| termcolor::downgrade_color(c, caps);
+        Self {
+            text: d(self.text),
+            _muted: d(self._muted),
+            title: d(self.title),
+            border: d(self.border),
+            header_bg: d(self.header_bg),
+            header_fg: d(self.header_fg),
+            status_bg: d(self.status_bg),
+            status_fg: d(self.status_fg),
+            highlight_fg: d(self.highlight_fg),
+            highlight_bg: d(self.highlight_bg),
+        }
+    }
+
     /// Ensure a config file exists; if missing, write one with the current default theme and return it.
     /// If present, load from it; on parse errors, return `mocha`.
     pub fn load_or_init(path: &str) -> Self {
@@ -253,47 +278,89 @@ impl Theme {
 }
 
 /// Modal dialog states for user and group actions.
+///
+/// Variants opened only from the action menus are allowed to go unused in a
+/// `read-only-build`, which leaves those menus out.
 #[derive(Clone, Debug)]
 pub enum ModalState {
+    #[cfg_attr(feature = "read-only-build", allow(dead_code))]
     Actions {
         selected: usize,
     },
     FilterMenu {
         selected: usize,
     },
+    /// Pick an action for the current selection and copy its shell command.
+    CopyCommandMenu {
+        selected: usize,
+    },
     ModifyMenu {
         selected: usize,
     },
     ModifyGroupsAdd {
         selected: usize,
         offset: usize,
-        selected_multi: Vec<usize>,
+        /// Names of the checked rows, kept by name so they survive re-sorting.
+        selected_multi: BTreeSet<String>,
+        /// List ineligible groups too, annotated with why they cannot be picked.
+        show_all: bool,
     },
     ModifyGroupsRemove {
         selected: usize,
         offset: usize,
-        selected_multi: Vec<usize>,
+        selected_multi: BTreeSet<String>,
     },
     ModifyDetailsMenu {
         selected: usize,
     },
     ModifyShell {
+        /// Index into the shells matching `filter`.
         selected: usize,
         offset: usize,
         shells: Vec<String>,
+        /// Typed text narrowing `shells` down, matched case-insensitively.
+        filter: String,
     },
     ModifyTextInput {
         field: ModifyField,
         value: String,
+        /// Cursor position in `value`, in characters.
+        cursor: usize,
+    },
+    /// The comma-separated GECOS field edited as full name, room, work phone
+    /// and home phone; see [`GECOS_LABELS`].
+    EditGecos {
+        /// Index into `fields` of the field being edited.
+        selected: usize,
+        fields: [String; 4],
+        /// Anything after the home phone, written back unchanged.
+        other: String,
+        /// Cursor position in the selected field, in characters.
+        cursor: usize,
     },
     DeleteConfirm {
         selected: usize,
         allowed: bool,
         delete_home: bool,
+        /// Bytes under the home directory, measured when `delete_home` is
+        /// checked; `None` if it could not be measured.
+        home_size: Option<u64>,
+        /// The size is still being measured on a worker thread.
+        measuring: bool,
     },
     ModifyPasswordMenu {
         selected: usize,
     },
+    /// Read-only view of the user's `/etc/sudoers.d` drop-in.
+    SudoersDropin {
+        username: String,
+        /// The drop-in is still being read on a worker thread.
+        loading: bool,
+        /// Current drop-in content; `None` if the user has none.
+        content: Option<String>,
+        /// Why the drop-in could not be read, if it could not.
+        error: Option<String>,
+    },
     ChangePassword {
         selected: usize,
         password: String,
@@ -305,23 +372,86 @@ pub enum ModalState {
     },
     Help {
         scroll: u16,
+        /// Term searched for with `/`; lines containing it are highlighted.
+        query: String,
+        /// Keys are typed into `query` instead of scrolling.
+        searching: bool,
+    },
+    /// Yes/No before `next` runs, for actions `confirmations.conf` wants
+    /// confirmed that have no dialog of their own.
+    ConfirmAction {
+        selected: usize,
+        next: PendingAction,
+        /// Caveat shown above the question, if the action looks questionable.
+        warning: Option<String>,
+    },
+    /// Path of a CSV file of users to create; see [`import`].
+    #[cfg_attr(feature = "read-only-build", allow(dead_code))]
+    ImportPathInput {
+        path: String,
+        /// Cursor position in `path`, in characters.
+        cursor: usize,
+    },
+    /// Each account a multi-select membership change affects, listed before
+    /// `next` runs; see [`membership_preview`].
+    MembershipPreview {
+        next: PendingAction,
+        scroll: u16,
+    },
+    /// `next` runs once `keyword` is typed (`typed` in `confirmations.conf`).
+    TypedConfirm {
+        next: PendingAction,
+        keyword: String,
+        input: String,
+        cursor: usize,
+    },
+    /// The most recent performed actions, newest first.
+    ActionHistory {
+        scroll: u16,
+    },
+    /// Details of the selected user, for layouts without a details column.
+    UserDetails,
+    /// Details of the selected group, for layouts without a details column.
+    GroupDetails,
+    /// Members of `group_name` with no account in `/etc/passwd`, any of
+    /// which can be removed from the group.
+    OrphanMembers {
+        selected: usize,
+        group_name: String,
+        orphans: Vec<String>,
     },
     SudoPrompt {
         next: PendingAction,
         password: String,
         error: Option<String>,
+        /// Passwords already rejected for `next`; the prompt gives up after
+        /// [`MAX_SUDO_ATTEMPTS`].
+        attempts: u8,
     },
+    #[cfg_attr(feature = "read-only-build", allow(dead_code))]
     GroupsActions {
         selected: usize,
         target_gid: Option<u32>,
     },
     GroupAddInput {
         name: String,
+        cursor: usize,
+    },
+    /// Some users have the group being deleted as their primary group: pick
+    /// the group they move to first. Esc keeps the group.
+    GroupDeleteReassign {
+        selected: usize,
+        offset: usize,
+        target_gid: u32,
     },
+    /// Confirm deleting a group, after moving its primary users to
+    /// `reassign_to` when set.
     GroupDeleteConfirm {
         selected: usize,
         target_gid: Option<u32>,
+        reassign_to: Option<String>,
     },
+    #[cfg_attr(feature = "read-only-build", allow(dead_code))]
     ConfirmRemoveUserFromGroup {
         selected: usize,
         group_name: String,
@@ -330,30 +460,76 @@ pub enum ModalState {
         selected: usize,
         target_gid: Option<u32>,
     },
+    /// Pick the group the members of group `source_gid` are merged into
+    /// before it is deleted.
+    MergeGroup {
+        selected: usize,
+        offset: usize,
+        source_gid: u32,
+    },
+    /// Pick the group a member is moved to; `from_group` is left in one step.
+    MoveMemberPicker {
+        selected: usize,
+        offset: usize,
+        username: String,
+        from_group: String,
+    },
+    MoveMemberConfirm {
+        selected: usize,
+        username: String,
+        from_group: String,
+        to_group: String,
+    },
     GroupModifyAddMembers {
         selected: usize,
         offset: usize,
         target_gid: Option<u32>,
-        selected_multi: Vec<usize>,
+        selected_multi: BTreeSet<String>,
+        /// Name of a group that does not exist yet: Enter creates it with
+        /// the picked users as members instead of adding them to `target_gid`.
+        new_group: Option<String>,
     },
     GroupModifyRemoveMembers {
         selected: usize,
         offset: usize,
         target_gid: Option<u32>,
-        selected_multi: Vec<usize>,
+        selected_multi: BTreeSet<String>,
     },
     GroupRenameInput {
         name: String,
+        cursor: usize,
         target_gid: Option<u32>,
     },
+    /// Name the copy of group `source`.
+    GroupCloneInput {
+        source: String,
+        name: String,
+        cursor: usize,
+    },
+    #[cfg_attr(feature = "read-only-build", allow(dead_code))]
     UserAddInput {
         selected: usize,
         name: String,
         password: String,
         confirm: String,
+        /// Cursor position within the selected text field, in characters.
+        cursor: usize,
         create_home: bool,
         add_to_wheel: bool,
     },
+    /// Create a user with the shell and supplementary groups of `template`.
+    /// Fields as in `UserAddInput`, without the wheel toggle.
+    CloneUser {
+        template: String,
+        shell: String,
+        groups: Vec<String>,
+        selected: usize,
+        name: String,
+        password: String,
+        confirm: String,
+        cursor: usize,
+        create_home: bool,
+    },
 }
 
 /// Field selectors for text input dialogs.
@@ -361,6 +537,8 @@ pub enum ModalState {
 pub enum ModifyField {
     Username,
     Fullname,
+    /// A login shell path typed by hand instead of picked from `/etc/shells`.
+    Shell,
 }
 
 /// Combinable filter chips for users that refine the list further.
@@ -382,6 +560,1380 @@ pub struct UsersFilterChips {
     pub no_password: bool,
     /// Show only users whose password has expired.
     pub expired: bool,
+    /// Show only users whose primary GID has no entry in `/etc/group`.
+    pub no_primary_group: bool,
+}
+
+/// Combinable filter chips for groups, the counterpart of [`UsersFilterChips`].
+#[derive(Clone, Debug, Default)]
+pub struct GroupsFilterChips {
+    /// Show only groups with no members and no users having them as primary group.
+    pub empty_only: bool,
+    /// Show only groups with GID < 1000 (opposite of user_only).
+    pub system_only: bool,
+    /// Show only groups with GID >= 1000 (opposite of system_only).
+    pub user_only: bool,
+    /// Show only the group that grants sudo; see [`sudo_group_name`].
+    pub has_sudo_flag: bool,
+    /// Show only groups listing members that have no entry in `/etc/passwd`.
+    pub orphan_members: bool,
+}
+
+/// Which panes the body of the screen shows.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LayoutMode {
+    /// Table, details and keybindings side by side, until the terminal is
+    /// narrower than `compact_below`.
+    #[default]
+    Expanded,
+    /// Only the active table at any width; details open in a popup.
+    Compact,
+}
+
+/// How much the user details show.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum DetailVerbosity {
+    /// Everything known about the user, including the live stats.
+    #[default]
+    Full,
+    /// Name, UID, primary group and shell only, skipping the lookups the
+    /// full view makes on every frame.
+    Compact,
+}
+
+/// Where the Member of pane takes a user's groups from.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MembershipSource {
+    /// `/etc/passwd` primary group plus the member lists in `/etc/group`.
+    #[default]
+    Files,
+    /// `id -Gn`, which also sees memberships served by LDAP or SSSD.
+    Id,
+}
+
+/// Number base used to display UIDs and GIDs.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum IdRadix {
+    #[default]
+    Decimal,
+    Hex,
+    Octal,
+}
+
+impl IdRadix {
+    /// The radix after this one, wrapping back to decimal.
+    pub fn next(self) -> Self {
+        match self {
+            IdRadix::Decimal => IdRadix::Hex,
+            IdRadix::Hex => IdRadix::Octal,
+            IdRadix::Octal => IdRadix::Decimal,
+        }
+    }
+
+    /// Name used in `ui.conf`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IdRadix::Decimal => "decimal",
+            IdRadix::Hex => "hex",
+            IdRadix::Octal => "octal",
+        }
+    }
+
+    /// Parse a `ui.conf` value; unknown names give `None`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "decimal" | "dec" => Some(IdRadix::Decimal),
+            "hex" | "hexadecimal" => Some(IdRadix::Hex),
+            "octal" | "oct" => Some(IdRadix::Octal),
+            _ => None,
+        }
+    }
+}
+
+/// A column of the users table.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UserColumn {
+    Uid,
+    Name,
+    Gid,
+    /// Whether the home directory exists, as a one-character marker.
+    HomeExists,
+    Home,
+    Shell,
+}
+
+impl UserColumn {
+    /// Every column, in the default order.
+    pub const ALL: [UserColumn; 6] = [
+        UserColumn::Uid,
+        UserColumn::Name,
+        UserColumn::Gid,
+        UserColumn::HomeExists,
+        UserColumn::Home,
+        UserColumn::Shell,
+    ];
+
+    /// Table header, also the name used in `ui.conf` (case-insensitive).
+    pub fn header(self) -> &'static str {
+        match self {
+            UserColumn::Uid => "UID",
+            UserColumn::Name => "USER",
+            UserColumn::Gid => "GID",
+            UserColumn::HomeExists => "DIR",
+            UserColumn::Home => "HOME",
+            UserColumn::Shell => "SHELL",
+        }
+    }
+
+    /// Parse a comma-separated `ui.conf` list such as `user, uid, shell`.
+    ///
+    /// Unknown names and repeats are skipped, and USER is put first if the
+    /// list leaves it out, so the table always shows who each row is.
+    pub fn parse_list(s: &str) -> Vec<UserColumn> {
+        let mut columns: Vec<UserColumn> = Vec::new();
+        for name in s.split(',').map(str::trim) {
+            let column = match name.to_ascii_lowercase().as_str() {
+                "uid" => UserColumn::Uid,
+                "user" | "name" => UserColumn::Name,
+                "gid" => UserColumn::Gid,
+                "dir" => UserColumn::HomeExists,
+                "home" => UserColumn::Home,
+                "shell" => UserColumn::Shell,
+                _ => continue,
+            };
+            if !columns.contains(&column) {
+                columns.push(column);
+            }
+        }
+        if !columns.contains(&UserColumn::Name) {
+            columns.insert(0, UserColumn::Name);
+        }
+        columns
+    }
+}
+
+/// What Enter does in a multi-select picker while no item is checked.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum EmptyPickerEnter {
+    /// Act on the item under the cursor.
+    #[default]
+    Cursor,
+    /// Do nothing and ask for at least one checked item.
+    RequireChecked,
+}
+
+impl EmptyPickerEnter {
+    /// Name used in `ui.conf`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EmptyPickerEnter::Cursor => "cursor",
+            EmptyPickerEnter::RequireChecked => "require",
+        }
+    }
+
+    /// Parse a `ui.conf` value; unknown names give `None`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "cursor" => Some(EmptyPickerEnter::Cursor),
+            "require" | "require_checked" => Some(EmptyPickerEnter::RequireChecked),
+            _ => None,
+        }
+    }
+}
+
+/// Actions the copy-command menu offers for the current selection, with
+/// their menu labels.
+///
+/// On the Users tab these act on the selected user (and the selected Member
+/// of group); on the Groups tab on the selected group, its selected member,
+/// and the user last selected on the Users tab.
+pub fn copy_command_choices(app: &AppState) -> Vec<(String, PendingAction)> {
+    let mut choices = Vec::new();
+    let user = app.users.get(app.selected_user_index);
+    match app.active_tab {
+        ActiveTab::Users => {
+            let Some(u) = user else { return choices };
+            if app.users_focus == UsersFocus::MemberOf {
+                let member_of = app
+                    .groups
+                    .iter()
+                    .filter(|g| g.gid == u.primary_gid || g.members.contains(&u.name))
+                    .nth(app.selected_group_index);
+                if let Some(g) = member_of.filter(|g| g.gid != u.primary_gid) {
+                    choices.push((
+                        format!("Remove {} from {}", u.name, g.name),
+                        PendingAction::RemoveUserFromGroup {
+                            username: u.name.clone(),
+                            groupname: g.name.clone(),
+                        },
+                    ));
+                }
+            }
+            choices.push((
+                format!("Delete user {}", u.name),
+                PendingAction::DeleteUser {
+                    username: u.name.clone(),
+                    delete_home: false,
+                },
+            ));
+        }
+        ActiveTab::Groups => {
+            let Some(g) = app.groups.get(app.selected_group_index) else {
+                return choices;
+            };
+            if app.groups_focus == GroupsFocus::Members
+                && let Some((member, false)) = selected_group_member_rows(app)
+                    .into_iter()
+                    .nth(app.selected_group_member_index)
+            {
+                choices.push((
+                    format!("Remove {} from {}", member, g.name),
+                    PendingAction::RemoveUserFromGroup {
+                        username: member,
+                        groupname: g.name.clone(),
+                    },
+                ));
+            }
+            if let Some(u) = user
+                && group_eligibility(u, g) == GroupEligibility::Eligible
+            {
+                choices.push((
+                    format!("Add {} to {}", u.name, g.name),
+                    PendingAction::AddUserToGroup {
+                        username: u.name.clone(),
+                        groupname: g.name.clone(),
+                    },
+                ));
+            }
+            choices.push((
+                format!("Delete group {}", g.name),
+                PendingAction::DeleteGroup {
+                    groupname: g.name.clone(),
+                },
+            ));
+        }
+    }
+    choices
+}
+
+/// [`describe_command`] as an operator would paste it into a shell, with
+/// every command run through `sudo`; comment lines are kept as they are.
+pub fn ready_to_run_command(action: &PendingAction) -> String {
+    describe_command(action)
+        .lines()
+        .map(|line| {
+            if line.starts_with('#') {
+                line.to_string()
+            } else {
+                format!("sudo {line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Index of the user called `name` in `users`.
+///
+/// Several passwd entries may share a name; then the one with `uid` wins, and
+/// without a matching `uid` the first entry does, as for most system tools.
+pub fn find_user_index(users: &[sys::SystemUser], name: &str, uid: Option<u32>) -> Option<usize> {
+    uid.and_then(|uid| users.iter().position(|u| u.name == name && u.uid == uid))
+        .or_else(|| users.iter().position(|u| u.name == name))
+}
+
+/// Names of the users whose primary group is `gid`, in `users` order.
+///
+/// `groupdel` refuses to remove such a group, so they have to be moved first.
+pub fn primary_group_users(users: &[sys::SystemUser], gid: u32) -> Vec<String> {
+    users
+        .iter()
+        .filter(|u| u.primary_gid == gid)
+        .map(|u| u.name.clone())
+        .collect()
+}
+
+/// Groups the primary users of group `gid` can be moved to: every other group.
+pub fn reassign_targets(groups: &[sys::SystemGroup], gid: u32) -> Vec<&sys::SystemGroup> {
+    groups.iter().filter(|g| g.gid != gid).collect()
+}
+
+/// Action that deletes `group`, first moving its primary users to
+/// `reassign_to` when given.
+pub fn group_delete_action(
+    users: &[sys::SystemUser],
+    group: &sys::SystemGroup,
+    reassign_to: Option<&str>,
+) -> PendingAction {
+    match reassign_to {
+        Some(fallback) => PendingAction::ReassignAndDeleteGroup {
+            groupname: group.name.clone(),
+            fallback: fallback.to_string(),
+            usernames: primary_group_users(users, group.gid),
+        },
+        None => PendingAction::DeleteGroup {
+            groupname: group.name.clone(),
+        },
+    }
+}
+
+/// Action that merges `source` into `destination`: members `destination`
+/// lacks are added, primary users move over, and `source` is deleted.
+pub fn merge_group_action(
+    users: &[sys::SystemUser],
+    source: &sys::SystemGroup,
+    destination: &sys::SystemGroup,
+) -> PendingAction {
+    PendingAction::MergeGroup {
+        source: source.name.clone(),
+        destination: destination.name.clone(),
+        members: source
+            .members
+            .iter()
+            .filter(|m| !destination.members.contains(m))
+            .cloned()
+            .collect(),
+        primary_users: primary_group_users(users, source.gid),
+    }
+}
+
+/// Every reason `user` may not be deleted; empty when deletion is allowed.
+///
+/// Only regular accounts in UID 1000-1999 qualify, never root or the shared
+/// `nobody` account, and not while the user still runs processes (which
+/// includes being logged in) or their private group is another user's
+/// primary group.
+pub fn deletion_blockers(app: &AppState, user: &sys::SystemUser) -> Vec<String> {
+    let mut blockers = Vec::new();
+    if user.uid == 0 {
+        blockers.push("user is root".to_string());
+    }
+    if user.is_nobody() {
+        blockers.push("shared nobody account".to_string());
+    }
+    if !(1000..=1999).contains(&user.uid) {
+        blockers.push("UID outside 1000-1999".to_string());
+    }
+    match sys::process_count(user.uid) {
+        0 => {}
+        1 => blockers.push("user owns 1 process".to_string()),
+        n => blockers.push(format!("user owns {n} processes")),
+    }
+    let others: Vec<&str> = app
+        .users_all
+        .iter()
+        .filter(|u| u.primary_gid == user.primary_gid && u.name != user.name)
+        .map(|u| u.name.as_str())
+        .collect();
+    if !others.is_empty()
+        && let Some(g) = app
+            .groups_all
+            .iter()
+            .find(|g| g.gid == user.primary_gid && g.name == user.name)
+    {
+        blockers.push(format!(
+            "group '{}' is the primary group of {}",
+            g.name,
+            others.join(", ")
+        ));
+    }
+    blockers
+}
+
+/// Marker appended to the `nobody`/`nogroup` accounts in tables and details.
+pub const NOBODY_MARKER: &str = " (nobody)";
+
+/// Marker put in front of locked accounts in the users table.
+pub const LOCKED_MARKER: &str = "🔒 ";
+
+/// DIR column markers for a home directory that exists and one that does not.
+pub const HOME_EXISTS_MARKERS: (&str, &str) = ("✓", "✗");
+
+/// Render a UID or GID in `radix`; hex and octal carry a `0x`/`0o` prefix.
+pub fn format_id(id: u32, radix: IdRadix) -> String {
+    match radix {
+        IdRadix::Decimal => id.to_string(),
+        IdRadix::Hex => format!("{:#x}", id),
+        IdRadix::Octal => format!("{:#o}", id),
+    }
+}
+
+/// Widths of the three body columns (main table, details, keybinds panel) in percent.
+///
+/// The widths always add up to 100. When the keybinds panel is hidden the
+/// table and details columns share the full width in the same ratio.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PaneSplit {
+    pub table: u16,
+    pub details: u16,
+    pub keybinds: u16,
+}
+
+impl PaneSplit {
+    /// Narrowest each column may become, in the same order as the fields.
+    pub const MIN: [u16; 3] = [20, 20, 15];
+    /// Percentage moved per key press.
+    pub const STEP: i16 = 5;
+
+    /// Build a split from stored widths, falling back to the default when they
+    /// do not add up to 100 or violate the minimums.
+    pub fn new(table: u16, details: u16, keybinds: u16) -> Self {
+        let split = Self {
+            table,
+            details,
+            keybinds,
+        };
+        let widths = split.widths();
+        if widths.iter().sum::<u16>() == 100 && widths.iter().zip(Self::MIN).all(|(w, m)| *w >= m) {
+            split
+        } else {
+            Self::default()
+        }
+    }
+
+    fn widths(&self) -> [u16; 3] {
+        [self.table, self.details, self.keybinds]
+    }
+
+    /// Move the border right of column `left` (0 or 1) by `delta` percent.
+    ///
+    /// A positive delta widens column `left` at the expense of the one after
+    /// it; the shrinking column stops at its minimum.
+    pub fn shift(&mut self, left: usize, delta: i16) {
+        let mut widths = self.widths();
+        if left > 1 {
+            return;
+        }
+        let right = left + 1;
+        let applied = if delta >= 0 {
+            delta.min(widths[right].saturating_sub(Self::MIN[right]) as i16)
+        } else {
+            delta.max(-(widths[left].saturating_sub(Self::MIN[left]) as i16))
+        };
+        widths[left] = (widths[left] as i16 + applied) as u16;
+        widths[right] = (widths[right] as i16 - applied) as u16;
+        [self.table, self.details, self.keybinds] = widths;
+    }
+
+    /// Table share of the width when the keybinds panel is hidden.
+    pub fn table_without_keybinds(&self) -> u16 {
+        self.table * 100 / (self.table + self.details).max(1)
+    }
+}
+
+impl Default for PaneSplit {
+    fn default() -> Self {
+        Self {
+            table: 41,
+            details: 34,
+            keybinds: 25,
+        }
+    }
+}
+
+/// Why a group can or cannot be offered when adding a user to groups.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GroupEligibility {
+    /// The user can be added to the group.
+    Eligible,
+    /// The user is already a supplementary member of the group.
+    AlreadyMember,
+    /// The group is the user's primary group.
+    Primary,
+}
+
+impl GroupEligibility {
+    /// Compact annotation shown next to ineligible groups in pickers.
+    pub fn marker(self) -> &'static str {
+        match self {
+            GroupEligibility::Eligible => "",
+            GroupEligibility::AlreadyMember => "member",
+            GroupEligibility::Primary => "primary",
+        }
+    }
+}
+
+/// Classify whether `user` can be added to `group`.
+pub fn group_eligibility(user: &sys::SystemUser, group: &sys::SystemGroup) -> GroupEligibility {
+    if group.gid == user.primary_gid {
+        GroupEligibility::Primary
+    } else if group.members.iter().any(|m| m == &user.name) {
+        GroupEligibility::AlreadyMember
+    } else {
+        GroupEligibility::Eligible
+    }
+}
+
+/// Check every name in `selectable` in a multi-select picker, or uncheck
+/// everything when exactly those names are checked already.
+///
+/// `selectable` holds the rows the picker currently shows and allows picking,
+/// so items hidden or disabled by its filter are never checked.
+pub fn select_all_or_clear(selected_multi: &mut BTreeSet<String>, selectable: BTreeSet<String>) {
+    if !selectable.is_empty() && *selected_multi == selectable {
+        selected_multi.clear();
+    } else {
+        *selected_multi = selectable;
+    }
+}
+
+/// Rows of a picker listing `items` that are checked in `selection`, in list
+/// order rather than the order they were checked in.
+pub fn checked_items<'a>(
+    items: impl IntoIterator<Item = &'a str>,
+    selection: &BTreeSet<String>,
+) -> Vec<String> {
+    items
+        .into_iter()
+        .filter(|item| selection.contains(*item))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Replace the checked indices of a `total`-item picker with the unchecked
+/// ones, in ascending order.
+pub fn invert_selection(selected: &mut Vec<usize>, total: usize) {
+    let inverted: Vec<usize> = (0..total).filter(|i| !selected.contains(i)).collect();
+    *selected = inverted;
+}
+
+/// Groups listed by the add-to-group picker for `user`, in `groups` order.
+///
+/// Only eligible groups are returned unless `show_all` is set, in which case
+/// every group is returned together with its eligibility.
+pub fn add_group_candidates<'a>(
+    groups: &'a [sys::SystemGroup],
+    user: Option<&sys::SystemUser>,
+    show_all: bool,
+) -> Vec<(&'a sys::SystemGroup, GroupEligibility)> {
+    let Some(user) = user else {
+        return Vec::new();
+    };
+    groups
+        .iter()
+        .map(|g| (g, group_eligibility(user, g)))
+        .filter(|(_, e)| show_all || *e == GroupEligibility::Eligible)
+        .collect()
+}
+
+/// Groups `username` can be moved to from `from_group`, in `groups` order.
+///
+/// The source group and groups the user already belongs to (including their
+/// primary group) are left out. Members without a passwd entry are only
+/// checked against supplementary membership.
+pub fn move_member_targets<'a>(
+    groups: &'a [sys::SystemGroup],
+    users: &[sys::SystemUser],
+    username: &str,
+    from_group: &str,
+) -> Vec<&'a sys::SystemGroup> {
+    let user = users.iter().find(|u| u.name == username);
+    groups
+        .iter()
+        .filter(|g| g.name != from_group)
+        .filter(|g| match user {
+            Some(u) => group_eligibility(u, g) == GroupEligibility::Eligible,
+            None => !g.members.iter().any(|m| m == username),
+        })
+        .collect()
+}
+
+/// The entries of `shells` containing `filter`, ignoring case; all of them
+/// when `filter` is empty.
+pub fn matching_shells<'a>(shells: &'a [String], filter: &str) -> Vec<&'a String> {
+    let filter = filter.to_lowercase();
+    shells
+        .iter()
+        .filter(|s| s.to_lowercase().contains(&filter))
+        .collect()
+}
+
+/// Why `path` cannot be used as a login shell, if it cannot.
+///
+/// The path has to be absolute and name an existing executable file. Whether
+/// it is listed in `/etc/shells` is not checked here.
+pub fn custom_shell_problem(path: &str) -> Option<String> {
+    if !path.starts_with('/') {
+        return Some("Shell must be an absolute path".to_string());
+    }
+    let Ok(meta) = std::fs::metadata(path) else {
+        return Some(format!("{path} does not exist"));
+    };
+    #[cfg(unix)]
+    let executable = {
+        use std::os::unix::fs::PermissionsExt;
+        meta.permissions().mode() & 0o111 != 0
+    };
+    #[cfg(not(unix))]
+    let executable = true;
+    if !meta.is_file() || !executable {
+        return Some(format!("{path} is not an executable file"));
+    }
+    None
+}
+
+/// Highest score [`password_strength`] gives.
+pub const PASSWORD_STRENGTH_MAX: u8 = 6;
+
+/// Rough strength of `password` as a score from 0 to [`PASSWORD_STRENGTH_MAX`]
+/// and a label: "weak", "ok" or "strong".
+///
+/// Length earns up to three points (8, 12 and 16 characters) and each
+/// character class beyond the first (lowercase, uppercase, digits, other)
+/// one more. Anything shorter than 8 characters stays weak. Only informs the
+/// user; weak passwords are still accepted.
+pub fn password_strength(password: &str) -> (u8, &'static str) {
+    let len = password.chars().count();
+    let length_points: u8 = match len {
+        0..=7 => 0,
+        8..=11 => 1,
+        12..=15 => 2,
+        _ => 3,
+    };
+    let classes = [
+        password.chars().any(|c| c.is_lowercase()),
+        password.chars().any(|c| c.is_uppercase()),
+        password.chars().any(|c| c.is_ascii_digit()),
+        password.chars().any(|c| !c.is_alphanumeric()),
+    ]
+    .iter()
+    .filter(|&&present| present)
+    .count() as u8;
+    let mut score = length_points + classes.saturating_sub(1);
+    if len < 8 {
+        score = score.min(2);
+    }
+    let label = match score {
+        0..=2 => "weak",
+        3..=4 => "ok",
+        _ => "strong",
+    };
+    (score, label)
+}
+
+/// Why `password` falls short of the `min` length policy, if it does.
+///
+/// An empty password is left to the caller (it means "no password" when
+/// creating a user), and a `min` of 0 disables the check. Length counts
+/// characters, not bytes.
+pub fn password_length_problem(password: &str, min: usize) -> Option<String> {
+    let len = password.chars().count();
+    if len == 0 || len >= min {
+        return None;
+    }
+    Some(format!(
+        "Password must be at least {min} characters long (got {len})"
+    ))
+}
+
+/// Members of a group as shown in the Members list.
+///
+/// With `sorted` the names are ordered case-insensitively; otherwise they keep
+/// their `/etc/group` order. The selected member index refers to this list.
+pub fn displayed_members(members: &[String], sorted: bool) -> Vec<String> {
+    let mut shown = members.to_vec();
+    if sorted {
+        shown.sort_by_cached_key(|m| m.to_lowercase());
+    }
+    shown
+}
+
+/// Members of the selected group as shown in the Members list.
+pub fn selected_group_members(app: &AppState) -> Vec<String> {
+    selected_group_member_rows(app)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// Rows of the Members list: each name paired with whether the user is listed
+/// only because this is their primary group.
+///
+/// Those users follow the `/etc/group` members and are included only with
+/// `members_include_primary`.
+pub fn selected_group_member_rows(app: &AppState) -> Vec<(String, bool)> {
+    let Some(g) = app.groups.get(app.selected_group_index) else {
+        return Vec::new();
+    };
+    let mut rows: Vec<(String, bool)> = displayed_members(&g.members, app.sort_members)
+        .into_iter()
+        .map(|name| (name, false))
+        .collect();
+    if app.members_include_primary {
+        let primary_only: Vec<String> = app
+            .users_all
+            .iter()
+            .filter(|u| u.primary_gid == g.gid && !g.members.contains(&u.name))
+            .map(|u| u.name.clone())
+            .collect();
+        rows.extend(
+            displayed_members(&primary_only, app.sort_members)
+                .into_iter()
+                .map(|name| (name, true)),
+        );
+    }
+    rows
+}
+
+/// Per-group figures shown in the Group Details pane.
+///
+/// Computing them walks every member and reads the shadow status of each, so
+/// they are kept in [`GroupDetailsCache`] instead of being rebuilt per frame.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GroupDetailsStats {
+    pub primary_count: usize,
+    /// Up to ten secondary members, alphabetically, with a "(+N more)" suffix.
+    pub members_preview: String,
+    pub shell_interactive: usize,
+    pub shell_noninteractive: usize,
+    pub uid_system_count: usize,
+    pub uid_user_count: usize,
+    pub locked_count: usize,
+    pub nopass_count: usize,
+    pub expired_count: usize,
+    /// Secondary members with no matching user account.
+    pub orphan_count: usize,
+}
+
+impl GroupDetailsStats {
+    /// Gather the statistics for `group` from `users`; the shadow counts stay
+    /// zero unless `read_shadow` is set.
+    pub fn compute(group: &sys::SystemGroup, users: &[sys::SystemUser], read_shadow: bool) -> Self {
+        let user_by_name: std::collections::HashMap<&str, &sys::SystemUser> =
+            users.iter().map(|u| (u.name.as_str(), u)).collect();
+        let primary_usernames: Vec<&str> = users
+            .iter()
+            .filter(|u| u.primary_gid == group.gid)
+            .map(|u| u.name.as_str())
+            .collect();
+        let mut stats = Self {
+            primary_count: primary_usernames.len(),
+            ..Self::default()
+        };
+
+        // Combined member set (primary + secondary) for distributions
+        let mut member_set: std::collections::BTreeSet<&str> =
+            group.members.iter().map(String::as_str).collect();
+        member_set.extend(primary_usernames);
+
+        for name in &member_set {
+            if let Some(u) = user_by_name.get(name) {
+                let sh = u.shell.as_str();
+                if sh.ends_with("/nologin") || sh.ends_with("/false") {
+                    stats.shell_noninteractive += 1;
+                } else {
+                    stats.shell_interactive += 1;
+                }
+                if u.uid < 1000 {
+                    stats.uid_system_count += 1;
+                } else {
+                    stats.uid_user_count += 1;
+                }
+            } else {
+                stats.orphan_count += 1;
+            }
+        }
+
+        // Shadow status counts (best-effort); read the file once, not per member
+        if read_shadow && let Ok(shadow) = crate::search::get_shadow_status() {
+            for name in &member_set {
+                if let Some(sh) = shadow.get(*name) {
+                    stats.locked_count += usize::from(sh.locked);
+                    stats.nopass_count += usize::from(sh.no_password);
+                    stats.expired_count += usize::from(sh.expired);
+                }
+            }
+        }
+
+        // Alphabetical top-N preview of member names (secondary list only)
+        let mut names = group.members.clone();
+        names.sort_by_key(|a| a.to_lowercase());
+        let n: usize = 10;
+        let total = names.len();
+        let shown: Vec<String> = names.into_iter().take(n).collect();
+        stats.members_preview = if shown.is_empty() {
+            "-".to_string()
+        } else {
+            shown.join(", ")
+        };
+        if total > n {
+            stats
+                .members_preview
+                .push_str(&format!(" (+{} more)", total - n));
+        }
+        stats
+    }
+}
+
+/// Secondary members of `group` that have no matching entry in `users`, in
+/// file order.
+pub fn orphan_members(group: &sys::SystemGroup, users: &[sys::SystemUser]) -> Vec<String> {
+    let known: std::collections::HashSet<&str> = users.iter().map(|u| u.name.as_str()).collect();
+    group
+        .members
+        .iter()
+        .filter(|m| !known.contains(m.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// [`GroupDetailsStats`] for one group, valid while `/etc/group` keeps the
+/// modification time it had when they were computed.
+#[derive(Clone, Debug)]
+pub struct GroupDetailsCache {
+    pub group: String,
+    pub group_mtime: Option<std::time::SystemTime>,
+    /// Whether the shadow counts were read (live stats were on).
+    pub live_stats: bool,
+    pub stats: GroupDetailsStats,
+}
+
+/// Login shells listed in `/etc/shells`, read through `adapter` the first
+/// time and then served from [`AppState::shells_cache`] until it is cleared.
+pub fn known_shells<'a>(app: &'a mut AppState, adapter: &sys::SystemAdapter) -> &'a [String] {
+    app.shells_cache
+        .get_or_insert_with(|| adapter.list_shells().unwrap_or_default())
+}
+
+/// Users whose password is locked, read from `/etc/shadow` the first time
+/// and then served from [`AppState::locked_users_cache`] until it is cleared.
+/// Empty when the shadow file cannot be read.
+pub fn locked_users(app: &mut AppState) -> &std::collections::HashSet<String> {
+    app.locked_users_cache
+        .get_or_insert_with(|| crate::search::locked_usernames().unwrap_or_default())
+}
+
+/// Groups `username` is in according to `id -Gn`, asked of the app's
+/// backend once per user and then served from
+/// [`AppState::effective_groups_cache`] until it is cleared. `None` when `id`
+/// fails, so callers keep the file-derived membership.
+pub fn effective_groups<'a>(app: &'a mut AppState, username: &str) -> Option<&'a [String]> {
+    if app
+        .effective_groups_cache
+        .as_ref()
+        .is_none_or(|(name, _)| name != username)
+    {
+        let groups = (app.backend)(None).effective_groups(username).ok();
+        app.effective_groups_cache = Some((username.to_string(), groups));
+    }
+    app.effective_groups_cache
+        .as_ref()
+        .and_then(|(_, groups)| groups.as_deref())
+}
+
+/// Names of the groups `user` is in according to the account files: its
+/// primary group and every group listing it as a member.
+pub fn file_groups(user: &sys::SystemUser, groups: &[sys::SystemGroup]) -> Vec<String> {
+    groups
+        .iter()
+        .filter(|g| g.gid == user.primary_gid || g.members.contains(&user.name))
+        .map(|g| g.name.clone())
+        .collect()
+}
+
+/// Cached `id -Gn` groups of the selected user while the Member of pane is in
+/// [`MembershipSource::Id`] mode; `None` otherwise or when `id` failed.
+fn selected_id_groups(app: &AppState) -> Option<&[String]> {
+    if app.membership_source != MembershipSource::Id {
+        return None;
+    }
+    let user = app.users.get(app.selected_user_index)?;
+    match &app.effective_groups_cache {
+        Some((name, Some(groups))) if *name == user.name => Some(groups),
+        _ => None,
+    }
+}
+
+/// Groups listed in the selected user's Member of pane: the primary group and
+/// every group naming the user as a member, or in [`MembershipSource::Id`]
+/// mode the shown groups `id -Gn` reports. Without an `id` answer the files
+/// are used.
+pub fn member_of_groups(app: &AppState) -> Vec<sys::SystemGroup> {
+    let Some(u) = app.users.get(app.selected_user_index) else {
+        return Vec::new();
+    };
+    match selected_id_groups(app) {
+        Some(names) => names
+            .iter()
+            .filter_map(|n| app.groups.iter().find(|g| g.name == *n))
+            .cloned()
+            .collect(),
+        None => app
+            .groups
+            .iter()
+            .filter(|g| g.gid == u.primary_gid || g.members.contains(&u.name))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Groups `id -Gn` reports for the selected user that `/etc/group` does not
+/// list at all, such as LDAP groups; empty outside [`MembershipSource::Id`].
+pub fn nss_only_groups(app: &AppState) -> Vec<String> {
+    selected_id_groups(app)
+        .unwrap_or_default()
+        .iter()
+        .filter(|n| !app.groups_all.iter().any(|g| g.name == **n))
+        .cloned()
+        .collect()
+}
+
+/// Statistics for the selected group, recomputed only when the selection or
+/// `group_mtime` (the modification time of `/etc/group`) changed.
+pub fn selected_group_details(
+    app: &mut AppState,
+    group_mtime: Option<std::time::SystemTime>,
+) -> Option<GroupDetailsStats> {
+    let group = app.groups.get(app.selected_group_index)?;
+    if let Some(cache) = &app.group_details_cache
+        && cache.group == group.name
+        && cache.group_mtime == group_mtime
+        && cache.live_stats == app.live_stats
+    {
+        return Some(cache.stats.clone());
+    }
+    let stats = GroupDetailsStats::compute(group, &app.users_all, app.live_stats);
+    app.group_details_cache = Some(GroupDetailsCache {
+        group: group.name.clone(),
+        group_mtime,
+        live_stats: app.live_stats,
+        stats: stats.clone(),
+    });
+    Some(stats)
+}
+
+/// Add `username` to (or, with `added` false, remove it from) the member list
+/// of `groupname` in `groups`, mirroring a completed `gpasswd` call.
+///
+/// Returns false without changing anything when `groups` does not match the
+/// state the change started from: the group is unknown, or the user is already
+/// a member (or, for a removal, is not one). Callers then reload from disk.
+#[cfg(not(feature = "read-only-build"))]
+pub fn apply_membership_change(
+    groups: &mut [sys::SystemGroup],
+    username: &str,
+    groupname: &str,
+    added: bool,
+) -> bool {
+    let Some(group) = groups.iter_mut().find(|g| g.name == groupname) else {
+        return false;
+    };
+    let pos = group.members.iter().position(|m| m == username);
+    match (added, pos) {
+        (true, None) => group.members.push(username.to_string()),
+        (false, Some(idx)) => {
+            group.members.remove(idx);
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Supplementary groups `username` keeps after leaving `removed`, in the
+/// order of `groups`.
+///
+/// The group with `primary_gid` is left out even if it lists the user, since
+/// `usermod -G` takes only supplementary groups.
+pub fn surviving_supplementary_groups(
+    groups: &[sys::SystemGroup],
+    username: &str,
+    primary_gid: u32,
+    removed: &[String],
+) -> Vec<String> {
+    groups
+        .iter()
+        .filter(|g| g.gid != primary_gid && !removed.contains(&g.name))
+        .filter(|g| g.members.iter().any(|m| m == username))
+        .map(|g| g.name.clone())
+        .collect()
+}
+
+/// Create-user form pre-filled from `template`: its shell and supplementary
+/// groups. The UID, primary group and home contents are not copied.
+pub fn clone_user_modal(template: &sys::SystemUser, groups: &[sys::SystemGroup]) -> ModalState {
+    ModalState::CloneUser {
+        template: template.name.clone(),
+        shell: template.shell.clone(),
+        groups: surviving_supplementary_groups(groups, &template.name, template.primary_gid, &[]),
+        selected: 0,
+        name: String::new(),
+        password: String::new(),
+        confirm: String::new(),
+        cursor: 0,
+        create_home: true,
+    }
+}
+
+/// Wrong sudo passwords accepted for one action before it is cancelled, as
+/// `sudo` itself gives up after three tries.
+pub const MAX_SUDO_ATTEMPTS: u8 = 3;
+
+/// Labels of the GECOS fields [`ModalState::EditGecos`] edits, in order.
+pub const GECOS_LABELS: [&str; 4] = ["Full name", "Room", "Work phone", "Home phone"];
+
+/// Split a GECOS string into full name, room, work phone and home phone,
+/// plus whatever follows them. Missing fields are empty.
+pub fn split_gecos(gecos: &str) -> ([String; 4], String) {
+    let mut parts = gecos.splitn(5, ',');
+    let fields = std::array::from_fn(|_| parts.next().unwrap_or("").to_string());
+    (fields, parts.next().unwrap_or("").to_string())
+}
+
+/// Join GECOS fields back into one string, dropping empty trailing fields
+/// so a lone full name is not followed by commas.
+pub fn join_gecos(fields: &[String; 4], other: &str) -> String {
+    let mut joined = fields.join(",");
+    if !other.is_empty() {
+        joined.push(',');
+        joined.push_str(other);
+    }
+    joined.trim_end_matches(',').to_string()
+}
+
+/// Action that creates `new_name` with the supplementary members of `source`.
+///
+/// Users who only have `source` as their primary group are not listed as
+/// members and so are not carried over.
+pub fn clone_group_action(source: &sys::SystemGroup, new_name: &str) -> PendingAction {
+    PendingAction::CloneGroup {
+        source: source.name.clone(),
+        groupname: new_name.to_string(),
+        members: source.members.clone(),
+    }
+}
+
+/// One line per account `action` adds to (`+ alice → devs`) or removes from
+/// (`- bob → devs`) a group; empty for other actions.
+pub fn membership_preview(action: &PendingAction) -> Vec<String> {
+    let (sign, groupname, usernames) = match action {
+        PendingAction::AddMembersToGroup {
+            groupname,
+            usernames,
+        } => ('+', groupname, usernames),
+        PendingAction::RemoveMembersFromGroup {
+            groupname,
+            usernames,
+        } => ('-', groupname, usernames),
+        _ => return Vec::new(),
+    };
+    usernames
+        .iter()
+        .map(|u| format!("{sign} {u} → {groupname}"))
+        .collect()
+}
+
+/// The commands [`PendingAction`] runs, one per line, as shown in confirm
+/// dialogs before anything is changed.
+///
+/// Lines starting with `#` are commands run only if the ones above cannot be.
+///
+/// Passwords are never included; `chpasswd` reads them from stdin.
+pub fn describe_command(action: &PendingAction) -> String {
+    let gpasswd =
+        |flag: &str, user: &str, group: &str| sys::command_line("gpasswd", &[flag, user, group]);
+    let chpasswd = |user: &str| format!("chpasswd  # {user}:<password> on stdin");
+    let lines: Vec<String> = match action {
+        PendingAction::AddUserToGroup {
+            username,
+            groupname,
+        } => vec![gpasswd("-a", username, groupname)],
+        PendingAction::RemoveUserFromGroup {
+            username,
+            groupname,
+        } => vec![gpasswd("-d", username, groupname)],
+        PendingAction::AddUserToGroups {
+            username,
+            groupnames,
+        } => groupnames
+            .iter()
+            .map(|g| gpasswd("-a", username, g))
+            .collect(),
+        PendingAction::RemoveUserFromGroups {
+            username,
+            groupnames,
+        } => std::iter::once(format!(
+            "usermod -G <remaining groups> {}",
+            sys::shell_quote(username)
+        ))
+        .chain(
+            groupnames
+                .iter()
+                .map(|g| format!("# fallback: {}", gpasswd("-d", username, g))),
+        )
+        .collect(),
+        PendingAction::AddMembersToGroup {
+            groupname,
+            usernames,
+        } => usernames
+            .iter()
+            .map(|u| gpasswd("-a", u, groupname))
+            .collect(),
+        PendingAction::RemoveMembersFromGroup {
+            groupname,
+            usernames,
+        } => usernames
+            .iter()
+            .map(|u| gpasswd("-d", u, groupname))
+            .collect(),
+        PendingAction::CreateSudoersDropin { username } => {
+            let dest = sys::sudoers_dropin_path(username);
+            vec![
+                "visudo -cf <temp file>".to_string(),
+                format!(
+                    "install -m 0440 -o root -g root <temp file> {}",
+                    dest.display()
+                ),
+            ]
+        }
+        PendingAction::MoveUserBetweenGroups {
+            username,
+            from_group,
+            to_group,
+        } => vec![
+            gpasswd("-d", username, from_group),
+            gpasswd("-a", username, to_group),
+        ],
+        PendingAction::ChangeShell {
+            username,
+            new_shell,
+        } => vec![sys::command_line(
+            "usermod",
+            &["-s", new_shell.as_str(), username],
+        )],
+        PendingAction::ChangeFullname {
+            username,
+            new_fullname,
+        } => vec![sys::command_line(
+            "usermod",
+            &["-c", new_fullname.as_str(), username],
+        )],
+        PendingAction::ChangeUsername {
+            old_username,
+            new_username,
+        } => vec![sys::command_line(
+            "usermod",
+            &["-l", new_username.as_str(), old_username],
+        )],
+        PendingAction::CreateGroup { groupname } => {
+            vec![sys::command_line("groupadd", &[groupname])]
+        }
+        PendingAction::CreateGroupWithMembers { groupname, members } => {
+            std::iter::once(sys::command_line("groupadd", &[groupname]))
+                .chain(members.iter().map(|u| gpasswd("-a", u, groupname)))
+                .collect()
+        }
+        PendingAction::DeleteGroup { groupname } => {
+            vec![sys::command_line("groupdel", &[groupname])]
+        }
+        PendingAction::ReassignAndDeleteGroup {
+            groupname,
+            fallback,
+            usernames,
+        } => usernames
+            .iter()
+            .map(|u| sys::command_line("usermod", &["-g", fallback, u]))
+            .chain([sys::command_line("groupdel", &[groupname])])
+            .collect(),
+        PendingAction::MergeGroup {
+            source,
+            destination,
+            members,
+            primary_users,
+        } => members
+            .iter()
+            .map(|u| gpasswd("-a", u, destination))
+            .chain(
+                primary_users
+                    .iter()
+                    .map(|u| sys::command_line("usermod", &["-g", destination, u])),
+            )
+            .chain([sys::command_line("groupdel", &[source])])
+            .collect(),
+        PendingAction::RenameGroup { old_name, new_name } => vec![sys::command_line(
+            "groupmod",
+            &["-n", new_name.as_str(), old_name],
+        )],
+        PendingAction::CloneGroup {
+            groupname, members, ..
+        } => std::iter::once(sys::command_line("groupadd", &[groupname]))
+            .chain(members.iter().map(|u| gpasswd("-a", u, groupname)))
+            .collect(),
+        PendingAction::CreateUserWithOptions {
+            username,
+            password,
+            create_home,
+            add_to_wheel,
+        } => {
+            let mut lines = vec![sys::useradd_command_line(username, *create_home)];
+            if password.is_some() {
+                lines.push(chpasswd(username));
+            }
+            if *add_to_wheel {
+                lines.push(gpasswd("-a", username, &sudo_group_name()));
+            }
+            lines
+        }
+        PendingAction::ImportUsers { rows, .. } => rows
+            .iter()
+            .flat_map(|row| {
+                let username = row.username.as_str();
+                let mut lines = vec![sys::useradd_command_line(username, row.create_home)];
+                if !row.full_name.is_empty() {
+                    lines.push(sys::command_line(
+                        "usermod",
+                        &["-c", &row.full_name, username],
+                    ));
+                }
+                if !row.shell.is_empty() {
+                    lines.push(sys::command_line("usermod", &["-s", &row.shell, username]));
+                }
+                lines.extend(row.groups.iter().map(|g| gpasswd("-a", username, g)));
+                lines
+            })
+            .collect(),
+        PendingAction::CloneUser {
+            username,
+            password,
+            create_home,
+            shell,
+            groupnames,
+            ..
+        } => {
+            let mut lines = vec![sys::useradd_command_line(username, *create_home)];
+            if password.is_some() {
+                lines.push(chpasswd(username));
+            }
+            lines.push(sys::command_line("usermod", &["-s", shell, username]));
+            lines.extend(groupnames.iter().map(|g| gpasswd("-a", username, g)));
+            lines
+        }
+        PendingAction::DeleteUser {
+            username,
+            delete_home,
+        } => {
+            let args: &[&str] = if *delete_home {
+                &["-r", username]
+            } else {
+                &[username]
+            };
+            vec![sys::command_line("userdel", args)]
+        }
+        PendingAction::SetPassword {
+            username,
+            must_change,
+            ..
+        } => {
+            let mut lines = vec![chpasswd(username)];
+            if *must_change {
+                lines.push(sys::command_line("chage", &["-d", "0", username]));
+            }
+            lines
+        }
+        PendingAction::ResetPassword { username } => {
+            vec![sys::command_line("chage", &["-d", "0", username])]
+        }
+    };
+    lines.join("\n")
+}
+
+/// Whether this is a `read-only-build`, which hides every action menu.
+pub const READ_ONLY: bool = cfg!(feature = "read-only-build");
+
+/// Terminals narrower than this show only the main table unless `ui.conf`
+/// says otherwise.
+pub const DEFAULT_COMPACT_BELOW: u16 = 100;
+
+/// Terminals narrower than this leave out the keybindings panel, whatever
+/// `show_keybinds` says, so the table and details keep readable widths.
+pub const KEYBINDS_MIN_WIDTH: u16 = 120;
+
+/// How many performed actions the in-app history keeps.
+#[cfg(not(feature = "read-only-build"))]
+pub const ACTION_HISTORY_LEN: usize = 50;
+
+/// One attempt at a [`PendingAction`], as listed in the action history.
+#[derive(Clone, Debug)]
+pub struct ActionRecord {
+    /// The commands the action runs, as [`describe_command`] gives them.
+    pub description: String,
+    /// `Err` holds the error message when the action failed.
+    pub outcome: Result<(), String>,
+    pub at: Instant,
+}
+
+/// Answer to a read started on a worker thread, so that a slow `sudo` cannot
+/// freeze the UI; see [`AppState::lookup`].
+#[derive(Debug)]
+pub enum Lookup {
+    /// The user's sudoers drop-in, for [`ModalState::SudoersDropin`].
+    SudoersDropin {
+        username: String,
+        result: std::result::Result<Option<String>, String>,
+    },
+    /// Bytes under `home`, for [`ModalState::DeleteConfirm`].
+    HomeSize { home: String, size: Option<u64> },
+}
+
+/// What the commands of a [`PendingAction`] did, for the main thread to
+/// bring the lists up to date with. Never read in a `read-only-build`.
+#[derive(Debug)]
+#[cfg_attr(feature = "read-only-build", allow(dead_code))]
+pub struct ActionDone {
+    /// Shown in the Info dialog once the action finishes.
+    pub message: String,
+    /// Memberships the commands changed, as (user, group, added).
+    pub memberships: Vec<(String, String, bool)>,
+}
+
+/// A [`PendingAction`] whose commands are running on a worker thread.
+#[derive(Debug)]
+pub struct ActionInFlight {
+    pub action: PendingAction,
+    /// Receives the outcome once the commands have finished.
+    #[cfg_attr(feature = "read-only-build", allow(dead_code))]
+    pub result: std::sync::mpsc::Receiver<crate::error::Result<ActionDone>>,
+    /// When the commands started, to animate the status bar spinner.
+    pub started: Instant,
+}
+
+/// How long a row stays highlighted after an action changed it.
+pub const RECENT_CHANGE_HIGHLIGHT: Duration = Duration::from_secs(2);
+
+/// Whether a change made `elapsed` ago is still within the highlight window.
+pub fn change_highlight_active(elapsed: Duration) -> bool {
+    elapsed < RECENT_CHANGE_HIGHLIGHT
+}
+
+/// Number of entries in the filter menu shown for `tab`.
+pub fn filter_menu_len(tab: ActiveTab) -> usize {
+    match tab {
+        ActiveTab::Users => 9,
+        ActiveTab::Groups => 8,
+    }
+}
+
+/// Last cursor position in the filter menu, remembered separately per tab.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FilterMenuSelection {
+    pub users: usize,
+    pub groups: usize,
+}
+
+impl FilterMenuSelection {
+    /// Remembered index for `tab`.
+    pub fn get(&self, tab: ActiveTab) -> usize {
+        match tab {
+            ActiveTab::Users => self.users,
+            ActiveTab::Groups => self.groups,
+        }
+    }
+
+    /// Remember `selected` as the index for `tab`.
+    pub fn set(&mut self, tab: ActiveTab, selected: usize) {
+        match tab {
+            ActiveTab::Users => self.users = selected,
+            ActiveTab::Groups => self.groups = selected,
+        }
+    }
 }
 
 /// Filter types for narrowing the users list.
@@ -408,10 +1960,14 @@ pub enum GroupsFilter {
 
 #[derive(Clone, Debug)]
 pub enum ActionsContext {
+    #[cfg_attr(feature = "read-only-build", allow(dead_code))]
     GroupMemberRemoval { group_name: String },
 }
 
 /// Actions that require privileged changes, executed via `sys::SystemAdapter`.
+///
+/// Fields read only when the commands run are allowed to go unused in a
+/// `read-only-build`.
 #[derive(Clone, Debug)]
 pub enum PendingAction {
     AddUserToGroup {
@@ -438,6 +1994,16 @@ pub enum PendingAction {
         groupname: String,
         usernames: Vec<String>,
     },
+    CreateSudoersDropin {
+        username: String,
+    },
+    /// Remove from `from_group`, then add to `to_group`. If the add fails the
+    /// removal is not rolled back; the outcome message says so.
+    MoveUserBetweenGroups {
+        username: String,
+        from_group: String,
+        to_group: String,
+    },
     ChangeShell {
         username: String,
         new_shell: String,
@@ -453,13 +2019,41 @@ pub enum PendingAction {
     CreateGroup {
         groupname: String,
     },
+    /// Create `groupname`, then add `members` to it. Members that cannot be
+    /// added are reported, not rolled back.
+    CreateGroupWithMembers {
+        groupname: String,
+        members: Vec<String>,
+    },
     DeleteGroup {
         groupname: String,
     },
+    /// Move `usernames` to the primary group `fallback`, then delete `groupname`.
+    ReassignAndDeleteGroup {
+        groupname: String,
+        fallback: String,
+        usernames: Vec<String>,
+    },
     RenameGroup {
         old_name: String,
         new_name: String,
     },
+    /// Add `members` of `source` to `destination`, move the users whose
+    /// primary group is `source` there too, then delete `source`.
+    MergeGroup {
+        source: String,
+        destination: String,
+        members: Vec<String>,
+        primary_users: Vec<String>,
+    },
+    /// Create `groupname`, then add `members` (copied from `source`) to it.
+    /// Members that cannot be added are reported, not rolled back.
+    CloneGroup {
+        #[cfg_attr(feature = "read-only-build", allow(dead_code))]
+        source: String,
+        groupname: String,
+        members: Vec<String>,
+    },
 
     CreateUserWithOptions {
         username: String,
@@ -467,12 +2061,32 @@ pub enum PendingAction {
         create_home: bool,
         add_to_wheel: bool,
     },
+    /// Create the accounts of a CSV import one after another; see
+    /// [`import::parse_csv`]. `skipped` notes the lines that were left out.
+    ImportUsers {
+        rows: Vec<import::ImportRow>,
+        #[cfg_attr(feature = "read-only-build", allow(dead_code))]
+        skipped: Vec<String>,
+    },
+    /// Create `username`, then give it the shell and supplementary groups
+    /// copied from `template`. Steps after the create are reported, not
+    /// rolled back.
+    CloneUser {
+        #[cfg_attr(feature = "read-only-build", allow(dead_code))]
+        template: String,
+        username: String,
+        password: Option<String>,
+        create_home: bool,
+        shell: String,
+        groupnames: Vec<String>,
+    },
     DeleteUser {
         username: String,
         delete_home: bool,
     },
     SetPassword {
         username: String,
+        #[cfg_attr(feature = "read-only-build", allow(dead_code))]
         password: String,
         must_change: bool,
     },
@@ -495,17 +2109,101 @@ pub struct AppState {
     pub _table_state: TableState,
     pub input_mode: InputMode,
     pub search_query: String,
+    /// Cursor position in `search_query`, in characters.
+    pub search_cursor: usize,
+    /// Colors drawn with: `configured_theme` fitted to the terminal.
     pub theme: Theme,
+    /// Theme as loaded from `theme.conf`, still in RGB on terminals without
+    /// truecolor, so saving it writes back what the user configured.
+    pub configured_theme: Theme,
     pub keymap: keymap::Keymap,
-    pub modal: Option<ModalState>,
+    /// Open modals, innermost last; only the topmost one is shown and receives keys.
+    pub modal_stack: Vec<ModalState>,
     pub users_focus: UsersFocus,
     pub groups_focus: GroupsFocus,
     pub sudo_password: Option<String>,
     pub users_filter: Option<UsersFilter>,
     pub groups_filter: Option<GroupsFilter>,
     pub users_filter_chips: UsersFilterChips,
+    pub groups_filter_chips: GroupsFilterChips,
+    pub filter_menu_selected: FilterMenuSelection,
     pub actions_context: Option<ActionsContext>,
     pub show_keybinds: bool,
+    /// Show group members alphabetically (the default) instead of in
+    /// `/etc/group` order.
+    pub sort_members: bool,
+    /// Widths of the body columns, adjustable from the keyboard and saved in `ui.conf`.
+    pub pane_split: PaneSplit,
+    /// Base UIDs and GIDs are shown in; saved in `ui.conf`.
+    pub id_radix: IdRadix,
+    /// Fill the details panes with fields that read `/proc`, `/etc/shadow` or
+    /// the home directory; off by default since they are read on every frame.
+    pub live_stats: bool,
+    /// One-off hint shown in the footer while a modal is open; cleared on the next key.
+    pub modal_notice: Option<String>,
+    /// Row touched by the last successful action, highlighted for a short while.
+    pub last_changed: Option<(ActiveTab, String, Instant)>,
+    /// Modification times of [`ACCOUNT_FILES`] when the lists were last read.
+    pub accounts_mtime: [Option<std::time::SystemTime>; 2],
+    /// Whether [`ACCOUNT_FILES`] changed on disk since then, so the lists may
+    /// be stale until reloaded.
+    pub accounts_stale: bool,
+    /// Header label when the process already runs as root; sudo prompts are skipped then.
+    pub running_as_root: Option<String>,
+    /// Statistics last shown in the Group Details pane; see [`selected_group_details`].
+    pub group_details_cache: Option<GroupDetailsCache>,
+    /// Login shells from `/etc/shells`; see [`known_shells`]. Cleared on reload.
+    pub shells_cache: Option<Vec<String>>,
+    /// `id -Gn` groups of one user, `None` when `id` failed; see
+    /// [`effective_groups`]. Cleared on reload.
+    pub effective_groups_cache: Option<(String, Option<Vec<String>>)>,
+    /// Users with a locked password in `/etc/shadow`; see [`locked_users`].
+    /// Cleared on reload and after every action.
+    pub locked_users_cache: Option<std::collections::HashSet<String>>,
+    /// Whether each home directory seen in the users table exists, so the
+    /// DIR column stats each one once. Cleared on reload and after every
+    /// action.
+    pub home_exists_cache: std::collections::HashMap<String, bool>,
+    /// Where the Member of pane takes its groups from.
+    pub membership_source: MembershipSource,
+    /// Groups flagged with the privilege they confer, from `privileges.conf`.
+    pub privileged_groups: privconf::PrivilegedGroups,
+    /// What Enter does in a multi-select picker with nothing checked.
+    pub empty_picker_enter: EmptyPickerEnter,
+    /// Also list users whose primary group is the selected group under Members.
+    pub members_include_primary: bool,
+    /// Shortest password the forms accept; 0 accepts any length.
+    pub min_password_length: usize,
+    /// Columns of the users table, in display order.
+    pub user_columns: Vec<UserColumn>,
+    /// Name and UID of the user whose Member of entry was used to jump to the
+    /// Groups tab; the jump key returns to them.
+    pub group_jump_origin: Option<(String, u32)>,
+    /// Terminal width in columns below which only the main table is shown;
+    /// 0 always keeps the details column. From `ui.conf`.
+    pub compact_below: u16,
+    /// Layout picked with `ToggleLayout`; see [`AppState::is_compact`].
+    pub layout_mode: LayoutMode,
+    /// Amount of user details shown, picked with `ToggleDetailVerbosity`.
+    pub detail_verbosity: DetailVerbosity,
+    /// Confirmation each kind of destructive action asks for, from
+    /// `confirmations.conf`.
+    pub confirmations: confirmconf::Confirmations,
+    /// The most recent performed actions, oldest first; at most
+    /// [`ACTION_HISTORY_LEN`] are kept.
+    pub action_history: VecDeque<ActionRecord>,
+    /// Privileged action waiting for the event loop to start it.
+    pub queued_action: Option<PendingAction>,
+    /// The action running on a worker thread, if any. Only one runs at a
+    /// time; keys are ignored until it finishes.
+    pub pending_result: Option<ActionInFlight>,
+    /// Receives the answer to the read a modal is waiting for, if any.
+    pub lookup: Option<std::sync::mpsc::Receiver<Lookup>>,
+    /// Where actions and reloads get their backend from.
+    pub backend: sys::BackendFactory,
+    /// Where attempted actions are recorded; `None` keeps no audit trail.
+    #[cfg(not(feature = "read-only-build"))]
+    pub audit_log: Option<crate::audit::AuditLog>,
 }
 
 impl AppState {
@@ -513,9 +2211,10 @@ impl AppState {
     pub fn new() -> Self {
         let adapter = crate::sys::SystemAdapter::new();
         let mut users_all = adapter.list_users().unwrap_or_default();
-        users_all.sort_by_key(|u| u.uid);
+        sys::sort_users(&mut users_all);
         let mut groups_all = adapter.list_groups().unwrap_or_default();
-        groups_all.sort_by_key(|g| g.gid);
+        sys::sort_groups(&mut groups_all);
+        let configured_theme = Theme::load_or_init(&startup_config_path("theme.conf"));
         let mut app = Self {
             started_at: Instant::now(),
             users: users_all.clone(),
@@ -530,39 +2229,181 @@ impl AppState {
             _table_state: TableState::default(),
             input_mode: InputMode::Normal,
             search_query: String::new(),
-            theme: Theme::load_or_init(
-                &config_file_read_path("theme.conf")
-                    .unwrap_or_else(|| config_file_write_path("theme.conf")),
-            ),
-            keymap: keymap::Keymap::load_or_init(
-                &config_file_read_path("keybinds.conf")
-                    .unwrap_or_else(|| config_file_write_path("keybinds.conf")),
-            ),
-            modal: None,
+            search_cursor: 0,
+            theme: configured_theme.downgraded(termcolor::ColorCaps::detect()),
+            configured_theme,
+            keymap: keymap::Keymap::load_or_init(&startup_config_path("keybinds.conf")),
+            modal_stack: Vec::new(),
             users_focus: UsersFocus::UsersList,
             groups_focus: GroupsFocus::GroupsList,
             sudo_password: None,
             users_filter: None,
             groups_filter: None,
             users_filter_chips: UsersFilterChips::default(),
+            groups_filter_chips: GroupsFilterChips::default(),
+            filter_menu_selected: FilterMenuSelection::default(),
             actions_context: None,
             show_keybinds: true,
+            sort_members: true,
+            pane_split: PaneSplit::default(),
+            id_radix: IdRadix::Decimal,
+            live_stats: false,
+            modal_notice: None,
+            last_changed: None,
+            accounts_mtime: account_files_mtime(),
+            accounts_stale: false,
+            running_as_root: sys::root_indicator(
+                sys::is_root(),
+                std::env::var("SUDO_USER").ok().as_deref(),
+            ),
+            group_details_cache: None,
+            shells_cache: None,
+            effective_groups_cache: None,
+            locked_users_cache: None,
+            home_exists_cache: std::collections::HashMap::new(),
+            membership_source: MembershipSource::Files,
+            privileged_groups: privconf::PrivilegedGroups::load_or_init(&startup_config_path(
+                "privileges.conf",
+            )),
+            empty_picker_enter: EmptyPickerEnter::Cursor,
+            members_include_primary: false,
+            min_password_length: 0,
+            user_columns: UserColumn::ALL.to_vec(),
+            group_jump_origin: None,
+            compact_below: DEFAULT_COMPACT_BELOW,
+            layout_mode: LayoutMode::Expanded,
+            detail_verbosity: DetailVerbosity::Full,
+            confirmations: confirmconf::Confirmations::load_or_init(&startup_config_path(
+                "confirmations.conf",
+            )),
+            action_history: VecDeque::new(),
+            queued_action: None,
+            pending_result: None,
+            lookup: None,
+            backend: sys::system_backend(),
+            #[cfg(not(feature = "read-only-build"))]
+            audit_log: Some(crate::audit::AuditLog::default_location()),
         };
 
         // Load and apply filter configuration from filter.conf (creates default if missing/empty)
-        let filters_cfg = filterconf::FiltersConfig::load_or_init(
-            &config_file_read_path("filter.conf")
-                .unwrap_or_else(|| config_file_write_path("filter.conf")),
-        );
+        let filters_cfg =
+            filterconf::FiltersConfig::load_or_init(&startup_config_path("filter.conf"));
         filters_cfg.apply_to(&mut app);
+        uiconf::UiConfig::load_or_init(&startup_config_path("ui.conf")).apply_to(&mut app);
+        app.apply_start_view(
+            std::env::var("UGM_DEFAULT_TAB").ok().as_deref(),
+            std::env::var("UGM_DEFAULT_FOCUS").ok().as_deref(),
+        );
 
         // Apply the loaded filters to seed the initial views
         crate::search::apply_filters_and_search(&mut app);
 
+        let duplicates = sys::duplicate_user_names(&app.users_all);
+        if !duplicates.is_empty() {
+            let names = duplicates.join(", ");
+            crate::logging::warn(format_args!("duplicate user names in passwd: {names}"));
+            app.open_modal(ModalState::Info {
+                message: format!(
+                    "Several passwd entries share the name {names}. \
+                     Changes by name affect the first entry only."
+                ),
+            });
+            app.input_mode = InputMode::Modal;
+        }
+
         app
     }
 }
 
+impl AppState {
+    /// Apply the `UGM_DEFAULT_TAB` (`users|groups`) and `UGM_DEFAULT_FOCUS`
+    /// (`list|members`) values, which take precedence over `ui.conf`.
+    /// Unset or unknown values leave the view as it is.
+    pub fn apply_start_view(&mut self, tab: Option<&str>, focus: Option<&str>) {
+        match tab.map(|t| t.trim().to_ascii_lowercase()).as_deref() {
+            Some("users") => self.active_tab = ActiveTab::Users,
+            Some("groups") => self.active_tab = ActiveTab::Groups,
+            _ => {}
+        }
+        match focus.map(|f| f.trim().to_ascii_lowercase()).as_deref() {
+            Some("list") => {
+                self.users_focus = UsersFocus::UsersList;
+                self.groups_focus = GroupsFocus::GroupsList;
+            }
+            Some("members") => {
+                self.users_focus = UsersFocus::MemberOf;
+                self.groups_focus = GroupsFocus::Members;
+            }
+            _ => {}
+        }
+    }
+
+    /// The modal currently shown, i.e. the top of the modal stack.
+    pub fn modal(&self) -> Option<&ModalState> {
+        self.modal_stack.last()
+    }
+
+    /// Open a top-level modal, discarding any modals that were open.
+    pub fn open_modal(&mut self, modal: ModalState) {
+        self.modal_stack.clear();
+        self.modal_stack.push(modal);
+    }
+
+    /// Open a child modal on top of the current one.
+    pub fn push_modal(&mut self, modal: ModalState) {
+        self.modal_stack.push(modal);
+    }
+
+    /// Swap the topmost modal for `modal`, keeping its parents.
+    pub fn replace_modal(&mut self, modal: ModalState) {
+        self.modal_stack.pop();
+        self.modal_stack.push(modal);
+    }
+
+    /// Close the topmost modal; returns whether a parent modal is still open.
+    pub fn pop_modal(&mut self) -> bool {
+        self.modal_stack.pop();
+        !self.modal_stack.is_empty()
+    }
+
+    /// Whether the keybindings panel fits next to the table and details on a
+    /// terminal `width` columns wide, if it is turned on.
+    pub fn shows_keybinds(&self, width: u16) -> bool {
+        self.show_keybinds && width >= KEYBINDS_MIN_WIDTH
+    }
+
+    /// Whether a terminal `width` columns wide gets the compact layout.
+    pub fn is_compact(&self, width: u16) -> bool {
+        self.layout_mode == LayoutMode::Compact || width < self.compact_below
+    }
+
+    /// Add an attempt to run the commands `description` to the action
+    /// history, dropping the oldest entry when it is full.
+    #[cfg(not(feature = "read-only-build"))]
+    pub fn record_action(&mut self, description: String, outcome: Result<(), String>) {
+        if self.action_history.len() == ACTION_HISTORY_LEN {
+            self.action_history.pop_front();
+        }
+        self.action_history.push_back(ActionRecord {
+            description,
+            outcome,
+            at: Instant::now(),
+        });
+    }
+
+    /// Record that an action just changed the `name` row on `tab`.
+    #[cfg(not(feature = "read-only-build"))]
+    pub fn mark_changed(&mut self, tab: ActiveTab, name: impl Into<String>) {
+        self.last_changed = Some((tab, name.into(), Instant::now()));
+    }
+
+    /// Whether the `name` row on `tab` changed recently enough to be highlighted.
+    pub fn is_recently_changed(&self, tab: ActiveTab, name: &str) -> bool {
+        matches!(&self.last_changed, Some((t, n, at))
+            if *t == tab && n == name && change_highlight_active(at.elapsed()))
+    }
+}
+
 /// Candidate roots in priority order for config files.
 fn config_roots() -> Vec<PathBuf> {
     let mut roots: Vec<PathBuf> = Vec::new();
@@ -599,6 +2440,22 @@ pub fn config_file_read_path(name: &str) -> Option<String> {
     None
 }
 
+/// Path config file `name` is loaded from at startup: the first existing copy,
+/// or else the location its defaults are written to.
+fn startup_config_path(name: &str) -> String {
+    match config_file_read_path(name) {
+        Some(path) => {
+            crate::logging::info(format_args!("loading {name} from {path}"));
+            path
+        }
+        None => {
+            let path = config_file_write_path(name);
+            crate::logging::info(format_args!("no {name} found, using defaults at {path}"));
+            path
+        }
+    }
+}
+
 /// Resolve a path for writing a config file; ensures the directory exists.
 pub fn config_file_write_path(name: &str) -> String {
     if let Some(root) = config_roots().into_iter().next() {
@@ -619,6 +2476,26 @@ impl Default for AppState {
 /// Re-export the application event loop entry function.
 pub use update::run_app as run;
 
+/// Files the users and groups lists are read from.
+pub const ACCOUNT_FILES: [&str; 2] = ["/etc/passwd", "/etc/group"];
+
+/// Current modification times of [`ACCOUNT_FILES`], `None` where unreadable.
+pub fn account_files_mtime() -> [Option<std::time::SystemTime>; 2] {
+    ACCOUNT_FILES.map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+}
+
+/// Whether any of the `current` modification times is newer than the one in
+/// `loaded`, i.e. an account file was written since it was read.
+pub fn account_files_advanced(
+    loaded: &[Option<std::time::SystemTime>; 2],
+    current: &[Option<std::time::SystemTime>; 2],
+) -> bool {
+    loaded
+        .iter()
+        .zip(current)
+        .any(|(before, now)| now.is_some() && (before.is_none() || now > before))
+}
+
 /// Resolve the sudo group name from environment, defaulting to "wheel".
 pub fn sudo_group_name() -> String {
     std::env::var("UGM_SUDO_GROUP").unwrap_or_else(|_| "wheel".to_string())
@@ -0,0 +1,88 @@
+//! Privileged groups: parse/write `privileges.conf`.
+//!
+//! Membership in some groups grants much more than shared file access, e.g.
+//! `docker` is root-equivalent and `adm` can read the system logs. This file
+//! maps such group names to a short description of what they confer, shown as
+//! the group's privilege in the details pane.
+
+use std::collections::BTreeMap;
+
+/// Groups known to confer privileges, with the privilege each one grants.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrivilegedGroups {
+    groups: BTreeMap<String, String>,
+}
+
+impl Default for PrivilegedGroups {
+    fn default() -> Self {
+        let groups = [
+            ("wheel", "sudo (administrator)"),
+            ("sudo", "sudo (administrator)"),
+            ("admin", "sudo (administrator)"),
+            ("docker", "root-equivalent (Docker daemon)"),
+            ("lxd", "root-equivalent (LXD)"),
+            ("libvirt", "manage virtual machines"),
+            ("disk", "raw access to block devices"),
+            ("adm", "read system logs"),
+            ("systemd-journal", "read the systemd journal"),
+            ("shadow", "read password hashes"),
+        ]
+        .into_iter()
+        .map(|(name, what)| (name.to_string(), what.to_string()))
+        .collect();
+        Self { groups }
+    }
+}
+
+impl PrivilegedGroups {
+    /// What membership in `group` confers, if it is a privileged group.
+    pub fn label(&self, group: &str) -> Option<&str> {
+        self.groups.get(group).map(String::as_str)
+    }
+
+    /// Load the set from `path`, or write and return the defaults if it is missing.
+    pub fn load_or_init(path: &str) -> Self {
+        if let Some(cfg) = Self::from_file(path) {
+            return cfg;
+        }
+        let cfg = Self::default();
+        let _ = cfg.write_file(path);
+        cfg
+    }
+
+    /// Load the set from a `<group> = <privilege>` file.
+    ///
+    /// The file replaces the defaults entirely, so removing a line stops that
+    /// group from being flagged. Comments and lines without a group name are
+    /// ignored; an empty description falls back to "privileged".
+    pub fn from_file(path: &str) -> Option<Self> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        let mut groups = BTreeMap::new();
+        for raw in contents.lines() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, what) = line.split_once('=').unwrap_or((line, ""));
+            let (name, what) = (name.trim(), what.trim());
+            if name.is_empty() {
+                continue;
+            }
+            let what = if what.is_empty() { "privileged" } else { what };
+            groups.insert(name.to_string(), what.to_string());
+        }
+        Some(Self { groups })
+    }
+
+    /// Write the set to `path`.
+    pub fn write_file(&self, path: &str) -> std::io::Result<()> {
+        let mut buf = String::from(
+            "# usrgrp-manager privileged groups\n\
+             # <group> = <what membership grants>; groups not listed show no privilege\n",
+        );
+        for (name, what) in &self.groups {
+            buf.push_str(&format!("{name} = {what}\n"));
+        }
+        std::fs::write(path, buf)
+    }
+}
@@ -0,0 +1,181 @@
+//! Terminal color support: detect it and fit theme colors to it.
+//!
+//! Themes are written in 24-bit RGB. Terminals without truecolor either drop
+//! those colors or pick odd approximations, so on such terminals the theme is
+//! mapped to the nearest color in the xterm 256-color or 16-color palette.
+
+use ratatui::style::Color;
+
+/// How many colors the terminal can display.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColorCaps {
+    /// 24-bit RGB.
+    TrueColor,
+    /// The xterm 256-color palette.
+    Ansi256,
+    /// Only the 16 base colors.
+    Ansi16,
+}
+
+impl ColorCaps {
+    /// Capabilities advertised by the `COLORTERM` and `TERM` environment variables.
+    pub fn detect() -> Self {
+        Self::from_env_values(
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    /// Capabilities for the given `COLORTERM` and `TERM` values.
+    ///
+    /// `COLORTERM=truecolor` (or `24bit`) means full RGB. Otherwise a `TERM`
+    /// naming 256 colors gets that palette, consoles like `linux` and `vt100`
+    /// get 16 colors, and any other terminal is assumed to manage 256.
+    pub fn from_env_values(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        let colorterm = colorterm.unwrap_or("").to_ascii_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorCaps::TrueColor;
+        }
+        let term = term.unwrap_or("").to_ascii_lowercase();
+        if term.contains("256") {
+            ColorCaps::Ansi256
+        } else if term.is_empty()
+            || term == "dumb"
+            || term == "linux"
+            || term == "ansi"
+            || term.starts_with("vt")
+        {
+            ColorCaps::Ansi16
+        } else {
+            ColorCaps::Ansi256
+        }
+    }
+}
+
+/// The 16 base colors with their usual xterm RGB values.
+const BASE16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Channel values of the 6x6x6 color cube at indices 16-231.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+/// RGB value of a 256-color palette index.
+fn indexed_rgb(i: u8) -> (u8, u8, u8) {
+    match i {
+        0..=15 => BASE16[i as usize].1,
+        16..=231 => {
+            let i = i - 16;
+            (
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[(i / 6 % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let v = 8 + 10 * (i - 232);
+            (v, v, v)
+        }
+    }
+}
+
+/// Nearest 256-color index, choosing between the color cube and the gray ramp.
+fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |v: u8| match v {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (v - 35) / 40,
+    };
+    let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = 16 + 36 * r + 6 * g + b;
+    let avg = ((rgb.0 as u16 + rgb.1 as u16 + rgb.2 as u16) / 3) as u8;
+    let gray = 232 + (avg.saturating_sub(3) / 10).min(23);
+    if distance(rgb, indexed_rgb(gray)) < distance(rgb, indexed_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+/// Nearest of the 16 base colors.
+fn nearest_16(rgb: (u8, u8, u8)) -> Color {
+    BASE16
+        .iter()
+        .min_by_key(|(_, base)| distance(rgb, *base))
+        .map(|(c, _)| *c)
+        .unwrap_or(Color::Reset)
+}
+
+/// Map `c` to a color the terminal can show.
+///
+/// Named colors and `Reset` are left alone; RGB colors (and, for 16-color
+/// terminals, palette indices) become the nearest available color.
+pub fn downgrade_color(c: Color, caps: ColorCaps) -> Color {
+    match (c, caps) {
+        (_, ColorCaps::TrueColor) => c,
+        (Color::Rgb(r, g, b), ColorCaps::Ansi256) => Color::Indexed(nearest_256((r, g, b))),
+        (Color::Rgb(r, g, b), ColorCaps::Ansi16) => nearest_16((r, g, b)),
+        (Color::Indexed(i), ColorCaps::Ansi16) => nearest_16(indexed_rgb(i)),
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgb_maps_to_nearest_256_color() {
+        let to_256 = |r, g, b| downgrade_color(Color::Rgb(r, g, b), ColorCaps::Ansi256);
+        // Exact cube and gray-ramp entries
+        assert_eq!(to_256(0xff, 0x00, 0x00), Color::Indexed(196));
+        assert_eq!(to_256(0x5f, 0x87, 0xaf), Color::Indexed(67));
+        assert_eq!(to_256(0x80, 0x80, 0x80), Color::Indexed(244));
+        // Catppuccin Mocha text and surface1 colors
+        assert_eq!(to_256(0xcd, 0xd6, 0xf4), Color::Indexed(189));
+        assert_eq!(to_256(0x45, 0x47, 0x5a), Color::Indexed(239));
+        // Nothing changes on a truecolor terminal
+        assert_eq!(
+            downgrade_color(Color::Rgb(1, 2, 3), ColorCaps::TrueColor),
+            Color::Rgb(1, 2, 3)
+        );
+    }
+
+    #[test]
+    fn rgb_and_indexed_map_to_base_colors_on_16_color_terminals() {
+        let to_16 = |c| downgrade_color(c, ColorCaps::Ansi16);
+        assert_eq!(to_16(Color::Rgb(0xf9, 0xe2, 0xaf)), Color::Gray);
+        assert_eq!(to_16(Color::Rgb(0xf0, 0x10, 0x10)), Color::LightRed);
+        assert_eq!(to_16(Color::Indexed(21)), Color::Blue);
+        assert_eq!(to_16(Color::Cyan), Color::Cyan);
+    }
+
+    #[test]
+    fn caps_follow_colorterm_then_term() {
+        let caps = ColorCaps::from_env_values;
+        assert_eq!(caps(Some("truecolor"), Some("xterm")), ColorCaps::TrueColor);
+        assert_eq!(caps(None, Some("xterm-256color")), ColorCaps::Ansi256);
+        assert_eq!(caps(None, Some("linux")), ColorCaps::Ansi16);
+        assert_eq!(caps(None, None), ColorCaps::Ansi16);
+    }
+}
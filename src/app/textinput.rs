@@ -0,0 +1,233 @@
+//! Cursor-aware editing for single-line text inputs.
+//!
+//! Input modals and the search prompt keep a `String` buffer plus a cursor
+//! measured in characters (not bytes). [`edit`] applies one key press to such
+//! a pair: insertion and deletion happen at the cursor, Left/Right/Home/End
+//! move it, Ctrl+W deletes the previous word and Ctrl+U clears the line.
+//! [`edit_identifier`] does the same for user and group names, which never
+//! contain whitespace or control characters.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Byte offset of the character at `cursor`, or the buffer length past the end.
+fn byte_offset(buf: &str, cursor: usize) -> usize {
+    buf.char_indices()
+        .nth(cursor)
+        .map(|(i, _)| i)
+        .unwrap_or(buf.len())
+}
+
+/// Insert `c` at the cursor and advance past it.
+pub fn insert_char(buf: &mut String, cursor: &mut usize, c: char) {
+    *cursor = (*cursor).min(buf.chars().count());
+    buf.insert(byte_offset(buf, *cursor), c);
+    *cursor += 1;
+}
+
+/// Insert `s` at the cursor and advance past it.
+pub fn insert_str(buf: &mut String, cursor: &mut usize, s: &str) {
+    *cursor = (*cursor).min(buf.chars().count());
+    buf.insert_str(byte_offset(buf, *cursor), s);
+    *cursor += s.chars().count();
+}
+
+/// Reduce pasted text to what a single-line field accepts: the first line,
+/// with control characters (tabs, escapes, ...) removed.
+pub fn single_line(text: &str) -> String {
+    text.split(['\n', '\r'])
+        .next()
+        .unwrap_or("")
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect()
+}
+
+/// Whether `c` may appear in an identifier field (user or group name, shell
+/// path): anything but whitespace and control characters.
+pub fn identifier_char(c: char) -> bool {
+    !c.is_whitespace() && !c.is_control()
+}
+
+/// Reduce pasted text to what an identifier field accepts: the first line,
+/// with whitespace and control characters removed.
+pub fn identifier(text: &str) -> String {
+    single_line(text)
+        .chars()
+        .filter(|c| identifier_char(*c))
+        .collect()
+}
+
+/// Delete the character before the cursor.
+pub fn backspace(buf: &mut String, cursor: &mut usize) {
+    *cursor = (*cursor).min(buf.chars().count());
+    if *cursor == 0 {
+        return;
+    }
+    *cursor -= 1;
+    buf.remove(byte_offset(buf, *cursor));
+}
+
+/// Delete the character under the cursor.
+pub fn delete(buf: &mut String, cursor: &mut usize) {
+    if *cursor < buf.chars().count() {
+        buf.remove(byte_offset(buf, *cursor));
+    }
+}
+
+/// Delete the word before the cursor along with any whitespace after it.
+pub fn delete_prev_word(buf: &mut String, cursor: &mut usize) {
+    let chars: Vec<char> = buf.chars().collect();
+    let end = (*cursor).min(chars.len());
+    let mut start = end;
+    while start > 0 && chars[start - 1].is_whitespace() {
+        start -= 1;
+    }
+    while start > 0 && !chars[start - 1].is_whitespace() {
+        start -= 1;
+    }
+    buf.replace_range(byte_offset(buf, start)..byte_offset(buf, end), "");
+    *cursor = start;
+}
+
+/// Remove the whole buffer.
+pub fn clear(buf: &mut String, cursor: &mut usize) {
+    buf.clear();
+    *cursor = 0;
+}
+
+/// Apply `key` to `buf` at `cursor`. Returns `false` for keys that are not
+/// editing keys so callers can handle them (Enter, Esc, Up/Down, ...).
+pub fn edit(buf: &mut String, cursor: &mut usize, key: KeyEvent) -> bool {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Char('w') if ctrl => delete_prev_word(buf, cursor),
+        KeyCode::Char('u') if ctrl => clear(buf, cursor),
+        KeyCode::Char(_) if ctrl => return false,
+        KeyCode::Char(c) => insert_char(buf, cursor, c),
+        KeyCode::Backspace => backspace(buf, cursor),
+        KeyCode::Delete => delete(buf, cursor),
+        KeyCode::Left => *cursor = cursor.saturating_sub(1),
+        KeyCode::Right => *cursor = (*cursor + 1).min(buf.chars().count()),
+        KeyCode::Home => *cursor = 0,
+        KeyCode::End => *cursor = buf.chars().count(),
+        _ => return false,
+    }
+    true
+}
+
+/// [`edit`] for identifier fields: characters [`identifier_char`] rejects
+/// are swallowed instead of inserted.
+pub fn edit_identifier(buf: &mut String, cursor: &mut usize, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char(c)
+            if !key.modifiers.contains(KeyModifiers::CONTROL) && !identifier_char(c) =>
+        {
+            true
+        }
+        _ => edit(buf, cursor, key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    #[test]
+    fn inserts_and_deletes_mid_string() {
+        let mut buf = "helo".to_string();
+        let mut cursor = 4;
+        edit(&mut buf, &mut cursor, key(KeyCode::Left));
+        edit(&mut buf, &mut cursor, key(KeyCode::Char('l')));
+        assert_eq!((buf.as_str(), cursor), ("hello", 4));
+
+        edit(&mut buf, &mut cursor, key(KeyCode::Home));
+        edit(&mut buf, &mut cursor, key(KeyCode::Delete));
+        assert_eq!((buf.as_str(), cursor), ("ello", 0));
+        // Backspace at the start is a no-op
+        edit(&mut buf, &mut cursor, key(KeyCode::Backspace));
+        assert_eq!((buf.as_str(), cursor), ("ello", 0));
+
+        edit(&mut buf, &mut cursor, key(KeyCode::End));
+        edit(&mut buf, &mut cursor, key(KeyCode::Right));
+        assert_eq!(cursor, 4);
+        edit(&mut buf, &mut cursor, key(KeyCode::Backspace));
+        assert_eq!((buf.as_str(), cursor), ("ell", 3));
+    }
+
+    #[test]
+    fn cursor_counts_characters_not_bytes() {
+        let mut buf = "jürgen".to_string();
+        let mut cursor = 2;
+        edit(&mut buf, &mut cursor, key(KeyCode::Backspace));
+        assert_eq!((buf.as_str(), cursor), ("jrgen", 1));
+        edit(&mut buf, &mut cursor, key(KeyCode::Char('ö')));
+        assert_eq!((buf.as_str(), cursor), ("jörgen", 2));
+    }
+
+    #[test]
+    fn ctrl_w_deletes_previous_word_and_trailing_spaces() {
+        let mut buf = "Jane  Q Doe".to_string();
+        let mut cursor = buf.chars().count();
+        edit(&mut buf, &mut cursor, ctrl('w'));
+        assert_eq!((buf.as_str(), cursor), ("Jane  Q ", 8));
+        edit(&mut buf, &mut cursor, ctrl('w'));
+        assert_eq!((buf.as_str(), cursor), ("Jane  ", 6));
+
+        // Mid-string: only the word left of the cursor goes
+        let mut buf = "one two three".to_string();
+        let mut cursor = 7;
+        edit(&mut buf, &mut cursor, ctrl('w'));
+        assert_eq!((buf.as_str(), cursor), ("one  three", 4));
+    }
+
+    #[test]
+    fn multi_line_paste_keeps_only_the_first_line() {
+        assert_eq!(single_line("alice\nbob\n"), "alice");
+        assert_eq!(single_line("a\tb\x1b[0m\r\nrest"), "ab[0m");
+        assert_eq!(single_line("\nlater"), "");
+
+        let mut buf = "ac".to_string();
+        let mut cursor = 1;
+        insert_str(&mut buf, &mut cursor, &single_line("bbb\nccc"));
+        assert_eq!((buf.as_str(), cursor), ("abbbc", 4));
+    }
+
+    #[test]
+    fn identifier_fields_drop_whitespace_and_control_characters() {
+        let mut buf = "ali".to_string();
+        let mut cursor = 3;
+        for c in [' ', '\t', '\u{7f}', 'c', 'e'] {
+            assert!(edit_identifier(
+                &mut buf,
+                &mut cursor,
+                key(KeyCode::Char(c))
+            ));
+        }
+        assert_eq!((buf.as_str(), cursor), ("alice", 5));
+        // Editing keys still work
+        assert!(edit_identifier(&mut buf, &mut cursor, ctrl('w')));
+        assert_eq!(buf, "");
+
+        assert_eq!(identifier("new user\tname\nrest"), "newusername");
+    }
+
+    #[test]
+    fn ctrl_u_clears_and_other_ctrl_keys_pass_through() {
+        let mut buf = "something".to_string();
+        let mut cursor = 3;
+        assert!(edit(&mut buf, &mut cursor, ctrl('u')));
+        assert_eq!((buf.as_str(), cursor), ("", 0));
+
+        assert!(!edit(&mut buf, &mut cursor, ctrl('a')));
+        assert!(!edit(&mut buf, &mut cursor, key(KeyCode::Enter)));
+        assert_eq!(buf, "");
+    }
+}
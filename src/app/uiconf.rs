@@ -0,0 +1,193 @@
+//! Layout preferences: parse/write `ui.conf` and apply to AppState.
+//!
+//! Holds settings that change how the screen is laid out rather than what is
+//! shown in it, such as the widths of the body columns and the tab shown on
+//! startup.
+
+use super::{
+    ActiveTab, AppState, DEFAULT_COMPACT_BELOW, EmptyPickerEnter, IdRadix, PaneSplit, UserColumn,
+};
+
+/// Layout settings that can be loaded from or saved to `ui.conf`.
+#[derive(Clone, Debug)]
+pub struct UiConfig {
+    /// Widths of the table, details and keybinds columns in percent.
+    pub pane_split: PaneSplit,
+    /// Tab shown when the application starts.
+    pub default_tab: ActiveTab,
+    /// Base UIDs and GIDs are displayed in.
+    pub id_radix: IdRadix,
+    /// Whether the details panes show filesystem-backed stats.
+    pub live_stats: bool,
+    /// What Enter does in a multi-select picker with nothing checked.
+    pub empty_selection_enter: EmptyPickerEnter,
+    /// Whether the Members list also shows users by primary group.
+    pub members_include_primary: bool,
+    /// Shortest password the forms accept; 0 accepts any length.
+    pub min_password_length: usize,
+    /// Columns shown in the users table, in order.
+    pub user_columns: Vec<UserColumn>,
+    /// Terminal width below which the details column is hidden; 0 never hides it.
+    pub compact_below: u16,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            pane_split: PaneSplit::default(),
+            default_tab: ActiveTab::Users,
+            id_radix: IdRadix::Decimal,
+            live_stats: false,
+            empty_selection_enter: EmptyPickerEnter::Cursor,
+            members_include_primary: false,
+            min_password_length: 0,
+            user_columns: UserColumn::ALL.to_vec(),
+            compact_below: DEFAULT_COMPACT_BELOW,
+        }
+    }
+}
+
+impl UiConfig {
+    /// Store the settings `app` can change at runtime (column widths, ID
+    /// radix, live stats) in `path`, keeping the startup-only settings already
+    /// in the file.
+    pub fn save_from_app(app: &AppState, path: &str) -> std::io::Result<()> {
+        let mut cfg = Self::from_file(path).unwrap_or_default();
+        cfg.pane_split = app.pane_split;
+        cfg.id_radix = app.id_radix;
+        cfg.live_stats = app.live_stats;
+        cfg.write_file(path)
+    }
+
+    /// Load settings from `path`, or write and return defaults if no `ui.conf` exists.
+    pub fn load_or_init(path: &str) -> Self {
+        let p = std::path::Path::new(path);
+        if p.exists() {
+            return Self::from_file(path).unwrap_or_default();
+        }
+        if let Some(existing) = crate::app::config_file_read_path("ui.conf") {
+            return Self::from_file(&existing).unwrap_or_default();
+        }
+        let cfg = Self::default();
+        let _ = cfg.write_file(path);
+        cfg
+    }
+
+    /// Load settings from a `<key> = <value>` file.
+    ///
+    /// Comments, unknown keys and unparsable values are ignored; a split that
+    /// does not add up to 100 or breaks the column minimums falls back to the
+    /// default split, and a `default_tab` other than `users`/`groups` to Users.
+    pub fn from_file(path: &str) -> Option<Self> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        let mut cfg = Self::default();
+        for raw in contents.lines() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.splitn(2, '=');
+            let lhs = parts.next().map(|s| s.trim()).unwrap_or("");
+            let rhs = parts.next().map(|s| s.trim()).unwrap_or("");
+            match lhs {
+                "pane_split" => {
+                    let widths: Vec<u16> = rhs
+                        .split(',')
+                        .filter_map(|w| w.trim().parse().ok())
+                        .collect();
+                    if let [table, details, keybinds] = widths[..] {
+                        cfg.pane_split = PaneSplit::new(table, details, keybinds);
+                    }
+                }
+                "default_tab" => {
+                    cfg.default_tab = match rhs.to_ascii_lowercase().as_str() {
+                        "groups" => ActiveTab::Groups,
+                        _ => ActiveTab::Users,
+                    };
+                }
+                "id_radix" => cfg.id_radix = IdRadix::parse(rhs).unwrap_or_default(),
+                "live_stats" => cfg.live_stats = rhs.eq_ignore_ascii_case("true"),
+                "user_columns" => cfg.user_columns = UserColumn::parse_list(rhs),
+                "compact_below" => {
+                    if let Ok(width) = rhs.parse() {
+                        cfg.compact_below = width;
+                    }
+                }
+                "members_include_primary" => {
+                    cfg.members_include_primary = rhs.eq_ignore_ascii_case("true")
+                }
+                "min_password_length" => {
+                    if let Ok(len) = rhs.parse() {
+                        cfg.min_password_length = len;
+                    }
+                }
+                "empty_selection_enter" => {
+                    cfg.empty_selection_enter = EmptyPickerEnter::parse(rhs).unwrap_or_default()
+                }
+                _ => {}
+            }
+        }
+        Some(cfg)
+    }
+
+    /// Write the settings to `path`.
+    pub fn write_file(&self, path: &str) -> std::io::Result<()> {
+        let s = &self.pane_split;
+        let tab = match self.default_tab {
+            ActiveTab::Users => "users",
+            ActiveTab::Groups => "groups",
+        };
+        let buf = format!(
+            "# usrgrp-manager layout\n\
+             # Column widths in percent: table, details, keybinds (must add up to 100)\n\
+             pane_split = {}, {}, {}\n\
+             # Tab shown on startup: users|groups\n\
+             default_tab = {}\n\
+             # UID/GID display: decimal|hex|octal\n\
+             id_radix = {}\n\
+             # Home, password, SSH key and process stats in the details panes: true|false\n\
+             live_stats = {}\n\
+             # Enter in a multi-select picker with nothing checked: cursor (act on the\n\
+             # highlighted item) | require (ask for at least one checked item)\n\
+             empty_selection_enter = {}\n\
+             # Also list users whose primary group it is under a group's Members: true|false\n\
+             members_include_primary = {}\n\
+             # Refuse new passwords shorter than this many characters (0 = no minimum)\n\
+             min_password_length = {}\n\
+             # Users table columns in order: uid, user, gid, dir, home, shell (user is always shown;\n\
+             # dir marks whether the home directory exists)\n\
+             user_columns = {}\n\
+             # Below this many columns only the main table is shown; i opens the details (0 = never)\n\
+             compact_below = {}\n",
+            s.table,
+            s.details,
+            s.keybinds,
+            tab,
+            self.id_radix.as_str(),
+            self.live_stats,
+            self.empty_selection_enter.as_str(),
+            self.members_include_primary,
+            self.min_password_length,
+            self.user_columns
+                .iter()
+                .map(|c| c.header().to_ascii_lowercase())
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.compact_below
+        );
+        std::fs::write(path, buf)
+    }
+
+    /// Apply the settings to an [`AppState`].
+    pub fn apply_to(&self, app: &mut AppState) {
+        app.pane_split = self.pane_split;
+        app.active_tab = self.default_tab;
+        app.id_radix = self.id_radix;
+        app.live_stats = self.live_stats;
+        app.empty_picker_enter = self.empty_selection_enter;
+        app.members_include_primary = self.members_include_primary;
+        app.min_password_length = self.min_password_length;
+        app.user_columns = self.user_columns.clone();
+        app.compact_below = self.compact_below;
+    }
+}
@@ -4,19 +4,24 @@
 //! modal workflows for user and group management.
 //!
 use crate::error::Result;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
+use std::collections::BTreeSet;
 use std::time::Duration;
 
+use crate::app::confirmconf::ConfirmLevel;
 use crate::app::filterconf::FiltersConfig;
 use crate::app::keymap::KeyAction;
+use crate::app::textinput;
 use crate::app::{
-    ActionsContext, ActiveTab, AppState, GroupsFilter, GroupsFocus, InputMode, ModalState,
-    ModifyField, PendingAction, UsersFocus,
+    ActionsContext, ActiveTab, AppState, DetailVerbosity, EmptyPickerEnter, GroupEligibility,
+    GroupsFilter, GroupsFocus, InputMode, LayoutMode, MembershipSource, ModalState, ModifyField,
+    PendingAction, UsersFocus, filter_menu_len, member_of_groups,
 };
 use crate::search::apply_filters_and_search;
 use crate::sys;
+use crate::sys::UserGroupBackend;
 use crate::ui;
 
 /// Drive the TUI: draw frames and react to keyboard input until quit.
@@ -28,453 +33,26 @@ pub fn run_app(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> Re
             ui::render(f, &mut app);
         })?;
 
-        if event::poll(Duration::from_millis(100))?
-            && let Event::Key(key) = event::read()?
+        let ev = if event::poll(Duration::from_millis(100))? {
+            Some(event::read()?)
+        } else {
+            None
+        };
+        if let Some(Event::Paste(text)) = &ev {
+            handle_paste(&mut app, text);
+        }
+        // Keys are dropped while an action runs, so only one is in flight
+        if let Some(Event::Key(key)) = ev
             && key.kind == KeyEventKind::Press
+            && app.pending_result.is_none()
+            && handle_key(&mut app, key) == LoopControl::Quit
         {
-            match app.input_mode {
-                InputMode::Normal => match app.keymap.resolve(&key) {
-                    Some(KeyAction::Quit) => break,
-                    Some(KeyAction::OpenHelp) => {
-                        app.modal = Some(ModalState::Help { scroll: 0 });
-                        app.input_mode = InputMode::Modal;
-                    }
-                    Some(KeyAction::ToggleKeybindsPane) => {
-                        app.show_keybinds = !app.show_keybinds;
-                    }
-                    Some(KeyAction::Ignore) => { /* ignore */ }
-                    Some(KeyAction::OpenFilterMenu) => {
-                        app.modal = Some(ModalState::FilterMenu { selected: 0 });
-                        app.input_mode = InputMode::Modal;
-                    }
-                    Some(KeyAction::StartSearch) => {
-                        app.search_query.clear();
-                        app.input_mode = match app.active_tab {
-                            ActiveTab::Users => InputMode::SearchUsers,
-                            ActiveTab::Groups => InputMode::SearchGroups,
-                        };
-                    }
-                    Some(KeyAction::NewUser) => match app.active_tab {
-                        ActiveTab::Users => {
-                            if matches!(app.users_focus, UsersFocus::MemberOf) {
-                                // In Member of pane: open Add-to-groups multi-select
-                                app.modal = Some(ModalState::ModifyGroupsAdd {
-                                    selected: 0,
-                                    offset: 0,
-                                    selected_multi: Vec::new(),
-                                });
-                                app.input_mode = InputMode::Modal;
-                            } else {
-                                // Open create user modal; default to create home
-                                app.modal = Some(ModalState::UserAddInput {
-                                    selected: 0,
-                                    name: String::new(),
-                                    password: String::new(),
-                                    confirm: String::new(),
-                                    create_home: true,
-                                    add_to_wheel: false,
-                                });
-                                app.input_mode = InputMode::Modal;
-                            }
-                        }
-                        ActiveTab::Groups => {
-                            // Open create group input modal
-                            app.modal = Some(ModalState::GroupAddInput {
-                                name: String::new(),
-                            });
-                            app.input_mode = InputMode::Modal;
-                        }
-                    },
-                    Some(KeyAction::SwitchTab) => {
-                        app.active_tab = match app.active_tab {
-                            ActiveTab::Users => ActiveTab::Groups,
-                            ActiveTab::Groups => ActiveTab::Users,
-                        };
-                    }
-                    Some(KeyAction::ToggleUsersFocus) => match app.active_tab {
-                        ActiveTab::Users => {
-                            app.users_focus = match app.users_focus {
-                                UsersFocus::UsersList => UsersFocus::MemberOf,
-                                UsersFocus::MemberOf => UsersFocus::UsersList,
-                            };
-                        }
-                        ActiveTab::Groups => {
-                            app.groups_focus = match app.groups_focus {
-                                GroupsFocus::GroupsList => GroupsFocus::Members,
-                                GroupsFocus::Members => GroupsFocus::GroupsList,
-                            };
-                        }
-                    },
-                    Some(KeyAction::ToggleGroupsFocus) => {
-                        if let ActiveTab::Groups = app.active_tab {
-                            app.groups_focus = match app.groups_focus {
-                                GroupsFocus::GroupsList => GroupsFocus::Members,
-                                GroupsFocus::Members => GroupsFocus::GroupsList,
-                            };
-                        }
-                    }
-                    Some(KeyAction::EnterAction) => match app.active_tab {
-                        ActiveTab::Users => {
-                            if !app.users.is_empty() {
-                                if let UsersFocus::MemberOf = app.users_focus {
-                                    if let Some(u) = app.users.get(app.selected_user_index) {
-                                        let uname = u.name.clone();
-                                        let pgid = u.primary_gid;
-                                        let groups_for_user: Vec<sys::SystemGroup> = app
-                                            .groups
-                                            .iter()
-                                            .filter(|g| {
-                                                g.gid == pgid
-                                                    || g.members.iter().any(|m| m == &uname)
-                                            })
-                                            .cloned()
-                                            .collect();
-                                        if let Some(sel_group) =
-                                            groups_for_user.get(app.selected_group_index)
-                                        {
-                                            if let Some(idx) = app
-                                                .groups
-                                                .iter()
-                                                .position(|g| g.gid == sel_group.gid)
-                                            {
-                                                app.selected_group_index = idx;
-                                            }
-                                            app.modal = Some(ModalState::GroupsActions {
-                                                selected: 0,
-                                                target_gid: Some(sel_group.gid),
-                                            });
-                                            app.input_mode = InputMode::Modal;
-                                        }
-                                    }
-                                } else {
-                                    // Open Actions for Users section: ensure no residual context
-                                    app.actions_context = None;
-                                    app.modal = Some(ModalState::Actions { selected: 0 });
-                                    app.input_mode = InputMode::Modal;
-                                }
-                            }
-                        }
-                        ActiveTab::Groups => {
-                            if matches!(app.groups_focus, GroupsFocus::Members) {
-                                if let Some(g) = app.groups.get(app.selected_group_index) {
-                                    let members = g.members.clone();
-                                    if app.selected_group_member_index < members.len() {
-                                        let uname =
-                                            members[app.selected_group_member_index].clone();
-                                        if let Some(idx) =
-                                            app.users.iter().position(|u| u.name == uname)
-                                        {
-                                            app.selected_user_index = idx;
-                                        } else if let Some(idx_all) =
-                                            app.users_all.iter().position(|u| u.name == uname)
-                                        {
-                                            app.users = app.users_all.clone();
-                                            app.selected_user_index = idx_all;
-                                        }
-                                        app.actions_context =
-                                            Some(ActionsContext::GroupMemberRemoval {
-                                                group_name: uname,
-                                            });
-                                        app.modal = Some(ModalState::Actions { selected: 0 });
-                                        app.input_mode = InputMode::Modal;
-                                    }
-                                }
-                            } else if let Some(g) = app.groups.get(app.selected_group_index) {
-                                app.modal = Some(ModalState::GroupsActions {
-                                    selected: 0,
-                                    target_gid: Some(g.gid),
-                                });
-                                app.input_mode = InputMode::Modal;
-                            }
-                        }
-                    },
-                    Some(KeyAction::DeleteSelection) => match app.active_tab {
-                        ActiveTab::Users => {
-                            if app.users.is_empty() {
-                                break;
-                            }
-                            match app.users_focus {
-                                UsersFocus::UsersList => {
-                                    let allowed = app
-                                        .users
-                                        .get(app.selected_user_index)
-                                        .map(|u| u.uid >= 1000 && u.uid <= 1999)
-                                        .unwrap_or(false);
-                                    if allowed {
-                                        app.modal = Some(ModalState::DeleteConfirm {
-                                            selected: 1,
-                                            allowed,
-                                            delete_home: false,
-                                        });
-                                    } else {
-                                        app.modal = Some(ModalState::Info {
-                                            message:
-                                                "Deletion not allowed. Only UID 1000-1999 allowed"
-                                                    .to_string(),
-                                        });
-                                    }
-                                    app.input_mode = InputMode::Modal;
-                                }
-                                UsersFocus::MemberOf => {
-                                    if let Some(u) = app.users.get(app.selected_user_index) {
-                                        let uname = u.name.clone();
-                                        let pgid = u.primary_gid;
-                                        let groups_for_user: Vec<sys::SystemGroup> = app
-                                            .groups
-                                            .iter()
-                                            .filter(|g| {
-                                                g.gid == pgid
-                                                    || g.members.iter().any(|m| m == &uname)
-                                            })
-                                            .cloned()
-                                            .collect();
-                                        if let Some(sel_group) =
-                                            groups_for_user.get(app.selected_group_index)
-                                        {
-                                            app.modal =
-                                                Some(ModalState::ConfirmRemoveUserFromGroup {
-                                                    selected: 1,
-                                                    group_name: sel_group.name.clone(),
-                                                });
-                                            app.input_mode = InputMode::Modal;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        ActiveTab::Groups => {
-                            if !app.groups.is_empty() {
-                                let gid = app.groups.get(app.selected_group_index).map(|g| g.gid);
-                                app.modal = Some(ModalState::GroupDeleteConfirm {
-                                    selected: 1,
-                                    target_gid: gid,
-                                });
-                                app.input_mode = InputMode::Modal;
-                            }
-                        }
-                    },
-                    Some(KeyAction::MoveUp) => match app.active_tab {
-                        ActiveTab::Users => match app.users_focus {
-                            UsersFocus::UsersList => {
-                                if app.selected_user_index > 0 {
-                                    app.selected_user_index -= 1;
-                                } else if !app.users.is_empty() {
-                                    app.selected_user_index = app.users.len().saturating_sub(1);
-                                }
-                            }
-                            UsersFocus::MemberOf => {
-                                let groups_len = if let Some(u) =
-                                    app.users.get(app.selected_user_index)
-                                {
-                                    let name = u.name.clone();
-                                    let pgid = u.primary_gid;
-                                    app.groups
-                                        .iter()
-                                        .filter(|g| {
-                                            g.gid == pgid || g.members.iter().any(|m| m == &name)
-                                        })
-                                        .count()
-                                } else {
-                                    0
-                                };
-                                if app.selected_group_index > 0 {
-                                    app.selected_group_index -= 1;
-                                } else if groups_len > 0 {
-                                    app.selected_group_index = groups_len.saturating_sub(1);
-                                }
-                            }
-                        },
-                        ActiveTab::Groups => match app.groups_focus {
-                            GroupsFocus::GroupsList => {
-                                if app.selected_group_index > 0 {
-                                    app.selected_group_index -= 1;
-                                } else if !app.groups.is_empty() {
-                                    app.selected_group_index = app.groups.len().saturating_sub(1);
-                                }
-                            }
-                            GroupsFocus::Members => {
-                                if app.selected_group_member_index > 0 {
-                                    app.selected_group_member_index -= 1;
-                                } else {
-                                    let members_len = app
-                                        .groups
-                                        .get(app.selected_group_index)
-                                        .map(|g| g.members.len())
-                                        .unwrap_or(0);
-                                    if members_len > 0 {
-                                        app.selected_group_member_index =
-                                            members_len.saturating_sub(1);
-                                    }
-                                }
-                            }
-                        },
-                    },
-                    Some(KeyAction::MoveDown) => match app.active_tab {
-                        ActiveTab::Users => match app.users_focus {
-                            UsersFocus::UsersList => {
-                                if app.selected_user_index + 1 < app.users.len() {
-                                    app.selected_user_index += 1;
-                                } else if !app.users.is_empty() {
-                                    app.selected_user_index = 0;
-                                }
-                            }
-                            UsersFocus::MemberOf => {
-                                let groups_len = if let Some(u) =
-                                    app.users.get(app.selected_user_index)
-                                {
-                                    let name = u.name.clone();
-                                    let pgid = u.primary_gid;
-                                    app.groups
-                                        .iter()
-                                        .filter(|g| {
-                                            g.gid == pgid || g.members.iter().any(|m| m == &name)
-                                        })
-                                        .count()
-                                } else {
-                                    0
-                                };
-                                if app.selected_group_index + 1 < groups_len {
-                                    app.selected_group_index += 1;
-                                } else if groups_len > 0 {
-                                    app.selected_group_index = 0;
-                                }
-                            }
-                        },
-                        ActiveTab::Groups => match app.groups_focus {
-                            GroupsFocus::GroupsList => {
-                                if app.selected_group_index + 1 < app.groups.len() {
-                                    app.selected_group_index += 1;
-                                } else if !app.groups.is_empty() {
-                                    app.selected_group_index = 0;
-                                }
-                            }
-                            GroupsFocus::Members => {
-                                let members_len = app
-                                    .groups
-                                    .get(app.selected_group_index)
-                                    .map(|g| g.members.len())
-                                    .unwrap_or(0);
-                                if app.selected_group_member_index + 1 < members_len {
-                                    app.selected_group_member_index += 1;
-                                } else if members_len > 0 {
-                                    app.selected_group_member_index = 0;
-                                }
-                            }
-                        },
-                    },
-                    Some(KeyAction::MoveLeftPage) | Some(KeyAction::PageUp) => {
-                        let rpp = app.rows_per_page.max(1);
-                        match app.active_tab {
-                            ActiveTab::Users => match app.users_focus {
-                                UsersFocus::UsersList => {
-                                    if app.selected_user_index >= rpp {
-                                        app.selected_user_index -= rpp;
-                                    } else {
-                                        app.selected_user_index = 0;
-                                    }
-                                }
-                                UsersFocus::MemberOf => {
-                                    if app.selected_group_index >= rpp {
-                                        app.selected_group_index -= rpp;
-                                    } else {
-                                        app.selected_group_index = 0;
-                                    }
-                                }
-                            },
-                            ActiveTab::Groups => match app.groups_focus {
-                                GroupsFocus::GroupsList => {
-                                    if app.selected_group_index >= rpp {
-                                        app.selected_group_index -= rpp;
-                                    } else {
-                                        app.selected_group_index = 0;
-                                    }
-                                }
-                                GroupsFocus::Members => {
-                                    if app.selected_group_member_index >= rpp {
-                                        app.selected_group_member_index -= rpp;
-                                    } else {
-                                        app.selected_group_member_index = 0;
-                                    }
-                                }
-                            },
-                        }
-                    }
-                    Some(KeyAction::MoveRightPage) | Some(KeyAction::PageDown) => {
-                        let rpp = app.rows_per_page.max(1);
-                        match app.active_tab {
-                            ActiveTab::Users => match app.users_focus {
-                                UsersFocus::UsersList => {
-                                    let new_idx = app.selected_user_index.saturating_add(rpp);
-                                    app.selected_user_index =
-                                        new_idx.min(app.users.len().saturating_sub(1));
-                                }
-                                UsersFocus::MemberOf => {
-                                    let groups_len =
-                                        if let Some(u) = app.users.get(app.selected_user_index) {
-                                            let name = u.name.clone();
-                                            let pgid = u.primary_gid;
-                                            app.groups
-                                                .iter()
-                                                .filter(|g| {
-                                                    g.gid == pgid
-                                                        || g.members.iter().any(|m| m == &name)
-                                                })
-                                                .count()
-                                        } else {
-                                            0
-                                        };
-                                    let new_idx = app.selected_group_index.saturating_add(rpp);
-                                    app.selected_group_index =
-                                        new_idx.min(groups_len.saturating_sub(1));
-                                }
-                            },
-                            ActiveTab::Groups => match app.groups_focus {
-                                GroupsFocus::GroupsList => {
-                                    let new_idx = app.selected_group_index.saturating_add(rpp);
-                                    app.selected_group_index =
-                                        new_idx.min(app.groups.len().saturating_sub(1));
-                                }
-                                GroupsFocus::Members => {
-                                    let members_len = app
-                                        .groups
-                                        .get(app.selected_group_index)
-                                        .map(|g| g.members.len())
-                                        .unwrap_or(0);
-                                    let new_idx =
-                                        app.selected_group_member_index.saturating_add(rpp);
-                                    app.selected_group_member_index =
-                                        new_idx.min(members_len.saturating_sub(1));
-                                }
-                            },
-                        }
-                    }
-                    None => {}
-                },
-                InputMode::Modal => {
-                    handle_modal_key(&mut app, key);
-                }
-                InputMode::SearchUsers | InputMode::SearchGroups => match key.code {
-                    KeyCode::Enter => {
-                        apply_filters_and_search(&mut app);
-                        app.input_mode = InputMode::Normal;
-                    }
-                    KeyCode::Esc => {
-                        app.input_mode = InputMode::Normal;
-                        app.search_query.clear();
-                        apply_filters_and_search(&mut app);
-                    }
-                    KeyCode::Backspace => {
-                        app.search_query.pop();
-                        apply_filters_and_search(&mut app);
-                    }
-                    KeyCode::Char(c) => {
-                        app.search_query.push(c);
-                        apply_filters_and_search(&mut app);
-                    }
-                    _ => {}
-                },
-            }
+            break;
         }
+        run_queued_action(&mut app);
+        poll_action_result(&mut app);
+        poll_lookup(&mut app);
+        check_account_files(&mut app);
 
         let _uptime = app.started_at.elapsed();
     }
@@ -483,34 +61,73 @@ pub fn run_app(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> Re
 }
 
 /// Handle all key events while a modal dialog is open.
+///
+/// Modals live on `app.modal_stack`: opening a child pushes it, so the parent
+/// keeps its exact state underneath. `Esc` closes the whole stack. `Backspace`
+/// pops one level, which closes top-level modals. In text inputs it deletes a
+/// character first and only pops once the focused field is empty. Error `Info`
+/// and `SudoPrompt` dialogs are pushed over the form that raised them, while
+/// the result of a completed action replaces the stack.
+///
+/// Modal hierarchy (children indented under their parent):
+///
+/// ```text
+/// Actions
+///   ModifyMenu
+///     ModifyGroupsAdd / ModifyGroupsRemove
+///     ModifyDetailsMenu
+///       ModifyTextInput (username, full name) / ModifyShell / EditGecos
+///         ModifyTextInput (custom shell path, via "Other…")
+///     ModifyPasswordMenu
+///       ChangePassword
+///     SudoersDropin
+///   DeleteConfirm
+///   CloneUser
+///   MoveMemberPicker (group member context)
+///     MoveMemberConfirm
+/// GroupsActions
+///   GroupAddInput
+///     GroupModifyAddMembers (Down: members of the new group)
+///   GroupCloneInput
+///   MergeGroup
+///   GroupModifyMenu
+///     GroupModifyAddMembers / GroupModifyRemoveMembers
+///       MembershipPreview (several members checked)
+///     GroupRenameInput
+///       ConfirmAction (what the rename breaks)
+///   GroupDeleteReassign (group is someone's primary group)
+///     GroupDeleteConfirm
+///   GroupDeleteConfirm
+/// GroupDetails
+///   OrphanMembers
+/// UserDetails
+///   Info (home directory copied or opened)
+/// Top-level: FilterMenu, CopyCommandMenu, UserAddInput, ImportPathInput, ConfirmRemoveUserFromGroup, SudoPrompt, Info, Help, ActionHistory
+/// Confirmations from `confirmations.conf` stack on top: ConfirmAction, TypedConfirm
+/// ```
 fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
-    match &mut app.modal {
+    match app.modal_stack.last_mut() {
+        Some(ModalState::CopyCommandMenu { .. }) => copy_command_menu_key(app, key),
         Some(ModalState::FilterMenu { selected }) => match key.code {
             KeyCode::Esc => close_modal(app),
             KeyCode::Backspace => close_modal(app),
             KeyCode::Up | KeyCode::Char('k') => {
-                let max = if matches!(app.active_tab, ActiveTab::Users) {
-                    7
-                } else {
-                    2
-                };
+                let max = filter_menu_len(app.active_tab) - 1;
                 if *selected > 0 {
-                    *selected -= 1;
+                    *selected = (*selected - 1).min(max);
                 } else {
                     *selected = max;
                 }
+                app.filter_menu_selected.set(app.active_tab, *selected);
             }
             KeyCode::Down | KeyCode::Char('j') => {
-                let max = if matches!(app.active_tab, ActiveTab::Users) {
-                    7
-                } else {
-                    2
-                };
+                let max = filter_menu_len(app.active_tab) - 1;
                 if *selected < max {
                     *selected += 1;
                 } else {
                     *selected = 0;
                 }
+                app.filter_menu_selected.set(app.active_tab, *selected);
             }
             KeyCode::Char(' ') => {
                 if let ActiveTab::Users = app.active_tab {
@@ -535,6 +152,33 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                             app.users_filter_chips.no_password = !app.users_filter_chips.no_password
                         }
                         7 => app.users_filter_chips.expired = !app.users_filter_chips.expired,
+                        8 => {
+                            app.users_filter_chips.no_primary_group =
+                                !app.users_filter_chips.no_primary_group
+                        }
+                        _ => {}
+                    }
+                    let path = crate::app::config_file_read_path("filter.conf")
+                        .unwrap_or_else(|| crate::app::config_file_write_path("filter.conf"));
+                    let _ = FiltersConfig::save_from_app(app, &path);
+                } else {
+                    let chips = &mut app.groups_filter_chips;
+                    match *selected {
+                        3 => chips.empty_only = !chips.empty_only,
+                        4 => {
+                            chips.system_only = !chips.system_only;
+                            if chips.system_only {
+                                chips.user_only = false;
+                            }
+                        }
+                        5 => {
+                            chips.user_only = !chips.user_only;
+                            if chips.user_only {
+                                chips.system_only = false;
+                            }
+                        }
+                        6 => chips.has_sudo_flag = !chips.has_sudo_flag,
+                        7 => chips.orphan_members = !chips.orphan_members,
                         _ => {}
                     }
                     let path = crate::app::config_file_read_path("filter.conf")
@@ -566,20 +210,22 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
             _ => {}
         },
         Some(ModalState::Actions { selected }) => match key.code {
-            KeyCode::Esc => {
+            KeyCode::Esc | KeyCode::Backspace => {
                 // Leaving actions, clear any temporary context
                 app.actions_context = None;
                 close_modal(app)
             }
             KeyCode::Up | KeyCode::Char('k') => {
+                // Third entry: "Move to another group" in the group member
+                // context, "Create from template" otherwise
                 if *selected > 0 {
                     *selected -= 1;
                 } else {
-                    *selected = 1;
+                    *selected = 2;
                 }
             }
             KeyCode::Down | KeyCode::Char('j') => {
-                if *selected < 1 {
+                if *selected < 2 {
                     *selected += 1;
                 } else {
                     *selected = 0;
@@ -590,7 +236,25 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                     0 => {
                         // Modify path should not carry special context
                         app.actions_context = None;
-                        app.modal = Some(ModalState::ModifyMenu { selected: 0 });
+                        app.push_modal(ModalState::ModifyMenu { selected: 0 });
+                    }
+                    2 => {
+                        if let Some(ActionsContext::GroupMemberRemoval { group_name }) =
+                            app.actions_context.clone()
+                        {
+                            if let Some(user) = app.users.get(app.selected_user_index) {
+                                let username = user.name.clone();
+                                app.push_modal(ModalState::MoveMemberPicker {
+                                    selected: 0,
+                                    offset: 0,
+                                    username,
+                                    from_group: group_name,
+                                });
+                            }
+                        } else if let Some(user) = app.users.get(app.selected_user_index) {
+                            let modal = crate::app::clone_user_modal(user, &app.groups_all);
+                            app.push_modal(modal);
+                        }
                     }
                     1 => {
                         if let Some(ActionsContext::GroupMemberRemoval { group_name }) =
@@ -598,7 +262,7 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                         {
                             if let Some(user) = app.users.get(app.selected_user_index) {
                                 if group_name == user.name {
-                                    app.modal = Some(ModalState::Info {
+                                    app.push_modal(ModalState::Info {
                                         message: "Cannot remove from self-named group.".to_string(),
                                     });
                                 } else {
@@ -606,36 +270,13 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                                         username: user.name.clone(),
                                         groupname: group_name,
                                     };
-                                    if let Err(_e) = perform_pending_action(
-                                        app,
-                                        pending.clone(),
-                                        app.sudo_password.clone(),
-                                    ) {
-                                        app.modal = Some(ModalState::SudoPrompt {
-                                            next: pending,
-                                            password: String::new(),
-                                            error: None,
-                                        });
-                                    }
+                                    confirm_then_run(app, pending, true);
                                 }
                             }
                             app.actions_context = None;
                         } else if let Some(user) = app.users.get(app.selected_user_index) {
-                            let allowed = user.uid >= 1000 && user.uid <= 1999;
-                            if allowed {
-                                app.modal = Some(ModalState::DeleteConfirm {
-                                    selected: 1,
-                                    allowed,
-                                    delete_home: false,
-                                });
-                            } else {
-                                app.modal = Some(ModalState::Info {
-                                    message: format!(
-                                        "Deletion not allowed. Only UID 1000-1999 allowed: {}",
-                                        user.name
-                                    ),
-                                });
-                            }
+                            let modal = user_delete_modal(app, user);
+                            open_confirm(app, modal, true);
                         } else {
                             close_modal(app);
                         }
@@ -648,47 +289,81 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
         Some(ModalState::ModifyMenu { selected }) => match key.code {
             KeyCode::Esc => close_modal(app),
             KeyCode::Backspace => {
-                app.modal = Some(ModalState::Actions { selected: 0 });
+                back_modal(app);
             }
             KeyCode::Up | KeyCode::Char('k') => {
                 if *selected > 0 {
                     *selected -= 1;
                 } else {
-                    *selected = 3;
+                    *selected = 4;
                 }
             }
             KeyCode::Down | KeyCode::Char('j') => {
-                if *selected < 3 {
+                if *selected < 4 {
                     *selected += 1;
                 } else {
                     *selected = 0;
                 }
             }
             KeyCode::Enter => match *selected {
-                0 => {
-                    app.modal = Some(ModalState::ModifyGroupsAdd {
-                        selected: 0,
-                        offset: 0,
-                        selected_multi: Vec::new(),
-                    })
-                }
-                1 => {
-                    app.modal = Some(ModalState::ModifyGroupsRemove {
-                        selected: 0,
-                        offset: 0,
-                        selected_multi: Vec::new(),
-                    })
+                0 => app.push_modal(ModalState::ModifyGroupsAdd {
+                    selected: 0,
+                    offset: 0,
+                    selected_multi: BTreeSet::new(),
+                    show_all: false,
+                }),
+                1 => app.push_modal(ModalState::ModifyGroupsRemove {
+                    selected: 0,
+                    offset: 0,
+                    selected_multi: BTreeSet::new(),
+                }),
+                2 => app.push_modal(ModalState::ModifyDetailsMenu { selected: 0 }),
+                3 => app.push_modal(ModalState::ModifyPasswordMenu { selected: 0 }),
+                4 => {
+                    if let Some(user) = app.users.get(app.selected_user_index) {
+                        let username = user.name.clone();
+                        let adapter = (app.backend)(app.sudo_password.clone());
+                        let name = username.clone();
+                        start_lookup(app, move || crate::app::Lookup::SudoersDropin {
+                            result: adapter
+                                .read_sudoers_dropin(&name)
+                                .map_err(|e| e.to_string()),
+                            username: name,
+                        });
+                        app.push_modal(ModalState::SudoersDropin {
+                            username,
+                            loading: true,
+                            content: None,
+                            error: None,
+                        });
+                    }
                 }
-                2 => app.modal = Some(ModalState::ModifyDetailsMenu { selected: 0 }),
-                3 => app.modal = Some(ModalState::ModifyPasswordMenu { selected: 0 }),
                 _ => {}
             },
             _ => {}
         },
+        Some(ModalState::SudoersDropin {
+            username,
+            loading,
+            content,
+            ..
+        }) => match key.code {
+            KeyCode::Esc => close_modal(app),
+            KeyCode::Backspace | KeyCode::Enter => {
+                back_modal(app);
+            }
+            KeyCode::Char('c') if !*loading && content.is_none() => {
+                let pending = PendingAction::CreateSudoersDropin {
+                    username: username.clone(),
+                };
+                run_pending_action(app, pending);
+            }
+            _ => {}
+        },
         Some(ModalState::ModifyPasswordMenu { selected }) => match key.code {
             KeyCode::Esc => close_modal(app),
             KeyCode::Backspace => {
-                app.modal = Some(ModalState::ModifyMenu { selected: 3 });
+                back_modal(app);
             }
             KeyCode::Up | KeyCode::Char('k') => {
                 if *selected > 0 {
@@ -705,28 +380,18 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                 }
             }
             KeyCode::Enter => match *selected {
-                0 => {
-                    app.modal = Some(ModalState::ChangePassword {
-                        selected: 0,
-                        password: String::new(),
-                        confirm: String::new(),
-                        must_change: false,
-                    })
-                }
+                0 => app.push_modal(ModalState::ChangePassword {
+                    selected: 0,
+                    password: String::new(),
+                    confirm: String::new(),
+                    must_change: false,
+                }),
                 1 => {
                     if let Some(user) = app.users.get(app.selected_user_index) {
                         let pending = PendingAction::ResetPassword {
                             username: user.name.clone(),
                         };
-                        if let Err(_e) =
-                            perform_pending_action(app, pending.clone(), app.sudo_password.clone())
-                        {
-                            app.modal = Some(ModalState::SudoPrompt {
-                                next: pending,
-                                password: String::new(),
-                                error: None,
-                            });
-                        }
+                        confirm_then_run(app, pending, false);
                     } else {
                         close_modal(app);
                     }
@@ -742,67 +407,50 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
             must_change,
         }) => match key.code {
             KeyCode::Esc => close_modal(app),
-            KeyCode::Up => {
-                if *selected > 0 {
-                    *selected -= 1;
-                }
-            }
-            KeyCode::Down => {
-                if *selected < 3 {
-                    *selected += 1;
-                }
-            }
+            KeyCode::Up if *selected > 0 => *selected -= 1,
+            KeyCode::Down if *selected < 3 => *selected += 1,
             KeyCode::Backspace => match *selected {
                 0 => {
                     if password.is_empty() {
-                        app.modal = Some(ModalState::ModifyPasswordMenu { selected: 0 });
+                        back_modal(app);
                     } else {
                         password.pop();
                     }
                 }
                 1 => {
                     if confirm.is_empty() {
-                        app.modal = Some(ModalState::ModifyPasswordMenu { selected: 0 });
+                        back_modal(app);
                     } else {
                         confirm.pop();
                     }
                 }
-                _ => {}
+                _ => back_modal(app),
             },
-            KeyCode::Char(' ') => {
-                if *selected == 2 {
-                    *must_change = !*must_change;
-                }
-            }
+            KeyCode::Char(' ') if *selected == 2 => *must_change = !*must_change,
+            KeyCode::Char(' ') => {}
             KeyCode::Char(c) => match *selected {
                 0 => password.push(c),
                 1 => confirm.push(c),
                 _ => {}
             },
-            KeyCode::Enter => {
-                if *selected == 3 {
-                    if password.is_empty() || password != confirm {
-                        app.modal = Some(ModalState::Info {
-                            message: "Passwords do not match or empty".to_string(),
-                        });
-                    } else if let Some(user) = app.users.get(app.selected_user_index) {
-                        let pending = PendingAction::SetPassword {
-                            username: user.name.clone(),
-                            password: password.clone(),
-                            must_change: *must_change,
-                        };
-                        if let Err(_e) =
-                            perform_pending_action(app, pending.clone(), app.sudo_password.clone())
-                        {
-                            app.modal = Some(ModalState::SudoPrompt {
-                                next: pending,
-                                password: String::new(),
-                                error: None,
-                            });
-                        }
-                    } else {
-                        close_modal(app);
-                    }
+            KeyCode::Enter if *selected == 3 => {
+                if password.is_empty() || password != confirm {
+                    app.push_modal(ModalState::Info {
+                        message: "Passwords do not match or empty".to_string(),
+                    });
+                } else if let Some(message) =
+                    crate::app::password_length_problem(password, app.min_password_length)
+                {
+                    app.push_modal(ModalState::Info { message });
+                } else if let Some(user) = app.users.get(app.selected_user_index) {
+                    let pending = PendingAction::SetPassword {
+                        username: user.name.clone(),
+                        password: password.clone(),
+                        must_change: *must_change,
+                    };
+                    confirm_then_run(app, pending, false);
+                } else {
+                    close_modal(app);
                 }
             }
             _ => {}
@@ -811,22 +459,27 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
             selected,
             offset,
             selected_multi,
+            show_all,
         }) => {
-            // Compute eligible groups count (not primary group, not already member)
-            let (username, primary_gid) = if let Some(u) = app.users.get(app.selected_user_index) {
-                (u.name.clone(), u.primary_gid)
-            } else {
-                (String::new(), 0)
-            };
-            let total = app
-                .groups_all
+            // Groups listed in the picker; ineligible ones only appear with `show_all`
+            let user = app.users.get(app.selected_user_index);
+            let candidates: Vec<(String, GroupEligibility)> =
+                crate::app::add_group_candidates(&app.groups_all, user, *show_all)
+                    .into_iter()
+                    .map(|(g, e)| (g.name.clone(), e))
+                    .collect();
+            let total = candidates.len();
+            let is_eligible =
+                |i: usize| matches!(candidates.get(i), Some((_, GroupEligibility::Eligible)));
+            let eligible_names: Vec<&str> = candidates
                 .iter()
-                .filter(|g| g.gid != primary_gid && !g.members.iter().any(|m| m == &username))
-                .count();
+                .filter(|(_, e)| *e == GroupEligibility::Eligible)
+                .map(|(n, _)| n.as_str())
+                .collect();
             match key.code {
                 KeyCode::Esc => close_modal(app),
                 KeyCode::Backspace => {
-                    app.modal = Some(ModalState::ModifyMenu { selected: 0 });
+                    back_modal(app);
                 }
                 KeyCode::Up | KeyCode::Char('k') => {
                     if *selected > 0 {
@@ -847,6 +500,9 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                         *offset = 0;
                     }
                 }
+                KeyCode::Home | KeyCode::End | KeyCode::Char('g') | KeyCode::Char('G') => {
+                    jump_to_edge(key.code, selected, offset, total)
+                }
                 KeyCode::PageUp => {
                     let step = 10usize;
                     if *selected >= step {
@@ -862,70 +518,69 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                     let step = 10usize;
                     *selected = (*selected + step).min(total.saturating_sub(1));
                 }
+                KeyCode::Char('a') | KeyCode::Char('d')
+                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    bulk_select(key.code, selected_multi, eligible_names.iter().copied());
+                }
+                KeyCode::Char('*') => {
+                    let listed: Vec<&str> = candidates.iter().map(|(n, _)| n.as_str()).collect();
+                    invert_checked(selected_multi, &listed);
+                    selected_multi.retain(|n| eligible_names.contains(&n.as_str()));
+                }
+                KeyCode::Char('a') => {
+                    // Switch list mode, keeping the cursor on the same group
+                    let current = candidates.get(*selected).map(|(n, _)| n.clone());
+                    *show_all = !*show_all;
+                    let next: Vec<String> =
+                        crate::app::add_group_candidates(&app.groups_all, user, *show_all)
+                            .into_iter()
+                            .map(|(g, _)| g.name.clone())
+                            .collect();
+                    *selected = current
+                        .and_then(|c| next.iter().position(|n| *n == c))
+                        .unwrap_or(0);
+                    *offset = 0;
+                }
                 KeyCode::Char(' ') => {
-                    if let Some(pos) = selected_multi.iter().position(|&i| i == *selected) {
-                        selected_multi.remove(pos);
-                    } else {
-                        selected_multi.push(*selected);
+                    if let Some((name, _)) = candidates.get(*selected)
+                        && (selected_multi.contains(name) || is_eligible(*selected))
+                    {
+                        toggle_checked(selected_multi, name);
                     }
                 }
+                KeyCode::Enter
+                    if selected_multi.is_empty()
+                        && app.empty_picker_enter == EmptyPickerEnter::RequireChecked =>
+                {
+                    require_checked_items(app)
+                }
                 KeyCode::Enter => {
                     if let Some(user) = app.users.get(app.selected_user_index) {
-                        if !selected_multi.is_empty() {
-                            let mut names: Vec<String> = Vec::with_capacity(selected_multi.len());
-                            // Recompute eligible groups to map indices correctly
-                            let username = user.name.clone();
-                            let primary_gid = user.primary_gid;
-                            let eligible: Vec<&crate::sys::SystemGroup> = app
-                                .groups_all
-                                .iter()
-                                .filter(|g| {
-                                    g.gid != primary_gid
-                                        && !g.members.iter().any(|m| m == &username)
-                                })
-                                .collect();
-                            for idx in selected_multi.iter() {
-                                if let Some(g) = eligible.get(*idx) {
-                                    names.push(g.name.clone());
-                                }
-                            }
-                            if !names.is_empty() {
-                                let pending = PendingAction::AddUserToGroups {
-                                    username: user.name.clone(),
-                                    groupnames: names,
-                                };
-                                if let Err(_e) = perform_pending_action(
-                                    app,
-                                    pending.clone(),
-                                    app.sudo_password.clone(),
-                                ) {
-                                    app.modal = Some(ModalState::SudoPrompt {
-                                        next: pending,
-                                        password: String::new(),
-                                        error: None,
-                                    });
-                                }
-                            } else {
-                                close_modal(app);
-                            }
-                        } else if let Some(group_name) =
-                            app.groups_all.get(*selected).map(|g| g.name.clone())
-                        {
-                            let pending = PendingAction::AddUserToGroup {
+                        let names: Vec<String> = if selected_multi.is_empty() {
+                            candidates
+                                .get(*selected)
+                                .filter(|_| is_eligible(*selected))
+                                .map(|(n, _)| vec![n.clone()])
+                                .unwrap_or_default()
+                        } else {
+                            crate::app::checked_items(eligible_names, selected_multi)
+                        };
+                        let pending = match names.len() {
+                            0 => None,
+                            1 if selected_multi.is_empty() => Some(PendingAction::AddUserToGroup {
                                 username: user.name.clone(),
-                                groupname: group_name.clone(),
-                            };
-                            if let Err(_e) = perform_pending_action(
-                                app,
-                                pending.clone(),
-                                app.sudo_password.clone(),
-                            ) {
-                                app.modal = Some(ModalState::SudoPrompt {
-                                    next: pending,
-                                    password: String::new(),
-                                    error: None,
-                                });
-                            }
+                                groupname: names[0].clone(),
+                            }),
+                            _ => Some(PendingAction::AddUserToGroups {
+                                username: user.name.clone(),
+                                groupnames: names,
+                            }),
+                        };
+                        if let Some(pending) = pending {
+                            confirm_then_run(app, pending, true);
+                        } else if selected_multi.is_empty() && *selected < total {
+                            // Cursor on an annotated (ineligible) group: keep the picker open
                         } else {
                             close_modal(app);
                         }
@@ -956,7 +611,7 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
             match key.code {
                 KeyCode::Esc => close_modal(app),
                 KeyCode::Backspace => {
-                    app.modal = Some(ModalState::ModifyMenu { selected: 1 });
+                    back_modal(app);
                 }
                 KeyCode::Up | KeyCode::Char('k') => {
                     if *selected > 0 {
@@ -977,6 +632,9 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                         *offset = 0;
                     }
                 }
+                KeyCode::Home | KeyCode::End | KeyCode::Char('g') | KeyCode::Char('G') => {
+                    jump_to_edge(key.code, selected, offset, total)
+                }
                 KeyCode::PageUp => {
                     let step = 10usize;
                     if *selected >= step {
@@ -992,27 +650,46 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                     let step = 10usize;
                     *selected = (*selected + step).min(total.saturating_sub(1));
                 }
+                KeyCode::Char('a') | KeyCode::Char('d')
+                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    // The primary group cannot be removed, so it is never picked
+                    let removable = user_groups.iter().filter(|g| g.gid != primary_gid);
+                    bulk_select(key.code, selected_multi, removable.map(|g| g.name.as_str()));
+                }
+                KeyCode::Char('*') => {
+                    let listed: Vec<&str> = user_groups.iter().map(|g| g.name.as_str()).collect();
+                    invert_checked(selected_multi, &listed);
+                    selected_multi.retain(|n| {
+                        user_groups
+                            .iter()
+                            .any(|g| g.name == *n && g.gid != primary_gid)
+                    });
+                }
                 KeyCode::Char(' ') => {
-                    if let Some(pos) = selected_multi.iter().position(|&i| i == *selected) {
-                        selected_multi.remove(pos);
-                    } else {
-                        selected_multi.push(*selected);
+                    if let Some(g) = user_groups.get(*selected) {
+                        toggle_checked(selected_multi, &g.name);
                     }
                 }
+                KeyCode::Enter
+                    if selected_multi.is_empty()
+                        && app.empty_picker_enter == EmptyPickerEnter::RequireChecked =>
+                {
+                    require_checked_items(app)
+                }
                 KeyCode::Enter => {
                     if let Some(user) = app.users.get(app.selected_user_index) {
                         if !selected_multi.is_empty() {
                             // Collect group names, skipping primary group
-                            let mut names: Vec<String> = Vec::new();
-                            for idx in selected_multi.iter() {
-                                if let Some(g) = user_groups.get(*idx)
-                                    && g.gid != user.primary_gid
-                                {
-                                    names.push(g.name.clone());
-                                }
-                            }
+                            let names = crate::app::checked_items(
+                                user_groups
+                                    .iter()
+                                    .filter(|g| g.gid != user.primary_gid)
+                                    .map(|g| g.name.as_str()),
+                                selected_multi,
+                            );
                             if names.is_empty() {
-                                app.modal = Some(ModalState::Info {
+                                app.push_modal(ModalState::Info {
                                     message: "No valid groups selected (cannot remove primary)."
                                         .to_string(),
                                 });
@@ -1021,21 +698,11 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                                     username: user.name.clone(),
                                     groupnames: names,
                                 };
-                                if let Err(_e) = perform_pending_action(
-                                    app,
-                                    pending.clone(),
-                                    app.sudo_password.clone(),
-                                ) {
-                                    app.modal = Some(ModalState::SudoPrompt {
-                                        next: pending,
-                                        password: String::new(),
-                                        error: None,
-                                    });
-                                }
+                                confirm_then_run(app, pending, true);
                             }
                         } else if let Some(group) = user_groups.get(*selected) {
                             if group.gid == user.primary_gid {
-                                app.modal = Some(ModalState::Info {
+                                app.push_modal(ModalState::Info {
                                     message: "Cannot remove user from primary group.".to_string(),
                                 });
                             } else {
@@ -1043,17 +710,7 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                                     username: user.name.clone(),
                                     groupname: group.name.clone(),
                                 };
-                                if let Err(_e) = perform_pending_action(
-                                    app,
-                                    pending.clone(),
-                                    app.sudo_password.clone(),
-                                ) {
-                                    app.modal = Some(ModalState::SudoPrompt {
-                                        next: pending,
-                                        password: String::new(),
-                                        error: None,
-                                    });
-                                }
+                                confirm_then_run(app, pending, true);
                             }
                         } else {
                             close_modal(app);
@@ -1068,60 +725,122 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
         Some(ModalState::ModifyDetailsMenu { selected }) => match key.code {
             KeyCode::Esc => close_modal(app),
             KeyCode::Backspace => {
-                app.modal = Some(ModalState::ModifyMenu { selected: 2 });
+                back_modal(app);
             }
             KeyCode::Up | KeyCode::Char('k') => {
                 if *selected > 0 {
                     *selected -= 1;
                 } else {
-                    *selected = 2;
+                    *selected = 3;
                 }
             }
             KeyCode::Down | KeyCode::Char('j') => {
-                if *selected < 2 {
+                if *selected < 3 {
                     *selected += 1;
                 } else {
                     *selected = 0;
                 }
             }
             KeyCode::Enter => match *selected {
-                0 => {
-                    app.modal = Some(ModalState::ModifyTextInput {
-                        field: ModifyField::Username,
-                        value: String::new(),
-                    })
-                }
-                1 => {
-                    app.modal = Some(ModalState::ModifyTextInput {
-                        field: ModifyField::Fullname,
-                        value: String::new(),
-                    })
-                }
+                0 => app.push_modal(ModalState::ModifyTextInput {
+                    field: ModifyField::Username,
+                    value: String::new(),
+                    cursor: 0,
+                }),
+                1 => app.push_modal(ModalState::ModifyTextInput {
+                    field: ModifyField::Fullname,
+                    value: String::new(),
+                    cursor: 0,
+                }),
                 2 => {
-                    let adapter = crate::sys::SystemAdapter::new();
-                    let shells = adapter.list_shells().unwrap_or_default();
-                    app.modal = Some(ModalState::ModifyShell {
+                    let shells =
+                        crate::app::known_shells(app, &crate::sys::SystemAdapter::new()).to_vec();
+                    app.push_modal(ModalState::ModifyShell {
                         selected: 0,
                         offset: 0,
                         shells,
+                        filter: String::new(),
                     });
                 }
-                _ => {}
-            },
-            _ => {}
+                3 => {
+                    let gecos = app
+                        .users
+                        .get(app.selected_user_index)
+                        .and_then(|u| u.full_name.clone())
+                        .unwrap_or_default();
+                    let (fields, other) = crate::app::split_gecos(&gecos);
+                    let cursor = fields[0].chars().count();
+                    app.push_modal(ModalState::EditGecos {
+                        selected: 0,
+                        fields,
+                        other,
+                        cursor,
+                    });
+                }
+                _ => {}
+            },
+            _ => {}
+        },
+        Some(ModalState::EditGecos {
+            selected,
+            fields,
+            other,
+            cursor,
+        }) => match key.code {
+            KeyCode::Esc => close_modal(app),
+            KeyCode::Backspace if fields[*selected].is_empty() => back_modal(app),
+            KeyCode::Up | KeyCode::Down => {
+                if key.code == KeyCode::Up && *selected > 0 {
+                    *selected -= 1;
+                } else if key.code == KeyCode::Down && *selected + 1 < fields.len() {
+                    *selected += 1;
+                }
+                *cursor = fields[*selected].chars().count();
+            }
+            KeyCode::Enter => {
+                if let Some(user) = app.users.get(app.selected_user_index) {
+                    for field in fields.iter_mut() {
+                        *field = field.trim().to_string();
+                    }
+                    let pending = PendingAction::ChangeFullname {
+                        username: user.name.clone(),
+                        new_fullname: crate::app::join_gecos(fields, other),
+                    };
+                    run_pending_action(app, pending);
+                } else {
+                    close_modal(app);
+                }
+            }
+            // Commas separate the fields and colons the passwd columns
+            KeyCode::Char(',' | ':') => {}
+            _ => {
+                textinput::edit(&mut fields[*selected], cursor, key);
+            }
         },
         Some(ModalState::ModifyShell {
             selected,
             offset,
             shells,
+            filter,
         }) => {
-            let total = shells.len();
+            let matching = crate::app::matching_shells(shells, filter);
+            // "Other..." always follows the matches
+            let total = matching.len() + 1;
             match key.code {
                 KeyCode::Esc => close_modal(app),
+                KeyCode::Backspace if filter.is_empty() => back_modal(app),
                 KeyCode::Backspace => {
-                    app.modal = Some(ModalState::ModifyDetailsMenu { selected: 2 });
+                    filter.pop();
+                    *selected = 0;
+                    *offset = 0;
                 }
-                KeyCode::Up | KeyCode::Char('k') => {
+                // Letters narrow the list, so only the arrows move
+                KeyCode::Char(c) => {
+                    filter.push(c);
+                    *selected = 0;
+                    *offset = 0;
+                }
+                KeyCode::Up => {
                     if *selected > 0 {
                         *selected -= 1;
                         if *selected < *offset {
@@ -1132,7 +851,7 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                         *offset = *selected;
                     }
                 }
-                KeyCode::Down | KeyCode::Char('j') => {
+                KeyCode::Down => {
                     if *selected + 1 < total {
                         *selected += 1;
                     } else if total > 0 {
@@ -1140,6 +859,7 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                         *offset = 0;
                     }
                 }
+                KeyCode::Home | KeyCode::End => jump_to_edge(key.code, selected, offset, total),
                 KeyCode::PageUp => {
                     let step = 10usize;
                     if *selected >= step {
@@ -1155,24 +875,23 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                     let step = 10usize;
                     *selected = (*selected + step).min(total.saturating_sub(1));
                 }
+                KeyCode::Enter if *selected == matching.len() => {
+                    app.push_modal(ModalState::ModifyTextInput {
+                        field: ModifyField::Shell,
+                        value: String::new(),
+                        cursor: 0,
+                    });
+                }
                 KeyCode::Enter => {
                     if let (Some(user), Some(new_shell)) = (
                         app.users.get(app.selected_user_index),
-                        shells.get(*selected),
+                        matching.get(*selected),
                     ) {
                         let pending = PendingAction::ChangeShell {
                             username: user.name.clone(),
-                            new_shell: new_shell.clone(),
+                            new_shell: new_shell.to_string(),
                         };
-                        if let Err(_e) =
-                            perform_pending_action(app, pending.clone(), app.sudo_password.clone())
-                        {
-                            app.modal = Some(ModalState::SudoPrompt {
-                                next: pending,
-                                password: String::new(),
-                                error: None,
-                            });
-                        }
+                        run_pending_action(app, pending);
                     } else {
                         close_modal(app);
                     }
@@ -1180,8 +899,40 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                 _ => {}
             }
         }
-        Some(ModalState::ModifyTextInput { field, value }) => match key.code {
+        Some(ModalState::ModifyTextInput {
+            field,
+            value,
+            cursor,
+        }) => match key.code {
             KeyCode::Esc => close_modal(app),
+            KeyCode::Enter if matches!(field, ModifyField::Shell) => {
+                let path = value.clone();
+                if let Some(problem) = crate::app::custom_shell_problem(&path) {
+                    app.modal_notice = Some(problem);
+                } else if let Some(user) = app.users.get(app.selected_user_index) {
+                    let pending = PendingAction::ChangeShell {
+                        username: user.name.clone(),
+                        new_shell: path.clone(),
+                    };
+                    // The picker below this input holds the /etc/shells list
+                    let listed = app.modal_stack.iter().any(|m| {
+                        matches!(m, ModalState::ModifyShell { shells, .. } if shells.contains(&path))
+                    });
+                    if listed {
+                        run_pending_action(app, pending);
+                    } else {
+                        app.push_modal(ModalState::ConfirmAction {
+                            selected: 1,
+                            next: pending,
+                            warning: Some(format!(
+                                "{path} is not listed in /etc/shells; some programs (e.g. FTP or chsh) may refuse it."
+                            )),
+                        });
+                    }
+                } else {
+                    close_modal(app);
+                }
+            }
             KeyCode::Enter => {
                 if let Some(user) = app.users.get(app.selected_user_index) {
                     let pending = match field {
@@ -1191,45 +942,51 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                         },
                         ModifyField::Fullname => PendingAction::ChangeFullname {
                             username: user.name.clone(),
-                            new_fullname: value.clone(),
+                            new_fullname: value.trim().to_string(),
+                        },
+                        ModifyField::Shell => PendingAction::ChangeShell {
+                            username: user.name.clone(),
+                            new_shell: value.clone(),
                         },
                     };
-                    if let Err(_e) =
-                        perform_pending_action(app, pending.clone(), app.sudo_password.clone())
-                    {
-                        app.modal = Some(ModalState::SudoPrompt {
-                            next: pending,
-                            password: String::new(),
-                            error: None,
-                        });
-                    }
+                    run_pending_action(app, pending);
                 } else {
                     close_modal(app);
                 }
             }
-            KeyCode::Backspace => {
-                if value.is_empty() {
-                    app.modal = Some(ModalState::ModifyDetailsMenu { selected: 0 });
-                } else {
-                    value.pop();
-                }
+            KeyCode::Backspace if value.is_empty() => back_modal(app),
+            _ if matches!(field, ModifyField::Fullname) => {
+                textinput::edit(value, cursor, key);
             }
-            KeyCode::Char(c) => {
-                value.push(c);
+            _ => {
+                textinput::edit_identifier(value, cursor, key);
             }
-            _ => {}
         },
         Some(ModalState::DeleteConfirm {
             selected,
             allowed,
             delete_home,
+            home_size,
+            measuring,
         }) => match key.code {
             KeyCode::Esc => close_modal(app),
             KeyCode::Backspace => {
-                app.modal = Some(ModalState::Actions { selected: 1 });
+                back_modal(app);
             }
             KeyCode::Char(' ') => {
                 *delete_home = !*delete_home;
+                // Measured once when checked rather than on every frame, and
+                // off the UI thread since a large home can take a while
+                *home_size = None;
+                *measuring = false;
+                if *delete_home && let Some(user) = app.users.get(app.selected_user_index) {
+                    *measuring = true;
+                    let home = user.home_dir.clone();
+                    start_lookup(app, move || crate::app::Lookup::HomeSize {
+                        size: sys::home_dir_size(std::path::Path::new(&home)),
+                        home,
+                    });
+                }
             }
             KeyCode::Left | KeyCode::Right => {
                 *selected = if *selected == 0 { 1 } else { 0 };
@@ -1242,22 +999,12 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                                 username: user.name.clone(),
                                 delete_home: *delete_home,
                             };
-                            if let Err(_e) = perform_pending_action(
-                                app,
-                                pending.clone(),
-                                app.sudo_password.clone(),
-                            ) {
-                                app.modal = Some(ModalState::SudoPrompt {
-                                    next: pending,
-                                    password: String::new(),
-                                    error: None,
-                                });
-                            }
+                            confirm_then_run(app, pending, true);
                         } else {
                             close_modal(app);
                         }
                     } else {
-                        app.modal = Some(ModalState::Info {
+                        app.push_modal(ModalState::Info {
                             message: "Deletion not allowed.".to_string(),
                         });
                     }
@@ -1287,17 +1034,7 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                                 username: user.name.clone(),
                                 groupname: group_name.clone(),
                             };
-                            if let Err(_e) = perform_pending_action(
-                                app,
-                                pending.clone(),
-                                app.sudo_password.clone(),
-                            ) {
-                                app.modal = Some(ModalState::SudoPrompt {
-                                    next: pending,
-                                    password: String::new(),
-                                    error: None,
-                                });
-                            }
+                            confirm_then_run(app, pending, true);
                         }
                     } else {
                         close_modal(app);
@@ -1308,6 +1045,91 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
             }
             _ => {}
         },
+        Some(ModalState::MoveMemberPicker {
+            selected,
+            offset,
+            username,
+            from_group,
+        }) => {
+            let targets: Vec<String> = crate::app::move_member_targets(
+                &app.groups_all,
+                &app.users_all,
+                username,
+                from_group,
+            )
+            .into_iter()
+            .map(|g| g.name.clone())
+            .collect();
+            let total = targets.len();
+            match key.code {
+                KeyCode::Esc => close_modal(app),
+                KeyCode::Backspace => {
+                    back_modal(app);
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    if *selected > 0 {
+                        *selected -= 1;
+                    } else if total > 0 {
+                        *selected = total - 1;
+                    }
+                    if *selected < *offset {
+                        *offset = *selected;
+                    }
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    if *selected + 1 < total {
+                        *selected += 1;
+                    } else {
+                        *selected = 0;
+                        *offset = 0;
+                    }
+                }
+                KeyCode::Home | KeyCode::End | KeyCode::Char('g') | KeyCode::Char('G') => {
+                    jump_to_edge(key.code, selected, offset, total)
+                }
+                KeyCode::Enter => {
+                    if let Some(to_group) = targets.get(*selected).cloned() {
+                        let username = username.clone();
+                        let from_group = from_group.clone();
+                        app.push_modal(ModalState::MoveMemberConfirm {
+                            selected: 1,
+                            username,
+                            from_group,
+                            to_group,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(ModalState::MoveMemberConfirm {
+            selected,
+            username,
+            from_group,
+            to_group,
+        }) => match key.code {
+            KeyCode::Esc => close_modal(app),
+            KeyCode::Backspace => {
+                back_modal(app);
+            }
+            KeyCode::Left | KeyCode::Right => {
+                *selected = if *selected == 0 { 1 } else { 0 };
+            }
+            KeyCode::Enter => {
+                if *selected == 0 {
+                    let pending = PendingAction::MoveUserBetweenGroups {
+                        username: username.clone(),
+                        from_group: from_group.clone(),
+                        to_group: to_group.clone(),
+                    };
+                    app.actions_context = None;
+                    run_pending_action(app, pending);
+                } else {
+                    back_modal(app);
+                }
+            }
+            _ => {}
+        },
         Some(ModalState::GroupsActions {
             selected,
             target_gid,
@@ -1315,7 +1137,7 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
             KeyCode::Esc => close_modal(app),
             KeyCode::Backspace => close_modal(app),
             KeyCode::Up | KeyCode::Char('k') => {
-                let max_index = if target_gid.is_some() { 1 } else { 2 };
+                let max_index = if target_gid.is_some() { 3 } else { 4 };
                 if *selected > 0 {
                     *selected -= 1;
                 } else {
@@ -1323,7 +1145,7 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                 }
             }
             KeyCode::Down | KeyCode::Char('j') => {
-                let max_index = if target_gid.is_some() { 1 } else { 2 };
+                let max_index = if target_gid.is_some() { 3 } else { 4 };
                 if *selected < max_index {
                     *selected += 1;
                 } else {
@@ -1332,119 +1154,222 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
             }
             KeyCode::Enter => {
                 if target_gid.is_some() {
-                    // Options: 0 => Modify group, 1 => Remove group
+                    // Options: 0 => Modify group, 1 => Remove group, 2 => Clone group,
+                    // 3 => Merge into another group
                     match *selected {
                         0 => {
-                            app.modal = Some(ModalState::GroupModifyMenu {
+                            let target_gid = *target_gid;
+                            app.push_modal(ModalState::GroupModifyMenu {
                                 selected: 0,
-                                target_gid: *target_gid,
+                                target_gid,
                             });
                         }
                         1 => {
-                            app.modal = Some(ModalState::GroupDeleteConfirm {
-                                selected: 1,
-                                target_gid: *target_gid,
-                            });
+                            let target_gid = *target_gid;
+                            let modal = group_delete_modal(app, target_gid);
+                            open_confirm(app, modal, true);
+                        }
+                        2 => {
+                            let gid = *target_gid;
+                            open_group_clone_input(app, gid);
+                        }
+                        3 => {
+                            let gid = *target_gid;
+                            open_group_merge_picker(app, gid);
                         }
                         _ => {}
                     }
                 } else {
-                    // Options: 0 => Add group, 1 => Remove group, 2 => Modify group (members)
+                    // Options: 0 => Add group, 1 => Remove group, 2 => Modify group (members),
+                    // 3 => Clone group, 4 => Merge into another group
                     match *selected {
-                        0 => {
-                            app.modal = Some(ModalState::GroupAddInput {
-                                name: String::new(),
-                            })
-                        }
+                        0 => app.push_modal(ModalState::GroupAddInput {
+                            name: String::new(),
+                            cursor: 0,
+                        }),
                         1 => {
                             let gid = app.groups.get(app.selected_group_index).map(|g| g.gid);
-                            app.modal = Some(ModalState::GroupDeleteConfirm {
-                                selected: 1,
-                                target_gid: gid,
-                            });
+                            let modal = group_delete_modal(app, gid);
+                            open_confirm(app, modal, true);
                         }
                         2 => {
-                            app.modal = Some(ModalState::GroupModifyMenu {
+                            let target_gid = *target_gid;
+                            app.push_modal(ModalState::GroupModifyMenu {
                                 selected: 0,
-                                target_gid: *target_gid,
+                                target_gid,
                             })
                         }
+                        3 => open_group_clone_input(app, None),
+                        4 => open_group_merge_picker(app, None),
                         _ => {}
                     }
                 }
             }
             _ => {}
         },
-        Some(ModalState::GroupAddInput { name }) => match key.code {
+        Some(ModalState::GroupAddInput { name, cursor }) => match key.code {
             KeyCode::Esc => close_modal(app),
+            // As in the other forms, Down moves on to the next field: the
+            // members the group is created with
+            KeyCode::Down if !name.is_empty() => {
+                let new_group = Some(name.clone());
+                app.push_modal(ModalState::GroupModifyAddMembers {
+                    selected: 0,
+                    offset: 0,
+                    target_gid: None,
+                    selected_multi: BTreeSet::new(),
+                    new_group,
+                });
+            }
             KeyCode::Enter => {
                 let pending = PendingAction::CreateGroup {
                     groupname: name.clone(),
                 };
-                if let Err(_e) =
-                    perform_pending_action(app, pending.clone(), app.sudo_password.clone())
-                {
-                    app.modal = Some(ModalState::SudoPrompt {
-                        next: pending,
-                        password: String::new(),
-                        error: None,
-                    });
-                }
+                run_pending_action(app, pending);
             }
-            KeyCode::Backspace => {
-                if name.is_empty() {
-                    app.modal = Some(ModalState::GroupsActions {
-                        selected: 0,
-                        target_gid: None,
-                    });
-                } else {
-                    name.pop();
+            KeyCode::Backspace if name.is_empty() => back_modal(app),
+            _ => {
+                textinput::edit_identifier(name, cursor, key);
+            }
+        },
+        Some(ModalState::GroupCloneInput {
+            source,
+            name,
+            cursor,
+        }) => match key.code {
+            KeyCode::Esc => close_modal(app),
+            KeyCode::Enter if !name.is_empty() => {
+                let source = app.groups_all.iter().find(|g| g.name == *source).cloned();
+                let pending = source.map(|g| crate::app::clone_group_action(&g, name));
+                match pending {
+                    Some(pending) => run_pending_action(app, pending),
+                    None => close_modal(app),
                 }
             }
-            KeyCode::Char(c) => {
-                name.push(c);
+            KeyCode::Backspace if name.is_empty() => back_modal(app),
+            _ => {
+                textinput::edit_identifier(name, cursor, key);
             }
-            _ => {}
         },
+        Some(ModalState::GroupDeleteReassign {
+            selected,
+            offset,
+            target_gid,
+        }) => {
+            let targets: Vec<String> = crate::app::reassign_targets(&app.groups_all, *target_gid)
+                .into_iter()
+                .map(|g| g.name.clone())
+                .collect();
+            let total = targets.len();
+            match key.code {
+                KeyCode::Esc => close_modal(app),
+                KeyCode::Backspace => {
+                    back_modal(app);
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    if *selected > 0 {
+                        *selected -= 1;
+                    } else if total > 0 {
+                        *selected = total - 1;
+                    }
+                    if *selected < *offset {
+                        *offset = *selected;
+                    }
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    if *selected + 1 < total {
+                        *selected += 1;
+                    } else {
+                        *selected = 0;
+                        *offset = 0;
+                    }
+                }
+                KeyCode::Home | KeyCode::End | KeyCode::Char('g') | KeyCode::Char('G') => {
+                    jump_to_edge(key.code, selected, offset, total)
+                }
+                KeyCode::Enter => {
+                    if let Some(fallback) = targets.get(*selected).cloned() {
+                        let target_gid = Some(*target_gid);
+                        let modal = ModalState::GroupDeleteConfirm {
+                            selected: 1,
+                            target_gid,
+                            reassign_to: Some(fallback),
+                        };
+                        open_confirm(app, modal, true);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(ModalState::MergeGroup {
+            selected,
+            offset,
+            source_gid,
+        }) => {
+            let targets: Vec<String> = crate::app::reassign_targets(&app.groups_all, *source_gid)
+                .into_iter()
+                .map(|g| g.name.clone())
+                .collect();
+            let total = targets.len();
+            match key.code {
+                KeyCode::Esc => close_modal(app),
+                KeyCode::Backspace => {
+                    back_modal(app);
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    if *selected > 0 {
+                        *selected -= 1;
+                    } else if total > 0 {
+                        *selected = total - 1;
+                    }
+                    if *selected < *offset {
+                        *offset = *selected;
+                    }
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    if *selected + 1 < total {
+                        *selected += 1;
+                    } else {
+                        *selected = 0;
+                        *offset = 0;
+                    }
+                }
+                KeyCode::Home | KeyCode::End | KeyCode::Char('g') | KeyCode::Char('G') => {
+                    jump_to_edge(key.code, selected, offset, total)
+                }
+                KeyCode::Enter => {
+                    let source_gid = *source_gid;
+                    if let Some(destination) = targets.get(*selected).cloned() {
+                        confirm_group_merge(app, source_gid, &destination);
+                    }
+                }
+                _ => {}
+            }
+        }
         Some(ModalState::GroupDeleteConfirm {
             selected,
             target_gid,
+            reassign_to,
         }) => match key.code {
             KeyCode::Esc => close_modal(app),
             KeyCode::Backspace => {
-                app.modal = Some(ModalState::GroupsActions {
-                    selected: 1,
-                    target_gid: *target_gid,
-                });
+                back_modal(app);
             }
             KeyCode::Left | KeyCode::Right => {
                 *selected = if *selected == 0 { 1 } else { 0 };
             }
             KeyCode::Enter => {
                 if *selected == 0 {
-                    let group_name_opt = if let Some(gid) = *target_gid {
-                        app.groups
-                            .iter()
-                            .find(|g| g.gid == gid)
-                            .map(|g| g.name.clone())
+                    let group = if let Some(gid) = *target_gid {
+                        app.groups.iter().find(|g| g.gid == gid)
                     } else {
-                        app.groups
-                            .get(app.selected_group_index)
-                            .map(|g| g.name.clone())
+                        app.groups.get(app.selected_group_index)
                     };
-                    if let Some(group_name) = group_name_opt {
-                        let pending = PendingAction::DeleteGroup {
-                            groupname: group_name.clone(),
-                        };
-                        if let Err(_e) =
-                            perform_pending_action(app, pending.clone(), app.sudo_password.clone())
-                        {
-                            app.modal = Some(ModalState::SudoPrompt {
-                                next: pending,
-                                password: String::new(),
-                                error: None,
-                            });
-                        }
+                    let pending = group.map(|g| {
+                        crate::app::group_delete_action(&app.users_all, g, reassign_to.as_deref())
+                    });
+                    if let Some(pending) = pending {
+                        confirm_then_run(app, pending, true);
                     } else {
                         close_modal(app);
                     }
@@ -1460,10 +1385,7 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
         }) => match key.code {
             KeyCode::Esc => close_modal(app),
             KeyCode::Backspace => {
-                app.modal = Some(ModalState::GroupsActions {
-                    selected: 2,
-                    target_gid: *target_gid,
-                });
+                back_modal(app);
             }
             KeyCode::Up | KeyCode::Char('k') => {
                 if *selected > 0 {
@@ -1481,19 +1403,22 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
             }
             KeyCode::Enter => match *selected {
                 0 => {
-                    app.modal = Some(ModalState::GroupModifyAddMembers {
+                    let target_gid = *target_gid;
+                    app.push_modal(ModalState::GroupModifyAddMembers {
                         selected: 0,
                         offset: 0,
-                        target_gid: *target_gid,
-                        selected_multi: Vec::new(),
+                        target_gid,
+                        selected_multi: BTreeSet::new(),
+                        new_group: None,
                     })
                 }
                 1 => {
-                    app.modal = Some(ModalState::GroupModifyRemoveMembers {
+                    let target_gid = *target_gid;
+                    app.push_modal(ModalState::GroupModifyRemoveMembers {
                         selected: 0,
                         offset: 0,
-                        target_gid: *target_gid,
-                        selected_multi: Vec::new(),
+                        target_gid,
+                        selected_multi: BTreeSet::new(),
                     })
                 }
                 2 => {
@@ -1512,16 +1437,18 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                             .find(|g| g.gid == effective_gid)
                             .map(|g| g.name.clone())
                             .unwrap_or_else(|| "<unknown>".to_string());
-                        app.modal = Some(ModalState::Info {
+                        app.push_modal(ModalState::Info {
                             message: format!(
                                 "Renaming system groups is disabled ({}: GID {}).",
                                 gname, effective_gid
                             ),
                         });
                     } else {
-                        app.modal = Some(ModalState::GroupRenameInput {
+                        let target_gid = *target_gid;
+                        app.push_modal(ModalState::GroupRenameInput {
                             name: String::new(),
-                            target_gid: *target_gid,
+                            cursor: 0,
+                            target_gid,
                         });
                     }
                 }
@@ -1529,21 +1456,13 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
             },
             _ => {}
         },
-        Some(ModalState::GroupRenameInput { name, target_gid }) => match key.code {
+        Some(ModalState::GroupRenameInput {
+            name,
+            cursor,
+            target_gid,
+        }) => match key.code {
             KeyCode::Esc => close_modal(app),
-            KeyCode::Backspace => {
-                if name.is_empty() {
-                    app.modal = Some(ModalState::GroupModifyMenu {
-                        selected: 2,
-                        target_gid: *target_gid,
-                    });
-                } else {
-                    name.pop();
-                }
-            }
-            KeyCode::Char(c) => {
-                name.push(c);
-            }
+            KeyCode::Backspace if name.is_empty() => back_modal(app),
             KeyCode::Enter => {
                 let (old_opt, gid_opt) = if let Some(gid) = *target_gid {
                     (
@@ -1566,7 +1485,7 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                         .find(|g| g.gid == gid)
                         .map(|g| g.name.clone())
                         .unwrap_or_else(|| "<unknown>".to_string());
-                    app.modal = Some(ModalState::Info {
+                    app.push_modal(ModalState::Info {
                         message: format!(
                             "Renaming system groups is disabled ({}: GID {}).",
                             gname, gid
@@ -1575,46 +1494,45 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                     return;
                 }
 
-                if let Some(old) = old_opt {
+                if let (Some(old), Some(gid)) = (old_opt, gid_opt) {
                     if name.trim().is_empty() {
-                        app.modal = Some(ModalState::Info {
+                        app.push_modal(ModalState::Info {
                             message: "Group name cannot be empty".to_string(),
                         });
                     } else {
+                        let sudoers = sys::sudoers_files_naming_group(&old);
+                        let warning = Some(rename_group_warning(&old, gid, &sudoers));
                         let pending = PendingAction::RenameGroup {
                             old_name: old,
                             new_name: name.trim().to_string(),
                         };
-                        if let Err(_e) =
-                            perform_pending_action(app, pending.clone(), app.sudo_password.clone())
-                        {
-                            app.modal = Some(ModalState::SudoPrompt {
-                                next: pending,
-                                password: String::new(),
-                                error: None,
-                            });
-                        }
+                        app.push_modal(ModalState::ConfirmAction {
+                            selected: 1,
+                            next: pending,
+                            warning,
+                        });
                     }
                 } else {
                     close_modal(app);
                 }
             }
-            _ => {}
+            _ => {
+                textinput::edit_identifier(name, cursor, key);
+            }
         },
         Some(ModalState::GroupModifyAddMembers {
             selected,
             offset,
             target_gid,
             selected_multi,
+            new_group,
         }) => {
-            let total = app.users_all.len();
+            let listed: Vec<&str> = app.users_all.iter().map(|u| u.name.as_str()).collect();
+            let total = listed.len();
             match key.code {
                 KeyCode::Esc => close_modal(app),
                 KeyCode::Backspace => {
-                    app.modal = Some(ModalState::GroupModifyMenu {
-                        selected: 0,
-                        target_gid: *target_gid,
-                    });
+                    back_modal(app);
                 }
                 KeyCode::Up | KeyCode::Char('k') => {
                     if *selected > 0 {
@@ -1635,6 +1553,9 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                         *offset = 0;
                     }
                 }
+                KeyCode::Home | KeyCode::End | KeyCode::Char('g') | KeyCode::Char('G') => {
+                    jump_to_edge(key.code, selected, offset, total)
+                }
                 KeyCode::PageUp => {
                     let step = 10usize;
                     if *selected >= step {
@@ -1650,11 +1571,40 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                     let step = 10usize;
                     *selected = (*selected + step).min(total.saturating_sub(1));
                 }
+                KeyCode::Char('a') | KeyCode::Char('d')
+                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    bulk_select(key.code, selected_multi, listed.iter().copied());
+                }
+                KeyCode::Char('*') => invert_checked(selected_multi, &listed),
                 KeyCode::Char(' ') => {
-                    if let Some(pos) = selected_multi.iter().position(|&i| i == *selected) {
-                        selected_multi.remove(pos);
+                    if let Some(name) = listed.get(*selected) {
+                        toggle_checked(selected_multi, name);
+                    }
+                }
+                KeyCode::Enter
+                    if selected_multi.is_empty()
+                        && app.empty_picker_enter == EmptyPickerEnter::RequireChecked =>
+                {
+                    require_checked_items(app)
+                }
+                KeyCode::Enter if new_group.is_some() => {
+                    let groupname = new_group.clone().unwrap_or_default();
+                    let members = if selected_multi.is_empty() {
+                        listed.get(*selected).map(|n| vec![n.to_string()])
                     } else {
-                        selected_multi.push(*selected);
+                        Some(crate::app::checked_items(
+                            listed.iter().copied(),
+                            selected_multi,
+                        ))
+                    };
+                    match members {
+                        Some(members) if !members.is_empty() => confirm_then_run(
+                            app,
+                            PendingAction::CreateGroupWithMembers { groupname, members },
+                            true,
+                        ),
+                        _ => run_pending_action(app, PendingAction::CreateGroup { groupname }),
                     }
                 }
                 KeyCode::Enter => {
@@ -1670,30 +1620,18 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                     };
                     if let Some(group_name) = group_name {
                         if !selected_multi.is_empty() {
-                            let mut usernames: Vec<String> =
-                                Vec::with_capacity(selected_multi.len());
-                            for idx in selected_multi.iter() {
-                                if let Some(u) = app.users_all.get(*idx) {
-                                    usernames.push(u.name.clone());
-                                }
-                            }
+                            let usernames =
+                                crate::app::checked_items(listed.iter().copied(), selected_multi);
                             if !usernames.is_empty() {
                                 let pending = PendingAction::AddMembersToGroup {
                                     groupname: group_name.clone(),
                                     usernames,
                                 };
-                                if let Err(_e) = perform_pending_action(
-                                    app,
-                                    pending.clone(),
-                                    app.sudo_password.clone(),
-                                ) {
-                                    app.modal = Some(ModalState::SudoPrompt {
-                                        next: pending,
-                                        password: String::new(),
-                                        error: None,
-                                    });
-                                }
-                            } else {
+                                app.push_modal(ModalState::MembershipPreview {
+                                    next: pending,
+                                    scroll: 0,
+                                });
+                            } else {
                                 close_modal(app);
                             }
                         } else if let Some(user_name) =
@@ -1703,17 +1641,7 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                                 username: user_name.clone(),
                                 groupname: group_name.clone(),
                             };
-                            if let Err(_e) = perform_pending_action(
-                                app,
-                                pending.clone(),
-                                app.sudo_password.clone(),
-                            ) {
-                                app.modal = Some(ModalState::SudoPrompt {
-                                    next: pending,
-                                    password: String::new(),
-                                    error: None,
-                                });
-                            }
+                            confirm_then_run(app, pending, true);
                         } else {
                             close_modal(app);
                         }
@@ -1758,10 +1686,7 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
             match key.code {
                 KeyCode::Esc => close_modal(app),
                 KeyCode::Backspace => {
-                    app.modal = Some(ModalState::GroupModifyMenu {
-                        selected: 1,
-                        target_gid: *target_gid,
-                    });
+                    back_modal(app);
                 }
                 KeyCode::Up | KeyCode::Char('k') => {
                     if *selected > 0 {
@@ -1782,6 +1707,9 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                         *offset = 0;
                     }
                 }
+                KeyCode::Home | KeyCode::End | KeyCode::Char('g') | KeyCode::Char('G') => {
+                    jump_to_edge(key.code, selected, offset, total)
+                }
                 KeyCode::PageUp => {
                     let step = 10usize;
                     if *selected >= step {
@@ -1797,13 +1725,26 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                     let step = 10usize;
                     *selected = (*selected + step).min(total.saturating_sub(1));
                 }
+                KeyCode::Char('a') | KeyCode::Char('d')
+                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    bulk_select(key.code, selected_multi, members.iter().map(String::as_str));
+                }
+                KeyCode::Char('*') => {
+                    let listed: Vec<&str> = members.iter().map(String::as_str).collect();
+                    invert_checked(selected_multi, &listed);
+                }
                 KeyCode::Char(' ') => {
-                    if let Some(pos) = selected_multi.iter().position(|&i| i == *selected) {
-                        selected_multi.remove(pos);
-                    } else {
-                        selected_multi.push(*selected);
+                    if let Some(name) = members.get(*selected) {
+                        toggle_checked(selected_multi, name);
                     }
                 }
+                KeyCode::Enter
+                    if selected_multi.is_empty()
+                        && app.empty_picker_enter == EmptyPickerEnter::RequireChecked =>
+                {
+                    require_checked_items(app)
+                }
                 KeyCode::Enter => {
                     let gname_opt = if let Some(gid) = *target_gid {
                         app.groups
@@ -1815,29 +1756,19 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                     };
                     if let Some(group_name) = gname_opt {
                         if !selected_multi.is_empty() {
-                            let mut usernames: Vec<String> =
-                                Vec::with_capacity(selected_multi.len());
-                            for idx in selected_multi.iter() {
-                                if let Some(u) = members.get(*idx) {
-                                    usernames.push(u.clone());
-                                }
-                            }
+                            let usernames = crate::app::checked_items(
+                                members.iter().map(String::as_str),
+                                selected_multi,
+                            );
                             if !usernames.is_empty() {
                                 let pending = PendingAction::RemoveMembersFromGroup {
                                     groupname: group_name.clone(),
                                     usernames,
                                 };
-                                if let Err(_e) = perform_pending_action(
-                                    app,
-                                    pending.clone(),
-                                    app.sudo_password.clone(),
-                                ) {
-                                    app.modal = Some(ModalState::SudoPrompt {
-                                        next: pending,
-                                        password: String::new(),
-                                        error: None,
-                                    });
-                                }
+                                app.push_modal(ModalState::MembershipPreview {
+                                    next: pending,
+                                    scroll: 0,
+                                });
                             } else {
                                 close_modal(app);
                             }
@@ -1846,17 +1777,7 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                                 username: username.clone(),
                                 groupname: group_name.clone(),
                             };
-                            if let Err(_e) = perform_pending_action(
-                                app,
-                                pending.clone(),
-                                app.sudo_password.clone(),
-                            ) {
-                                app.modal = Some(ModalState::SudoPrompt {
-                                    next: pending,
-                                    password: String::new(),
-                                    error: None,
-                                });
-                            }
+                            confirm_then_run(app, pending, true);
                         } else {
                             close_modal(app);
                         }
@@ -1867,48 +1788,40 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                 _ => {}
             }
         }
+        Some(ModalState::ImportPathInput { path, cursor }) => match key.code {
+            KeyCode::Esc => close_modal(app),
+            KeyCode::Backspace if path.is_empty() => back_modal(app),
+            KeyCode::Enter => {
+                let path = path.trim().to_string();
+                start_import(app, &path);
+            }
+            _ => {
+                textinput::edit(path, cursor, key);
+            }
+        },
         Some(ModalState::UserAddInput {
             selected,
             name,
             password,
             confirm,
+            cursor,
             create_home,
             add_to_wheel,
         }) => match key.code {
             KeyCode::Esc => close_modal(app),
-            KeyCode::Up => {
-                if *selected > 0 {
+            KeyCode::Up | KeyCode::Down => {
+                if key.code == KeyCode::Up && *selected > 0 {
                     *selected -= 1;
-                }
-            }
-            KeyCode::Down => {
-                if *selected < 5 {
+                } else if key.code == KeyCode::Down && *selected < 5 {
                     *selected += 1;
                 }
+                *cursor = user_add_field(*selected, name, password, confirm)
+                    .map(|f| f.chars().count())
+                    .unwrap_or(0);
             }
-            KeyCode::Backspace => match *selected {
-                0 => {
-                    if name.is_empty() {
-                        close_modal(app);
-                    } else {
-                        name.pop();
-                    }
-                }
-                1 => {
-                    if password.is_empty() {
-                        close_modal(app);
-                    } else {
-                        password.pop();
-                    }
-                }
-                2 => {
-                    if confirm.is_empty() {
-                        close_modal(app);
-                    } else {
-                        confirm.pop();
-                    }
-                }
-                _ => {}
+            KeyCode::Backspace => match user_add_field(*selected, name, password, confirm) {
+                Some(field) if !field.is_empty() => textinput::backspace(field, cursor),
+                _ => close_modal(app),
             },
             KeyCode::Char(' ') => match *selected {
                 3 => {
@@ -1919,76 +1832,128 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
                 }
                 _ => {}
             },
-            KeyCode::Char(c) => match *selected {
-                0 => name.push(c),
-                1 => password.push(c),
-                2 => confirm.push(c),
-                _ => {}
+            KeyCode::Enter if *selected == 5 => {
+                let uname = name.trim().to_string();
+                if uname.is_empty() {
+                    app.push_modal(ModalState::Info {
+                        message: "Username cannot be empty".to_string(),
+                    });
+                } else if (!password.is_empty() || !confirm.is_empty()) && *password != *confirm {
+                    app.push_modal(ModalState::Info {
+                        message: "Passwords do not match".to_string(),
+                    });
+                } else if let Some(message) =
+                    crate::app::password_length_problem(password, app.min_password_length)
+                {
+                    app.push_modal(ModalState::Info { message });
+                } else {
+                    let pending = PendingAction::CreateUserWithOptions {
+                        username: uname,
+                        password: if password.is_empty() {
+                            None
+                        } else {
+                            Some(password.clone())
+                        },
+                        create_home: *create_home,
+                        add_to_wheel: *add_to_wheel,
+                    };
+                    confirm_then_run(app, pending, true);
+                }
+            }
+            _ => {
+                let identifier = *selected == 0;
+                if let Some(field) = user_add_field(*selected, name, password, confirm) {
+                    if identifier {
+                        textinput::edit_identifier(field, cursor, key);
+                    } else {
+                        textinput::edit(field, cursor, key);
+                    }
+                }
+            }
+        },
+        Some(ModalState::CloneUser {
+            template,
+            shell,
+            groups,
+            selected,
+            name,
+            password,
+            confirm,
+            cursor,
+            create_home,
+        }) => match key.code {
+            KeyCode::Esc => close_modal(app),
+            KeyCode::Up | KeyCode::Down => {
+                if key.code == KeyCode::Up && *selected > 0 {
+                    *selected -= 1;
+                } else if key.code == KeyCode::Down && *selected < 4 {
+                    *selected += 1;
+                }
+                *cursor = user_add_field(*selected, name, password, confirm)
+                    .map(|f| f.chars().count())
+                    .unwrap_or(0);
+            }
+            KeyCode::Backspace => match user_add_field(*selected, name, password, confirm) {
+                Some(field) if !field.is_empty() => textinput::backspace(field, cursor),
+                _ => back_modal(app),
             },
-            KeyCode::Enter => {
-                if *selected == 5 {
-                    let uname = name.trim().to_string();
-                    if uname.is_empty() {
-                        app.modal = Some(ModalState::Info {
-                            message: "Username cannot be empty".to_string(),
-                        });
-                    } else if (!password.is_empty() || !confirm.is_empty()) && *password != *confirm
-                    {
-                        app.modal = Some(ModalState::Info {
-                            message: "Passwords do not match".to_string(),
-                        });
+            KeyCode::Char(' ') if *selected == 3 => {
+                *create_home = !*create_home;
+            }
+            KeyCode::Enter if *selected == 4 => {
+                let uname = name.trim().to_string();
+                if uname.is_empty() {
+                    app.push_modal(ModalState::Info {
+                        message: "Username cannot be empty".to_string(),
+                    });
+                } else if (!password.is_empty() || !confirm.is_empty()) && *password != *confirm {
+                    app.push_modal(ModalState::Info {
+                        message: "Passwords do not match".to_string(),
+                    });
+                } else if let Some(message) =
+                    crate::app::password_length_problem(password, app.min_password_length)
+                {
+                    app.push_modal(ModalState::Info { message });
+                } else {
+                    let pending = PendingAction::CloneUser {
+                        template: template.clone(),
+                        username: uname,
+                        password: (!password.is_empty()).then(|| password.clone()),
+                        create_home: *create_home,
+                        shell: shell.clone(),
+                        groupnames: groups.clone(),
+                    };
+                    confirm_then_run(app, pending, true);
+                }
+            }
+            _ => {
+                let identifier = *selected == 0;
+                if let Some(field) = user_add_field(*selected, name, password, confirm) {
+                    if identifier {
+                        textinput::edit_identifier(field, cursor, key);
                     } else {
-                        let pending = PendingAction::CreateUserWithOptions {
-                            username: uname,
-                            password: if password.is_empty() {
-                                None
-                            } else {
-                                Some(password.clone())
-                            },
-                            create_home: *create_home,
-                            add_to_wheel: *add_to_wheel,
-                        };
-                        if let Err(_e) =
-                            perform_pending_action(app, pending.clone(), app.sudo_password.clone())
-                        {
-                            app.modal = Some(ModalState::SudoPrompt {
-                                next: pending,
-                                password: String::new(),
-                                error: None,
-                            });
-                        }
+                        textinput::edit(field, cursor, key);
                     }
                 }
             }
-            _ => {}
         },
         Some(ModalState::SudoPrompt {
             next,
             password,
             error: _,
+            ..
         }) => match key.code {
             KeyCode::Esc => close_modal(app),
             KeyCode::Backspace => {
                 if password.is_empty() {
-                    close_modal(app);
+                    back_modal(app);
                 } else {
                     password.pop();
                 }
             }
             KeyCode::Enter => {
-                let pw = password.clone();
-                app.sudo_password = Some(pw.clone());
-                let pending = next.clone();
-                match perform_pending_action(app, pending.clone(), Some(pw)) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        app.modal = Some(ModalState::SudoPrompt {
-                            next: pending,
-                            password: String::new(),
-                            error: Some(e.to_string()),
-                        });
-                    }
-                }
+                app.sudo_password = Some(password.clone());
+                app.queued_action = Some(next.clone());
             }
             KeyCode::Char(c) => {
                 password.push(c);
@@ -1997,511 +1962,4544 @@ fn handle_modal_key(app: &mut AppState, key: KeyEvent) {
         },
         Some(ModalState::Info { .. }) => match key.code {
             KeyCode::Esc | KeyCode::Enter => close_modal(app),
+            KeyCode::Backspace => back_modal(app),
             _ => {}
         },
-        Some(ModalState::Help { scroll }) => match key.code {
-            KeyCode::Esc | KeyCode::Enter => close_modal(app),
-            KeyCode::Up => {
-                let s = scroll.saturating_sub(1);
-                app.modal = Some(ModalState::Help { scroll: s });
+        Some(ModalState::ConfirmAction { selected, next, .. }) => match key.code {
+            KeyCode::Esc => close_modal(app),
+            KeyCode::Backspace => back_modal(app),
+            KeyCode::Left | KeyCode::Right => {
+                *selected = if *selected == 0 { 1 } else { 0 };
             }
-            KeyCode::Down => {
-                let s = scroll.saturating_add(1);
-                app.modal = Some(ModalState::Help { scroll: s });
+            KeyCode::Enter if *selected == 0 => {
+                let pending = next.clone();
+                run_pending_action(app, pending);
+            }
+            KeyCode::Enter => close_modal(app),
+            _ => {}
+        },
+        Some(ModalState::MembershipPreview { next, scroll }) => match key.code {
+            KeyCode::Esc | KeyCode::Backspace => back_modal(app),
+            KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                let last = crate::app::membership_preview(next).len().saturating_sub(1);
+                *scroll = (*scroll + 1).min(last as u16);
+            }
+            KeyCode::Enter => {
+                let pending = next.clone();
+                confirm_then_run(app, pending, true);
+            }
+            _ => {}
+        },
+        Some(ModalState::TypedConfirm {
+            next,
+            keyword,
+            input,
+            cursor,
+        }) => match key.code {
+            KeyCode::Esc => close_modal(app),
+            KeyCode::Backspace if input.is_empty() => back_modal(app),
+            KeyCode::Enter if input == keyword => {
+                let pending = next.clone();
+                run_pending_action(app, pending);
+            }
+            KeyCode::Enter => {
+                app.modal_notice = Some(format!("Type '{keyword}' exactly to confirm"));
+            }
+            _ => {
+                textinput::edit(input, cursor, key);
+            }
+        },
+        Some(ModalState::UserDetails)
+            if matches!(key.code, KeyCode::Char('c') | KeyCode::Char('o')) =>
+        {
+            user_home_key(app, key.code)
+        }
+        Some(ModalState::GroupDetails) if key.code == KeyCode::Char('o') => {
+            open_orphan_members(app)
+        }
+        Some(ModalState::OrphanMembers {
+            selected,
+            group_name,
+            orphans,
+        }) => match key.code {
+            KeyCode::Esc => close_modal(app),
+            KeyCode::Backspace => back_modal(app),
+            KeyCode::Up | KeyCode::Char('k') if !orphans.is_empty() => {
+                *selected = selected.checked_sub(1).unwrap_or(orphans.len() - 1);
+            }
+            KeyCode::Down | KeyCode::Char('j') if !orphans.is_empty() => {
+                *selected = (*selected + 1) % orphans.len();
+            }
+            KeyCode::Home | KeyCode::Char('g') => *selected = 0,
+            KeyCode::End | KeyCode::Char('G') => *selected = orphans.len().saturating_sub(1),
+            KeyCode::Enter => {
+                if let Some(username) = orphans.get(*selected) {
+                    let pending = PendingAction::RemoveUserFromGroup {
+                        username: username.clone(),
+                        groupname: group_name.clone(),
+                    };
+                    confirm_then_run(app, pending, true);
+                }
+            }
+            _ => {}
+        },
+        Some(ModalState::UserDetails | ModalState::GroupDetails) => match key.code {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Backspace | KeyCode::Char('q') => {
+                close_modal(app)
+            }
+            _ if app.keymap.resolve(&key) == Some(KeyAction::ShowDetails) => close_modal(app),
+            _ => {}
+        },
+        Some(ModalState::Help {
+            scroll,
+            query,
+            searching: searching @ true,
+        }) => match key.code {
+            KeyCode::Esc | KeyCode::Enter => *searching = false,
+            KeyCode::Backspace if query.is_empty() => *searching = false,
+            KeyCode::Backspace => {
+                query.pop();
+            }
+            KeyCode::Char(c) => {
+                query.push(c);
+                if let Some(&first) = crate::ui::components::help_matches(query).first() {
+                    *scroll = first;
+                }
+            }
+            _ => {}
+        },
+        Some(ModalState::Help {
+            scroll,
+            query,
+            searching,
+        }) => match key.code {
+            KeyCode::Char('/') => {
+                query.clear();
+                *searching = true;
+            }
+            // Next match below the top line, wrapping around to the first
+            KeyCode::Char('n') => {
+                let matches = crate::ui::components::help_matches(query);
+                if let Some(&next) = matches.iter().find(|&&i| i > *scroll).or(matches.first()) {
+                    *scroll = next;
+                }
             }
-            KeyCode::PageUp => {
-                let s = scroll.saturating_sub(10);
-                app.modal = Some(ModalState::Help { scroll: s });
+            KeyCode::Esc | KeyCode::Enter => close_modal(app),
+            KeyCode::Backspace => back_modal(app),
+            KeyCode::Up => *scroll = scroll.saturating_sub(1),
+            KeyCode::Down => {
+                *scroll = scroll
+                    .saturating_add(1)
+                    .min(crate::ui::components::help_max_scroll())
             }
+            KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
             KeyCode::PageDown => {
-                let s = scroll.saturating_add(10);
-                app.modal = Some(ModalState::Help { scroll: s });
+                *scroll = scroll
+                    .saturating_add(10)
+                    .min(crate::ui::components::help_max_scroll())
             }
+            KeyCode::Home => *scroll = 0,
+            KeyCode::End => *scroll = crate::ui::components::help_max_scroll(),
+            _ => {}
+        },
+        Some(ModalState::ActionHistory { scroll }) => match key.code {
+            KeyCode::Esc | KeyCode::Enter => close_modal(app),
+            KeyCode::Backspace => back_modal(app),
+            KeyCode::Up => *scroll = scroll.saturating_sub(1),
+            KeyCode::Down => *scroll = scroll.saturating_add(1),
+            KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+            KeyCode::PageDown => *scroll = scroll.saturating_add(10),
             _ => {}
         },
         None => {}
     }
 }
 
-/// Close the currently open modal and return to normal mode.
-fn close_modal(app: &mut AppState) {
-    app.modal = None;
-    app.input_mode = InputMode::Normal;
+/// Insert bracketed-paste `text` into whichever text input is active.
+///
+/// All inputs are single-line, so only the first line is kept. Pastes outside
+/// a text input are dropped.
+fn handle_paste(app: &mut AppState, text: &str) {
+    let text = textinput::single_line(text);
+    match app.input_mode {
+        InputMode::SearchUsers | InputMode::SearchGroups => {
+            textinput::insert_str(&mut app.search_query, &mut app.search_cursor, &text);
+            apply_filters_and_search(app);
+        }
+        InputMode::Modal => match app.modal_stack.last_mut() {
+            Some(ModalState::ModifyTextInput {
+                field: ModifyField::Fullname,
+                value,
+                cursor,
+            }) => textinput::insert_str(value, cursor, &text),
+            Some(ModalState::ModifyTextInput { value, cursor, .. }) => {
+                textinput::insert_str(value, cursor, &textinput::identifier(&text))
+            }
+            Some(ModalState::ModifyShell {
+                selected,
+                offset,
+                filter,
+                ..
+            }) => {
+                filter.push_str(&text);
+                *selected = 0;
+                *offset = 0;
+            }
+            Some(ModalState::ImportPathInput { path, cursor }) => {
+                textinput::insert_str(path, cursor, &text)
+            }
+            Some(ModalState::GroupAddInput { name, cursor })
+            | Some(ModalState::GroupRenameInput { name, cursor, .. })
+            | Some(ModalState::GroupCloneInput { name, cursor, .. }) => {
+                textinput::insert_str(name, cursor, &textinput::identifier(&text))
+            }
+            Some(ModalState::UserAddInput {
+                selected,
+                name,
+                password,
+                confirm,
+                cursor,
+                ..
+            })
+            | Some(ModalState::CloneUser {
+                selected,
+                name,
+                password,
+                confirm,
+                cursor,
+                ..
+            }) => {
+                let text = if *selected == 0 {
+                    textinput::identifier(&text)
+                } else {
+                    text
+                };
+                if let Some(field) = user_add_field(*selected, name, password, confirm) {
+                    textinput::insert_str(field, cursor, &text);
+                }
+            }
+            Some(ModalState::EditGecos {
+                selected,
+                fields,
+                cursor,
+                ..
+            }) => {
+                let text: String = text.chars().filter(|c| !matches!(c, ',' | ':')).collect();
+                textinput::insert_str(&mut fields[*selected], cursor, &text);
+            }
+            Some(ModalState::ChangePassword {
+                selected: 0,
+                password: field,
+                ..
+            })
+            | Some(ModalState::ChangePassword {
+                selected: 1,
+                confirm: field,
+                ..
+            })
+            | Some(ModalState::SudoPrompt {
+                password: field, ..
+            }) => field.push_str(&text),
+            _ => {}
+        },
+        InputMode::Normal => {}
+    }
 }
 
-/// Execute a queued privileged action and refresh state lists.
-fn perform_pending_action(
-    app: &mut AppState,
-    pending: PendingAction,
-    sudo_password: Option<String>,
-) -> Result<()> {
-    let adapter = crate::sys::SystemAdapter::with_sudo_password(sudo_password);
-    match pending.clone() {
-        PendingAction::AddUserToGroup {
-            username,
-            groupname,
-        } => {
-            adapter.add_user_to_group(&username, &groupname)?;
-            app.groups_all = adapter.list_groups().unwrap_or_default();
-            app.groups_all.sort_by_key(|g| g.gid);
-            apply_filters_and_search(app);
-            app.modal = Some(ModalState::Info {
-                message: format!("Added '{}' to '{}'", username, groupname),
-            });
+/// The text buffer behind `UserAddInput` (or `CloneUser`) field `selected`,
+/// if it is a text field.
+fn user_add_field<'a>(
+    selected: usize,
+    name: &'a mut String,
+    password: &'a mut String,
+    confirm: &'a mut String,
+) -> Option<&'a mut String> {
+    match selected {
+        0 => Some(name),
+        1 => Some(password),
+        2 => Some(confirm),
+        _ => None,
+    }
+}
+
+/// The table row an action touched, for the post-action highlight.
+///
+/// Membership changes started from a user highlight the user; changes made
+/// through a group's member list highlight the group. Deletions have no row
+/// left to highlight.
+#[cfg(not(feature = "read-only-build"))]
+fn changed_row(pending: &PendingAction) -> Option<(ActiveTab, String)> {
+    match pending {
+        PendingAction::AddUserToGroup { username, .. }
+        | PendingAction::RemoveUserFromGroup { username, .. }
+        | PendingAction::AddUserToGroups { username, .. }
+        | PendingAction::RemoveUserFromGroups { username, .. }
+        | PendingAction::MoveUserBetweenGroups { username, .. }
+        | PendingAction::ChangeShell { username, .. }
+        | PendingAction::ChangeFullname { username, .. }
+        | PendingAction::CreateUserWithOptions { username, .. }
+        | PendingAction::CloneUser { username, .. }
+        | PendingAction::SetPassword { username, .. }
+        | PendingAction::ResetPassword { username }
+        | PendingAction::CreateSudoersDropin { username } => {
+            Some((ActiveTab::Users, username.clone()))
         }
-        PendingAction::RemoveUserFromGroup {
-            username,
-            groupname,
-        } => {
-            adapter.remove_user_from_group(&username, &groupname)?;
-            app.groups_all = adapter.list_groups().unwrap_or_default();
-            app.groups_all.sort_by_key(|g| g.gid);
-            apply_filters_and_search(app);
-            app.modal = Some(ModalState::Info {
-                message: format!("Removed '{}' from '{}'", username, groupname),
-            });
+        PendingAction::ChangeUsername { new_username, .. } => {
+            Some((ActiveTab::Users, new_username.clone()))
         }
-        PendingAction::ChangeShell {
-            username,
-            new_shell,
-        } => {
-            adapter.change_user_shell(&username, &new_shell)?;
-            app.users_all = adapter.list_users().unwrap_or_default();
-            app.users_all.sort_by_key(|u| u.uid);
-            apply_filters_and_search(app);
-            app.modal = Some(ModalState::Info {
-                message: format!("Changed shell to '{}'", new_shell),
-            });
+        PendingAction::AddMembersToGroup { groupname, .. }
+        | PendingAction::RemoveMembersFromGroup { groupname, .. }
+        | PendingAction::CreateGroup { groupname }
+        | PendingAction::CreateGroupWithMembers { groupname, .. }
+        | PendingAction::CloneGroup { groupname, .. } => {
+            Some((ActiveTab::Groups, groupname.clone()))
         }
-        PendingAction::ChangeFullname {
-            username,
-            new_fullname,
-        } => {
-            adapter.change_user_fullname(&username, &new_fullname)?;
-            app.users_all = adapter.list_users().unwrap_or_default();
-            app.users_all.sort_by_key(|u| u.uid);
-            apply_filters_and_search(app);
-            app.modal = Some(ModalState::Info {
-                message: "Changed successfully".to_string(),
-            });
+        PendingAction::RenameGroup { new_name, .. } => Some((ActiveTab::Groups, new_name.clone())),
+        PendingAction::MergeGroup { destination, .. } => {
+            Some((ActiveTab::Groups, destination.clone()))
         }
-        PendingAction::ChangeUsername {
-            old_username,
-            new_username,
-        } => {
-            adapter.change_username(&old_username, &new_username)?;
-            app.users_all = adapter.list_users().unwrap_or_default();
-            app.users_all.sort_by_key(|u| u.uid);
-            apply_filters_and_search(app);
-            app.modal = Some(ModalState::Info {
-                message: "Changed successfully".to_string(),
+        PendingAction::DeleteGroup { .. }
+        | PendingAction::ReassignAndDeleteGroup { .. }
+        | PendingAction::DeleteUser { .. }
+        | PendingAction::ImportUsers { .. } => None,
+    }
+}
+
+/// Whether the event loop keeps running after a key press.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LoopControl {
+    Continue,
+    Quit,
+}
+
+/// Route a key press to the handler for the current input mode.
+fn handle_key(app: &mut AppState, key: KeyEvent) -> LoopControl {
+    match app.input_mode {
+        InputMode::Normal => return handle_normal_key(app, key),
+        InputMode::Modal => handle_modal_mode_key(app, key),
+        InputMode::SearchUsers | InputMode::SearchGroups => handle_search_key(app, key),
+    }
+    LoopControl::Continue
+}
+
+/// Run one iteration of input handling for `key`, as the event loop would,
+/// without a terminal. Lets tests script key sequences and inspect the state.
+#[cfg(test)]
+pub fn step(app: &mut AppState, key: KeyEvent) -> LoopControl {
+    let control = handle_key(app, key);
+    run_until_idle(app);
+    control
+}
+
+/// Start the queued action and wait for it and any lookup to finish, as the
+/// event loop would over its next few iterations.
+#[cfg(test)]
+fn run_until_idle(app: &mut AppState) {
+    run_queued_action(app);
+    while app.pending_result.is_some() || app.lookup.is_some() {
+        std::thread::sleep(Duration::from_millis(5));
+        poll_action_result(app);
+        poll_lookup(app);
+    }
+}
+
+/// Handle a key press in normal mode, where keys resolve through the keymap.
+fn handle_normal_key(app: &mut AppState, key: KeyEvent) -> LoopControl {
+    match app.keymap.resolve(&key) {
+        Some(KeyAction::Quit) => return LoopControl::Quit,
+        Some(KeyAction::OpenHelp) => {
+            app.open_modal(ModalState::Help {
+                scroll: 0,
+                query: String::new(),
+                searching: false,
             });
+            app.input_mode = InputMode::Modal;
         }
-        PendingAction::CreateGroup { groupname } => {
-            adapter.create_group(&groupname)?;
-            app.groups_all = adapter.list_groups().unwrap_or_default();
-            app.groups_all.sort_by_key(|g| g.gid);
-            apply_filters_and_search(app);
-            app.modal = Some(ModalState::Info {
-                message: format!("Created group '{}'", groupname),
+        Some(KeyAction::ToggleKeybindsPane) => {
+            app.show_keybinds = !app.show_keybinds;
+        }
+        Some(KeyAction::ToggleMemberSort) => toggle_member_sort(app),
+        Some(KeyAction::CycleIdRadix) => cycle_id_radix(app),
+        Some(KeyAction::ToggleLiveStats) => toggle_live_stats(app),
+        Some(KeyAction::ToggleLayout) => {
+            app.layout_mode = match app.layout_mode {
+                LayoutMode::Expanded => LayoutMode::Compact,
+                LayoutMode::Compact => LayoutMode::Expanded,
+            };
+        }
+        Some(KeyAction::ToggleDetailVerbosity) => {
+            app.detail_verbosity = match app.detail_verbosity {
+                DetailVerbosity::Full => DetailVerbosity::Compact,
+                DetailVerbosity::Compact => DetailVerbosity::Full,
+            };
+        }
+        Some(KeyAction::ToggleMembershipSource) => {
+            app.membership_source = match app.membership_source {
+                MembershipSource::Files => MembershipSource::Id,
+                MembershipSource::Id => MembershipSource::Files,
+            };
+        }
+        Some(KeyAction::Reload) => {
+            let adapter = (app.backend)(None);
+            reload_accounts(app, &*adapter);
+        }
+        Some(KeyAction::ShrinkTablePane) => resize_panes(app, 0, -1),
+        Some(KeyAction::GrowTablePane) => resize_panes(app, 0, 1),
+        Some(KeyAction::GrowKeybindsPane) => resize_panes(app, 1, -1),
+        Some(KeyAction::ShrinkKeybindsPane) => resize_panes(app, 1, 1),
+        Some(KeyAction::Ignore) => { /* ignore */ }
+        Some(KeyAction::OpenFilterMenu) => open_filter_menu(app),
+        Some(KeyAction::CopyCommand) => open_copy_command_menu(app),
+        Some(KeyAction::JumpToGroup) => jump_to_group_or_back(app),
+        Some(KeyAction::ExportKeybinds) => export_keybinds_reference(app),
+        Some(KeyAction::SaveConfig) => save_config(app),
+        Some(KeyAction::ShowHistory) => {
+            app.open_modal(ModalState::ActionHistory { scroll: 0 });
+            app.input_mode = InputMode::Modal;
+        }
+        Some(KeyAction::ShowDetails) => {
+            app.open_modal(match app.active_tab {
+                ActiveTab::Users => ModalState::UserDetails,
+                ActiveTab::Groups => ModalState::GroupDetails,
             });
+            app.input_mode = InputMode::Modal;
         }
-        PendingAction::DeleteGroup { groupname } => {
-            adapter.delete_group(&groupname)?;
-            app.groups_all = adapter.list_groups().unwrap_or_default();
-            app.groups_all.sort_by_key(|g| g.gid);
-            apply_filters_and_search(app);
-            app.modal = Some(ModalState::Info {
-                message: format!("Deleted group '{}'", groupname),
+        Some(KeyAction::StartSearch) => start_search(app),
+        // A viewer build has nothing to create, change or delete.
+        #[cfg(feature = "read-only-build")]
+        Some(
+            KeyAction::NewUser
+            | KeyAction::EnterAction
+            | KeyAction::DeleteSelection
+            | KeyAction::Import,
+        ) => {}
+        #[cfg(not(feature = "read-only-build"))]
+        Some(KeyAction::NewUser) => open_create_modal(app),
+        #[cfg(not(feature = "read-only-build"))]
+        Some(KeyAction::Import) => {
+            app.open_modal(ModalState::ImportPathInput {
+                path: String::new(),
+                cursor: 0,
             });
+            app.input_mode = InputMode::Modal;
         }
-        PendingAction::RenameGroup { old_name, new_name } => {
-            adapter.rename_group(&old_name, &new_name)?;
-            app.groups_all = adapter.list_groups().unwrap_or_default();
-            app.groups_all.sort_by_key(|g| g.gid);
-            apply_filters_and_search(app);
-            app.modal = Some(ModalState::Info {
-                message: format!("Renamed group to '{}'", new_name),
-            });
+        #[cfg(not(feature = "read-only-build"))]
+        Some(KeyAction::EnterAction) => open_selection_actions(app),
+        #[cfg(not(feature = "read-only-build"))]
+        Some(KeyAction::DeleteSelection) => open_selection_delete(app),
+        Some(KeyAction::SwitchTab) => {
+            app.active_tab = match app.active_tab {
+                ActiveTab::Users => ActiveTab::Groups,
+                ActiveTab::Groups => ActiveTab::Users,
+            };
         }
-
-        PendingAction::CreateUserWithOptions {
-            username,
-            password,
-            create_home,
-            add_to_wheel,
-        } => {
-            adapter.create_user(&username, create_home)?;
-            let had_pw = password.is_some();
-            if let Some(pw) = password {
-                adapter.set_user_password(&username, &pw)?;
-            }
-            if add_to_wheel {
-                adapter.add_user_to_group(&username, "wheel")?;
+        Some(KeyAction::ToggleUsersFocus) => match app.active_tab {
+            ActiveTab::Users => {
+                app.users_focus = match app.users_focus {
+                    UsersFocus::UsersList => UsersFocus::MemberOf,
+                    UsersFocus::MemberOf => UsersFocus::UsersList,
+                };
             }
-            app.users_all = adapter.list_users().unwrap_or_default();
-            app.users_all.sort_by_key(|u| u.uid);
-            apply_filters_and_search(app);
-            let mut msg = format!(
-                "Created user '{}'{}",
-                username,
-                if create_home { " with home" } else { "" }
-            );
-            if had_pw {
-                msg.push_str(" with password");
+            ActiveTab::Groups => toggle_groups_focus(app),
+        },
+        Some(KeyAction::ToggleGroupsFocus) => {
+            if let ActiveTab::Groups = app.active_tab {
+                toggle_groups_focus(app);
             }
-            if add_to_wheel {
-                msg.push_str(" and wheel");
+        }
+        Some(KeyAction::MoveUp) => {
+            let (index, len) = focused_selection(app);
+            if *index > 0 {
+                *index -= 1;
+            } else if len > 0 {
+                *index = len - 1;
             }
-            app.modal = Some(ModalState::Info { message: msg });
         }
-        PendingAction::DeleteUser {
-            username,
-            delete_home,
-        } => {
-            adapter.delete_user(&username, delete_home)?;
-            app.users_all = adapter.list_users().unwrap_or_default();
-            app.users_all.sort_by_key(|u| u.uid);
-            apply_filters_and_search(app);
-            if app.selected_user_index >= app.users.len() {
-                app.selected_user_index = app.users.len().saturating_sub(1);
+        Some(KeyAction::MoveDown) => {
+            let (index, len) = focused_selection(app);
+            if *index + 1 < len {
+                *index += 1;
+            } else if len > 0 {
+                *index = 0;
             }
-            let suffix = if delete_home { " and home" } else { "" };
-            app.modal = Some(ModalState::Info {
-                message: format!("Deleted user '{}'{}", username, suffix),
+        }
+        Some(KeyAction::GoTop) => {
+            let (index, _) = focused_selection(app);
+            *index = 0;
+        }
+        Some(KeyAction::GoBottom) => {
+            let (index, len) = focused_selection(app);
+            *index = len.saturating_sub(1);
+        }
+        Some(KeyAction::MoveLeftPage) | Some(KeyAction::PageUp) => {
+            let rpp = app.rows_per_page.max(1);
+            let (index, _) = focused_selection(app);
+            *index = index.saturating_sub(rpp);
+        }
+        Some(KeyAction::MoveRightPage) | Some(KeyAction::PageDown) => {
+            let rpp = app.rows_per_page.max(1);
+            let (index, len) = focused_selection(app);
+            *index = index.saturating_add(rpp).min(len.saturating_sub(1));
+        }
+        None => {}
+    }
+    LoopControl::Continue
+}
+
+/// Home/`g` or End/`G` in a picker: select the first or the last of its
+/// `total` entries. The list is scrolled back to the top when jumping up;
+/// rendering keeps the last entry in view when jumping down.
+fn jump_to_edge(code: KeyCode, selected: &mut usize, offset: &mut usize, total: usize) {
+    if matches!(code, KeyCode::Home | KeyCode::Char('g')) {
+        *selected = 0;
+        *offset = 0;
+    } else {
+        *selected = total.saturating_sub(1);
+    }
+}
+
+fn toggle_groups_focus(app: &mut AppState) {
+    app.groups_focus = match app.groups_focus {
+        GroupsFocus::GroupsList => GroupsFocus::Members,
+        GroupsFocus::Members => GroupsFocus::GroupsList,
+    };
+}
+
+fn start_search(app: &mut AppState) {
+    app.search_query.clear();
+    app.search_cursor = 0;
+    app.input_mode = match app.active_tab {
+        ActiveTab::Users => InputMode::SearchUsers,
+        ActiveTab::Groups => InputMode::SearchGroups,
+    };
+}
+
+/// The selection index that movement keys change in the focused list, and
+/// that list's length.
+fn focused_selection(app: &mut AppState) -> (&mut usize, usize) {
+    match (app.active_tab, app.users_focus, app.groups_focus) {
+        (ActiveTab::Users, UsersFocus::UsersList, _) => {
+            (&mut app.selected_user_index, app.users.len())
+        }
+        (ActiveTab::Users, UsersFocus::MemberOf, _) => {
+            let len = member_of_groups(app).len();
+            (&mut app.selected_group_index, len)
+        }
+        (ActiveTab::Groups, _, GroupsFocus::GroupsList) => {
+            (&mut app.selected_group_index, app.groups.len())
+        }
+        (ActiveTab::Groups, _, GroupsFocus::Members) => {
+            let len = crate::app::selected_group_members(app).len();
+            (&mut app.selected_group_member_index, len)
+        }
+    }
+}
+
+/// Read the CSV at `path` and ask before creating the users it lists.
+///
+/// Always asks Yes/No, since nothing was confirmed yet and the file may
+/// create many accounts; lines that will be skipped are listed in the
+/// warning. A file with no usable line only reports why.
+fn start_import(app: &mut AppState, path: &str) {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            app.push_modal(ModalState::Info {
+                message: format!("Could not read {}: {}", path, e),
             });
+            return;
         }
-        PendingAction::SetPassword {
-            username,
-            password,
-            must_change,
-        } => {
-            adapter.set_user_password(&username, &password)?;
-            if must_change {
-                let _ = adapter.expire_user_password(&username);
+    };
+    let (rows, skipped) = crate::app::import::parse_csv(&contents, &app.users_all, &app.groups_all);
+    if rows.is_empty() {
+        let mut message = format!("No users to import from {}", path);
+        for note in &skipped {
+            message.push_str(&format!("\n{}", note));
+        }
+        app.push_modal(ModalState::Info { message });
+        return;
+    }
+    let mut notes = Vec::new();
+    if !skipped.is_empty() {
+        notes.push(format!("skipping {}", skipped.join("; ")));
+    }
+    let pending = PendingAction::ImportUsers { rows, skipped };
+    let grantees = crate::app::confirmconf::sudo_grantees(&pending);
+    if !grantees.is_empty() {
+        notes.push(format!("this grants sudo to {}", grantees.join(", ")));
+    }
+    if app.confirmations.level_for(&pending) == Some(ConfirmLevel::Typed) {
+        confirm_then_run(app, pending, false);
+    } else {
+        app.push_modal(ModalState::ConfirmAction {
+            selected: 1,
+            next: pending,
+            warning: (!notes.is_empty()).then(|| format!("{}.", notes.join("; "))),
+        });
+    }
+}
+
+/// `n`: create a user or group, or add the selected user to groups from the
+/// Member of pane.
+#[cfg(not(feature = "read-only-build"))]
+fn open_create_modal(app: &mut AppState) {
+    let modal = match (app.active_tab, app.users_focus) {
+        (ActiveTab::Users, UsersFocus::MemberOf) => ModalState::ModifyGroupsAdd {
+            selected: 0,
+            offset: 0,
+            selected_multi: BTreeSet::new(),
+            show_all: false,
+        },
+        // Creating a home directory is the default for new users
+        (ActiveTab::Users, UsersFocus::UsersList) => ModalState::UserAddInput {
+            selected: 0,
+            name: String::new(),
+            password: String::new(),
+            confirm: String::new(),
+            cursor: 0,
+            create_home: true,
+            add_to_wheel: false,
+        },
+        (ActiveTab::Groups, _) => ModalState::GroupAddInput {
+            name: String::new(),
+            cursor: 0,
+        },
+    };
+    app.open_modal(modal);
+    app.input_mode = InputMode::Modal;
+}
+
+/// Enter: open the actions menu for whatever is selected in the focused list.
+#[cfg(not(feature = "read-only-build"))]
+fn open_selection_actions(app: &mut AppState) {
+    match (app.active_tab, app.users_focus, app.groups_focus) {
+        (ActiveTab::Users, _, _) if app.users.is_empty() => {}
+        (ActiveTab::Users, UsersFocus::MemberOf, _) => {
+            let Some(group) = member_of_groups(app)
+                .get(app.selected_group_index)
+                .map(|g| g.gid)
+            else {
+                return;
+            };
+            if let Some(idx) = app.groups.iter().position(|g| g.gid == group) {
+                app.selected_group_index = idx;
             }
-            app.modal = Some(ModalState::Info {
-                message: format!(
-                    "Password set{}",
-                    if must_change {
-                        ", must change at next login"
-                    } else {
-                        ""
-                    }
-                ),
+            app.open_modal(ModalState::GroupsActions {
+                selected: 0,
+                target_gid: Some(group),
             });
+            app.input_mode = InputMode::Modal;
         }
-        PendingAction::ResetPassword { username } => {
-            adapter.expire_user_password(&username)?;
-            app.modal = Some(ModalState::Info {
-                message: "Password reset (must change at next login)".to_string(),
-            });
+        (ActiveTab::Users, UsersFocus::UsersList, _) => {
+            // No residual member context from an earlier Members-list action
+            app.actions_context = None;
+            app.open_modal(ModalState::Actions { selected: 0 });
+            app.input_mode = InputMode::Modal;
         }
-        PendingAction::AddUserToGroups {
-            username,
-            groupnames,
-        } => {
-            for g in groupnames.iter() {
-                adapter.add_user_to_group(&username, g)?;
+        (ActiveTab::Groups, _, GroupsFocus::Members) => {
+            let Some(group_name) = app
+                .groups
+                .get(app.selected_group_index)
+                .map(|g| g.name.clone())
+            else {
+                return;
+            };
+            let members = crate::app::selected_group_member_rows(app);
+            if let Some((uname, primary)) = members.get(app.selected_group_member_index).cloned() {
+                select_user_by_name(app, &uname, None);
+                // A primary group cannot be left with gpasswd, so
+                // such members get the plain user actions
+                app.actions_context =
+                    (!primary).then_some(ActionsContext::GroupMemberRemoval { group_name });
+                app.open_modal(ModalState::Actions { selected: 0 });
+                app.input_mode = InputMode::Modal;
             }
-            app.groups_all = adapter.list_groups().unwrap_or_default();
-            app.groups_all.sort_by_key(|g| g.gid);
-            apply_filters_and_search(app);
-            app.modal = Some(ModalState::Info {
-                message: format!("Added '{}' to selected groups", username),
-            });
         }
-        PendingAction::RemoveUserFromGroups {
-            username,
-            groupnames,
-        } => {
-            for g in groupnames.iter() {
-                adapter.remove_user_from_group(&username, g)?;
+        (ActiveTab::Groups, _, GroupsFocus::GroupsList) => {
+            if let Some(g) = app.groups.get(app.selected_group_index) {
+                app.open_modal(ModalState::GroupsActions {
+                    selected: 0,
+                    target_gid: Some(g.gid),
+                });
+                app.input_mode = InputMode::Modal;
             }
-            app.groups_all = adapter.list_groups().unwrap_or_default();
-            app.groups_all.sort_by_key(|g| g.gid);
-            apply_filters_and_search(app);
-            app.modal = Some(ModalState::Info {
-                message: format!("Removed '{}' from selected groups", username),
-            });
         }
-        PendingAction::AddMembersToGroup {
-            groupname,
-            usernames,
-        } => {
-            for u in usernames.iter() {
-                adapter.add_user_to_group(u, &groupname)?;
+    }
+}
+
+/// Delete: confirm deleting the selected user or group, or removing the
+/// selected membership in the Member of and Members lists.
+#[cfg(not(feature = "read-only-build"))]
+fn open_selection_delete(app: &mut AppState) {
+    let modal = match (app.active_tab, app.users_focus, app.groups_focus) {
+        (ActiveTab::Users, UsersFocus::UsersList, _) => {
+            let Some(u) = app.users.get(app.selected_user_index) else {
+                return;
+            };
+            user_delete_modal(app, u)
+        }
+        (ActiveTab::Users, UsersFocus::MemberOf, _) => {
+            let Some(group) = member_of_groups(app)
+                .get(app.selected_group_index)
+                .map(|g| g.name.clone())
+            else {
+                return;
+            };
+            ModalState::ConfirmRemoveUserFromGroup {
+                selected: 1,
+                group_name: group,
             }
-            app.groups_all = adapter.list_groups().unwrap_or_default();
-            app.groups_all.sort_by_key(|g| g.gid);
+        }
+        (ActiveTab::Groups, _, GroupsFocus::Members) => return confirm_member_removal(app),
+        (ActiveTab::Groups, _, GroupsFocus::GroupsList) => {
+            let Some(gid) = app.groups.get(app.selected_group_index).map(|g| g.gid) else {
+                return;
+            };
+            group_delete_modal(app, Some(gid))
+        }
+    };
+    open_confirm(app, modal, false);
+}
+
+/// Handle a key press while typing a search query; the list filters as it changes.
+fn handle_search_key(app: &mut AppState, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => {
+            apply_filters_and_search(app);
+            app.input_mode = InputMode::Normal;
+        }
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+            app.search_query.clear();
+            app.search_cursor = 0;
             apply_filters_and_search(app);
-            app.modal = Some(ModalState::Info {
-                message: format!("Added selected users to '{}'", groupname),
+        }
+        _ => {
+            let before = app.search_query.clone();
+            textinput::edit(&mut app.search_query, &mut app.search_cursor, key);
+            // Cursor movement alone does not re-filter
+            if app.search_query != before {
+                apply_filters_and_search(app);
+            }
+        }
+    }
+}
+
+/// Handle a key while a modal is open.
+///
+/// Tabs never switch under an open modal, since that would discard whatever
+/// the dialog holds; the tab-switch key only leaves a footer hint saying so.
+/// Plain characters still reach the modal so text inputs can use them.
+fn handle_modal_mode_key(app: &mut AppState, key: KeyEvent) {
+    app.modal_notice = None;
+    let typed = matches!(key.code, KeyCode::Char(_))
+        && !key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+    if !typed && app.keymap.resolve(&key) == Some(KeyAction::SwitchTab) {
+        app.modal_notice = Some("Close this dialog before switching tabs".to_string());
+        return;
+    }
+    handle_modal_key(app, key);
+}
+
+/// Open the filter menu on the entry last used for the active tab.
+///
+/// The remembered index is clamped to the tab's option count, since the users
+/// and groups menus differ in length.
+fn open_filter_menu(app: &mut AppState) {
+    let max = filter_menu_len(app.active_tab) - 1;
+    let selected = app.filter_menu_selected.get(app.active_tab).min(max);
+    app.filter_menu_selected.set(app.active_tab, selected);
+    app.open_modal(ModalState::FilterMenu { selected });
+    app.input_mode = InputMode::Modal;
+}
+
+/// List the selected group's members that have no account, read afresh
+/// from the current user list.
+fn open_orphan_members(app: &mut AppState) {
+    let Some(group) = app.groups.get(app.selected_group_index) else {
+        return;
+    };
+    let orphans = crate::app::orphan_members(group, &app.users_all);
+    let group_name = group.name.clone();
+    app.push_modal(ModalState::OrphanMembers {
+        selected: 0,
+        group_name,
+        orphans,
+    });
+}
+
+/// `c`/`o` in the user details: copy the selected user's home path, or open
+/// it in a file manager, and report the outcome.
+fn user_home_key(app: &mut AppState, code: KeyCode) {
+    let Some(home) = app
+        .users
+        .get(app.selected_user_index)
+        .map(|u| u.home_dir.clone())
+    else {
+        return;
+    };
+    let message = if code == KeyCode::Char('c') {
+        match crate::app::clipboard::copy(&home) {
+            Ok(()) => format!("Copied to clipboard:\n{}", home),
+            Err(e) => format!("Could not copy ({}):\n{}", e, home),
+        }
+    } else {
+        match crate::util::open_path(std::path::Path::new(&home)) {
+            Ok(launcher) => format!("Opened {} with {}.", home, launcher),
+            Err(e) => format!("Could not open the home directory: {}", e),
+        }
+    };
+    app.push_modal(ModalState::Info { message });
+}
+
+/// Keys for the copy-command menu: move between the choices, copy on Enter.
+fn copy_command_menu_key(app: &mut AppState, key: KeyEvent) {
+    let choices = crate::app::copy_command_choices(app);
+    let Some(ModalState::CopyCommandMenu { selected }) = app.modal_stack.last_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Esc | KeyCode::Backspace => close_modal(app),
+        KeyCode::Up | KeyCode::Char('k') if !choices.is_empty() => {
+            *selected = selected.checked_sub(1).unwrap_or(choices.len() - 1);
+        }
+        KeyCode::Down | KeyCode::Char('j') if !choices.is_empty() => {
+            *selected = (*selected + 1) % choices.len();
+        }
+        KeyCode::Enter => {
+            let Some((_, action)) = choices.get(*selected) else {
+                return close_modal(app);
+            };
+            let command = crate::app::ready_to_run_command(action);
+            let message = match crate::app::clipboard::copy(&command) {
+                Ok(()) => format!("Copied to clipboard:\n{}", command),
+                Err(e) => format!("Could not copy ({}):\n{}", e, command),
+            };
+            app.replace_modal(ModalState::Info { message });
+        }
+        _ => {}
+    }
+}
+
+/// Open the copy-command menu, or explain that the selection offers nothing.
+fn open_copy_command_menu(app: &mut AppState) {
+    if crate::app::copy_command_choices(app).is_empty() {
+        app.open_modal(ModalState::Info {
+            message: "Nothing selected to copy a command for.".to_string(),
+        });
+    } else {
+        app.open_modal(ModalState::CopyCommandMenu { selected: 0 });
+    }
+    app.input_mode = InputMode::Modal;
+}
+
+/// Close every open modal and return to normal mode.
+fn close_modal(app: &mut AppState) {
+    app.modal_stack.clear();
+    app.input_mode = InputMode::Normal;
+}
+
+/// Close the topmost modal, revealing its parent in the state it was left in.
+///
+/// Returns to normal mode once the last modal is closed.
+fn back_modal(app: &mut AppState) {
+    if !app.pop_modal() {
+        app.input_mode = InputMode::Normal;
+    }
+}
+
+/// Ask for the name of a copy of the group `target_gid` (or the selected group).
+fn open_group_clone_input(app: &mut AppState, target_gid: Option<u32>) {
+    let source = match target_gid {
+        Some(gid) => app.groups.iter().find(|g| g.gid == gid),
+        None => app.groups.get(app.selected_group_index),
+    };
+    if let Some(source) = source.map(|g| g.name.clone()) {
+        app.push_modal(ModalState::GroupCloneInput {
+            source,
+            name: String::new(),
+            cursor: 0,
+        });
+    }
+}
+
+/// Pick the group the group `target_gid` (or the selected group) is merged
+/// into, or refuse for `nogroup`.
+fn open_group_merge_picker(app: &mut AppState, target_gid: Option<u32>) {
+    let source = match target_gid {
+        Some(gid) => app.groups.iter().find(|g| g.gid == gid),
+        None => app.groups.get(app.selected_group_index),
+    };
+    match source {
+        Some(g) if g.is_nobody() => {
+            let message = format!(
+                "Merge not allowed: '{}' is the shared unprivileged nobody group",
+                g.name
+            );
+            app.push_modal(ModalState::Info { message });
+        }
+        Some(g) => {
+            let source_gid = g.gid;
+            app.push_modal(ModalState::MergeGroup {
+                selected: 0,
+                offset: 0,
+                source_gid,
             });
         }
-        PendingAction::RemoveMembersFromGroup {
-            groupname,
-            usernames,
+        None => {}
+    }
+}
+
+/// Confirm merging the group `source_gid` into `destination`. Since the
+/// source is deleted afterwards, system groups get the same warning as a
+/// plain delete.
+fn confirm_group_merge(app: &mut AppState, source_gid: u32, destination: &str) {
+    let source = app.groups_all.iter().find(|g| g.gid == source_gid);
+    let dest = app.groups_all.iter().find(|g| g.name == destination);
+    let (Some(source), Some(dest)) = (source, dest) else {
+        return;
+    };
+    let pending = crate::app::merge_group_action(&app.users_all, source, dest);
+    let system = source_gid < 1000 && source_gid != 0;
+    if system && app.confirmations.level_for(&pending) == Some(ConfirmLevel::YesNo) {
+        let warning = Some(format!(
+            "'{}' appears to be a system group (GID {}); deleting it may break the system.",
+            source.name, source_gid
+        ));
+        app.push_modal(ModalState::ConfirmAction {
+            selected: 1,
+            next: pending,
+            warning,
+        });
+    } else {
+        confirm_then_run(app, pending, false);
+    }
+}
+
+/// Action a Yes/No confirm dialog runs when answered Yes, as it would be
+/// answered right after opening.
+fn yes_no_action(app: &AppState, modal: &ModalState) -> Option<PendingAction> {
+    let user = app.users.get(app.selected_user_index);
+    match modal {
+        ModalState::DeleteConfirm {
+            allowed: true,
+            delete_home,
+            ..
+        } => user.map(|u| PendingAction::DeleteUser {
+            username: u.name.clone(),
+            delete_home: *delete_home,
+        }),
+        ModalState::GroupDeleteConfirm {
+            target_gid,
+            reassign_to,
+            ..
         } => {
-            for u in usernames.iter() {
-                adapter.remove_user_from_group(u, &groupname)?;
-            }
-            app.groups_all = adapter.list_groups().unwrap_or_default();
-            app.groups_all.sort_by_key(|g| g.gid);
-            apply_filters_and_search(app);
-            app.modal = Some(ModalState::Info {
-                message: format!("Removed selected users from '{}'", groupname),
+            let group = match target_gid {
+                Some(gid) => app.groups.iter().find(|g| g.gid == *gid),
+                None => app.groups.get(app.selected_group_index),
+            };
+            group
+                .map(|g| crate::app::group_delete_action(&app.users_all, g, reassign_to.as_deref()))
+        }
+        ModalState::ConfirmRemoveUserFromGroup { group_name, .. } => {
+            user.map(|u| PendingAction::RemoveUserFromGroup {
+                username: u.name.clone(),
+                groupname: group_name.clone(),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Show the confirm dialog `modal` (pushed over the current modal when
+/// `push`), or run its action straight away when `confirmations.conf` sets
+/// that kind of action to `none`.
+fn open_confirm(app: &mut AppState, modal: ModalState, push: bool) {
+    app.input_mode = InputMode::Modal;
+    if let Some(pending) = yes_no_action(app, &modal)
+        && app.confirmations.level_for(&pending) == Some(ConfirmLevel::None)
+    {
+        run_pending_action(app, pending);
+    } else if push {
+        app.push_modal(modal);
+    } else {
+        app.open_modal(modal);
+    }
+}
+
+/// Run `pending` once it has the confirmation `confirmations.conf` asks for.
+///
+/// `asked` tells whether the user already answered a Yes/No dialog or chose
+/// the action from a menu; a `yesno` level is satisfied by that, while
+/// `typed` still wants the name typed out. Granting sudo always asks at the
+/// `yesno` level, naming who gets it.
+fn confirm_then_run(app: &mut AppState, pending: PendingAction, asked: bool) {
+    let grantees = crate::app::confirmconf::sudo_grantees(&pending);
+    match app.confirmations.level_for(&pending) {
+        Some(ConfirmLevel::Typed) => {
+            let keyword = crate::app::confirmconf::confirm_keyword(&pending);
+            app.push_modal(ModalState::TypedConfirm {
+                next: pending,
+                keyword,
+                input: String::new(),
+                cursor: 0,
             });
         }
+        Some(ConfirmLevel::YesNo) if !asked || !grantees.is_empty() => {
+            let warning = (!grantees.is_empty())
+                .then(|| format!("this grants sudo to {}.", grantees.join(", ")));
+            app.push_modal(ModalState::ConfirmAction {
+                selected: 1,
+                next: pending,
+                warning,
+            })
+        }
+        _ => run_pending_action(app, pending),
+    }
+}
+
+/// Caveat shown before renaming group `old`: files follow GID `gid` and keep
+/// working, but sudoers rules naming the group, listed in `sudoers`, do not.
+fn rename_group_warning(old: &str, gid: u32, sudoers: &[std::path::PathBuf]) -> String {
+    let files = format!("files stay owned by GID {}", gid);
+    if sudoers.is_empty() {
+        format!(
+            "{}, but sudoers rules or configs naming '{}' will stop matching.",
+            files, old
+        )
+    } else {
+        let paths: Vec<String> = sudoers.iter().map(|p| p.display().to_string()).collect();
+        format!(
+            "{}, but %{} in {} will stop matching until edited.",
+            files,
+            old,
+            paths.join(", ")
+        )
+    }
+}
+
+/// Confirm dialog for deleting `user`, or every reason it is blocked.
+fn user_delete_modal(app: &AppState, user: &sys::SystemUser) -> ModalState {
+    let blockers = crate::app::deletion_blockers(app, user);
+    if blockers.is_empty() {
+        ModalState::DeleteConfirm {
+            selected: 1,
+            allowed: true,
+            delete_home: false,
+            home_size: None,
+            measuring: false,
+        }
+    } else {
+        ModalState::Info {
+            message: format!("Cannot delete '{}': {}.", user.name, blockers.join("; ")),
+        }
+    }
+}
+
+/// Confirm dialog for deleting the group `gid`, or a refusal for `nogroup`.
+///
+/// When the group is still some user's primary group, `groupdel` would fail,
+/// so a picker for the group those users move to comes first.
+fn group_delete_modal(app: &AppState, gid: Option<u32>) -> ModalState {
+    match gid.and_then(|gid| app.groups.iter().find(|g| g.gid == gid)) {
+        Some(g) if g.is_nobody() => ModalState::Info {
+            message: format!(
+                "Deletion not allowed: '{}' is the shared unprivileged nobody group",
+                g.name
+            ),
+        },
+        Some(g) if !crate::app::primary_group_users(&app.users_all, g.gid).is_empty() => {
+            ModalState::GroupDeleteReassign {
+                selected: 0,
+                offset: 0,
+                target_gid: g.gid,
+            }
+        }
+        _ => ModalState::GroupDeleteConfirm {
+            selected: 1,
+            target_gid: gid,
+            reassign_to: None,
+        },
+    }
+}
+
+/// Make `name` the selected user, showing all users again if the current
+/// filter hides them. `uid`, when known, picks between duplicate names.
+fn select_user_by_name(app: &mut AppState, name: &str, uid: Option<u32>) {
+    if let Some(idx) = crate::app::find_user_index(&app.users, name, uid) {
+        app.selected_user_index = idx;
+    } else if let Some(idx_all) = crate::app::find_user_index(&app.users_all, name, uid) {
+        app.users = app.users_all.clone();
+        app.selected_user_index = idx_all;
+    }
+}
+
+/// Make the group `gid` the selected group, showing all groups again if the
+/// current filter hides it.
+fn select_group_by_gid(app: &mut AppState, gid: u32) {
+    if let Some(idx) = app.groups.iter().position(|g| g.gid == gid) {
+        app.selected_group_index = idx;
+    } else if let Some(idx_all) = app.groups_all.iter().position(|g| g.gid == gid) {
+        app.groups = app.groups_all.clone();
+        app.selected_group_index = idx_all;
+    }
+}
+
+/// From a Member of entry, switch to that group on the Groups tab. After such
+/// a jump the same key returns to the user with the entry selected again.
+fn jump_to_group_or_back(app: &mut AppState) {
+    match app.active_tab {
+        ActiveTab::Users if app.users_focus == UsersFocus::MemberOf => {
+            let Some(gid) = member_of_groups(app)
+                .get(app.selected_group_index)
+                .map(|g| g.gid)
+            else {
+                return;
+            };
+            app.group_jump_origin = app
+                .users
+                .get(app.selected_user_index)
+                .map(|u| (u.name.clone(), u.uid));
+            select_group_by_gid(app, gid);
+            app.selected_group_member_index = 0;
+            app.active_tab = ActiveTab::Groups;
+            app.groups_focus = GroupsFocus::GroupsList;
+        }
+        ActiveTab::Groups => {
+            let Some((username, uid)) = app.group_jump_origin.take() else {
+                return;
+            };
+            let gid = app.groups.get(app.selected_group_index).map(|g| g.gid);
+            select_user_by_name(app, &username, Some(uid));
+            app.active_tab = ActiveTab::Users;
+            app.users_focus = UsersFocus::MemberOf;
+            app.selected_group_index = member_of_groups(app)
+                .iter()
+                .position(|g| Some(g.gid) == gid)
+                .unwrap_or(0);
+        }
+        ActiveTab::Users => {}
+    }
+}
+
+/// Delete on a Members row: ask to remove that user from the group.
+///
+/// Users listed through their primary group cannot leave it with gpasswd, so
+/// they get the same refusal as in the Member of pane instead.
+#[cfg(not(feature = "read-only-build"))]
+fn confirm_member_removal(app: &mut AppState) {
+    let Some(group_name) = app
+        .groups
+        .get(app.selected_group_index)
+        .map(|g| g.name.clone())
+    else {
+        return;
+    };
+    let Some((username, primary)) = crate::app::selected_group_member_rows(app)
+        .get(app.selected_group_member_index)
+        .cloned()
+    else {
+        return;
+    };
+    if primary {
+        app.open_modal(ModalState::Info {
+            message: "Cannot remove user from primary group.".to_string(),
+        });
+    } else {
+        select_user_by_name(app, &username, None);
+        let modal = ModalState::ConfirmRemoveUserFromGroup {
+            selected: 1,
+            group_name,
+        };
+        open_confirm(app, modal, false);
+    }
+    app.input_mode = InputMode::Modal;
+}
+
+/// Flip the Members list between file order and alphabetical order, keeping
+/// the same member selected.
+fn toggle_member_sort(app: &mut AppState) {
+    let selected = crate::app::selected_group_members(app)
+        .get(app.selected_group_member_index)
+        .cloned();
+    app.sort_members = !app.sort_members;
+    if let Some(name) = selected
+        && let Some(idx) = crate::app::selected_group_members(app)
+            .iter()
+            .position(|m| *m == name)
+    {
+        app.selected_group_member_index = idx;
+    }
+}
+
+/// Switch UIDs/GIDs to the next display radix and save it to `ui.conf`.
+fn cycle_id_radix(app: &mut AppState) {
+    app.id_radix = app.id_radix.next();
+    let path = crate::app::config_file_read_path("ui.conf")
+        .unwrap_or_else(|| crate::app::config_file_write_path("ui.conf"));
+    let _ = crate::app::uiconf::UiConfig::save_from_app(app, &path);
+}
+
+/// Ctrl+A / Ctrl+D in a multi-select picker: select everything in
+/// `selectable` (a second Ctrl+A clears), or clear the selection.
+fn bulk_select<'a>(
+    code: KeyCode,
+    selected_multi: &mut BTreeSet<String>,
+    selectable: impl Iterator<Item = &'a str>,
+) {
+    if code == KeyCode::Char('d') {
+        selected_multi.clear();
+    } else {
+        let all = selectable.map(str::to_string).collect();
+        crate::app::select_all_or_clear(selected_multi, all);
+    }
+}
+
+/// Invert a picker selection over `items`, the rows the picker lists.
+fn invert_checked(selected_multi: &mut BTreeSet<String>, items: &[&str]) {
+    let mut checked: Vec<usize> = (0..items.len())
+        .filter(|i| selected_multi.contains(items[*i]))
+        .collect();
+    crate::app::invert_selection(&mut checked, items.len());
+    *selected_multi = checked.into_iter().map(|i| items[i].to_string()).collect();
+}
+
+/// Check the row named `name`, or uncheck it if it is checked already.
+fn toggle_checked(selected_multi: &mut BTreeSet<String>, name: &str) {
+    if !selected_multi.remove(name) {
+        selected_multi.insert(name.to_string());
+    }
+}
+
+/// Enter in a picker with nothing checked while `empty_selection_enter = require`:
+/// leave the picker open and say why nothing happened.
+fn require_checked_items(app: &mut AppState) {
+    app.push_modal(ModalState::Info {
+        message: "Nothing checked. Mark items with Space, then press Enter.".to_string(),
+    });
+}
+
+/// Re-apply the filters after a password change, so the `locked`,
+/// `no_password` and `expired` chips see the new `/etc/shadow` entries.
+///
+/// The passwd and group lists are unchanged, but the users view was filtered
+/// on the shadow state from before the action.
+#[cfg(not(feature = "read-only-build"))]
+fn refresh_shadow_filters(app: &mut AppState) {
+    app.group_details_cache = None;
+    apply_filters_and_search(app);
+}
+
+/// Write the keybinding reference next to the config files and say where.
+fn export_keybinds_reference(app: &mut AppState) {
+    let path = crate::app::config_file_write_path("keybinds_reference.txt");
+    let message = match crate::app::keymap::export_keybinds(&app.keymap, &path) {
+        Ok(()) => format!("Keybindings written to {path}"),
+        Err(e) => format!("Could not write {path}: {e}"),
+    };
+    app.open_modal(ModalState::Info { message });
+    app.input_mode = InputMode::Modal;
+}
+
+/// Write the theme, keybindings and filters to the files they were loaded
+/// from (or would be created in) and list where each went.
+fn save_config(app: &mut AppState) {
+    let path = |name: &str| {
+        crate::app::config_file_read_path(name)
+            .unwrap_or_else(|| crate::app::config_file_write_path(name))
+    };
+    let theme = path("theme.conf");
+    let keybinds = path("keybinds.conf");
+    let filters = path("filter.conf");
+    let results = [
+        (&theme, app.configured_theme.write_file(&theme)),
+        (&keybinds, app.keymap.write_file(&keybinds)),
+        (&filters, FiltersConfig::save_from_app(app, &filters)),
+    ];
+    let lines: Vec<String> = results
+        .iter()
+        .map(|(path, result)| match result {
+            Ok(()) => format!("Saved {path}"),
+            Err(e) => format!("Could not write {path}: {e}"),
+        })
+        .collect();
+    app.open_modal(ModalState::Info {
+        message: lines.join("\n"),
+    });
+    app.input_mode = InputMode::Modal;
+}
+
+/// Note when `/etc/passwd` or `/etc/group` was written by something other
+/// than this app, so the status bar can ask for a reload. Skipped while an
+/// action runs, since its own writes are expected.
+fn check_account_files(app: &mut AppState) {
+    if app.accounts_stale || app.pending_result.is_some() || app.queued_action.is_some() {
+        return;
+    }
+    let current = crate::app::account_files_mtime();
+    if crate::app::account_files_advanced(&app.accounts_mtime, &current) {
+        app.accounts_stale = true;
+    }
+}
+
+/// Re-read users and groups from `adapter`, replacing the in-memory lists.
+///
+/// The cached `/etc/shells`, `id -Gn` output, locked users and home
+/// directory checks are dropped too, so shells installed and accounts
+/// changed since are picked up on next use.
+fn reload_accounts(app: &mut AppState, adapter: &dyn UserGroupBackend) {
+    app.accounts_mtime = crate::app::account_files_mtime();
+    app.accounts_stale = false;
+    app.users_all = adapter.list_users().unwrap_or_default();
+    sys::sort_users(&mut app.users_all);
+    app.groups_all = adapter.list_groups().unwrap_or_default();
+    sys::sort_groups(&mut app.groups_all);
+    app.group_details_cache = None;
+    app.shells_cache = None;
+    app.effective_groups_cache = None;
+    app.locked_users_cache = None;
+    app.home_exists_cache.clear();
+    apply_filters_and_search(app);
+}
+
+/// Record completed `(username, groupname, added)` membership changes in
+/// `groups_all` without re-reading `/etc/group`.
+///
+/// If the in-memory list disagrees with any change, the groups are reloaded
+/// from `adapter` instead.
+#[cfg(not(feature = "read-only-build"))]
+fn update_memberships(
+    app: &mut AppState,
+    adapter: &dyn UserGroupBackend,
+    changes: &[(&str, &str, bool)],
+) {
+    let consistent = changes.iter().all(|(user, group, added)| {
+        crate::app::apply_membership_change(&mut app.groups_all, user, group, *added)
+    });
+    if !consistent {
+        app.groups_all = adapter.list_groups().unwrap_or_default();
+        sys::sort_groups(&mut app.groups_all);
+    }
+    apply_filters_and_search(app);
+}
+
+/// The supplementary groups `username` keeps once dropped from `removed`,
+/// computed from a fresh read of the account files so that a single
+/// `usermod -G` can set them.
+#[cfg(not(feature = "read-only-build"))]
+fn remaining_groups(
+    adapter: &dyn UserGroupBackend,
+    username: &str,
+    removed: &[String],
+) -> Result<Vec<String>> {
+    let users = adapter.list_users()?;
+    let user = users
+        .iter()
+        .find(|u| u.name == username)
+        .ok_or_else(|| crate::error::simple_error(format!("unknown user '{username}'")))?;
+    let groups = adapter.list_groups()?;
+    Ok(crate::app::surviving_supplementary_groups(
+        &groups,
+        username,
+        user.primary_gid,
+        removed,
+    ))
+}
+
+/// Turn the filesystem-backed detail fields on or off and save the choice to `ui.conf`.
+fn toggle_live_stats(app: &mut AppState) {
+    app.live_stats = !app.live_stats;
+    let path = crate::app::config_file_read_path("ui.conf")
+        .unwrap_or_else(|| crate::app::config_file_write_path("ui.conf"));
+    let _ = crate::app::uiconf::UiConfig::save_from_app(app, &path);
+}
+
+/// Move a column border by one step (`direction` is -1 or 1) and save the
+/// new widths to `ui.conf`.
+///
+/// Border 1 sits between the details column and the keybinds panel, so it is
+/// left alone while the panel is hidden.
+fn resize_panes(app: &mut AppState, border: usize, direction: i16) {
+    if border == 1 && !app.show_keybinds {
+        return;
+    }
+    app.pane_split
+        .shift(border, direction * crate::app::PaneSplit::STEP);
+    let path = crate::app::config_file_read_path("ui.conf")
+        .unwrap_or_else(|| crate::app::config_file_write_path("ui.conf"));
+    let _ = crate::app::uiconf::UiConfig::save_from_app(app, &path);
+}
+
+/// Run `read` on a worker thread; [`poll_lookup`] hands its answer to the
+/// modal waiting for it. A lookup still running is abandoned.
+fn start_lookup(app: &mut AppState, read: impl FnOnce() -> crate::app::Lookup + Send + 'static) {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(read());
+    });
+    app.lookup = Some(rx);
+}
+
+/// Fill in the modal waiting for the running lookup once its answer is in.
+///
+/// An answer for a modal that has since been closed is dropped.
+fn poll_lookup(app: &mut AppState) {
+    let Some(rx) = &app.lookup else {
+        return;
+    };
+    let answer = match rx.try_recv() {
+        Ok(answer) => answer,
+        Err(std::sync::mpsc::TryRecvError::Empty) => return,
+        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+            app.lookup = None;
+            return;
+        }
+    };
+    app.lookup = None;
+    match answer {
+        crate::app::Lookup::SudoersDropin { username, result } => {
+            if let Some(ModalState::SudoersDropin {
+                username: shown,
+                loading,
+                content,
+                error,
+            }) = app.modal_stack.last_mut()
+                && *shown == username
+            {
+                *loading = false;
+                match result {
+                    Ok(text) => *content = text,
+                    Err(e) => *error = Some(e),
+                }
+            }
+        }
+        crate::app::Lookup::HomeSize { home, size } => {
+            let selected_home = app.users.get(app.selected_user_index).map(|u| &u.home_dir);
+            if let Some(ModalState::DeleteConfirm {
+                delete_home: true,
+                home_size,
+                measuring,
+                ..
+            }) = app.modal_stack.last_mut()
+                && *measuring
+                && selected_home == Some(&home)
+            {
+                *measuring = false;
+                *home_size = size;
+            }
+        }
+    }
+}
+
+/// Queue `pending` for the event loop, which starts it through
+/// [`run_queued_action`].
+#[cfg(not(feature = "read-only-build"))]
+fn run_pending_action(app: &mut AppState, pending: PendingAction) {
+    app.queued_action = Some(pending);
+}
+
+/// Start the queued action, unless one is still running.
+fn run_queued_action(app: &mut AppState) {
+    if app.pending_result.is_some() {
+        return;
+    }
+    let Some(pending) = app.queued_action.take() else {
+        return;
+    };
+    start_action(app, pending);
+}
+
+/// Show how a finished action went, asking for the sudo password if sudo
+/// could not authenticate.
+///
+/// An authentication failure with the sudo prompt already open keeps the
+/// prompt up with the error, until [`MAX_SUDO_ATTEMPTS`] passwords have failed
+/// and the action is cancelled. Any other failure, such as the command itself
+/// exiting with an error, is shown in place of the prompt: the password was
+/// accepted, so asking again would not help. As root there is no password to
+/// ask for, so every failure is shown.
+fn report_action_result(app: &mut AppState, pending: PendingAction, result: Result<()>) {
+    let Err(e) = result else {
+        return;
+    };
+    let prompt_open = matches!(app.modal(), Some(ModalState::SudoPrompt { .. }));
+    if !crate::error::is_auth_error(&*e) || app.running_as_root.is_some() {
+        let info = ModalState::Info {
+            message: format!("Action failed: {e}"),
+        };
+        if prompt_open {
+            app.replace_modal(info);
+        } else {
+            app.push_modal(info);
+        }
+    } else if let Some(ModalState::SudoPrompt { attempts, .. }) = app.modal() {
+        let attempts = attempts + 1;
+        if attempts >= crate::app::MAX_SUDO_ATTEMPTS {
+            app.sudo_password = None;
+            app.replace_modal(ModalState::Info {
+                message: format!(
+                    "Action failed: {e}\n\n{attempts} incorrect password attempts; the action was cancelled."
+                ),
+            });
+        } else {
+            app.replace_modal(ModalState::SudoPrompt {
+                next: pending,
+                password: String::new(),
+                error: Some(e.to_string()),
+                attempts,
+            });
+        }
+    } else {
+        app.push_modal(ModalState::SudoPrompt {
+            next: pending,
+            password: String::new(),
+            error: None,
+            attempts: 0,
+        });
+    }
+}
+
+/// Stand-in for [`run_pending_action`] in a `read-only-build`.
+#[cfg(feature = "read-only-build")]
+fn run_pending_action(app: &mut AppState, _pending: PendingAction) {
+    app.push_modal(ModalState::Info {
+        message: READ_ONLY_MESSAGE.to_string(),
+    });
+}
+
+#[cfg(feature = "read-only-build")]
+const READ_ONLY_MESSAGE: &str = "This is a read-only build; changes are disabled.";
+
+/// Stand-in for [`start_action`] in a `read-only-build`: always fails.
+#[cfg(feature = "read-only-build")]
+fn start_action(app: &mut AppState, pending: PendingAction) {
+    report_action_result(
+        app,
+        pending,
+        Err(crate::error::simple_error(READ_ONLY_MESSAGE)),
+    );
+}
+
+/// Stand-in for [`poll_action_result`] in a `read-only-build`, where no
+/// action ever runs.
+#[cfg(feature = "read-only-build")]
+fn poll_action_result(_app: &mut AppState) {}
+
+/// Run the commands of `pending` on a worker thread; [`poll_action_result`]
+/// picks up the outcome.
+#[cfg(not(feature = "read-only-build"))]
+fn start_action(app: &mut AppState, pending: PendingAction) {
+    let commands = crate::app::describe_command(&pending).replace('\n', "; ");
+    crate::logging::info(format_args!("performing: {commands}"));
+    let adapter = (app.backend)(app.sudo_password.clone());
+    let job = pending.clone();
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(run_action_commands(&job, &*adapter));
+    });
+    app.pending_result = Some(crate::app::ActionInFlight {
+        action: pending,
+        result: rx,
+        started: std::time::Instant::now(),
+    });
+}
+
+/// Finish the running action once its worker has sent the outcome.
+#[cfg(not(feature = "read-only-build"))]
+fn poll_action_result(app: &mut AppState) {
+    let Some(flight) = app.pending_result.take() else {
+        return;
+    };
+    let result = match flight.result.try_recv() {
+        Ok(result) => result,
+        Err(std::sync::mpsc::TryRecvError::Empty) => {
+            app.pending_result = Some(flight);
+            return;
+        }
+        Err(std::sync::mpsc::TryRecvError::Disconnected) => Err(crate::error::simple_error(
+            "the action stopped without a result",
+        )),
+    };
+    // Reloading the lists needs no privileges
+    let adapter = (app.backend)(None);
+    let result = finish_action(app, flight.action.clone(), result, &*adapter);
+    if let Err(e) = &result {
+        crate::logging::warn(format_args!("action failed: {e}"));
+    }
+    report_action_result(app, flight.action, result);
+}
+
+/// Note the outcome of `pending` in the action history and, if its commands
+/// succeeded, bring the lists up to date and show what was done.
+///
+/// `adapter` is only used to reload the account lists.
+#[cfg(not(feature = "read-only-build"))]
+fn finish_action(
+    app: &mut AppState,
+    pending: PendingAction,
+    result: Result<crate::app::ActionDone>,
+    adapter: &dyn UserGroupBackend,
+) -> Result<()> {
+    let description = crate::app::describe_command(&pending).replace('\n', "; ");
+    app.record_action(
+        description,
+        result.as_ref().map(|_| ()).map_err(|e| e.to_string()),
+    );
+    if let Some(log) = &app.audit_log {
+        log.record(&pending, &result);
+    }
+    // A failed action may still have written part of its changes, which the
+    // lists below are not reloaded with; leaving the mtime alone lets the
+    // stale hint ask for a reload.
+    let done = result?;
+    // Shells and passwords live outside /etc/group, so its mtime alone would
+    // not notice every change an action makes to the group statistics.
+    app.group_details_cache = None;
+    app.effective_groups_cache = None;
+    app.locked_users_cache = None;
+    app.home_exists_cache.clear();
+    if !done.memberships.is_empty() {
+        let changes: Vec<_> = done
+            .memberships
+            .iter()
+            .map(|(u, g, added)| (u.as_str(), g.as_str(), *added))
+            .collect();
+        update_memberships(app, adapter, &changes);
+    } else {
+        let (users, groups) = match &pending {
+            PendingAction::ChangeShell { .. }
+            | PendingAction::ChangeFullname { .. }
+            | PendingAction::ChangeUsername { .. }
+            | PendingAction::CreateUserWithOptions { .. }
+            | PendingAction::DeleteUser { .. } => (true, false),
+            PendingAction::CreateGroup { .. }
+            | PendingAction::CreateGroupWithMembers { .. }
+            | PendingAction::DeleteGroup { .. }
+            | PendingAction::CloneGroup { .. }
+            | PendingAction::RenameGroup { .. } => (false, true),
+            PendingAction::ReassignAndDeleteGroup { .. }
+            | PendingAction::MergeGroup { .. }
+            | PendingAction::CloneUser { .. }
+            | PendingAction::ImportUsers { .. } => (true, true),
+            _ => (false, false),
+        };
+        if users {
+            app.users_all = adapter.list_users().unwrap_or_default();
+            sys::sort_users(&mut app.users_all);
+        }
+        if groups {
+            app.groups_all = adapter.list_groups().unwrap_or_default();
+            sys::sort_groups(&mut app.groups_all);
+        }
+        match pending {
+            PendingAction::SetPassword { .. } | PendingAction::ResetPassword { .. } => {
+                refresh_shadow_filters(app)
+            }
+            _ => apply_filters_and_search(app),
+        }
+    }
+    if matches!(pending, PendingAction::DeleteUser { .. })
+        && app.selected_user_index >= app.users.len()
+    {
+        app.selected_user_index = app.users.len().saturating_sub(1);
+    }
+    app.open_modal(ModalState::Info {
+        message: done.message,
+    });
+    if let Some((tab, name)) = changed_row(&pending) {
+        app.mark_changed(tab, name);
+    }
+    // The action's own writes are not changes from elsewhere
+    app.accounts_mtime = crate::app::account_files_mtime();
+    Ok(())
+}
+
+/// Add `members` to the just created `groupname`, noting in `message` who
+/// was added and who could not be.
+///
+/// Once the group exists a retry would fail on the create, so members that
+/// cannot be added are listed instead of returned as an error.
+#[cfg(not(feature = "read-only-build"))]
+fn add_new_group_members(
+    adapter: &dyn UserGroupBackend,
+    groupname: &str,
+    members: &[String],
+    message: &mut String,
+) {
+    let mut added = Vec::new();
+    let mut failed = Vec::new();
+    for m in members.iter() {
+        match adapter.add_user_to_group(m, groupname) {
+            Ok(()) => added.push(m.as_str()),
+            Err(e) => failed.push(format!("{} ({})", m, e)),
+        }
+    }
+    if added.is_empty() {
+        message.push_str("; no members added");
+    } else {
+        message.push_str(&format!("; added {}", added.join(", ")));
+    }
+    if !failed.is_empty() {
+        message.push_str(&format!("; failed to add {}", failed.join(", ")));
+    }
+}
+
+/// The commands of `pending`, one branch per action. Touches no app state,
+/// so it can run on a worker thread.
+#[cfg(not(feature = "read-only-build"))]
+fn run_action_commands(
+    pending: &PendingAction,
+    adapter: &dyn UserGroupBackend,
+) -> Result<crate::app::ActionDone> {
+    let mut memberships = Vec::new();
+    let mut changed = |user: &str, group: &str, added: bool| {
+        memberships.push((user.to_string(), group.to_string(), added))
+    };
+    let message = match pending {
+        PendingAction::AddUserToGroup {
+            username,
+            groupname,
+        } => {
+            adapter.add_user_to_group(username, groupname)?;
+            changed(username, groupname, true);
+            format!("Added '{}' to '{}'", username, groupname)
+        }
+        PendingAction::RemoveUserFromGroup {
+            username,
+            groupname,
+        } => {
+            adapter.remove_user_from_group(username, groupname)?;
+            changed(username, groupname, false);
+            format!("Removed '{}' from '{}'", username, groupname)
+        }
+        PendingAction::ChangeShell {
+            username,
+            new_shell,
+        } => {
+            adapter.change_user_shell(username, new_shell)?;
+            format!("Changed shell to '{}'", new_shell)
+        }
+        PendingAction::ChangeFullname {
+            username,
+            new_fullname,
+        } => {
+            adapter.change_user_fullname(username, new_fullname)?;
+            "Changed successfully".to_string()
+        }
+        PendingAction::ChangeUsername {
+            old_username,
+            new_username,
+        } => {
+            adapter.change_username(old_username, new_username)?;
+            "Changed successfully".to_string()
+        }
+        PendingAction::CreateGroup { groupname } => {
+            adapter.create_group(groupname)?;
+            format!("Created group '{}'", groupname)
+        }
+        PendingAction::DeleteGroup { groupname } => {
+            adapter.delete_group(groupname)?;
+            format!("Deleted group '{}'", groupname)
+        }
+        PendingAction::ReassignAndDeleteGroup {
+            groupname,
+            fallback,
+            usernames,
+        } => {
+            // As for MergeGroup, a failure is returned only while nothing has
+            // changed; after that the users already moved are listed and the
+            // group is kept while anyone still has it as primary group.
+            let mut moved = Vec::new();
+            let mut failed = Vec::new();
+            for u in usernames.iter() {
+                match adapter.change_primary_group(u, fallback) {
+                    Ok(()) => moved.push(u.as_str()),
+                    Err(e) if moved.is_empty() => return Err(e),
+                    Err(e) => failed.push(format!("{} ({})", u, e)),
+                }
+            }
+            if !failed.is_empty() {
+                format!(
+                    "Moved {} to '{}'; failed: {}; kept group '{}'",
+                    moved.join(", "),
+                    fallback,
+                    failed.join(", "),
+                    groupname
+                )
+            } else {
+                match adapter.delete_group(groupname) {
+                    Ok(()) => format!(
+                        "Moved {} to '{}' and deleted group '{}'",
+                        moved.join(", "),
+                        fallback,
+                        groupname
+                    ),
+                    Err(e) if moved.is_empty() => return Err(e),
+                    Err(e) => format!(
+                        "Moved {} to '{}', but deleting group '{}' failed: {}",
+                        moved.join(", "),
+                        fallback,
+                        groupname,
+                        e
+                    ),
+                }
+            }
+        }
+        PendingAction::MergeGroup {
+            source,
+            destination,
+            members,
+            primary_users,
+        } => {
+            // Only a failure before anything changed is returned, so a retry
+            // after the sudo prompt starts from scratch. Later failures are
+            // listed, as for CloneUser, and the source group is kept so no
+            // one loses a membership that was not carried over.
+            let mut added = Vec::new();
+            let mut moved = Vec::new();
+            let mut failed = Vec::new();
+            for m in members.iter() {
+                match adapter.add_user_to_group(m, destination) {
+                    Ok(()) => added.push(m.as_str()),
+                    Err(e) if added.is_empty() => return Err(e),
+                    Err(e) => failed.push(format!("{} ({})", m, e)),
+                }
+            }
+            for u in primary_users.iter() {
+                match adapter.change_primary_group(u, destination) {
+                    Ok(()) => moved.push(u.as_str()),
+                    Err(e) if added.is_empty() && moved.is_empty() => return Err(e),
+                    Err(e) => failed.push(format!("primary group of {} ({})", u, e)),
+                }
+            }
+            if failed.is_empty()
+                && let Err(e) = adapter.delete_group(source)
+            {
+                if added.is_empty() && moved.is_empty() {
+                    return Err(e);
+                }
+                failed.push(format!("groupdel {} ({})", source, e));
+            }
+            let mut message = if failed.is_empty() {
+                format!("Merged '{}' into '{}'", source, destination)
+            } else {
+                format!(
+                    "Merging '{}' into '{}' stopped partway",
+                    source, destination
+                )
+            };
+            if !added.is_empty() {
+                message.push_str(&format!("; added {}", added.join(", ")));
+            }
+            if !moved.is_empty() {
+                message.push_str(&format!("; moved primary group of {}", moved.join(", ")));
+            }
+            if failed.is_empty() {
+                message.push_str(&format!("; deleted group '{}'", source));
+            } else {
+                message.push_str(&format!(
+                    "; failed: {}; kept group '{}'",
+                    failed.join(", "),
+                    source
+                ));
+            }
+            message
+        }
+        PendingAction::CreateGroupWithMembers { groupname, members } => {
+            adapter.create_group(groupname)?;
+            let mut message = format!("Created group '{}'", groupname);
+            add_new_group_members(adapter, groupname, members, &mut message);
+            message
+        }
+        PendingAction::CloneGroup {
+            source,
+            groupname,
+            members,
+        } => {
+            adapter.create_group(groupname)?;
+            let mut message = format!("Created group '{}' from '{}'", groupname, source);
+            add_new_group_members(adapter, groupname, members, &mut message);
+            message
+        }
+        PendingAction::RenameGroup { old_name, new_name } => {
+            adapter.rename_group(old_name, new_name)?;
+            format!("Renamed group to '{}'", new_name)
+        }
+
+        PendingAction::CreateUserWithOptions {
+            username,
+            password,
+            create_home,
+            add_to_wheel,
+        } => {
+            adapter.create_user(username, *create_home)?;
+            if let Some(pw) = password {
+                adapter.set_user_password(username, pw)?;
+            }
+            let sudo_group = crate::app::sudo_group_name();
+            if *add_to_wheel {
+                adapter.add_user_to_group(username, &sudo_group)?;
+            }
+            let mut msg = format!(
+                "Created user '{}'{}",
+                username,
+                if *create_home { " with home" } else { "" }
+            );
+            if password.is_some() {
+                msg.push_str(" with password");
+            }
+            if *add_to_wheel {
+                msg.push_str(&format!(" and {sudo_group}"));
+            }
+            msg
+        }
+        PendingAction::ImportUsers { rows, skipped } => {
+            let mut created = Vec::new();
+            let mut failed = Vec::new();
+            for row in rows {
+                let username = row.username.as_str();
+                if let Err(e) = adapter.create_user(username, row.create_home) {
+                    // Nothing done yet, so the whole import can be retried
+                    // (after a sudo prompt, say)
+                    if created.is_empty() && failed.is_empty() {
+                        return Err(e);
+                    }
+                    failed.push(format!("{} ({})", username, e));
+                    continue;
+                }
+                // Later steps are reported, not rolled back, as for CloneUser
+                if !row.full_name.is_empty()
+                    && let Err(e) = adapter.change_user_fullname(username, &row.full_name)
+                {
+                    failed.push(format!("{} full name ({})", username, e));
+                }
+                if !row.shell.is_empty()
+                    && let Err(e) = adapter.change_user_shell(username, &row.shell)
+                {
+                    failed.push(format!("{} shell {} ({})", username, row.shell, e));
+                }
+                for g in row.groups.iter() {
+                    if let Err(e) = adapter.add_user_to_group(username, g) {
+                        failed.push(format!("{} group {} ({})", username, g, e));
+                    }
+                }
+                created.push(username);
+            }
+            let mut message = format!(
+                "Imported {} of {} users: {}",
+                created.len(),
+                rows.len(),
+                created.join(", ")
+            );
+            if !failed.is_empty() {
+                message.push_str(&format!("\nFailed: {}", failed.join(", ")));
+            }
+            for note in skipped {
+                message.push_str(&format!("\nSkipped {}", note));
+            }
+            message
+        }
+        PendingAction::CloneUser {
+            template,
+            username,
+            password,
+            create_home,
+            shell,
+            groupnames,
+        } => {
+            // As with CloneGroup, a retry would fail on the create, so later
+            // steps that fail are listed instead of returned.
+            adapter.create_user(username, *create_home)?;
+            let mut failed = Vec::new();
+            if let Some(pw) = password
+                && let Err(e) = adapter.set_user_password(username, pw)
+            {
+                failed.push(format!("password ({})", e));
+            }
+            if let Err(e) = adapter.change_user_shell(username, shell) {
+                failed.push(format!("shell {} ({})", shell, e));
+            }
+            let mut added = Vec::new();
+            for g in groupnames.iter() {
+                match adapter.add_user_to_group(username, g) {
+                    Ok(()) => added.push(g.as_str()),
+                    Err(e) => failed.push(format!("{} ({})", g, e)),
+                }
+            }
+            let mut message = format!("Created user '{}' from '{}'", username, template);
+            if !added.is_empty() {
+                message.push_str(&format!("; added to {}", added.join(", ")));
+            }
+            if !failed.is_empty() {
+                message.push_str(&format!("; failed: {}", failed.join(", ")));
+            }
+            message
+        }
+        PendingAction::DeleteUser {
+            username,
+            delete_home,
+        } => {
+            adapter.delete_user(username, *delete_home)?;
+            let suffix = if *delete_home { " and home" } else { "" };
+            format!("Deleted user '{}'{}", username, suffix)
+        }
+        PendingAction::SetPassword {
+            username,
+            password,
+            must_change,
+        } => {
+            adapter.set_user_password(username, password)?;
+            if *must_change {
+                let _ = adapter.expire_user_password(username);
+            }
+            format!(
+                "Password set{}",
+                if *must_change {
+                    ", must change at next login"
+                } else {
+                    ""
+                }
+            )
+        }
+        PendingAction::CreateSudoersDropin { username } => {
+            adapter.create_sudoers_dropin(username)?;
+            format!(
+                "Created {}",
+                crate::sys::sudoers_dropin_path(username).display()
+            )
+        }
+        PendingAction::ResetPassword { username } => {
+            adapter.expire_user_password(username)?;
+            "Password reset (must change at next login)".to_string()
+        }
+        PendingAction::AddUserToGroups {
+            username,
+            groupnames,
+        } => {
+            for g in groupnames.iter() {
+                adapter.add_user_to_group(username, g)?;
+            }
+            for g in groupnames.iter() {
+                changed(username, g, true);
+            }
+            format!("Added '{}' to selected groups", username)
+        }
+        PendingAction::RemoveUserFromGroups {
+            username,
+            groupnames,
+        } => {
+            match remaining_groups(adapter, username, groupnames) {
+                Ok(keep) => {
+                    let keep: Vec<&str> = keep.iter().map(String::as_str).collect();
+                    adapter.set_supplementary_groups(username, &keep)?;
+                }
+                Err(e) if crate::error::is_auth_error(&*e) => return Err(e),
+                Err(e) => {
+                    crate::logging::warn(format_args!(
+                        "cannot work out the groups '{username}' keeps ({e}); \
+                         removing one group at a time"
+                    ));
+                    for g in groupnames.iter() {
+                        adapter.remove_user_from_group(username, g)?;
+                    }
+                }
+            }
+            for g in groupnames.iter() {
+                changed(username, g, false);
+            }
+            format!("Removed '{}' from selected groups", username)
+        }
+        PendingAction::AddMembersToGroup {
+            groupname,
+            usernames,
+        } => {
+            for u in usernames.iter() {
+                adapter.add_user_to_group(u, groupname)?;
+            }
+            for u in usernames.iter() {
+                changed(u, groupname, true);
+            }
+            format!("Added selected users to '{}'", groupname)
+        }
+        PendingAction::RemoveMembersFromGroup {
+            groupname,
+            usernames,
+        } => {
+            for u in usernames.iter() {
+                adapter.remove_user_from_group(u, groupname)?;
+            }
+            for u in usernames.iter() {
+                changed(u, groupname, false);
+            }
+            format!("Removed selected users from '{}'", groupname)
+        }
+        PendingAction::MoveUserBetweenGroups {
+            username,
+            from_group,
+            to_group,
+        } => {
+            // A failed removal aborts before anything changed, so the usual
+            // sudo retry applies. Once removed, retrying would fail on the
+            // remove, so a failed add is reported rather than returned.
+            adapter.remove_user_from_group(username, from_group)?;
+            changed(username, from_group, false);
+            match adapter.add_user_to_group(username, to_group) {
+                Ok(()) => {
+                    changed(username, to_group, true);
+                    format!(
+                        "Moved '{}' from '{}' to '{}'",
+                        username, from_group, to_group
+                    )
+                }
+                Err(e) => format!(
+                    "Removed '{}' from '{}', but adding to '{}' failed: {}",
+                    username, from_group, to_group, e
+                ),
+            }
+        }
+    };
+    Ok(crate::app::ActionDone {
+        message,
+        memberships,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys::fake::FakeBackend;
+    use crossterm::event::{KeyEvent, KeyModifiers};
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    /// App state whose backend fails the test when used and that keeps no
+    /// audit trail; tests that run actions or reloads install a fake with
+    /// [`use_fake`].
+    fn test_app() -> AppState {
+        AppState {
+            backend: std::sync::Arc::new(|_| {
+                panic!("the test ran an action without a fake backend")
+            }),
+            #[cfg(not(feature = "read-only-build"))]
+            audit_log: None,
+            ..AppState::default()
+        }
+    }
+
+    /// Group `name` listing `members`, for test fixtures.
+    fn group(gid: u32, name: &str, members: &[&str]) -> crate::sys::SystemGroup {
+        crate::sys::SystemGroup {
+            gid,
+            name: name.into(),
+            members: members.iter().map(|m| m.to_string()).collect(),
+        }
+    }
+
+    /// User `name` whose primary group has the same id, with a home under
+    /// `/home` and bash as shell, for test fixtures.
+    fn user(uid: u32, name: &str) -> crate::sys::SystemUser {
+        crate::sys::SystemUser {
+            uid,
+            name: name.into(),
+            primary_gid: uid,
+            full_name: None,
+            home_dir: format!("/home/{name}"),
+            shell: "/bin/bash".into(),
+        }
+    }
+
+    /// Run the actions and reloads of `app` against clones of `fake`, which
+    /// share its call log and are given the app's sudo password.
+    fn use_fake(app: &mut AppState, fake: &FakeBackend) {
+        let fake = fake.clone();
+        app.backend = std::sync::Arc::new(move |password| {
+            Box::new(FakeBackend {
+                password,
+                ..fake.clone()
+            })
+        });
+    }
+
+    /// Execute a queued action against a fake `adapter` and refresh state lists,
+    /// noting the attempt in the action history.
+    ///
+    /// The event loop runs the same two halves apart, with the commands on a
+    /// worker thread.
+    #[cfg(not(feature = "read-only-build"))]
+    fn perform_pending_action_with(
+        app: &mut AppState,
+        pending: PendingAction,
+        adapter: &dyn UserGroupBackend,
+    ) -> Result<()> {
+        let result = run_action_commands(&pending, adapter);
+        finish_action(app, pending, result, adapter)
+    }
+
+    fn normal_mode_app() -> AppState {
+        AppState {
+            users: vec![user(1000, "alice"), user(1001, "bob"), user(1002, "carol")],
+            groups: vec![group(10, "wheel", &["alice"]), group(20, "audio", &[])],
+            keymap: crate::app::keymap::Keymap::default(),
+            rows_per_page: 2,
+            ..test_app()
+        }
+    }
+
+    #[test]
+    fn membership_source_toggles_member_of_between_files_and_id() {
+        let mut app = normal_mode_app();
+        app.groups_all = app.groups.clone();
+        app.effective_groups_cache = Some((
+            "alice".into(),
+            Some(vec!["alice".into(), "audio".into(), "ldap-devs".into()]),
+        ));
+        let names = |app: &AppState| -> Vec<String> {
+            member_of_groups(app).into_iter().map(|g| g.name).collect()
+        };
+        assert_eq!(names(&app), ["wheel"]);
+        assert!(crate::app::nss_only_groups(&app).is_empty());
+
+        handle_normal_key(&mut app, key(KeyCode::Char('M')));
+        assert_eq!(app.membership_source, MembershipSource::Id);
+        assert_eq!(names(&app), ["audio"]);
+        assert_eq!(crate::app::nss_only_groups(&app), ["alice", "ldap-devs"]);
+
+        // Without an answer from `id` the pane keeps the files view
+        app.effective_groups_cache = Some(("alice".into(), None));
+        assert_eq!(names(&app), ["wheel"]);
+
+        handle_normal_key(&mut app, key(KeyCode::Char('M')));
+        assert_eq!(app.membership_source, MembershipSource::Files);
+    }
+
+    #[test]
+    fn effective_groups_fall_back_silently_and_stay_cached() {
+        let mut app = normal_mode_app();
+        let fake = FakeBackend {
+            users: app.users.clone(),
+            groups: app.groups.clone(),
+            ..Default::default()
+        };
+        use_fake(&mut app, &fake);
+
+        // Unknown to the backend, so only the file-derived groups remain
+        assert!(crate::app::effective_groups(&mut app, "no-such-user").is_none());
+        assert_eq!(
+            app.effective_groups_cache,
+            Some(("no-such-user".to_string(), None))
+        );
+
+        // A cached answer is reused for the same user without asking again
+        for _ in 0..2 {
+            assert_eq!(
+                crate::app::effective_groups(&mut app, "alice"),
+                Some(["wheel".to_string()].as_slice())
+            );
+        }
+        assert_eq!(fake.calls(), ["id no-such-user", "id alice"]);
+    }
+
+    #[test]
+    fn normal_keys_move_and_wrap_in_the_focused_list() {
+        let mut app = normal_mode_app();
+
+        handle_normal_key(&mut app, key(KeyCode::Up));
+        assert_eq!(app.selected_user_index, 2);
+        handle_normal_key(&mut app, key(KeyCode::Down));
+        assert_eq!(app.selected_user_index, 0);
+        handle_normal_key(&mut app, key(KeyCode::PageDown));
+        assert_eq!(app.selected_user_index, 2);
+        handle_normal_key(&mut app, key(KeyCode::PageDown));
+        assert_eq!(app.selected_user_index, 2);
+        handle_normal_key(&mut app, key(KeyCode::PageUp));
+        assert_eq!(app.selected_user_index, 0);
+
+        // In Member of, movement stays within alice's groups (wheel only)
+        app.users_focus = UsersFocus::MemberOf;
+        app.selected_group_index = 0;
+        handle_normal_key(&mut app, key(KeyCode::Down));
+        assert_eq!(app.selected_group_index, 0);
+        assert_eq!(app.selected_user_index, 0);
+    }
+
+    #[test]
+    fn home_and_end_jump_to_the_edges_of_lists_and_pickers() {
+        let mut app = normal_mode_app();
+        app.selected_user_index = 1;
+
+        handle_normal_key(&mut app, key(KeyCode::End));
+        assert_eq!(app.selected_user_index, 2);
+        handle_normal_key(&mut app, key(KeyCode::Home));
+        assert_eq!(app.selected_user_index, 0);
+        handle_normal_key(&mut app, key(KeyCode::Char('G')));
+        assert_eq!(app.selected_user_index, 2);
+
+        // In a picker `g` is free, so both vim keys work there
+        app.groups_all = (0..30)
+            .map(|i| crate::sys::SystemGroup {
+                gid: 3000 + i,
+                name: format!("team{i:02}"),
+                members: vec![],
+            })
+            .collect();
+        app.open_modal(ModalState::ModifyGroupsAdd {
+            selected: 5,
+            offset: 0,
+            selected_multi: BTreeSet::new(),
+            show_all: false,
+        });
+        app.input_mode = InputMode::Modal;
+        let cursor = |app: &AppState| match app.modal() {
+            Some(ModalState::ModifyGroupsAdd {
+                selected, offset, ..
+            }) => (*selected, *offset),
+            other => panic!("expected the group picker, got {other:?}"),
+        };
+
+        handle_modal_key(&mut app, key(KeyCode::Char('G')));
+        assert_eq!(cursor(&app).0, 29);
+        handle_modal_key(&mut app, key(KeyCode::Char('g')));
+        assert_eq!(cursor(&app), (0, 0));
+        handle_modal_key(&mut app, key(KeyCode::End));
+        assert_eq!(cursor(&app).0, 29);
+        handle_modal_key(&mut app, key(KeyCode::Home));
+        assert_eq!(cursor(&app), (0, 0));
+    }
+
+    #[test]
+    fn jump_from_member_of_selects_group_and_returns_to_user() {
+        let mut app = normal_mode_app();
+        app.selected_user_index = 0;
+        app.users_focus = UsersFocus::MemberOf;
+        // wheel (gid 10) is alice's only Member of entry
+        app.selected_group_index = 0;
+
+        handle_normal_key(&mut app, key(KeyCode::Char('g')));
+        assert_eq!(app.active_tab, ActiveTab::Groups);
+        assert_eq!(app.groups[app.selected_group_index].gid, 10);
+        assert_eq!(app.group_jump_origin, Some(("alice".to_string(), 1000)));
+
+        app.selected_user_index = 2;
+        handle_normal_key(&mut app, key(KeyCode::Char('g')));
+        assert_eq!(app.active_tab, ActiveTab::Users);
+        assert_eq!(app.users[app.selected_user_index].name, "alice");
+        assert_eq!(app.users_focus, UsersFocus::MemberOf);
+        assert_eq!(app.selected_group_index, 0);
+        assert!(app.group_jump_origin.is_none());
+    }
+
+    #[test]
+    fn duplicate_user_names_are_resolved_by_uid() {
+        let mut app = normal_mode_app();
+        let mut second = app.users[0].clone();
+        second.uid = 1500;
+        app.users.push(second);
+        assert_eq!(
+            crate::sys::duplicate_user_names(&app.users),
+            vec!["alice".to_string()]
+        );
+        assert_eq!(
+            crate::app::find_user_index(&app.users, "alice", Some(1500)),
+            Some(3)
+        );
+        assert_eq!(
+            crate::app::find_user_index(&app.users, "alice", Some(42)),
+            Some(0)
+        );
+        assert_eq!(
+            crate::app::find_user_index(&app.users, "alice", None),
+            Some(0)
+        );
+
+        // Jumping from the second alice's Member of comes back to that entry
+        app.selected_user_index = 3;
+        app.users_focus = UsersFocus::MemberOf;
+        app.selected_group_index = 0;
+        handle_normal_key(&mut app, key(KeyCode::Char('g')));
+        assert_eq!(app.group_jump_origin, Some(("alice".to_string(), 1500)));
+        handle_normal_key(&mut app, key(KeyCode::Char('g')));
+        assert_eq!(app.selected_user_index, 3);
+    }
+
+    #[test]
+    fn normal_keys_switch_tabs_and_quit() {
+        let mut app = normal_mode_app();
+
+        assert_eq!(
+            handle_normal_key(&mut app, key(KeyCode::Tab)),
+            LoopControl::Continue
+        );
+        assert_eq!(app.active_tab, ActiveTab::Groups);
+        // Movement now applies to the groups table
+        handle_normal_key(&mut app, key(KeyCode::Down));
+        assert_eq!(app.selected_group_index, 1);
+        assert_eq!(app.selected_user_index, 0);
+
+        handle_normal_key(&mut app, key(KeyCode::Tab));
+        assert_eq!(app.active_tab, ActiveTab::Users);
+        assert_eq!(
+            handle_normal_key(&mut app, key(KeyCode::Char('q'))),
+            LoopControl::Quit
+        );
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn scripted_create_group_flow_reaches_sudo_prompt() {
+        let fake = FakeBackend {
+            accepts_password: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let mut app = AppState {
+            keymap: crate::app::keymap::Keymap::default(),
+            running_as_root: None,
+            ..test_app()
+        };
+        use_fake(&mut app, &fake);
+
+        step(&mut app, key(KeyCode::Tab));
+        assert_eq!(app.active_tab, ActiveTab::Groups);
+        step(&mut app, key(KeyCode::Char('n')));
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::GroupAddInput { .. })
+        ));
+        for c in "devs".chars() {
+            step(&mut app, key(KeyCode::Char(c)));
+        }
+        step(&mut app, key(KeyCode::Enter));
+
+        match app.modal() {
+            Some(ModalState::SudoPrompt {
+                next: PendingAction::CreateGroup { groupname },
+                ..
+            }) => assert_eq!(groupname, "devs"),
+            other => panic!("expected sudo prompt, got {:?}", other),
+        }
+        // The name input is kept underneath for when authentication is cancelled
+        assert_eq!(app.modal_stack.len(), 2);
+        // Nothing runs until sudo accepts a password
+        assert!(fake.calls().is_empty());
+
+        step(&mut app, key(KeyCode::Esc));
+        assert!(matches!(app.input_mode, InputMode::Normal));
+        assert_eq!(step(&mut app, key(KeyCode::Char('q'))), LoopControl::Quit);
+    }
+
+    #[test]
+    fn filter_menu_show_all_clears_users_filter_and_closes() {
+        let mut app = AppState {
+            active_tab: ActiveTab::Users,
+            input_mode: InputMode::Modal,
+            modal_stack: vec![ModalState::FilterMenu { selected: 0 }],
+            ..test_app()
+        };
+
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+
+        assert!(app.users_filter.is_none());
+        assert!(app.modal().is_none());
+        assert!(matches!(app.input_mode, InputMode::Normal));
+    }
+
+    #[test]
+    fn actions_delete_opens_delete_confirm_with_allowed_flag() {
+        let mut app = AppState {
+            users: vec![crate::sys::SystemUser {
+                uid: 1500,
+                name: "testuser".to_string(),
+                primary_gid: 1500,
+                full_name: None,
+                home_dir: "/home/testuser".to_string(),
+                shell: "/bin/bash".to_string(),
+            }],
+            input_mode: InputMode::Modal,
+            modal_stack: vec![ModalState::Actions { selected: 1 }],
+            ..test_app()
+        };
+
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+
+        match app.modal() {
+            Some(ModalState::DeleteConfirm { allowed, .. }) => assert!(*allowed),
+            other => panic!("unexpected modal state: {:?}", other),
+        }
+
+        // Checking "delete home" measures it on a worker thread; a missing
+        // home has no size
+        let home = std::env::temp_dir().join(format!("ugm_delete_home_{}", std::process::id()));
+        std::fs::create_dir_all(&home).unwrap();
+        std::fs::write(home.join("notes"), "x".repeat(100)).unwrap();
+        app.users[0].home_dir = home.to_string_lossy().into_owned();
+        handle_modal_key(&mut app, key(KeyCode::Char(' ')));
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::DeleteConfirm {
+                delete_home: true,
+                home_size: None,
+                measuring: true,
+                ..
+            })
+        ));
+        run_until_idle(&mut app);
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::DeleteConfirm {
+                delete_home: true,
+                home_size: Some(100),
+                measuring: false,
+                ..
+            })
+        ));
+        std::fs::remove_dir_all(&home).unwrap();
+        handle_modal_key(&mut app, key(KeyCode::Char(' ')));
+        handle_modal_key(&mut app, key(KeyCode::Char(' ')));
+        run_until_idle(&mut app);
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::DeleteConfirm {
+                delete_home: true,
+                home_size: None,
+                ..
+            })
+        ));
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn typed_confirmation_for_delete_user_asks_for_the_name() {
+        use crate::app::confirmconf::{ConfirmLevel, Confirmations};
+        let fake = FakeBackend::default();
+        let mut app = AppState {
+            users: vec![crate::sys::SystemUser {
+                uid: 1500,
+                name: "testuser".to_string(),
+                primary_gid: 1500,
+                full_name: None,
+                home_dir: "/home/testuser".to_string(),
+                shell: "/bin/bash".to_string(),
+            }],
+            keymap: crate::app::keymap::Keymap::default(),
+            confirmations: Confirmations {
+                delete_user: ConfirmLevel::Typed,
+                ..Confirmations::default()
+            },
+            running_as_root: Some("root".to_string()),
+            ..test_app()
+        };
+        use_fake(&mut app, &fake);
+
+        step(&mut app, key(KeyCode::Delete));
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::DeleteConfirm { .. })
+        ));
+        step(&mut app, key(KeyCode::Left));
+        step(&mut app, key(KeyCode::Enter));
+        match app.modal() {
+            Some(ModalState::TypedConfirm { keyword, next, .. }) => {
+                assert_eq!(keyword, "testuser");
+                assert!(matches!(next, PendingAction::DeleteUser { .. }));
+            }
+            other => panic!("expected typed confirm, got {:?}", other),
+        }
+        for c in "testuse".chars() {
+            step(&mut app, key(KeyCode::Char(c)));
+        }
+        step(&mut app, key(KeyCode::Enter));
+        assert!(matches!(app.modal(), Some(ModalState::TypedConfirm { .. })));
+        step(&mut app, key(KeyCode::Char('r')));
+        step(&mut app, key(KeyCode::Enter));
+
+        let calls = fake.calls();
+        assert_eq!(calls[0], "delete_user testuser false");
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn no_confirmation_for_delete_user_runs_directly() {
+        use crate::app::confirmconf::{ConfirmLevel, Confirmations};
+        let fake = FakeBackend::default();
+        let mut app = AppState {
+            users: vec![crate::sys::SystemUser {
+                uid: 1500,
+                name: "testuser".to_string(),
+                primary_gid: 1500,
+                full_name: None,
+                home_dir: "/home/testuser".to_string(),
+                shell: "/bin/bash".to_string(),
+            }],
+            keymap: crate::app::keymap::Keymap::default(),
+            confirmations: Confirmations {
+                delete_user: ConfirmLevel::None,
+                ..Confirmations::default()
+            },
+            running_as_root: Some("root".to_string()),
+            ..test_app()
+        };
+        use_fake(&mut app, &fake);
+
+        step(&mut app, key(KeyCode::Delete));
+
+        let calls = fake.calls();
+        assert_eq!(calls[0], "delete_user testuser false");
+        assert!(matches!(app.modal(), Some(ModalState::Info { .. })));
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn orphan_members_lists_unknown_members_and_removes_one() {
+        let fake = FakeBackend::default();
+        let mut app = normal_mode_app();
+        use_fake(&mut app, &fake);
+        app.users_all = app.users.clone();
+        app.groups = vec![crate::sys::SystemGroup {
+            gid: 2000,
+            name: "devs".into(),
+            members: vec!["ghost".into(), "alice".into(), "olduser".into()],
+        }];
+        app.active_tab = ActiveTab::Groups;
+        app.running_as_root = Some("root".to_string());
+        app.open_modal(ModalState::GroupDetails);
+        app.input_mode = InputMode::Modal;
+
+        step(&mut app, key(KeyCode::Char('o')));
+        match app.modal() {
+            Some(ModalState::OrphanMembers {
+                group_name,
+                orphans,
+                ..
+            }) => {
+                assert_eq!(group_name, "devs");
+                assert_eq!(orphans, &["ghost", "olduser"]);
+            }
+            other => panic!("expected the orphan list, got {other:?}"),
+        }
+
+        // Backspace returns to the details the list was opened from
+        step(&mut app, key(KeyCode::Backspace));
+        assert!(matches!(app.modal(), Some(ModalState::GroupDetails)));
+
+        step(&mut app, key(KeyCode::Char('o')));
+        step(&mut app, key(KeyCode::Up));
+        step(&mut app, key(KeyCode::Enter));
+        let calls = fake.calls();
+        assert_eq!(calls[0], "remove olduser devs");
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn clone_user_copies_shell_and_supplementary_groups() {
+        let fake = FakeBackend::default();
+        let mut app = normal_mode_app();
+        use_fake(&mut app, &fake);
+        app.users[0].shell = "/bin/zsh".to_string();
+        app.groups_all = vec![
+            crate::sys::SystemGroup {
+                gid: 1000,
+                name: "alice".into(),
+                members: vec!["alice".into()],
+            },
+            crate::sys::SystemGroup {
+                gid: 10,
+                name: "wheel".into(),
+                members: vec!["alice".into()],
+            },
+        ];
+        app.running_as_root = Some("root".to_string());
+
+        step(&mut app, key(KeyCode::Enter));
+        step(&mut app, key(KeyCode::Up));
+        step(&mut app, key(KeyCode::Enter));
+        match app.modal() {
+            Some(ModalState::CloneUser {
+                template,
+                shell,
+                groups,
+                name,
+                ..
+            }) => {
+                assert_eq!(template, "alice");
+                assert_eq!(shell, "/bin/zsh");
+                assert_eq!(groups, &vec!["wheel".to_string()]);
+                assert!(name.is_empty());
+            }
+            other => panic!("expected clone form, got {:?}", other),
+        }
+        for c in "dave".chars() {
+            step(&mut app, key(KeyCode::Char(c)));
+        }
+        for _ in 0..4 {
+            step(&mut app, key(KeyCode::Down));
+        }
+        step(&mut app, key(KeyCode::Enter));
+        // Copying the wheel membership grants sudo, which is confirmed first
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::ConfirmAction {
+                warning: Some(_),
+                ..
+            })
+        ));
+        step(&mut app, key(KeyCode::Left));
+        step(&mut app, key(KeyCode::Enter));
+
+        let calls = fake.calls();
+        assert_eq!(
+            calls[..3],
+            [
+                "create_user dave true",
+                "shell dave /bin/zsh",
+                "add dave wheel"
+            ]
+        );
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn adding_to_the_sudo_group_asks_unless_configured_off() {
+        use crate::app::confirmconf::{ConfirmLevel, Confirmations};
+        let fake = FakeBackend::default();
+        let sudo = crate::app::sudo_group_name();
+        let mut app = normal_mode_app();
+        use_fake(&mut app, &fake);
+        app.running_as_root = Some("root".to_string());
+        let picker = ModalState::ModifyGroupsAdd {
+            selected: 0,
+            offset: 0,
+            selected_multi: [sudo.clone()].into(),
+            show_all: false,
+        };
+        app.groups_all = vec![crate::sys::SystemGroup {
+            gid: 10,
+            name: sudo.clone(),
+            members: vec![],
+        }];
+        app.input_mode = InputMode::Modal;
+        app.open_modal(picker.clone());
+
+        step(&mut app, key(KeyCode::Enter));
+        match app.modal() {
+            Some(ModalState::ConfirmAction {
+                warning: Some(warning),
+                ..
+            }) => assert!(warning.contains("grants sudo to alice"), "{warning}"),
+            other => panic!("expected the sudo confirmation, got {other:?}"),
+        }
+        // No is the default answer
+        step(&mut app, key(KeyCode::Enter));
+        assert!(fake.calls().is_empty());
+
+        app.confirmations = Confirmations {
+            grant_sudo: ConfirmLevel::None,
+            ..Confirmations::default()
+        };
+        app.open_modal(picker);
+        app.input_mode = InputMode::Modal;
+        step(&mut app, key(KeyCode::Enter));
+        let calls = fake.calls();
+        assert_eq!(calls[0], format!("add alice {sudo}"));
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn shell_picker_filters_as_you_type() {
+        let fake = FakeBackend::default();
+        let mut app = normal_mode_app();
+        use_fake(&mut app, &fake);
+        app.running_as_root = Some("root".to_string());
+        app.input_mode = InputMode::Modal;
+        app.open_modal(ModalState::ModifyShell {
+            selected: 0,
+            offset: 0,
+            shells: ["/bin/bash", "/bin/sh", "/usr/bin/zsh", "/usr/bin/fish"]
+                .map(String::from)
+                .to_vec(),
+            filter: String::new(),
+        });
+
+        for c in "usr".chars() {
+            step(&mut app, key(KeyCode::Char(c)));
+        }
+        step(&mut app, key(KeyCode::Down));
+        match app.modal() {
+            Some(ModalState::ModifyShell {
+                selected,
+                shells,
+                filter,
+                ..
+            }) => {
+                assert_eq!(filter, "usr");
+                assert_eq!(
+                    crate::app::matching_shells(shells, filter),
+                    vec!["/usr/bin/zsh", "/usr/bin/fish"]
+                );
+                assert_eq!(*selected, 1);
+            }
+            other => panic!("expected shell picker, got {:?}", other),
+        }
+
+        // Editing the filter starts over at the top of the new matches
+        step(&mut app, key(KeyCode::Backspace));
+        step(&mut app, key(KeyCode::Char('r')));
+        step(&mut app, key(KeyCode::Char('/')));
+        step(&mut app, key(KeyCode::Char('b')));
+        step(&mut app, key(KeyCode::Enter));
+
+        let calls = fake.calls();
+        assert_eq!(calls[0], "shell alice /usr/bin/zsh");
+    }
+
+    #[cfg(all(unix, not(feature = "read-only-build")))]
+    #[test]
+    fn custom_shell_path_must_be_executable_and_warns_when_unlisted() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let plain = dir.path().join("notes");
+        let shell = dir.path().join("fish");
+        std::fs::write(&plain, "").unwrap();
+        std::fs::write(&shell, "").unwrap();
+        std::fs::set_permissions(&shell, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let plain = plain.to_str().unwrap().to_string();
+        let shell = shell.to_str().unwrap().to_string();
+
+        let fake = FakeBackend::default();
+        let mut app = normal_mode_app();
+        use_fake(&mut app, &fake);
+        app.running_as_root = Some("root".to_string());
+        app.input_mode = InputMode::Modal;
+        app.open_modal(ModalState::ModifyShell {
+            selected: 0,
+            offset: 0,
+            shells: vec!["/bin/bash".to_string()],
+            filter: String::new(),
+        });
+
+        // "Other…" sits below the last shell
+        step(&mut app, key(KeyCode::Down));
+        step(&mut app, key(KeyCode::Enter));
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::ModifyTextInput {
+                field: ModifyField::Shell,
+                ..
+            })
+        ));
+
+        for c in plain.chars() {
+            step(&mut app, key(KeyCode::Char(c)));
+        }
+        step(&mut app, key(KeyCode::Enter));
+        assert_eq!(
+            app.modal_notice.as_deref(),
+            Some(format!("{plain} is not an executable file").as_str())
+        );
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::ModifyTextInput { .. })
+        ));
+
+        if let Some(ModalState::ModifyTextInput { value, cursor, .. }) = app.modal_stack.last_mut()
+        {
+            *value = shell.clone();
+            *cursor = shell.chars().count();
+        }
+        step(&mut app, key(KeyCode::Enter));
+        match app.modal() {
+            Some(ModalState::ConfirmAction { warning, .. }) => {
+                assert!(
+                    warning
+                        .as_deref()
+                        .unwrap()
+                        .contains("not listed in /etc/shells")
+                );
+            }
+            other => panic!("expected a warning before running, got {:?}", other),
+        }
+        step(&mut app, key(KeyCode::Left));
+        step(&mut app, key(KeyCode::Enter));
+
+        let calls = fake.calls();
+        assert_eq!(calls[0], format!("shell alice {shell}"));
+    }
+
+    #[test]
+    fn member_sort_defaults_to_alphabetical_and_keeps_the_selection() {
+        let mut app = AppState {
+            groups: vec![crate::sys::SystemGroup {
+                gid: 30,
+                name: "staff".into(),
+                members: vec!["mallory".into(), "Bob".into(), "alice".into()],
+            }],
+            ..test_app()
+        };
+        assert_eq!(
+            crate::app::selected_group_members(&app),
+            vec!["alice", "Bob", "mallory"]
+        );
+
+        app.selected_group_member_index = 1;
+        toggle_member_sort(&mut app);
+        assert_eq!(
+            crate::app::selected_group_members(&app),
+            vec!["mallory", "Bob", "alice"]
+        );
+        assert_eq!(app.selected_group_member_index, 1);
+
+        app.selected_group_member_index = 0;
+        toggle_member_sort(&mut app);
+        assert_eq!(app.selected_group_member_index, 2);
+    }
+
+    #[test]
+    fn change_password_mismatch_shows_info() {
+        let mut app = AppState {
+            input_mode: InputMode::Modal,
+            modal_stack: vec![ModalState::ChangePassword {
+                selected: 3, // Submit
+                password: "secret".to_string(),
+                confirm: "different".to_string(),
+                must_change: false,
+            }],
+            ..test_app()
+        };
+
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+
+        match app.modal() {
+            Some(ModalState::Info { message }) => {
+                assert!(message.contains("Passwords do not match"))
+            }
+            other => panic!("expected Info modal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn min_password_length_refuses_short_passwords_at_the_boundary() {
+        use crate::app::password_length_problem;
+        assert!(password_length_problem("", 8).is_none());
+        assert!(password_length_problem("1234567", 8).is_some());
+        assert!(password_length_problem("12345678", 8).is_none());
+        assert!(password_length_problem("x", 0).is_none());
+        // Characters, not bytes
+        assert!(password_length_problem("ääää", 5).is_some());
+
+        let too_short = |message: Option<&ModalState>| matches!(message, Some(ModalState::Info { message }) if message.contains("at least 8"));
+        let change_password = |pw: &str| ModalState::ChangePassword {
+            selected: 3,
+            password: pw.to_string(),
+            confirm: pw.to_string(),
+            must_change: false,
+        };
+        let mut app = AppState {
+            input_mode: InputMode::Modal,
+            min_password_length: 8,
+            users: normal_mode_app().users,
+            modal_stack: vec![change_password("1234567")],
+            ..test_app()
+        };
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+        assert!(too_short(app.modal()));
+
+        app.modal_stack = vec![change_password("12345678")];
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+        assert!(!too_short(app.modal()));
+
+        let user_add = |pw: &str| ModalState::UserAddInput {
+            selected: 5,
+            name: "dave".to_string(),
+            password: pw.to_string(),
+            confirm: pw.to_string(),
+            cursor: 0,
+            create_home: false,
+            add_to_wheel: false,
+        };
+        app.queued_action = None;
+        app.modal_stack = vec![user_add("1234567")];
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+        assert!(too_short(app.modal()));
+        assert!(app.queued_action.is_none());
+
+        // No password at all is still allowed when creating a user
+        app.modal_stack = vec![user_add("")];
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+        assert!(!too_short(app.modal()));
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn sudo_prompt_gives_up_after_three_wrong_passwords() {
+        let fake = FakeBackend {
+            accepts_password: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let mut app = normal_mode_app();
+        use_fake(&mut app, &fake);
+        app.running_as_root = None;
+        app.input_mode = InputMode::Modal;
+        app.modal_stack = vec![ModalState::GroupAddInput {
+            name: "devs".into(),
+            cursor: 4,
+        }];
+        step(&mut app, key(KeyCode::Enter));
+
+        for attempt in 0..crate::app::MAX_SUDO_ATTEMPTS - 1 {
+            match app.modal() {
+                Some(ModalState::SudoPrompt { attempts, .. }) => assert_eq!(*attempts, attempt),
+                other => panic!("expected sudo prompt, got {other:?}"),
+            }
+            step(&mut app, key(KeyCode::Char('x')));
+            step(&mut app, key(KeyCode::Enter));
+        }
+        step(&mut app, key(KeyCode::Char('x')));
+        step(&mut app, key(KeyCode::Enter));
+        match app.modal() {
+            Some(ModalState::Info { message }) => {
+                assert!(message.contains("action was cancelled"), "{message}")
+            }
+            other => panic!("expected the action to be cancelled, got {other:?}"),
+        }
+        assert!(app.sudo_password.is_none());
+        assert!(fake.calls().is_empty());
+
+        // Running the action again starts with a fresh count
+        app.modal_stack = vec![ModalState::GroupAddInput {
+            name: "devs".into(),
+            cursor: 4,
+        }];
+        step(&mut app, key(KeyCode::Enter));
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::SudoPrompt { attempts: 0, .. })
+        ));
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn command_failure_after_the_password_is_shown_not_reprompted() {
+        let fake = FakeBackend {
+            accepts_password: Some("secret".to_string()),
+            fail_on: Some("create_group".to_string()),
+            ..Default::default()
+        };
+        let mut app = normal_mode_app();
+        use_fake(&mut app, &fake);
+        app.running_as_root = None;
+        app.input_mode = InputMode::Modal;
+        app.modal_stack = vec![ModalState::GroupAddInput {
+            name: "devs".into(),
+            cursor: 4,
+        }];
+        step(&mut app, key(KeyCode::Enter));
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::SudoPrompt { attempts: 0, .. })
+        ));
+
+        for c in "secret".chars() {
+            step(&mut app, key(KeyCode::Char(c)));
+        }
+        step(&mut app, key(KeyCode::Enter));
+        match app.modal() {
+            Some(ModalState::Info { message }) => {
+                assert_eq!(message, "Action failed: create_group devs failed")
+            }
+            other => panic!("expected the command error, got {other:?}"),
+        }
+        assert!(
+            !app.modal_stack
+                .iter()
+                .any(|m| matches!(m, ModalState::SudoPrompt { .. }))
+        );
+        // The password was right, so it is kept for the next action
+        assert_eq!(app.sudo_password.as_deref(), Some("secret"));
+        assert_eq!(fake.calls(), ["create_group devs"]);
+    }
+
+    #[test]
+    fn sudo_prompt_backspace_closes_when_empty() {
+        let mut app = AppState {
+            input_mode: InputMode::Modal,
+            modal_stack: vec![ModalState::SudoPrompt {
+                next: PendingAction::ResetPassword {
+                    username: "user".to_string(),
+                },
+                password: String::new(),
+                error: None,
+                attempts: 0,
+            }],
+            ..test_app()
+        };
+
+        handle_modal_key(&mut app, key(KeyCode::Backspace));
+
+        assert!(app.modal().is_none());
+        assert!(matches!(app.input_mode, InputMode::Normal));
+    }
+
+    #[test]
+    fn modify_groups_remove_primary_group_shows_info() {
+        // Create a user 'alice' with primary_gid 100 and groups including that primary group
+        let mut app = AppState {
+            users: vec![crate::sys::SystemUser {
+                uid: 1000,
+                name: "alice".to_string(),
+                primary_gid: 100,
+                full_name: None,
+                home_dir: "/home/alice".to_string(),
+                shell: "/bin/bash".to_string(),
+            }],
+            groups_all: vec![
+                crate::sys::SystemGroup {
+                    gid: 100,
+                    name: "users".to_string(),
+                    members: vec![],
+                },
+                crate::sys::SystemGroup {
+                    gid: 10,
+                    name: "wheel".to_string(),
+                    members: vec!["alice".to_string()],
+                },
+            ],
+            selected_user_index: 0,
+            ..test_app()
+        };
+
+        // Open ModifyGroupsRemove and select the primary group entry (index 0 in the filtered list)
+        app.input_mode = InputMode::Modal;
+        app.open_modal(ModalState::ModifyGroupsRemove {
+            selected: 0,
+            offset: 0,
+            selected_multi: BTreeSet::new(),
+        });
+
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+
+        match app.modal() {
+            Some(ModalState::Info { message }) => {
+                assert!(message.contains("Cannot remove user from primary group"))
+            }
+            other => panic!("expected Info modal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn empty_selection_enter_follows_configured_mode() {
+        for (mode, expected) in [
+            (
+                EmptyPickerEnter::Cursor,
+                "Cannot remove user from primary group",
+            ),
+            (EmptyPickerEnter::RequireChecked, "Nothing checked"),
+        ] {
+            let mut app = AppState {
+                users: vec![crate::sys::SystemUser {
+                    uid: 1000,
+                    name: "alice".to_string(),
+                    primary_gid: 100,
+                    full_name: None,
+                    home_dir: "/home/alice".to_string(),
+                    shell: "/bin/bash".to_string(),
+                }],
+                groups_all: vec![crate::sys::SystemGroup {
+                    gid: 100,
+                    name: "users".to_string(),
+                    members: vec![],
+                }],
+                input_mode: InputMode::Modal,
+                empty_picker_enter: mode,
+                ..test_app()
+            };
+            app.open_modal(ModalState::ModifyGroupsRemove {
+                selected: 0,
+                offset: 0,
+                selected_multi: BTreeSet::new(),
+            });
+
+            handle_modal_key(&mut app, key(KeyCode::Enter));
+
+            match app.modal() {
+                Some(ModalState::Info { message }) => assert!(message.contains(expected)),
+                other => panic!("{mode:?}: expected Info modal, got {:?}", other),
+            }
+            // Either way the picker stays underneath the notice
+            assert_eq!(app.modal_stack.len(), 2);
+        }
+    }
+
+    fn members_app() -> AppState {
+        let alice = crate::sys::SystemUser {
+            primary_gid: 2000,
+            ..user(1000, "alice")
+        };
+        let users = vec![alice, user(1001, "bob")];
+        let devs = group(2000, "devs", &["bob"]);
+        AppState {
+            users_all: users.clone(),
+            users: users[..1].to_vec(),
+            groups_all: vec![devs.clone()],
+            groups: vec![devs],
+            active_tab: ActiveTab::Groups,
+            groups_focus: GroupsFocus::Members,
+            members_include_primary: true,
+            ..test_app()
+        }
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn delete_on_secondary_member_confirms_removal_of_that_user() {
+        let mut app = members_app();
+        app.selected_group_member_index = 0;
+
+        confirm_member_removal(&mut app);
+
+        match app.modal() {
+            Some(ModalState::ConfirmRemoveUserFromGroup {
+                selected,
+                group_name,
+            }) => {
+                assert_eq!(*selected, 1);
+                assert_eq!(group_name, "devs");
+            }
+            other => panic!("expected removal confirm, got {:?}", other),
+        }
+        // bob was filtered out of the users list but is still selected
+        assert_eq!(app.users[app.selected_user_index].name, "bob");
+        assert!(matches!(app.input_mode, InputMode::Modal));
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn delete_on_primary_member_is_refused() {
+        let mut app = members_app();
+        app.selected_group_member_index = 1;
+
+        confirm_member_removal(&mut app);
+
+        match app.modal() {
+            Some(ModalState::Info { message }) => {
+                assert!(message.contains("Cannot remove user from primary group"))
+            }
+            other => panic!("expected Info modal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn actions_delete_blocked_for_non_user_uid_range() {
+        let mut app = AppState {
+            users: vec![crate::sys::SystemUser {
+                uid: 0,
+                name: "root".to_string(),
+                primary_gid: 0,
+                full_name: None,
+                home_dir: "/root".to_string(),
+                shell: "/bin/bash".to_string(),
+            }],
+            selected_user_index: 0,
+            input_mode: InputMode::Modal,
+            modal_stack: vec![ModalState::Actions { selected: 1 }], // Delete
+            ..test_app()
+        };
+
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+
+        match app.modal() {
+            Some(ModalState::Info { message }) => {
+                assert!(message.starts_with("Cannot delete 'root': user is root"));
+                assert!(message.contains("UID outside 1000-1999"));
+            }
+            other => panic!("expected Info modal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn nobody_accounts_are_flagged_and_never_deletable() {
+        let nobody = crate::sys::SystemUser {
+            uid: crate::sys::NOBODY_ID,
+            name: "nfsnobody".to_string(),
+            primary_gid: crate::sys::NOBODY_ID,
+            full_name: None,
+            home_dir: "/nonexistent".to_string(),
+            shell: "/usr/sbin/nologin".to_string(),
+        };
+        assert!(nobody.is_nobody());
+        let app = test_app();
+        assert!(!crate::app::deletion_blockers(&app, &nobody).is_empty());
+        // Recognized by name even inside the deletable UID range
+        let renumbered = crate::sys::SystemUser {
+            uid: 1500,
+            name: "nobody".to_string(),
+            ..nobody.clone()
+        };
+        assert!(
+            crate::app::deletion_blockers(&app, &renumbered)
+                .contains(&"shared nobody account".to_string())
+        );
+
+        let mut app = AppState {
+            users: vec![renumbered],
+            input_mode: InputMode::Modal,
+            modal_stack: vec![ModalState::Actions { selected: 1 }], // Delete
+            ..test_app()
+        };
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+        match app.modal() {
+            Some(ModalState::Info { message }) => assert!(message.contains("nobody account")),
+            other => panic!("expected Info modal, got {:?}", other),
+        }
+
+        let nogroup = crate::sys::SystemGroup {
+            gid: crate::sys::NOBODY_ID,
+            name: "nogroup".to_string(),
+            members: vec![],
+        };
+        assert!(nogroup.is_nobody());
+        app.groups = vec![nogroup];
+        assert!(matches!(
+            group_delete_modal(&app, Some(crate::sys::NOBODY_ID)),
+            ModalState::Info { .. }
+        ));
+    }
+
+    #[test]
+    fn deleting_a_primary_group_reassigns_its_users_first() {
+        let mut app = members_app();
+        let staff = crate::sys::SystemGroup {
+            gid: 100,
+            name: "staff".to_string(),
+            members: vec![],
+        };
+        app.groups_all.push(staff);
+        let devs = app.groups[0].clone();
+        assert_eq!(
+            crate::app::primary_group_users(&app.users_all, devs.gid),
+            vec!["alice"]
+        );
+
+        // devs is alice's primary group, so a fallback is picked first
+        app.input_mode = InputMode::Modal;
+        app.modal_stack = vec![group_delete_modal(&app, Some(devs.gid))];
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::GroupDeleteReassign {
+                target_gid: 2000,
+                ..
+            })
+        ));
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+        match app.modal() {
+            Some(ModalState::GroupDeleteConfirm { reassign_to, .. }) => {
+                assert_eq!(reassign_to.as_deref(), Some("staff"))
+            }
+            other => panic!("expected delete confirm, got {:?}", other),
+        }
+
+        let pending = crate::app::group_delete_action(&app.users_all, &devs, Some("staff"));
+        assert_eq!(
+            crate::app::describe_command(&pending),
+            "usermod -g staff alice\ngroupdel devs"
+        );
+        // Declining leaves the group alone
+        handle_modal_key(&mut app, key(KeyCode::Esc));
+        assert!(app.modal_stack.is_empty());
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn reassign_and_delete_moves_users_before_groupdel() {
+        let backend = FakeBackend::default();
+        let mut app = test_app();
+        let pending = PendingAction::ReassignAndDeleteGroup {
+            groupname: "devs".into(),
+            fallback: "staff".into(),
+            usernames: vec!["alice".into(), "carol".into()],
+        };
+
+        perform_pending_action_with(&mut app, pending, &backend).unwrap();
+
+        assert_eq!(
+            backend.calls(),
+            vec![
+                "primary alice staff",
+                "primary carol staff",
+                "delete_group devs",
+                "list_users",
+                "list_groups"
+            ]
+        );
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn reassign_and_delete_lists_users_moved_before_a_failure() {
+        let pending = PendingAction::ReassignAndDeleteGroup {
+            groupname: "devs".into(),
+            fallback: "staff".into(),
+            usernames: vec!["alice".into(), "carol".into()],
+        };
+
+        let backend = FakeBackend {
+            fail_on: Some("primary carol".into()),
+            ..Default::default()
+        };
+        let done = run_action_commands(&pending, &backend).unwrap();
+        assert_eq!(
+            backend.calls(),
+            ["primary alice staff", "primary carol staff"]
+        );
+        assert_eq!(
+            done.message,
+            "Moved alice to 'staff'; failed: carol (primary carol staff failed); \
+             kept group 'devs'"
+        );
+
+        let backend = FakeBackend {
+            fail_on: Some("delete_group".into()),
+            ..Default::default()
+        };
+        let done = run_action_commands(&pending, &backend).unwrap();
+        assert_eq!(
+            done.message,
+            "Moved alice, carol to 'staff', but deleting group 'devs' failed: \
+             delete_group devs failed"
+        );
+
+        // Nothing moved yet, so the error is returned for a retry
+        let backend = FakeBackend {
+            fail_on: Some("primary alice".into()),
+            ..Default::default()
+        };
+        assert!(run_action_commands(&pending, &backend).is_err());
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn merge_reports_partial_progress_and_keeps_the_source_group() {
+        let pending = PendingAction::MergeGroup {
+            source: "devs".into(),
+            destination: "staff".into(),
+            members: vec!["bob".into(), "dave".into()],
+            primary_users: vec!["alice".into()],
+        };
+
+        // A failure before anything changed is returned for a retry
+        let backend = FakeBackend {
+            fail_on: Some("add bob".into()),
+            ..Default::default()
+        };
+        assert!(run_action_commands(&pending, &backend).is_err());
+        assert_eq!(backend.calls(), ["add bob staff"]);
+
+        // Once a member has moved, the rest run and are reported instead
+        let backend = FakeBackend {
+            fail_on: Some("add dave".into()),
+            ..Default::default()
+        };
+        let done = run_action_commands(&pending, &backend).unwrap();
+        assert_eq!(
+            backend.calls(),
+            ["add bob staff", "add dave staff", "primary alice staff"]
+        );
+        assert_eq!(
+            done.message,
+            "Merging 'devs' into 'staff' stopped partway; added bob; \
+             moved primary group of alice; failed: dave (add dave staff failed); \
+             kept group 'devs'"
+        );
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn spaces_are_ignored_in_names_and_trimmed_from_full_names() {
+        let fake = FakeBackend::default();
+        let mut app = normal_mode_app();
+        use_fake(&mut app, &fake);
+        app.running_as_root = Some("root".to_string());
+        app.input_mode = InputMode::Modal;
+        app.modal_stack = vec![ModalState::ModifyTextInput {
+            field: ModifyField::Username,
+            value: String::new(),
+            cursor: 0,
+        }];
+        for c in ['a', ' ', 'l', '\t', 'i'] {
+            step(&mut app, key(KeyCode::Char(c)));
+        }
+        match app.modal() {
+            Some(ModalState::ModifyTextInput { value, .. }) => assert_eq!(value, "ali"),
+            other => panic!("expected the username input, got {other:?}"),
+        }
+
+        app.modal_stack = vec![ModalState::GroupAddInput {
+            name: String::new(),
+            cursor: 0,
+        }];
+        for c in "dev ops".chars() {
+            step(&mut app, key(KeyCode::Char(c)));
+        }
+        match app.modal() {
+            Some(ModalState::GroupAddInput { name, .. }) => assert_eq!(name, "devops"),
+            other => panic!("expected the group name input, got {other:?}"),
+        }
+
+        // Full names keep inner spaces but lose the surrounding ones
+        app.modal_stack = vec![ModalState::ModifyTextInput {
+            field: ModifyField::Fullname,
+            value: String::new(),
+            cursor: 0,
+        }];
+        for c in " Alice Liddell ".chars() {
+            step(&mut app, key(KeyCode::Char(c)));
+        }
+        step(&mut app, key(KeyCode::Enter));
+        let calls = fake.calls();
+        assert_eq!(calls[0], "fullname alice Alice Liddell");
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn merging_a_group_moves_members_and_primary_users_before_groupdel() {
+        let fake = FakeBackend::default();
+        let mut app = members_app();
+        use_fake(&mut app, &fake);
+        let staff = crate::sys::SystemGroup {
+            gid: 3000,
+            name: "staff".to_string(),
+            members: vec![],
+        };
+        app.groups_all.push(staff.clone());
+        app.groups.push(staff.clone());
+        app.running_as_root = Some("root".to_string());
+        app.input_mode = InputMode::Modal;
+        app.modal_stack = vec![ModalState::GroupsActions {
+            selected: 3,
+            target_gid: Some(2000),
+        }];
+
+        step(&mut app, key(KeyCode::Enter));
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::MergeGroup {
+                source_gid: 2000,
+                ..
+            })
+        ));
+        step(&mut app, key(KeyCode::Enter));
+        match app.modal() {
+            Some(ModalState::ConfirmAction { next, warning, .. }) => {
+                assert_eq!(
+                    crate::app::describe_command(next),
+                    "gpasswd -a bob staff\nusermod -g staff alice\ngroupdel devs"
+                );
+                assert!(warning.is_none());
+            }
+            other => panic!("expected a merge confirmation, got {other:?}"),
+        }
+        step(&mut app, key(KeyCode::Left));
+        step(&mut app, key(KeyCode::Enter));
+        let calls = fake.calls();
+        assert_eq!(
+            calls[..3],
+            ["add bob staff", "primary alice staff", "delete_group devs"]
+        );
+
+        // System groups carry the delete warning
+        let lp = crate::sys::SystemGroup {
+            gid: 7,
+            name: "lp".to_string(),
+            members: vec![],
+        };
+        app.groups_all = vec![lp, staff];
+        app.modal_stack.clear();
+        confirm_group_merge(&mut app, 7, "staff");
+        match app.modal() {
+            Some(ModalState::ConfirmAction {
+                warning: Some(w), ..
+            }) => assert!(w.contains("system group")),
+            other => panic!("expected a system group warning, got {other:?}"),
+        }
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn password_actions_refresh_shadow_filters() {
+        use crate::search::{clear_shadow_provider, parse_shadow, set_shadow_provider};
+
+        let dir = tempfile::tempdir().unwrap();
+        let shadow = dir.path().join("shadow");
+        std::fs::write(&shadow, "alice:$6$hash:19000::::::\nbob:!:19000::::::\n").unwrap();
+        let path = shadow.clone();
+        set_shadow_provider(move || Ok(parse_shadow(&std::fs::read_to_string(&path)?, 20000)));
+
+        let mut app = normal_mode_app();
+        app.users_all = app.users.clone();
+        app.users_filter_chips.locked = true;
+        apply_filters_and_search(&mut app);
+        let names = |app: &AppState| app.users.iter().map(|u| u.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&app), vec!["bob"]);
+        assert!(!crate::app::locked_users(&mut app).contains("alice"));
+
+        // usermod/passwd rewrite the shadow entry; simulate alice being locked
+        std::fs::write(&shadow, "alice:!$6$hash:19000::::::\nbob:!:19000::::::\n").unwrap();
+        let backend = FakeBackend::default();
+        let pending = PendingAction::ResetPassword {
+            username: "alice".into(),
+        };
+        perform_pending_action_with(&mut app, pending, &backend).unwrap();
+        assert_eq!(names(&app), vec!["alice", "bob"]);
+        assert!(crate::app::locked_users(&mut app).contains("alice"));
+        clear_shadow_provider();
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn performed_actions_are_listed_in_the_history() {
+        use ratatui::{Terminal, backend::TestBackend};
+        let backend = FakeBackend {
+            fail_on: Some("delete_group".to_string()),
+            ..Default::default()
+        };
+        let mut app = normal_mode_app();
+        let add = PendingAction::AddUserToGroup {
+            username: "bob".into(),
+            groupname: "audio".into(),
+        };
+        perform_pending_action_with(&mut app, add, &backend).unwrap();
+        let delete = PendingAction::DeleteGroup {
+            groupname: "audio".into(),
+        };
+        assert!(perform_pending_action_with(&mut app, delete, &backend).is_err());
+
+        assert_eq!(app.action_history.len(), 2);
+        assert_eq!(app.action_history[0].description, "gpasswd -a bob audio");
+        assert!(app.action_history[0].outcome.is_ok());
+        assert_eq!(app.action_history[1].description, "groupdel audio");
+        assert!(app.action_history[1].outcome.is_err());
+
+        app.open_modal(ModalState::ActionHistory { scroll: 0 });
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| crate::ui::render(f, &mut app)).unwrap();
+        let buf = terminal.backend().buffer();
+        let rows: Vec<String> = (0..buf.area.height)
+            .map(|y| (0..buf.area.width).map(|x| buf[(x, y)].symbol()).collect())
+            .collect();
+        let row = |needle: &str| rows.iter().position(|r| r.contains(needle)).unwrap();
+        assert!(rows.iter().any(|r| r.contains("Action history (2)")));
+        // Newest first, each with its outcome
+        assert!(row("groupdel audio") < row("gpasswd -a bob audio"));
+        assert!(rows[row("groupdel audio")].contains("failed"));
+        assert!(rows[row("gpasswd -a bob audio")].contains("ok"));
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn history_keeps_only_the_latest_actions() {
+        let mut app = normal_mode_app();
+        for i in 0..crate::app::ACTION_HISTORY_LEN + 5 {
+            app.record_action(format!("cmd {i}"), Ok(()));
+        }
+        assert_eq!(app.action_history.len(), crate::app::ACTION_HISTORY_LEN);
+        assert_eq!(app.action_history[0].description, "cmd 5");
     }
-    Ok(())
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crossterm::event::{KeyEvent, KeyModifiers};
+    #[test]
+    fn copy_command_for_add_to_group_matches_adapter_invocation() {
+        let alice = crate::sys::SystemUser {
+            uid: 1000,
+            name: "alice".to_string(),
+            primary_gid: 1000,
+            full_name: None,
+            home_dir: "/home/alice".to_string(),
+            shell: "/bin/bash".to_string(),
+        };
+        let wheel = crate::sys::SystemGroup {
+            gid: 10,
+            name: "wheel".to_string(),
+            members: vec![],
+        };
+        let mut app = AppState {
+            users: vec![alice],
+            groups: vec![wheel],
+            active_tab: ActiveTab::Groups,
+            ..test_app()
+        };
 
-    fn key(code: KeyCode) -> KeyEvent {
-        KeyEvent::new(code, KeyModifiers::NONE)
+        open_copy_command_menu(&mut app);
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::CopyCommandMenu { selected: 0 })
+        ));
+        let choices = crate::app::copy_command_choices(&app);
+        let (label, action) = &choices[0];
+        assert_eq!(label, "Add alice to wheel");
+        // SystemAdapter::add_user_to_group runs `gpasswd -a <user> <group>`
+        assert_eq!(
+            crate::app::ready_to_run_command(action),
+            "sudo gpasswd -a alice wheel"
+        );
+        assert_eq!(
+            crate::app::clipboard::osc52_sequence("hi"),
+            "\x1b]52;c;aGk=\x07"
+        );
     }
 
     #[test]
-    fn filter_menu_show_all_clears_users_filter_and_closes() {
+    fn groups_rename_blocked_for_system_gid() {
         let mut app = AppState {
-            active_tab: ActiveTab::Users,
+            groups: vec![
+                crate::sys::SystemGroup {
+                    gid: 10,
+                    name: "wheel".to_string(),
+                    members: vec![],
+                },
+                crate::sys::SystemGroup {
+                    gid: 1000,
+                    name: "users".to_string(),
+                    members: vec![],
+                },
+            ],
+            selected_group_index: 0,
             input_mode: InputMode::Modal,
-            modal: Some(ModalState::FilterMenu { selected: 0 }),
-            ..AppState::default()
+            modal_stack: vec![ModalState::GroupModifyMenu {
+                selected: 2,
+                target_gid: None,
+            }], // Rename
+            ..test_app()
         };
 
         handle_modal_key(&mut app, key(KeyCode::Enter));
 
-        assert!(app.users_filter.is_none());
-        assert!(app.modal.is_none());
-        assert!(matches!(app.input_mode, InputMode::Normal));
+        match app.modal() {
+            Some(ModalState::Info { message }) => {
+                assert!(message.contains("Renaming system groups is disabled"));
+                assert!(message.contains("wheel"));
+                assert!(message.contains("10"));
+            }
+            other => panic!("expected Info modal, got {:?}", other),
+        }
     }
 
+    #[cfg(not(feature = "read-only-build"))]
     #[test]
-    fn actions_delete_opens_delete_confirm_with_allowed_flag() {
+    fn privileged_action_opens_sudo_prompt_without_credentials() {
+        let fake = FakeBackend {
+            accepts_password: Some("secret".to_string()),
+            ..Default::default()
+        };
+        // Set up a normal user entry
         let mut app = AppState {
             users: vec![crate::sys::SystemUser {
-                uid: 1500,
-                name: "testuser".to_string(),
-                primary_gid: 1500,
+                uid: 1000,
+                name: "userx".to_string(),
+                primary_gid: 1000,
                 full_name: None,
-                home_dir: "/home/testuser".to_string(),
+                home_dir: "/home/userx".to_string(),
                 shell: "/bin/bash".to_string(),
             }],
-            input_mode: InputMode::Modal,
-            modal: Some(ModalState::Actions { selected: 1 }),
-            ..AppState::default()
+            selected_user_index: 0,
+            running_as_root: None,
+            ..test_app()
         };
+        use_fake(&mut app, &fake);
+
+        // Open ModifyPasswordMenu and choose Reset (selection 1) which requires privileges
+        app.input_mode = InputMode::Modal;
+        app.open_modal(ModalState::ModifyPasswordMenu { selected: 1 });
 
         handle_modal_key(&mut app, key(KeyCode::Enter));
+        // The action only runs once the event loop picks it up
+        assert!(matches!(
+            app.queued_action,
+            Some(PendingAction::ResetPassword { .. })
+        ));
+        run_until_idle(&mut app);
 
-        match &app.modal {
-            Some(ModalState::DeleteConfirm { allowed, .. }) => assert!(*allowed),
-            other => panic!("unexpected modal state: {:?}", other),
+        match app.modal() {
+            Some(ModalState::SudoPrompt {
+                next,
+                password,
+                error,
+                attempts: 0,
+            }) => {
+                // Should queue the reset action and prompt for sudo
+                match next {
+                    PendingAction::ResetPassword { username } => {
+                        assert_eq!(username, "userx");
+                    }
+                    other => panic!("unexpected pending: {:?}", other),
+                }
+                assert!(password.is_empty());
+                assert!(error.is_none());
+            }
+            other => panic!("expected SudoPrompt, got {:?}", other),
         }
     }
 
+    #[cfg(not(feature = "read-only-build"))]
     #[test]
-    fn change_password_mismatch_shows_info() {
+    fn failed_action_as_root_reports_error_instead_of_prompting() {
+        let fake = FakeBackend {
+            fail_on: Some("expire_password".to_string()),
+            ..Default::default()
+        };
         let mut app = AppState {
-            input_mode: InputMode::Modal,
-            modal: Some(ModalState::ChangePassword {
-                selected: 3, // Submit
-                password: "secret".to_string(),
-                confirm: "different".to_string(),
-                must_change: false,
-            }),
-            ..AppState::default()
+            users: vec![crate::sys::SystemUser {
+                uid: 1000,
+                name: "userx".to_string(),
+                primary_gid: 1000,
+                full_name: None,
+                home_dir: "/home/userx".to_string(),
+                shell: "/bin/bash".to_string(),
+            }],
+            selected_user_index: 0,
+            running_as_root: Some("running as root".to_string()),
+            ..test_app()
         };
+        use_fake(&mut app, &fake);
 
+        app.input_mode = InputMode::Modal;
+        app.open_modal(ModalState::ModifyPasswordMenu { selected: 1 });
         handle_modal_key(&mut app, key(KeyCode::Enter));
+        run_until_idle(&mut app);
 
-        match &app.modal {
-            Some(ModalState::Info { message }) => {
-                assert!(message.contains("Passwords do not match"))
-            }
+        assert!(
+            !app.modal_stack
+                .iter()
+                .any(|m| matches!(m, ModalState::SudoPrompt { .. })),
+            "no sudo prompt expected as root: {:?}",
+            app.modal_stack
+        );
+        match app.modal() {
+            Some(ModalState::Info { message }) => assert!(message.starts_with("Action failed")),
             other => panic!("expected Info modal, got {:?}", other),
         }
+        let calls = fake.calls();
+        assert_eq!(calls, vec!["expire_password userx"]);
     }
 
+    // Test-only helper: simulate effects of a subset of PendingAction without system calls
+    #[cfg(not(feature = "read-only-build"))]
     #[test]
-    fn sudo_prompt_backspace_closes_when_empty() {
+    fn add_user_to_groups_calls_backend_per_group_and_refreshes() {
+        let backend = FakeBackend {
+            groups: vec![
+                crate::sys::SystemGroup {
+                    gid: 20,
+                    name: "video".into(),
+                    members: vec!["alice".into()],
+                },
+                crate::sys::SystemGroup {
+                    gid: 10,
+                    name: "audio".into(),
+                    members: vec!["alice".into()],
+                },
+            ],
+            ..Default::default()
+        };
+        let mut app = test_app();
+        app.groups_all.clear();
+
+        perform_pending_action_with(
+            &mut app,
+            PendingAction::AddUserToGroups {
+                username: "alice".into(),
+                groupnames: vec!["audio".into(), "video".into()],
+            },
+            &backend,
+        )
+        .unwrap();
+
+        assert_eq!(
+            backend.calls(),
+            vec!["add alice audio", "add alice video", "list_groups"]
+        );
+        let names: Vec<_> = app.groups_all.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, vec!["audio", "video"]);
+        assert!(matches!(app.modal(), Some(ModalState::Info { .. })));
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn add_user_to_group_updates_members_in_memory() {
+        let backend = FakeBackend::default();
         let mut app = AppState {
-            input_mode: InputMode::Modal,
-            modal: Some(ModalState::SudoPrompt {
-                next: PendingAction::ResetPassword {
-                    username: "user".to_string(),
+            groups_all: vec![
+                crate::sys::SystemGroup {
+                    gid: 10,
+                    name: "audio".into(),
+                    members: vec!["bob".into()],
                 },
-                password: String::new(),
-                error: None,
-            }),
-            ..AppState::default()
+                crate::sys::SystemGroup {
+                    gid: 20,
+                    name: "video".into(),
+                    members: vec![],
+                },
+            ],
+            ..test_app()
         };
 
-        handle_modal_key(&mut app, key(KeyCode::Backspace));
+        let add = PendingAction::AddUserToGroup {
+            username: "alice".into(),
+            groupname: "audio".into(),
+        };
+        perform_pending_action_with(&mut app, add.clone(), &backend).unwrap();
 
-        assert!(app.modal.is_none());
-        assert!(matches!(app.input_mode, InputMode::Normal));
+        // No list_groups: the change is applied to the cached list directly
+        assert_eq!(backend.calls(), vec!["add alice audio"]);
+        assert_eq!(app.groups_all[0].members, vec!["bob", "alice"]);
+        assert!(app.groups_all[1].members.is_empty());
+
+        // Adding again disagrees with the cached list, so it is re-read
+        perform_pending_action_with(&mut app, add, &backend).unwrap();
+        assert_eq!(
+            backend.calls(),
+            vec!["add alice audio", "add alice audio", "list_groups"]
+        );
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    fn move_backend(fail_on: Option<&str>) -> FakeBackend {
+        FakeBackend {
+            groups: vec![
+                crate::sys::SystemGroup {
+                    gid: 10,
+                    name: "devs".into(),
+                    members: vec![],
+                },
+                crate::sys::SystemGroup {
+                    gid: 20,
+                    name: "ops".into(),
+                    members: vec!["alice".into()],
+                },
+            ],
+            fail_on: fail_on.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    fn move_alice() -> PendingAction {
+        PendingAction::MoveUserBetweenGroups {
+            username: "alice".into(),
+            from_group: "devs".into(),
+            to_group: "ops".into(),
+        }
     }
 
+    #[cfg(not(feature = "read-only-build"))]
     #[test]
-    fn modify_groups_remove_primary_group_shows_info() {
-        // Create a user 'alice' with primary_gid 100 and groups including that primary group
+    fn move_member_removes_then_adds_and_reports_both() {
+        let backend = move_backend(None);
+        let mut app = test_app();
+
+        perform_pending_action_with(&mut app, move_alice(), &backend).unwrap();
+
+        assert_eq!(
+            backend.calls(),
+            vec!["remove alice devs", "add alice ops", "list_groups"]
+        );
+        match app.modal() {
+            Some(ModalState::Info { message }) => {
+                assert_eq!(message, "Moved 'alice' from 'devs' to 'ops'")
+            }
+            other => panic!("unexpected modal: {:?}", other),
+        }
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn move_member_reports_completed_remove_when_add_fails() {
+        let backend = move_backend(Some("add"));
+        let mut app = test_app();
+
+        // Not an Err: retrying the whole move would fail on the remove
+        perform_pending_action_with(&mut app, move_alice(), &backend).unwrap();
+
+        assert_eq!(
+            backend.calls(),
+            vec!["remove alice devs", "add alice ops", "list_groups"]
+        );
+        match app.modal() {
+            Some(ModalState::Info { message }) => {
+                assert!(
+                    message.starts_with("Removed 'alice' from 'devs', but adding to 'ops' failed")
+                );
+            }
+            other => panic!("unexpected modal: {:?}", other),
+        }
+
+        let backend = move_backend(Some("remove"));
+        assert!(perform_pending_action_with(&mut app, move_alice(), &backend).is_err());
+        assert_eq!(backend.calls(), vec!["remove alice devs"]);
+    }
+
+    #[test]
+    fn surviving_groups_exclude_removed_and_primary() {
+        let groups = vec![
+            group(1000, "alice", &["alice"]),
+            group(10, "wheel", &["alice", "bob"]),
+            group(20, "audio", &["alice"]),
+            group(30, "video", &["bob"]),
+            group(40, "docker", &["alice"]),
+        ];
+        let keep = crate::app::surviving_supplementary_groups(
+            &groups,
+            "alice",
+            1000,
+            &["audio".to_string()],
+        );
+        assert_eq!(keep, vec!["wheel", "docker"]);
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn running_action_finishes_from_its_channel_and_blocks_the_next() {
+        let mut app = normal_mode_app();
+        use_fake(&mut app, &FakeBackend::default());
+        let (tx, rx) = std::sync::mpsc::channel();
+        app.pending_result = Some(crate::app::ActionInFlight {
+            action: PendingAction::CreateGroup {
+                groupname: "devs".into(),
+            },
+            result: rx,
+            started: std::time::Instant::now(),
+        });
+        app.queued_action = Some(PendingAction::DeleteGroup {
+            groupname: "audio".into(),
+        });
+
+        // Still running: nothing finishes and the next action waits
+        poll_action_result(&mut app);
+        run_queued_action(&mut app);
+        assert!(app.pending_result.is_some());
+        assert!(app.queued_action.is_some());
+
+        tx.send(Ok(crate::app::ActionDone {
+            message: "Created group 'devs'".into(),
+            memberships: Vec::new(),
+        }))
+        .unwrap();
+        poll_action_result(&mut app);
+        assert!(app.pending_result.is_none());
+        match app.modal() {
+            Some(ModalState::Info { message }) => assert_eq!(message, "Created group 'devs'"),
+            other => panic!("expected Info modal, got {:?}", other),
+        }
+        assert_eq!(app.action_history.len(), 1);
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn remove_from_groups_sets_remaining_list_in_one_call() {
+        let mut app = normal_mode_app();
+        let pending = PendingAction::RemoveUserFromGroups {
+            username: "alice".into(),
+            groupnames: vec!["audio".into()],
+        };
+        let mut backend = FakeBackend {
+            users: app.users.clone(),
+            groups: vec![
+                group(10, "wheel", &["alice"]),
+                group(20, "audio", &["alice"]),
+            ],
+            ..Default::default()
+        };
+        app.groups_all = backend.groups.clone();
+        perform_pending_action_with(&mut app, pending.clone(), &backend).unwrap();
+        assert_eq!(
+            backend.calls(),
+            vec!["list_users", "list_groups", "set_groups alice wheel"]
+        );
+
+        // usermod failing is reported, not papered over
+        backend.fail_on = Some("set_groups".into());
+        backend.calls.lock().unwrap().clear();
+        app.groups_all = backend.groups.clone();
+        assert!(perform_pending_action_with(&mut app, pending.clone(), &backend).is_err());
+        assert_eq!(
+            backend.calls(),
+            vec!["list_users", "list_groups", "set_groups alice wheel"]
+        );
+
+        // Without the groups it keeps, one gpasswd -d per group instead
+        backend.fail_on = Some("list_groups".into());
+        backend.calls.lock().unwrap().clear();
+        app.groups_all = backend.groups.clone();
+        perform_pending_action_with(&mut app, pending.clone(), &backend).unwrap();
+        assert_eq!(
+            backend.calls(),
+            vec!["list_users", "list_groups", "remove alice audio"]
+        );
+
+        // A wrong sudo password is not retried group by group
+        backend.fail_on = None;
+        backend.accepts_password = Some("secret".into());
+        backend.calls.lock().unwrap().clear();
+        let err = perform_pending_action_with(&mut app, pending, &backend).unwrap_err();
+        assert!(crate::error::is_auth_error(&*err));
+        assert_eq!(backend.calls(), vec!["list_users", "list_groups"]);
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn clone_group_creates_then_adds_source_members() {
+        let source = crate::sys::SystemGroup {
+            gid: 20,
+            name: "ops".into(),
+            members: vec!["alice".into(), "bob".into()],
+        };
+        let pending = crate::app::clone_group_action(&source, "ops2");
+        match &pending {
+            PendingAction::CloneGroup {
+                source,
+                groupname,
+                members,
+            } => {
+                assert_eq!((source.as_str(), groupname.as_str()), ("ops", "ops2"));
+                assert_eq!(members, &["alice", "bob"]);
+            }
+            other => panic!("unexpected action: {:?}", other),
+        }
+
+        let backend = FakeBackend {
+            fail_on: Some("add bob".into()),
+            ..Default::default()
+        };
+        let mut app = test_app();
+        perform_pending_action_with(&mut app, pending, &backend).unwrap();
+
+        assert_eq!(
+            backend.calls(),
+            vec![
+                "create_group ops2",
+                "add alice ops2",
+                "add bob ops2",
+                "list_groups"
+            ]
+        );
+        match app.modal() {
+            Some(ModalState::Info { message }) => {
+                assert!(message.starts_with("Created group 'ops2' from 'ops'; added alice"));
+                assert!(message.contains("failed to add bob"));
+            }
+            other => panic!("unexpected modal: {:?}", other),
+        }
+        assert!(app.is_recently_changed(ActiveTab::Groups, "ops2"));
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn new_group_can_be_created_with_picked_members() {
+        let fake = FakeBackend::default();
+        let mut app = normal_mode_app();
+        use_fake(&mut app, &fake);
+        app.users_all = app.users.clone();
+        app.running_as_root = Some("root".to_string());
+        app.active_tab = ActiveTab::Groups;
+        step(&mut app, key(KeyCode::Char('n')));
+        for c in "devops".chars() {
+            step(&mut app, key(KeyCode::Char(c)));
+        }
+
+        step(&mut app, key(KeyCode::Down));
+        match app.modal() {
+            Some(ModalState::GroupModifyAddMembers { new_group, .. }) => {
+                assert_eq!(new_group.as_deref(), Some("devops"))
+            }
+            other => panic!("expected the member picker, got {other:?}"),
+        }
+        // Backspace goes back to the name, which is kept
+        step(&mut app, key(KeyCode::Backspace));
+        assert!(
+            matches!(app.modal(), Some(ModalState::GroupAddInput { name, .. }) if name == "devops")
+        );
+        step(&mut app, key(KeyCode::Down));
+
+        step(&mut app, key(KeyCode::Char(' ')));
+        step(&mut app, key(KeyCode::End));
+        step(&mut app, key(KeyCode::Char(' ')));
+        step(&mut app, key(KeyCode::Enter));
+
+        let calls = fake.calls();
+        assert_eq!(
+            calls[..3],
+            [
+                "create_group devops",
+                "add alice devops",
+                "add carol devops"
+            ]
+        );
+        match app.modal() {
+            Some(ModalState::Info { message }) => {
+                assert_eq!(message, "Created group 'devops'; added alice, carol")
+            }
+            other => panic!("expected the result, got {other:?}"),
+        }
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn checked_members_are_previewed_before_they_are_added() {
+        let fake = FakeBackend::default();
+        let mut app = members_app();
+        use_fake(&mut app, &fake);
+        app.running_as_root = Some("root".to_string());
+        app.input_mode = InputMode::Modal;
+        app.modal_stack = vec![ModalState::GroupModifyAddMembers {
+            selected: 0,
+            offset: 0,
+            target_gid: Some(2000),
+            selected_multi: Default::default(),
+            new_group: None,
+        }];
+        step(&mut app, key(KeyCode::Char(' ')));
+        step(&mut app, key(KeyCode::Down));
+        step(&mut app, key(KeyCode::Char(' ')));
+
+        step(&mut app, key(KeyCode::Enter));
+        match app.modal() {
+            Some(ModalState::MembershipPreview { next, .. }) => assert_eq!(
+                crate::app::membership_preview(next),
+                ["+ alice → devs", "+ bob → devs"]
+            ),
+            other => panic!("expected the preview, got {other:?}"),
+        }
+        // Esc goes back to the picker with both users still checked
+        step(&mut app, key(KeyCode::Esc));
+        match app.modal() {
+            Some(ModalState::GroupModifyAddMembers { selected_multi, .. }) => {
+                assert_eq!(selected_multi.len(), 2)
+            }
+            other => panic!("expected the picker, got {other:?}"),
+        }
+        assert!(fake.calls().is_empty());
+
+        step(&mut app, key(KeyCode::Enter));
+        step(&mut app, key(KeyCode::Enter));
+        let calls = fake.calls();
+        assert_eq!(calls[..2], ["add alice devs", "add bob devs"]);
+    }
+
+    #[test]
+    fn member_actions_open_move_picker_and_confirm() {
         let mut app = AppState {
-            users: vec![crate::sys::SystemUser {
+            users_all: vec![crate::sys::SystemUser {
                 uid: 1000,
-                name: "alice".to_string(),
-                primary_gid: 100,
+                name: "alice".into(),
+                primary_gid: 1000,
                 full_name: None,
-                home_dir: "/home/alice".to_string(),
-                shell: "/bin/bash".to_string(),
+                home_dir: "/home/alice".into(),
+                shell: "/bin/bash".into(),
             }],
             groups_all: vec![
                 crate::sys::SystemGroup {
-                    gid: 100,
-                    name: "users".to_string(),
-                    members: vec![],
+                    gid: 10,
+                    name: "devs".into(),
+                    members: vec!["alice".into()],
+                },
+                crate::sys::SystemGroup {
+                    gid: 20,
+                    name: "ops".into(),
+                    members: vec!["alice".into()],
                 },
                 crate::sys::SystemGroup {
-                    gid: 10,
-                    name: "wheel".to_string(),
-                    members: vec!["alice".to_string()],
+                    gid: 30,
+                    name: "qa".into(),
+                    members: vec![],
                 },
             ],
-            selected_user_index: 0,
-            ..AppState::default()
+            ..test_app()
         };
-
-        // Open ModifyGroupsRemove and select the primary group entry (index 0 in the filtered list)
+        app.users = app.users_all.clone();
+        app.groups = app.groups_all.clone();
+        app.active_tab = ActiveTab::Groups;
+        app.groups_focus = GroupsFocus::Members;
         app.input_mode = InputMode::Modal;
-        app.modal = Some(ModalState::ModifyGroupsRemove {
-            selected: 0,
-            offset: 0,
-            selected_multi: Vec::new(),
+        app.actions_context = Some(ActionsContext::GroupMemberRemoval {
+            group_name: "devs".into(),
         });
+        app.open_modal(ModalState::Actions { selected: 2 });
 
-        handle_modal_key(&mut app, key(KeyCode::Enter));
-
-        match &app.modal {
-            Some(ModalState::Info { message }) => {
-                assert!(message.contains("Cannot remove user from primary group"))
+        handle_modal_key(&mut app, KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        // "ops" already has alice, so "qa" is the only target
+        handle_modal_key(&mut app, KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        match app.modal() {
+            Some(ModalState::MoveMemberConfirm {
+                username,
+                from_group,
+                to_group,
+                ..
+            }) => {
+                assert_eq!(username, "alice");
+                assert_eq!(from_group, "devs");
+                assert_eq!(to_group, "qa");
             }
-            other => panic!("expected Info modal, got {:?}", other),
+            other => panic!("unexpected modal: {:?}", other),
         }
+        assert_eq!(app.modal_stack.len(), 3);
     }
 
     #[test]
-    fn actions_delete_blocked_for_non_user_uid_range() {
-        let mut app = AppState {
-            users: vec![crate::sys::SystemUser {
-                uid: 0,
-                name: "root".to_string(),
-                primary_gid: 0,
-                full_name: None,
-                home_dir: "/root".to_string(),
-                shell: "/bin/bash".to_string(),
-            }],
-            selected_user_index: 0,
-            input_mode: InputMode::Modal,
-            modal: Some(ModalState::Actions { selected: 1 }), // Delete
-            ..AppState::default()
+    fn compact_details_show_only_the_basics() {
+        let mut app = normal_mode_app();
+        let full = crate::ui::users::user_details_lines(&mut app).len();
+
+        step(&mut app, key(KeyCode::Char('v')));
+        assert_eq!(app.detail_verbosity, DetailVerbosity::Compact);
+        // /etc/shells is not read for the compact view
+        app.shells_cache = None;
+        let text: Vec<String> = crate::ui::users::user_details_lines(&mut app)
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(text.len(), 4);
+        assert_eq!(text[0], "Username: alice");
+        assert!(text[2].starts_with("Primary group: "));
+        assert!(text[3].starts_with("Shell: "));
+        assert!(app.shells_cache.is_none());
+
+        step(&mut app, key(KeyCode::Char('v')));
+        assert_eq!(crate::ui::users::user_details_lines(&mut app).len(), full);
+    }
+
+    #[test]
+    fn outside_changes_are_flagged_until_reload() {
+        let mut app = normal_mode_app();
+        app.accounts_mtime = [Some(std::time::UNIX_EPOCH); 2];
+        check_account_files(&mut app);
+        assert!(app.accounts_stale || crate::app::account_files_mtime() == [None; 2]);
+
+        app.accounts_stale = true;
+        reload_accounts(&mut app, &FakeBackend::default());
+        assert!(!app.accounts_stale);
+        check_account_files(&mut app);
+        assert!(!app.accounts_stale);
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn failed_action_leaves_outside_changes_flaggable() {
+        let fake = FakeBackend {
+            fail_on: Some("add alice".to_string()),
+            ..Default::default()
         };
+        let mut app = normal_mode_app();
+        app.accounts_mtime = [Some(std::time::UNIX_EPOCH); 2];
+        let pending = PendingAction::AddUserToGroups {
+            username: "alice".to_string(),
+            groupnames: vec!["wheel".to_string()],
+        };
+        assert!(perform_pending_action_with(&mut app, pending, &fake).is_err());
+        assert_eq!(app.accounts_mtime, [Some(std::time::UNIX_EPOCH); 2]);
 
-        handle_modal_key(&mut app, key(KeyCode::Enter));
+        let pending = PendingAction::AddUserToGroups {
+            username: "bob".to_string(),
+            groupnames: vec!["wheel".to_string()],
+        };
+        perform_pending_action_with(&mut app, pending, &fake).unwrap();
+        assert_eq!(app.accounts_mtime, crate::app::account_files_mtime());
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn csv_import_creates_valid_rows_and_reports_the_rest() {
+        let fake = FakeBackend {
+            fail_on: Some("create_user erin".to_string()),
+            ..Default::default()
+        };
+        let path = std::env::temp_dir().join(format!("ugm_import_{}.csv", std::process::id()));
+        std::fs::write(
+            &path,
+            "username,fullname,shell,groups,create_home\n\
+             carol,\"Carol, C\",/bin/zsh,devs,no\n\
+             alice,,,,\n\
+             dave,,,nosuch,\n\
+             erin\n",
+        )
+        .unwrap();
+        let mut app = members_app();
+        use_fake(&mut app, &fake);
+        app.running_as_root = Some("root".to_string());
+
+        step(&mut app, key(KeyCode::Char('I')));
+        handle_paste(&mut app, &path.to_string_lossy());
+        step(&mut app, key(KeyCode::Enter));
+        match app.modal() {
+            Some(ModalState::ConfirmAction {
+                next: PendingAction::ImportUsers { rows, skipped },
+                warning: Some(warning),
+                ..
+            }) => {
+                assert_eq!(rows.len(), 2);
+                assert_eq!(skipped.len(), 2);
+                assert!(warning.contains("line 3: user 'alice' already exists"));
+            }
+            other => panic!("expected the import to be confirmed, got {other:?}"),
+        }
+        step(&mut app, key(KeyCode::Left));
+        step(&mut app, key(KeyCode::Enter));
+        std::fs::remove_file(&path).unwrap();
 
-        match &app.modal {
+        let calls = fake.calls();
+        assert_eq!(
+            calls[..5],
+            [
+                "create_user carol false",
+                "fullname carol Carol, C",
+                "shell carol /bin/zsh",
+                "add carol devs",
+                "create_user erin true",
+            ]
+        );
+        match app.modal() {
             Some(ModalState::Info { message }) => {
-                assert!(message.contains("Deletion not allowed. Only UID 1000-1999 allowed"));
-                assert!(message.contains("root"));
+                assert!(message.starts_with("Imported 1 of 2 users: carol\nFailed: erin"));
+                assert!(message.ends_with("Skipped line 4: group 'nosuch' does not exist"));
             }
-            other => panic!("expected Info modal, got {:?}", other),
+            other => panic!("expected the import summary, got {other:?}"),
         }
     }
 
+    #[cfg(not(feature = "read-only-build"))]
     #[test]
-    fn groups_rename_blocked_for_system_gid() {
+    fn gecos_fields_are_edited_separately() {
+        let fake = FakeBackend::default();
+        let mut app = normal_mode_app();
+        use_fake(&mut app, &fake);
+        app.running_as_root = Some("root".to_string());
+        app.users[0].full_name = Some("Alice,,555-1234".to_string());
+        app.input_mode = InputMode::Modal;
+        app.modal_stack = vec![ModalState::ModifyDetailsMenu { selected: 3 }];
+
+        step(&mut app, key(KeyCode::Enter));
+        match app.modal() {
+            Some(ModalState::EditGecos {
+                selected: 0,
+                fields,
+                cursor: 5,
+                ..
+            }) => assert_eq!(fields, &["Alice", "", "555-1234", ""]),
+            other => panic!("expected the GECOS form, got {other:?}"),
+        }
+        step(&mut app, key(KeyCode::Down));
+        step(&mut app, key(KeyCode::Char('B')));
+        step(&mut app, key(KeyCode::Char(',')));
+        step(&mut app, key(KeyCode::Char('7')));
+        step(&mut app, key(KeyCode::Enter));
+
+        let calls = fake.calls();
+        assert_eq!(calls[0], "fullname alice Alice,B7,555-1234");
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn renaming_a_group_warns_before_it_runs() {
+        let fake = FakeBackend::default();
+        let mut app = members_app();
+        use_fake(&mut app, &fake);
+        app.running_as_root = Some("root".to_string());
+        app.input_mode = InputMode::Modal;
+        app.modal_stack = vec![ModalState::GroupRenameInput {
+            name: "developers".into(),
+            cursor: 10,
+            target_gid: Some(2000),
+        }];
+
+        step(&mut app, key(KeyCode::Enter));
+        match app.modal() {
+            Some(ModalState::ConfirmAction {
+                selected: 1,
+                warning: Some(warning),
+                ..
+            }) => assert!(
+                warning.starts_with("files stay owned by GID 2000"),
+                "{warning}"
+            ),
+            other => panic!("expected the rename warning, got {other:?}"),
+        }
+        // No is the default answer and leaves the group alone
+        step(&mut app, key(KeyCode::Enter));
+        assert!(fake.calls().is_empty());
+
+        app.modal_stack = vec![ModalState::GroupRenameInput {
+            name: "developers".into(),
+            cursor: 10,
+            target_gid: Some(2000),
+        }];
+        app.input_mode = InputMode::Modal;
+        step(&mut app, key(KeyCode::Enter));
+        step(&mut app, key(KeyCode::Left));
+        step(&mut app, key(KeyCode::Enter));
+        let calls = fake.calls();
+        assert_eq!(calls[0], "rename_group devs developers");
+
+        let sudoers = [std::path::PathBuf::from("/etc/sudoers.d/devs")];
+        assert_eq!(
+            rename_group_warning("devs", 2000, &sudoers),
+            "files stay owned by GID 2000, but %devs in /etc/sudoers.d/devs will stop matching until edited."
+        );
+    }
+
+    #[test]
+    fn paste_into_single_line_field_keeps_first_line_at_cursor() {
         let mut app = AppState {
-            groups: vec![
-                crate::sys::SystemGroup {
-                    gid: 10,
-                    name: "wheel".to_string(),
-                    members: vec![],
-                },
-                crate::sys::SystemGroup {
-                    gid: 1000,
-                    name: "users".to_string(),
-                    members: vec![],
-                },
-            ],
-            selected_group_index: 0,
             input_mode: InputMode::Modal,
-            modal: Some(ModalState::GroupModifyMenu {
-                selected: 2,
+            modal_stack: vec![ModalState::GroupRenameInput {
+                name: "ops".into(),
+                cursor: 0,
                 target_gid: None,
-            }), // Rename
-            ..AppState::default()
+            }],
+            ..test_app()
         };
-
-        handle_modal_key(&mut app, key(KeyCode::Enter));
-
-        match &app.modal {
-            Some(ModalState::Info { message }) => {
-                assert!(message.contains("Renaming system groups is disabled"));
-                assert!(message.contains("wheel"));
-                assert!(message.contains("10"));
+        handle_paste(&mut app, "dev\nignored second line\n");
+        match app.modal() {
+            Some(ModalState::GroupRenameInput { name, cursor, .. }) => {
+                assert_eq!(name, "devops");
+                assert_eq!(*cursor, 3);
             }
-            other => panic!("expected Info modal, got {:?}", other),
+            other => panic!("unexpected modal: {:?}", other),
         }
+
+        // Pastes outside a text input are ignored
+        app.modal_stack = vec![ModalState::Actions { selected: 0 }];
+        handle_paste(&mut app, "x");
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::Actions { selected: 0 })
+        ));
     }
 
     #[test]
-    fn privileged_action_opens_sudo_prompt_without_credentials() {
-        // Set up a normal user entry
+    fn switch_tab_is_a_no_op_while_a_modal_is_open() {
         let mut app = AppState {
-            users: vec![crate::sys::SystemUser {
-                uid: 1000,
-                name: "userx".to_string(),
-                primary_gid: 1000,
-                full_name: None,
-                home_dir: "/home/userx".to_string(),
-                shell: "/bin/bash".to_string(),
-            }],
-            selected_user_index: 0,
-            ..AppState::default()
+            active_tab: ActiveTab::Users,
+            input_mode: InputMode::Modal,
+            modal_stack: vec![ModalState::FilterMenu { selected: 3 }],
+            ..test_app()
         };
+        app.keymap = crate::app::keymap::Keymap::new_defaults();
 
-        // Open ModifyPasswordMenu and choose Reset (selection 1) which requires privileges
-        app.input_mode = InputMode::Modal;
-        app.modal = Some(ModalState::ModifyPasswordMenu { selected: 1 });
-
-        handle_modal_key(&mut app, key(KeyCode::Enter));
+        handle_modal_mode_key(&mut app, KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        assert_eq!(app.active_tab, ActiveTab::Users);
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::FilterMenu { selected: 3 })
+        ));
+        assert_eq!(
+            app.modal_notice.as_deref(),
+            Some("Close this dialog before switching tabs")
+        );
 
-        match &app.modal {
-            Some(ModalState::SudoPrompt {
-                next,
-                password,
-                error,
-            }) => {
-                // Should queue the reset action and prompt for sudo
-                match next {
-                    PendingAction::ResetPassword { username } => {
-                        assert_eq!(username, "userx");
-                    }
-                    other => panic!("unexpected pending: {:?}", other),
-                }
-                assert!(password.is_empty());
-                assert!(error.is_none());
-            }
-            other => panic!("expected SudoPrompt, got {:?}", other),
-        }
+        // The hint goes away with the next key
+        handle_modal_mode_key(&mut app, KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        assert!(app.modal_notice.is_none());
     }
 
-    // Test-only helper: simulate effects of a subset of PendingAction without system calls
     fn simulate_pending_action(app: &mut AppState, pending: PendingAction) {
         match pending {
             PendingAction::DeleteUser {
@@ -2509,7 +6507,7 @@ mod tests {
                 delete_home: _,
             } => {
                 app.users_all.retain(|u| u.name != username);
-                app.users_all.sort_by_key(|u| u.uid);
+                sys::sort_users(&mut app.users_all);
                 apply_filters_and_search(app);
                 if app.selected_user_index >= app.users.len() {
                     app.selected_user_index = app.users.len().saturating_sub(1);
@@ -2517,7 +6515,7 @@ mod tests {
             }
             PendingAction::DeleteGroup { groupname } => {
                 app.groups_all.retain(|g| g.name != groupname);
-                app.groups_all.sort_by_key(|g| g.gid);
+                sys::sort_groups(&mut app.groups_all);
                 apply_filters_and_search(app);
                 if app.selected_group_index >= app.groups.len() {
                     app.selected_group_index = app.groups.len().saturating_sub(1);
@@ -2529,7 +6527,7 @@ mod tests {
 
     #[test]
     fn selected_user_index_clamps_after_delete() {
-        let mut app = AppState::default();
+        let mut app = test_app();
         app.users_all = vec![
             crate::sys::SystemUser {
                 uid: 1000,
@@ -2566,7 +6564,7 @@ mod tests {
 
     #[test]
     fn selected_group_index_clamps_after_delete() {
-        let mut app = AppState::default();
+        let mut app = test_app();
         app.groups_all = vec![
             crate::sys::SystemGroup {
                 gid: 1000,
@@ -2593,4 +6591,379 @@ mod tests {
         assert_eq!(app.selected_group_index, 0);
         assert_eq!(app.groups[0].name, "g1");
     }
+
+    #[test]
+    fn backspace_pops_back_to_parent_with_its_selection() {
+        let mut app = AppState {
+            input_mode: InputMode::Modal,
+            modal_stack: vec![ModalState::Actions { selected: 0 }],
+            ..test_app()
+        };
+        // Actions -> Modify -> Modify details -> Full name
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+        handle_modal_key(&mut app, key(KeyCode::Down));
+        handle_modal_key(&mut app, key(KeyCode::Down));
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+        handle_modal_key(&mut app, key(KeyCode::Down));
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+        assert_eq!(app.modal_stack.len(), 4);
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::ModifyTextInput {
+                field: ModifyField::Fullname,
+                ..
+            })
+        ));
+
+        handle_modal_key(&mut app, key(KeyCode::Char('x')));
+        handle_modal_key(&mut app, key(KeyCode::Backspace));
+        handle_modal_key(&mut app, key(KeyCode::Backspace));
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::ModifyDetailsMenu { selected: 1 })
+        ));
+        handle_modal_key(&mut app, key(KeyCode::Backspace));
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::ModifyMenu { selected: 2 })
+        ));
+        handle_modal_key(&mut app, key(KeyCode::Backspace));
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::Actions { selected: 0 })
+        ));
+        handle_modal_key(&mut app, key(KeyCode::Backspace));
+        assert!(app.modal().is_none());
+        assert!(matches!(app.input_mode, InputMode::Normal));
+    }
+
+    #[test]
+    fn popping_group_member_picker_restores_group_modify_menu_state() {
+        let mut app = AppState {
+            input_mode: InputMode::Modal,
+            modal_stack: vec![
+                ModalState::GroupModifyMenu {
+                    selected: 1,
+                    target_gid: Some(1234),
+                },
+                ModalState::GroupModifyRemoveMembers {
+                    selected: 0,
+                    offset: 0,
+                    target_gid: Some(1234),
+                    selected_multi: BTreeSet::new(),
+                },
+            ],
+            ..test_app()
+        };
+        handle_modal_key(&mut app, key(KeyCode::Backspace));
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::GroupModifyMenu {
+                selected: 1,
+                target_gid: Some(1234)
+            })
+        ));
+        assert!(matches!(app.input_mode, InputMode::Modal));
+    }
+
+    fn picker_checked(app: &AppState) -> Vec<String> {
+        match app.modal() {
+            Some(ModalState::ModifyGroupsAdd { selected_multi, .. })
+            | Some(ModalState::GroupModifyRemoveMembers { selected_multi, .. }) => {
+                selected_multi.iter().cloned().collect()
+            }
+            other => panic!("unexpected modal: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn ctrl_a_selects_only_pickable_groups_and_toggles_off() {
+        let mut app = AppState {
+            users: vec![user(1000, "alice")],
+            selected_user_index: 0,
+            groups_all: vec![
+                group(1000, "alice", &[]),
+                group(10, "audio", &[]),
+                group(20, "video", &["alice"]),
+                group(30, "games", &[]),
+            ],
+            input_mode: InputMode::Modal,
+            // Ineligible groups are listed but must not be checked
+            modal_stack: vec![ModalState::ModifyGroupsAdd {
+                selected: 0,
+                offset: 0,
+                selected_multi: BTreeSet::new(),
+                show_all: true,
+            }],
+            ..test_app()
+        };
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+
+        handle_modal_key(&mut app, ctrl('a'));
+        assert_eq!(picker_checked(&app), ["audio", "games"]);
+        // Ctrl+A did not also flip the show-all mode bound to plain 'a'
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::ModifyGroupsAdd { show_all: true, .. })
+        ));
+        handle_modal_key(&mut app, ctrl('a'));
+        assert!(picker_checked(&app).is_empty());
+
+        handle_modal_key(&mut app, key(KeyCode::Char(' ')));
+        handle_modal_key(&mut app, key(KeyCode::Down));
+        handle_modal_key(&mut app, key(KeyCode::Char(' ')));
+        assert_eq!(picker_checked(&app), ["audio"]);
+        handle_modal_key(&mut app, ctrl('a'));
+        assert_eq!(picker_checked(&app), ["audio", "games"]);
+        handle_modal_key(&mut app, ctrl('d'));
+        assert!(picker_checked(&app).is_empty());
+    }
+
+    #[test]
+    fn picker_selection_survives_resorting_the_list() {
+        let mut app = AppState {
+            users: vec![user(1000, "alice")],
+            selected_user_index: 0,
+            groups_all: vec![
+                group(10, "audio", &[]),
+                group(20, "video", &[]),
+                group(30, "games", &[]),
+            ],
+            input_mode: InputMode::Modal,
+            modal_stack: vec![ModalState::ModifyGroupsAdd {
+                selected: 1,
+                offset: 0,
+                selected_multi: BTreeSet::new(),
+                show_all: false,
+            }],
+            ..test_app()
+        };
+        handle_modal_key(&mut app, key(KeyCode::Char(' ')));
+        assert_eq!(picker_checked(&app), ["video"]);
+
+        // A reload reorders the groups; "video" stays checked, not row 1
+        app.groups_all.sort_by(|a, b| b.name.cmp(&a.name));
+        assert_eq!(picker_checked(&app), ["video"]);
+        handle_modal_key(&mut app, key(KeyCode::Char('*')));
+        assert_eq!(picker_checked(&app), ["audio", "games"]);
+    }
+
+    #[test]
+    fn ctrl_a_selects_every_listed_member() {
+        let mut app = AppState {
+            groups: vec![crate::sys::SystemGroup {
+                gid: 50,
+                name: "devs".into(),
+                members: vec!["alice".into(), "bob".into(), "carol".into()],
+            }],
+            input_mode: InputMode::Modal,
+            modal_stack: vec![ModalState::GroupModifyRemoveMembers {
+                selected: 0,
+                offset: 0,
+                target_gid: Some(50),
+                selected_multi: BTreeSet::from(["carol".to_string()]),
+            }],
+            ..test_app()
+        };
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        handle_modal_key(&mut app, ctrl('a'));
+        assert_eq!(picker_checked(&app), ["alice", "bob", "carol"]);
+        handle_modal_key(&mut app, ctrl('d'));
+        assert!(picker_checked(&app).is_empty());
+    }
+
+    #[test]
+    fn sudoers_dropin_is_read_on_a_worker_thread() {
+        let fake = FakeBackend::default();
+        let mut app = normal_mode_app();
+        use_fake(&mut app, &fake);
+        app.input_mode = InputMode::Modal;
+        app.modal_stack = vec![ModalState::ModifyMenu { selected: 4 }];
+
+        // The modal opens at once and waits for the read
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::SudoersDropin { loading: true, .. })
+        ));
+        run_until_idle(&mut app);
+        match app.modal() {
+            Some(ModalState::SudoersDropin {
+                username,
+                loading: false,
+                content: None,
+                error: None,
+            }) => assert_eq!(username, "alice"),
+            other => panic!("expected the drop-in view, got {other:?}"),
+        }
+        assert_eq!(fake.calls(), ["read_sudoers alice"]);
+
+        // An answer for a closed view is dropped
+        app.modal_stack = vec![ModalState::ModifyMenu { selected: 4 }];
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+        handle_modal_key(&mut app, key(KeyCode::Esc));
+        run_until_idle(&mut app);
+        assert!(app.modal().is_none());
+    }
+
+    #[test]
+    fn esc_closes_the_whole_modal_stack() {
+        let mut app = AppState {
+            input_mode: InputMode::Modal,
+            modal_stack: vec![
+                ModalState::Actions { selected: 0 },
+                ModalState::ModifyMenu { selected: 3 },
+                ModalState::ModifyPasswordMenu { selected: 1 },
+            ],
+            ..test_app()
+        };
+        handle_modal_key(&mut app, key(KeyCode::Esc));
+        assert!(app.modal_stack.is_empty());
+        assert!(matches!(app.input_mode, InputMode::Normal));
+    }
+
+    #[test]
+    fn backspace_closes_top_level_info_help_and_filter() {
+        for modal in [
+            ModalState::Info {
+                message: "done".to_string(),
+            },
+            ModalState::Help {
+                scroll: 3,
+                query: String::new(),
+                searching: false,
+            },
+            ModalState::FilterMenu { selected: 2 },
+        ] {
+            let mut app = AppState {
+                input_mode: InputMode::Modal,
+                modal_stack: vec![modal],
+                ..test_app()
+            };
+            handle_modal_key(&mut app, key(KeyCode::Backspace));
+            assert!(app.modal().is_none());
+            assert!(matches!(app.input_mode, InputMode::Normal));
+        }
+    }
+
+    #[test]
+    fn default_bindings_toggle_keybinds_pane_and_open_help() {
+        let mut app = test_app();
+        let shift_k = KeyEvent::new(KeyCode::Char('K'), KeyModifiers::SHIFT);
+        assert_eq!(
+            app.keymap.resolve(&shift_k),
+            Some(KeyAction::ToggleKeybindsPane)
+        );
+        let shown = app.show_keybinds;
+        handle_normal_key(&mut app, shift_k);
+        assert_eq!(app.show_keybinds, !shown);
+        handle_normal_key(&mut app, shift_k);
+        assert_eq!(app.show_keybinds, shown);
+
+        let help = key(KeyCode::Char('?'));
+        assert_eq!(app.keymap.resolve(&help), Some(KeyAction::OpenHelp));
+        handle_normal_key(&mut app, help);
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::Help { scroll: 0, .. })
+        ));
+        assert!(matches!(app.input_mode, InputMode::Modal));
+    }
+
+    #[test]
+    fn help_scrolls_within_its_content() {
+        let mut app = test_app();
+        handle_normal_key(&mut app, key(KeyCode::Char('?')));
+        let scroll = |app: &AppState| match app.modal() {
+            Some(ModalState::Help { scroll, .. }) => *scroll,
+            other => panic!("expected help, got {:?}", other),
+        };
+        let max = crate::ui::components::help_max_scroll();
+
+        handle_modal_key(&mut app, key(KeyCode::Down));
+        assert_eq!(scroll(&app), 1);
+        handle_modal_key(&mut app, key(KeyCode::PageDown));
+        assert_eq!(scroll(&app), 11.min(max));
+        for _ in 0..20 {
+            handle_modal_key(&mut app, key(KeyCode::PageDown));
+        }
+        assert_eq!(scroll(&app), max);
+        handle_modal_key(&mut app, key(KeyCode::Down));
+        assert_eq!(scroll(&app), max);
+        handle_modal_key(&mut app, key(KeyCode::PageUp));
+        assert_eq!(scroll(&app), max - 10);
+        handle_modal_key(&mut app, key(KeyCode::Home));
+        assert_eq!(scroll(&app), 0);
+        handle_modal_key(&mut app, key(KeyCode::Up));
+        assert_eq!(scroll(&app), 0);
+
+        handle_modal_key(&mut app, key(KeyCode::Esc));
+        assert!(app.modal().is_none());
+    }
+
+    #[test]
+    fn help_search_jumps_to_matching_lines() {
+        let mut app = test_app();
+        handle_normal_key(&mut app, key(KeyCode::Char('?')));
+        assert!(matches!(app.modal(), Some(ModalState::Help { .. })));
+
+        handle_modal_key(&mut app, key(KeyCode::Char('/')));
+        for c in "OCTAL".chars() {
+            handle_modal_key(&mut app, key(KeyCode::Char(c)));
+        }
+        let octal = crate::ui::components::help_lines()
+            .iter()
+            .position(|l| l.to_string().contains("octal"))
+            .unwrap() as u16;
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::Help { scroll, searching: true, .. }) if *scroll == octal
+        ));
+
+        // Enter only ends the search; the next Enter closes help as usual
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::Help {
+                searching: false,
+                ..
+            })
+        ));
+        handle_modal_key(&mut app, key(KeyCode::Char('n')));
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::Help { scroll, .. }) if *scroll == octal
+        ));
+        handle_modal_key(&mut app, key(KeyCode::Enter));
+        assert!(app.modal().is_none());
+    }
+
+    #[test]
+    fn filter_menu_remembers_and_clamps_selection_per_tab() {
+        let mut app = AppState {
+            active_tab: ActiveTab::Users,
+            ..test_app()
+        };
+        app.filter_menu_selected = crate::app::FilterMenuSelection {
+            users: 6,
+            groups: 11,
+        };
+
+        app.active_tab = ActiveTab::Groups;
+        open_filter_menu(&mut app);
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::FilterMenu { selected: 7 })
+        ));
+        handle_modal_key(&mut app, key(KeyCode::Up));
+        handle_modal_key(&mut app, key(KeyCode::Esc));
+        assert_eq!(app.filter_menu_selected.groups, 6);
+
+        app.active_tab = ActiveTab::Users;
+        open_filter_menu(&mut app);
+        assert!(matches!(
+            app.modal(),
+            Some(ModalState::FilterMenu { selected: 6 })
+        ));
+    }
 }
@@ -0,0 +1,146 @@
+//! Audit trail of the privileged actions the tool ran.
+//!
+//! Every attempt, successful or not, is appended to `audit.log` next to the
+//! config files: when it ran, who ran it, the commands and, for a failure, the
+//! error text. Unlike the debug log it is always on. Once the file grows past
+//! [`MAX_AUDIT_BYTES`] it is moved to `audit.log.1` and a new one is started,
+//! so at most two files are kept.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::app::PendingAction;
+
+/// Size at which the audit log is rotated.
+pub const MAX_AUDIT_BYTES: u64 = 1024 * 1024;
+
+/// An append-only audit file with a size cap.
+#[derive(Clone, Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    max_bytes: u64,
+}
+
+impl AuditLog {
+    pub fn new(path: impl Into<PathBuf>, max_bytes: u64) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes,
+        }
+    }
+
+    /// `audit.log` in the config directory, capped at [`MAX_AUDIT_BYTES`].
+    pub fn default_location() -> Self {
+        Self::new(
+            crate::app::config_file_write_path("audit.log"),
+            MAX_AUDIT_BYTES,
+        )
+    }
+
+    /// The file holding the entries from before the last rotation.
+    pub fn rotated_path(&self) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(".1");
+        PathBuf::from(name)
+    }
+
+    /// Append one entry, first rotating the file if it is full.
+    ///
+    /// `outcome` is `Err` with the error text for a failed attempt.
+    pub fn append(
+        &self,
+        actor: &str,
+        description: &str,
+        outcome: Result<(), &str>,
+    ) -> std::io::Result<()> {
+        if std::fs::metadata(&self.path).is_ok_and(|m| m.len() >= self.max_bytes) {
+            std::fs::rename(&self.path, self.rotated_path())?;
+        }
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let line = format_entry(secs, actor, description, outcome);
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())
+    }
+
+    /// Where entries are appended.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record an attempt at `action`; write errors are only logged, so
+    /// auditing never gets in the way of the action itself.
+    pub fn record<T>(&self, action: &PendingAction, result: &crate::error::Result<T>) {
+        let actor = crate::sys::current_username().unwrap_or_else(|| "unknown".to_string());
+        let description = crate::app::describe_command(action);
+        let error = result.as_ref().err().map(|e| e.to_string());
+        let outcome = match &error {
+            Some(e) => Err(e.as_str()),
+            None => Ok(()),
+        };
+        if let Err(e) = self.append(&actor, &description, outcome) {
+            crate::logging::warn(format_args!(
+                "could not write {}: {e}",
+                self.path().display()
+            ));
+        }
+    }
+}
+
+/// One audit line: UTC time, actor, `ok` or `FAILED`, the commands and any
+/// error, with line breaks flattened so each entry stays on one line.
+pub fn format_entry(
+    secs: u64,
+    actor: &str,
+    description: &str,
+    outcome: Result<(), &str>,
+) -> String {
+    let flat = |s: &str| s.replace('\n', "; ");
+    match outcome {
+        Ok(()) => format!(
+            "{} {} ok {}\n",
+            utc_timestamp(secs),
+            actor,
+            flat(description)
+        ),
+        Err(e) => format!(
+            "{} {} FAILED {} -- {}\n",
+            utc_timestamp(secs),
+            actor,
+            flat(description),
+            flat(e)
+        ),
+    }
+}
+
+/// `secs` since the Unix epoch as an ISO 8601 UTC time, e.g.
+/// `2024-03-01T12:00:00Z`.
+pub fn utc_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    // Civil date from a day count (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem / 60 % 60,
+        rem % 60
+    )
+}
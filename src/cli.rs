@@ -0,0 +1,492 @@
+//! Non-interactive command-line mode.
+//!
+//! When the binary is started with a subcommand it runs that command and exits
+//! instead of starting the TUI:
+//!
+//! ```text
+//! usrgrp-manager list users|groups [--json] [--filter QUERY]
+//! usrgrp-manager add-to-group USER GROUP
+//! ```
+//!
+//! `--filter` takes the same query syntax as the in-app search, including
+//! `-term` negations. Output is a columnar table by default or JSON with `--json`.
+//!
+//! Changes such as `add-to-group` need privileges: when not running as root the
+//! sudo password is read from the program named by `SUDO_ASKPASS`, or prompted
+//! for on the terminal.
+//!
+use crate::error::{Result, simple_error};
+use crate::search::{filter_groups, filter_users};
+use crate::sys::{SystemAdapter, SystemGroup, SystemUser, sort_groups, sort_users};
+
+/// Usage text printed for `--help` and on argument errors.
+#[cfg(not(feature = "read-only-build"))]
+pub const USAGE: &str = "\
+Usage:
+  usrgrp-manager                      Start the interactive TUI
+  usrgrp-manager list users|groups [--json] [--filter QUERY]
+                                      Print users or groups and exit
+  usrgrp-manager add-to-group USER GROUP
+                                      Add USER to the supplementary group GROUP
+  usrgrp-manager --help               Show this help";
+
+/// Usage text printed for `--help` and on argument errors.
+#[cfg(feature = "read-only-build")]
+pub const USAGE: &str = "\
+Usage:
+  usrgrp-manager                      Start the interactive TUI (read-only)
+  usrgrp-manager list users|groups [--json] [--filter QUERY]
+                                      Print users or groups and exit
+  usrgrp-manager --help               Show this help";
+
+/// What the binary should do, as parsed from the command line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// No subcommand: run the interactive TUI.
+    Tui,
+    /// Print usage and exit.
+    Help,
+    /// Print users or groups and exit.
+    List(ListOptions),
+    /// Add a user to a supplementary group and exit.
+    #[cfg(not(feature = "read-only-build"))]
+    AddToGroup { username: String, groupname: String },
+}
+
+/// Which accounts the `list` subcommand prints.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ListKind {
+    Users,
+    Groups,
+}
+
+/// Options of the `list` subcommand.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListOptions {
+    pub kind: ListKind,
+    /// Emit JSON instead of a columnar table.
+    pub json: bool,
+    /// Search query applied before printing (same syntax as the TUI search).
+    pub filter: Option<String>,
+}
+
+/// Parse command-line arguments (without the program name) into a [`Command`].
+pub fn parse_args<I, S>(args: I) -> Result<Command>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut args = args.into_iter().map(Into::into);
+    let Some(sub) = args.next() else {
+        return Ok(Command::Tui);
+    };
+    match sub.as_str() {
+        "-h" | "--help" | "help" => Ok(Command::Help),
+        "list" => {
+            let kind = match args.next().as_deref() {
+                Some("users") => ListKind::Users,
+                Some("groups") => ListKind::Groups,
+                Some(other) => {
+                    return Err(simple_error(format!(
+                        "list: expected 'users' or 'groups', got '{}'",
+                        other
+                    )));
+                }
+                None => return Err(simple_error("list: expected 'users' or 'groups'")),
+            };
+            let mut opts = ListOptions {
+                kind,
+                json: false,
+                filter: None,
+            };
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--json" => opts.json = true,
+                    "--filter" => match args.next() {
+                        Some(q) => opts.filter = Some(q),
+                        None => return Err(simple_error("--filter requires a value")),
+                    },
+                    _ => {
+                        if let Some(q) = arg.strip_prefix("--filter=") {
+                            opts.filter = Some(q.to_string());
+                        } else {
+                            return Err(simple_error(format!("list: unknown option '{}'", arg)));
+                        }
+                    }
+                }
+            }
+            Ok(Command::List(opts))
+        }
+        #[cfg(feature = "read-only-build")]
+        "add-to-group" => Err(simple_error(
+            "add-to-group: not available in a read-only build",
+        )),
+        #[cfg(not(feature = "read-only-build"))]
+        "add-to-group" => match (args.next(), args.next(), args.next()) {
+            (Some(username), Some(groupname), None) => Ok(Command::AddToGroup {
+                username,
+                groupname,
+            }),
+            _ => Err(simple_error("add-to-group: expected USER GROUP")),
+        },
+        other => Err(simple_error(format!("unknown command '{}'", other))),
+    }
+}
+
+/// Run the `list` subcommand against `adapter` and return the text to print.
+///
+/// Entries are sorted by ID like in the TUI and filtered with the shared
+/// search helpers.
+pub fn run_list(adapter: &SystemAdapter, opts: &ListOptions) -> Result<String> {
+    let query = opts.filter.as_deref().unwrap_or("");
+    match opts.kind {
+        ListKind::Users => {
+            let mut users = adapter.list_users()?;
+            sort_users(&mut users);
+            let users = filter_users(&users, &[], None, &Default::default(), query);
+            Ok(if opts.json {
+                users_json(&users)
+            } else {
+                users_table(&users)
+            })
+        }
+        ListKind::Groups => {
+            let mut groups = adapter.list_groups()?;
+            sort_groups(&mut groups);
+            let groups = filter_groups(&groups, &[], None, &Default::default(), query);
+            Ok(if opts.json {
+                groups_json(&groups)
+            } else {
+                groups_table(&groups)
+            })
+        }
+    }
+}
+
+/// Run the `add-to-group` subcommand and return the message to print.
+#[cfg(not(feature = "read-only-build"))]
+pub fn run_add_to_group(
+    backend: &dyn crate::sys::UserGroupBackend,
+    audit_log: &crate::audit::AuditLog,
+    username: &str,
+    groupname: &str,
+) -> Result<String> {
+    let result = backend.add_user_to_group(username, groupname);
+    let action = crate::app::PendingAction::AddUserToGroup {
+        username: username.to_string(),
+        groupname: groupname.to_string(),
+    };
+    audit_log.record(&action, &result);
+    result?;
+    Ok(format!("Added '{}' to '{}'", username, groupname))
+}
+
+/// Obtain the sudo password for a privileged subcommand.
+///
+/// Returns `None` when running as root. Otherwise the password comes from the
+/// `SUDO_ASKPASS` program if set, or from an interactive prompt when stdin is
+/// a terminal.
+#[cfg(not(feature = "read-only-build"))]
+pub fn sudo_password() -> Result<Option<String>> {
+    if crate::sys::is_root() {
+        return Ok(None);
+    }
+    if let Ok(askpass) = std::env::var("SUDO_ASKPASS")
+        && !askpass.trim().is_empty()
+    {
+        let output = std::process::Command::new(&askpass)
+            .arg("[sudo] password:")
+            .stderr(std::process::Stdio::inherit())
+            .output()
+            .map_err(|e| simple_error(format!("failed to run SUDO_ASKPASS {}: {}", askpass, e)))?;
+        if !output.status.success() {
+            return Err(simple_error(format!(
+                "SUDO_ASKPASS {} returned non-zero status: {}",
+                askpass, output.status
+            )));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        return Ok(Some(stdout.lines().next().unwrap_or("").to_string()));
+    }
+    use std::io::IsTerminal;
+    if !std::io::stdin().is_terminal() {
+        return Err(simple_error(
+            "authentication required: run as root, set SUDO_ASKPASS, or use a terminal",
+        ));
+    }
+    read_password("[sudo] password: ").map(Some)
+}
+
+/// Prompt on stderr and read a line from the terminal without echoing it.
+#[cfg(not(feature = "read-only-build"))]
+fn read_password(prompt: &str) -> Result<String> {
+    use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers, read};
+    use std::io::Write;
+
+    eprint!("{}", prompt);
+    std::io::stderr().flush()?;
+    crossterm::terminal::enable_raw_mode()?;
+    let mut password = String::new();
+    let res = loop {
+        match read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(simple_error("cancelled"));
+                }
+                KeyCode::Backspace => {
+                    password.pop();
+                }
+                KeyCode::Char(c) => password.push(c),
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => break Err(e.into()),
+        }
+    };
+    crossterm::terminal::disable_raw_mode().ok();
+    eprintln!();
+    res.map(|_| password)
+}
+
+/// Serialize users as a JSON array, one object per line.
+fn users_json(users: &[SystemUser]) -> String {
+    let items: Vec<String> = users
+        .iter()
+        .map(|u| {
+            format!(
+                "{{\"uid\":{},\"name\":{},\"primary_gid\":{},\"full_name\":{},\"home_dir\":{},\"shell\":{}}}",
+                u.uid,
+                json_string(&u.name),
+                u.primary_gid,
+                u.full_name
+                    .as_deref()
+                    .map(json_string)
+                    .unwrap_or_else(|| "null".to_string()),
+                json_string(&u.home_dir),
+                json_string(&u.shell)
+            )
+        })
+        .collect();
+    json_array(&items)
+}
+
+/// Serialize groups as a JSON array, one object per line.
+fn groups_json(groups: &[SystemGroup]) -> String {
+    let items: Vec<String> = groups
+        .iter()
+        .map(|g| {
+            let members: Vec<String> = g.members.iter().map(|m| json_string(m)).collect();
+            format!(
+                "{{\"gid\":{},\"name\":{},\"members\":[{}]}}",
+                g.gid,
+                json_string(&g.name),
+                members.join(",")
+            )
+        })
+        .collect();
+    json_array(&items)
+}
+
+fn json_array(items: &[String]) -> String {
+    if items.is_empty() {
+        return "[]\n".to_string();
+    }
+    format!("[\n  {}\n]\n", items.join(",\n  "))
+}
+
+/// Quote and escape a string as a JSON string literal.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Format users as a columnar table matching the TUI columns.
+fn users_table(users: &[SystemUser]) -> String {
+    let rows: Vec<[String; 5]> = users
+        .iter()
+        .map(|u| {
+            [
+                u.uid.to_string(),
+                u.name.clone(),
+                u.primary_gid.to_string(),
+                u.home_dir.clone(),
+                u.shell.clone(),
+            ]
+        })
+        .collect();
+    columns(["UID", "USER", "GID", "HOME", "SHELL"], &rows)
+}
+
+/// Format groups as a columnar table with comma-separated members.
+fn groups_table(groups: &[SystemGroup]) -> String {
+    let rows: Vec<[String; 3]> = groups
+        .iter()
+        .map(|g| [g.gid.to_string(), g.name.clone(), g.members.join(",")])
+        .collect();
+    columns(["GID", "GROUP", "MEMBERS"], &rows)
+}
+
+/// Left-align `rows` under `header`, padding every column but the last.
+fn columns<const N: usize>(header: [&str; N], rows: &[[String; N]]) -> String {
+    let mut widths = header.map(|h| h.chars().count());
+    for row in rows {
+        for (w, cell) in widths.iter_mut().zip(row.iter()) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+    let mut out = String::new();
+    let header = header.map(|h| h.to_string());
+    for row in std::iter::once(&header).chain(rows.iter()) {
+        let mut line = String::new();
+        for (i, cell) in row.iter().enumerate() {
+            if i + 1 < N {
+                line.push_str(&format!("{:<width$}  ", cell, width = widths[i]));
+            } else {
+                line.push_str(cell);
+            }
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(feature = "read-only-build"))]
+    use crate::audit::{AuditLog, MAX_AUDIT_BYTES};
+    #[cfg(not(feature = "read-only-build"))]
+    use crate::sys::fake::FakeBackend;
+    use std::fs;
+
+    fn seeded_root() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("etc")).unwrap();
+        fs::write(
+            dir.path().join("etc/passwd"),
+            "\
+bob:x:1001:1001:Bob \"B\":/home/bob:/bin/bash
+root:x:0:0:root:/root:/bin/bash
+daemon:x:2:2::/sbin:/usr/sbin/nologin
+",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("etc/group"),
+            "\
+root:x:0:
+wheel:x:10:root,bob
+bob:x:1001:
+",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn parse_args_maps_subcommands() {
+        assert_eq!(parse_args(Vec::<String>::new()).unwrap(), Command::Tui);
+        assert_eq!(parse_args(["--help"]).unwrap(), Command::Help);
+        assert_eq!(
+            parse_args(["list", "groups", "--filter", "-bob", "--json"]).unwrap(),
+            Command::List(ListOptions {
+                kind: ListKind::Groups,
+                json: true,
+                filter: Some("-bob".to_string()),
+            })
+        );
+        assert!(parse_args(["list"]).is_err());
+        assert!(parse_args(["list", "users", "--bogus"]).is_err());
+        assert!(parse_args(["frobnicate"]).is_err());
+    }
+
+    #[test]
+    fn list_users_json_from_seeded_root() {
+        let root = seeded_root();
+        let adapter = SystemAdapter::with_root(root.path());
+        let opts = ListOptions {
+            kind: ListKind::Users,
+            json: true,
+            filter: Some("bash".to_string()),
+        };
+        let out = run_list(&adapter, &opts).unwrap();
+        assert_eq!(
+            out,
+            "[\n  \
+{\"uid\":0,\"name\":\"root\",\"primary_gid\":0,\"full_name\":\"root\",\"home_dir\":\"/root\",\"shell\":\"/bin/bash\"},\n  \
+{\"uid\":1001,\"name\":\"bob\",\"primary_gid\":1001,\"full_name\":\"Bob \\\"B\\\"\",\"home_dir\":\"/home/bob\",\"shell\":\"/bin/bash\"}\n]\n"
+        );
+    }
+
+    #[test]
+    fn list_groups_table_and_json_respect_filter() {
+        let root = seeded_root();
+        let adapter = SystemAdapter::with_root(root.path());
+        let mut opts = ListOptions {
+            kind: ListKind::Groups,
+            json: false,
+            filter: Some("-root".to_string()),
+        };
+        assert_eq!(
+            run_list(&adapter, &opts).unwrap(),
+            "GID   GROUP  MEMBERS\n1001  bob\n"
+        );
+
+        opts.json = true;
+        opts.filter = Some("wheel".to_string());
+        assert_eq!(
+            run_list(&adapter, &opts).unwrap(),
+            "[\n  {\"gid\":10,\"name\":\"wheel\",\"members\":[\"root\",\"bob\"]}\n]\n"
+        );
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn add_to_group_args_map_to_backend_call() {
+        let cmd = parse_args(["add-to-group", "alice", "devs"]).unwrap();
+        let Command::AddToGroup {
+            username,
+            groupname,
+        } = cmd
+        else {
+            panic!("unexpected command: {:?}", cmd);
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let audit_log = AuditLog::new(dir.path().join("audit.log"), MAX_AUDIT_BYTES);
+        let backend = FakeBackend::default();
+        let msg = run_add_to_group(&backend, &audit_log, &username, &groupname).unwrap();
+        assert_eq!(msg, "Added 'alice' to 'devs'");
+        assert_eq!(backend.calls(), vec!["add alice devs"]);
+
+        let failing = FakeBackend {
+            fail_on: Some("add".to_string()),
+            ..Default::default()
+        };
+        assert!(run_add_to_group(&failing, &audit_log, "alice", "nope").is_err());
+
+        // Both attempts land in the given log, not the user's own
+        let entries = std::fs::read_to_string(audit_log.path()).unwrap();
+        let entries: Vec<_> = entries.lines().collect();
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].ends_with(" ok gpasswd -a alice devs"));
+        assert!(entries[1].contains(" FAILED gpasswd -a alice nope -- "));
+
+        assert!(parse_args(["add-to-group", "alice"]).is_err());
+        assert!(parse_args(["add-to-group", "alice", "devs", "extra"]).is_err());
+    }
+}
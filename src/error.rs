@@ -92,3 +92,33 @@ impl std::error::Error for SimpleError {}
 pub fn simple_error(msg: impl Into<String>) -> DynError {
     Box::new(SimpleError::new(msg))
 }
+
+#[derive(Debug)]
+/// Sudo could not authenticate, as opposed to the command run through it
+/// failing.
+pub struct AuthError(pub String);
+
+impl std::fmt::Display for AuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+/// Create a boxed [`AuthError`] in one step.
+pub fn auth_error(msg: impl Into<String>) -> DynError {
+    Box::new(AuthError(msg.into()))
+}
+
+/// Whether `err`, or an error it wraps, is an [`AuthError`].
+pub fn is_auth_error(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut current = Some(err);
+    while let Some(e) = current {
+        if e.is::<AuthError>() {
+            return true;
+        }
+        current = e.source();
+    }
+    false
+}
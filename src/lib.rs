@@ -2,10 +2,14 @@
 //!
 //! This crate exposes the building blocks of the TUI:
 //! - Application state and update loop (`app`)
+//! - Audit trail of privileged actions (`audit`; not in a `read-only-build`)
+//! - Non-interactive command-line subcommands (`cli`)
 //! - Error and result types (`error`)
+//! - Debug logging to a file (`logging`)
 //! - In-memory search helpers (`search`)
 //! - System interaction layer for users/groups (`sys`)
 //! - UI rendering and widgets (`ui`)
+//! - Opening paths in desktop programs (`util`)
 //!
 //! It is used by the `usrgrp-manager` binary and by tests.
 #![doc = include_str!("../README.md")]
@@ -13,10 +17,15 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 pub mod app;
+#[cfg(not(feature = "read-only-build"))]
+pub mod audit;
+pub mod cli;
 pub mod error;
+pub mod logging;
 pub mod search;
 pub mod sys;
 pub mod ui;
+pub mod util;
 
 // Re-export commonly used items at the crate root for convenience
 /// Convenient error and result types shared across the crate.
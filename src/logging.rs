@@ -0,0 +1,123 @@
+//! Minimal file logger for debugging in the field.
+//!
+//! Enabled by `UGM_LOG=debug|info|warn`; lines go to `UGM_LOG_FILE` or to
+//! `ugm.log` next to the config files, never to stdout, which the TUI owns.
+//! Without `UGM_LOG` nothing is written.
+
+use std::fmt;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Severity of a log line; a logger writes its own level and above.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+}
+
+impl Level {
+    /// Parse a `UGM_LOG` value (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "debug" => Some(Level::Debug),
+            "info" => Some(Level::Info),
+            "warn" | "warning" => Some(Level::Warn),
+            _ => None,
+        }
+    }
+
+    /// Upper-case label written in front of each line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+        }
+    }
+}
+
+/// Appends timestamped lines at or above `level` to a file.
+#[derive(Clone, Debug)]
+pub struct Logger {
+    level: Level,
+    path: PathBuf,
+}
+
+impl Logger {
+    pub fn new(level: Level, path: impl Into<PathBuf>) -> Self {
+        Self {
+            level,
+            path: path.into(),
+        }
+    }
+
+    /// Logger configured from `UGM_LOG` and `UGM_LOG_FILE`, if logging is on.
+    pub fn from_env() -> Option<Self> {
+        let level = Level::parse(&std::env::var("UGM_LOG").ok()?)?;
+        let path = std::env::var("UGM_LOG_FILE")
+            .ok()
+            .filter(|p| !p.trim().is_empty())
+            .unwrap_or_else(|| crate::app::config_file_write_path("ugm.log"));
+        Some(Self::new(level, path))
+    }
+
+    /// Whether lines at `level` are written.
+    pub fn enabled(&self, level: Level) -> bool {
+        level >= self.level
+    }
+
+    /// Append one line; write errors are ignored so logging never breaks the app.
+    pub fn log(&self, level: Level, args: fmt::Arguments) {
+        if !self.enabled(level) {
+            return;
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let line = format!(
+            "{}.{:03} {:5} {}\n",
+            now.as_secs(),
+            now.subsec_millis(),
+            level.as_str(),
+            args
+        );
+        if let Ok(mut file) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+        {
+            let _ = file.write_all(line.as_bytes());
+        }
+    }
+}
+
+static LOGGER: OnceLock<Option<Logger>> = OnceLock::new();
+
+/// Set up the process-wide logger from the environment; later calls do nothing.
+pub fn init_from_env() {
+    LOGGER.get_or_init(Logger::from_env);
+}
+
+fn log(level: Level, args: fmt::Arguments) {
+    if let Some(Some(logger)) = LOGGER.get() {
+        logger.log(level, args);
+    }
+}
+
+/// Log detail useful when tracing a problem, such as each command run.
+pub fn debug(args: fmt::Arguments) {
+    log(Level::Debug, args);
+}
+
+/// Log a notable event, such as an action being performed.
+pub fn info(args: fmt::Arguments) {
+    log(Level::Info, args);
+}
+
+/// Log a failure the user may need to know about.
+pub fn warn(args: fmt::Arguments) {
+    log(Level::Warn, args);
+}
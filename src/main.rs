@@ -1,10 +1,13 @@
 //! usrgrp-manager binary entry point.
 //!
 //! Initializes the terminal in raw mode, runs the TUI event loop,
-//! and restores the terminal state on exit.
+//! and restores the terminal state on exit. Subcommands such as `list`
+//! run non-interactively instead (see the `cli` module).
 //!
 use crate::error::Result;
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+};
 use crossterm::execute;
 use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
@@ -13,23 +16,75 @@ use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
 
 mod app;
+#[cfg(not(feature = "read-only-build"))]
+mod audit;
+mod cli;
 mod error;
+mod logging;
 mod search;
 mod sys;
 mod ui;
+mod util;
 
 /// Initialize a Crossterm-backed `ratatui` terminal in raw mode.
 fn init_terminal() -> Result<Terminal<CrosstermBackend<std::io::Stdout>>> {
     enable_raw_mode()?;
     let mut stdout = std::io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
 }
 
-/// Program entry point: run the TUI and report any top-level error to stderr.
+/// Program entry point: run a CLI subcommand or the TUI and report any top-level error to stderr.
 fn main() -> Result<()> {
+    logging::init_from_env();
+    match cli::parse_args(std::env::args().skip(1)) {
+        Ok(cli::Command::Tui) => {}
+        Ok(cli::Command::Help) => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Ok(cli::Command::List(opts)) => {
+            match cli::run_list(&sys::SystemAdapter::new(), &opts) {
+                Ok(out) => print!("{out}"),
+                Err(err) => {
+                    eprintln!("error: {err}");
+                    std::process::exit(1);
+                }
+            }
+            return Ok(());
+        }
+        #[cfg(not(feature = "read-only-build"))]
+        Ok(cli::Command::AddToGroup {
+            username,
+            groupname,
+        }) => {
+            let res = cli::sudo_password().and_then(|pw| {
+                let adapter = sys::SystemAdapter::with_sudo_password(pw);
+                let audit_log = audit::AuditLog::default_location();
+                cli::run_add_to_group(&adapter, &audit_log, &username, &groupname)
+            });
+            match res {
+                Ok(msg) => println!("{msg}"),
+                Err(err) => {
+                    eprintln!("error: {err}");
+                    std::process::exit(1);
+                }
+            }
+            return Ok(());
+        }
+        Err(err) => {
+            eprintln!("error: {err}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    }
+
     let mut terminal = init_terminal().map_err(|e| format!("init terminal: {}", e))?;
 
     let res = app::run(&mut terminal);
@@ -38,7 +93,8 @@ fn main() -> Result<()> {
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )
     .ok();
     terminal.show_cursor().ok();
@@ -1,10 +1,14 @@
 //! Search utilities for filtering users and groups.
 //!
-//! Currently provides [`apply_filters_and_search`] which filters the `AppState` in-place
-//! based on the current input mode and query string.
+//! Provides [`apply_filters_and_search`] which filters the `AppState` in-place
+//! based on the current input mode and query string, on top of the reusable
+//! [`filter_users`] and [`filter_groups`] helpers.
 //!
-use crate::app::{AppState, GroupsFilter, InputMode, UsersFilter};
-use std::collections::HashMap;
+use crate::app::{
+    AppState, GroupsFilter, GroupsFilterChips, InputMode, UsersFilter, UsersFilterChips,
+};
+use crate::sys::{SystemGroup, SystemUser};
+use std::collections::{HashMap, HashSet};
 
 type ShadowMap = HashMap<String, ShadowStatus>;
 type ShadowMapResult = std::io::Result<ShadowMap>;
@@ -14,13 +18,51 @@ type ShadowProviderFn = dyn Fn() -> ShadowMapResult;
 ///
 /// - In `SearchUsers`, filters by username, full name, home directory, shell, UID, or GID.
 /// - In `SearchGroups`, filters by group name, GID, or any member name.
+/// - Whitespace-separated terms must all match; a term prefixed with `-` excludes
+///   entries matching it. See [`parse_query_terms`] for quoting rules.
 /// - For empty queries, restores the full lists.
 pub fn apply_filters_and_search(app: &mut AppState) {
-    let q = app.search_query.to_lowercase();
+    let users_query = match app.input_mode {
+        InputMode::SearchUsers => app.search_query.as_str(),
+        _ => "",
+    };
+    app.users = filter_users(
+        &app.users_all,
+        &app.groups_all,
+        app.users_filter,
+        &app.users_filter_chips,
+        users_query,
+    );
+    app.selected_user_index = 0;
 
-    // Users view
-    let mut users_view = app.users_all.clone();
-    if let Some(f) = app.users_filter {
+    let groups_query = match app.input_mode {
+        InputMode::SearchGroups => app.search_query.as_str(),
+        _ => "",
+    };
+    app.groups = filter_groups(
+        &app.groups_all,
+        &app.users_all,
+        app.groups_filter,
+        &app.groups_filter_chips,
+        groups_query,
+    );
+    app.selected_group_index = 0;
+}
+
+/// Apply the top-level filter, the combinable chips and a search query to `users`.
+///
+/// This is the filtering behind the users table, shared with the command-line
+/// `list` mode. An empty `query` matches everything. `groups` is only consulted
+/// by the `no_primary_group` chip.
+pub fn filter_users(
+    users: &[SystemUser],
+    groups: &[SystemGroup],
+    filter: Option<UsersFilter>,
+    chips: &UsersFilterChips,
+    query: &str,
+) -> Vec<SystemUser> {
+    let mut users_view = users.to_vec();
+    if let Some(f) = filter {
         match f {
             UsersFilter::OnlyUserIds => users_view.retain(|u| u.uid >= 1000),
             UsersFilter::OnlySystemIds => users_view.retain(|u| u.uid < 1000),
@@ -28,72 +70,275 @@ pub fn apply_filters_and_search(app: &mut AppState) {
     }
 
     // Apply chip filters (combinable)
+    if chips.human_only {
+        users_view.retain(|u| u.uid >= 1000);
+    }
+    if chips.system_only {
+        users_view.retain(|u| u.uid < 1000);
+    }
+    if chips.inactive {
+        users_view.retain(|u| {
+            let sh = u.shell.to_ascii_lowercase();
+            sh.contains("nologin") || sh.ends_with("/false")
+        });
+    }
+    if chips.no_home {
+        users_view.retain(|u| !std::path::Path::new(&u.home_dir).exists());
+    }
+    if chips.no_primary_group {
+        users_view.retain(|u| !groups.iter().any(|g| g.gid == u.primary_gid));
+    }
+    // System-backed filters via /etc/shadow (best-effort; ignored if unreadable)
+    if (chips.locked || chips.no_password || chips.expired)
+        && let Ok(shadow) = get_shadow_status()
     {
-        let chips = &app.users_filter_chips;
-        if chips.human_only {
-            users_view.retain(|u| u.uid >= 1000);
+        if chips.locked {
+            users_view.retain(|u| shadow.get(&u.name).map(|s| s.locked).unwrap_or(false));
         }
-        if chips.system_only {
-            users_view.retain(|u| u.uid < 1000);
+        if chips.no_password {
+            users_view.retain(|u| shadow.get(&u.name).map(|s| s.no_password).unwrap_or(false));
         }
-        if chips.inactive {
-            users_view.retain(|u| {
-                let sh = u.shell.to_ascii_lowercase();
-                sh.contains("nologin") || sh.ends_with("/false")
-            });
-        }
-        if chips.no_home {
-            users_view.retain(|u| !std::path::Path::new(&u.home_dir).exists());
-        }
-        // System-backed filters via /etc/shadow (best-effort; ignored if unreadable)
-        if (chips.locked || chips.no_password || chips.expired)
-            && let Ok(shadow) = get_shadow_status()
-        {
-            if chips.locked {
-                users_view.retain(|u| shadow.get(&u.name).map(|s| s.locked).unwrap_or(false));
-            }
-            if chips.no_password {
-                users_view.retain(|u| shadow.get(&u.name).map(|s| s.no_password).unwrap_or(false));
-            }
-            if chips.expired {
-                users_view.retain(|u| shadow.get(&u.name).map(|s| s.expired).unwrap_or(false));
-            }
+        if chips.expired {
+            users_view.retain(|u| shadow.get(&u.name).map(|s| s.expired).unwrap_or(false));
         }
     }
-    if matches!(app.input_mode, InputMode::SearchUsers) && !q.is_empty() {
-        users_view.retain(|u| {
-            u.name.to_lowercase().contains(&q)
-                || u.full_name
-                    .as_deref()
-                    .unwrap_or("")
-                    .to_lowercase()
-                    .contains(&q)
-                || u.home_dir.to_lowercase().contains(&q)
-                || u.shell.to_lowercase().contains(&q)
-                || u.uid.to_string().contains(&q)
-                || u.primary_gid.to_string().contains(&q)
-        });
+
+    let terms = parse_query_terms(&query.to_lowercase());
+    if !terms.is_empty() {
+        users_view.retain(|u| terms.matches(|t| user_matches_term(u, t)));
     }
-    app.users = users_view;
-    app.selected_user_index = 0.min(app.users.len().saturating_sub(1));
+    users_view
+}
 
-    // Groups view
-    let mut groups_view = app.groups_all.clone();
-    if let Some(f) = app.groups_filter {
+/// Apply the top-level filter, the combinable chips and a search query to `groups`.
+///
+/// Counterpart of [`filter_users`] for the groups table. `users` is only
+/// consulted by the `empty_only` and `orphan_members` chips.
+pub fn filter_groups(
+    groups: &[SystemGroup],
+    users: &[SystemUser],
+    filter: Option<GroupsFilter>,
+    chips: &GroupsFilterChips,
+    query: &str,
+) -> Vec<SystemGroup> {
+    let mut groups_view = groups.to_vec();
+    if let Some(f) = filter {
         match f {
             GroupsFilter::OnlyUserGids => groups_view.retain(|g| g.gid >= 1000),
             GroupsFilter::OnlySystemGids => groups_view.retain(|g| g.gid < 1000),
         }
     }
-    if matches!(app.input_mode, InputMode::SearchGroups) && !q.is_empty() {
-        groups_view.retain(|g| {
-            g.name.to_lowercase().contains(&q)
-                || g.gid.to_string().contains(&q)
-                || g.members.iter().any(|m| m.to_lowercase().contains(&q))
-        });
+
+    // Apply chip filters (combinable)
+    if chips.system_only {
+        groups_view.retain(|g| g.gid < 1000);
+    }
+    if chips.user_only {
+        groups_view.retain(|g| g.gid >= 1000);
+    }
+    if chips.empty_only {
+        groups_view
+            .retain(|g| g.members.is_empty() && !users.iter().any(|u| u.primary_gid == g.gid));
+    }
+    if chips.has_sudo_flag {
+        let sudo_group = crate::app::sudo_group_name();
+        groups_view.retain(|g| g.name == sudo_group);
+    }
+    if chips.orphan_members {
+        let known: HashSet<&str> = users.iter().map(|u| u.name.as_str()).collect();
+        groups_view.retain(|g| g.members.iter().any(|m| !known.contains(m.as_str())));
+    }
+    let terms = parse_query_terms(&query.to_lowercase());
+    if !terms.is_empty() {
+        groups_view.retain(|g| terms.matches(|t| group_matches_term(g, t)));
+    }
+    groups_view
+}
+
+/// How many users match `query` but are hidden by the top-level filter or chips.
+///
+/// Lets an empty search result point at the filters instead of looking like a
+/// failed search.
+pub fn hidden_user_matches(
+    users: &[SystemUser],
+    groups: &[SystemGroup],
+    filter: Option<UsersFilter>,
+    chips: &UsersFilterChips,
+    query: &str,
+) -> usize {
+    let unfiltered = filter_users(users, groups, None, &UsersFilterChips::default(), query);
+    let shown = filter_users(users, groups, filter, chips, query);
+    unfiltered.len().saturating_sub(shown.len())
+}
+
+/// How many groups match `query` but are hidden by the top-level filter or chips.
+pub fn hidden_group_matches(
+    groups: &[SystemGroup],
+    users: &[SystemUser],
+    filter: Option<GroupsFilter>,
+    chips: &GroupsFilterChips,
+    query: &str,
+) -> usize {
+    let unfiltered = filter_groups(groups, users, None, &GroupsFilterChips::default(), query);
+    let shown = filter_groups(groups, users, filter, chips, query);
+    unfiltered.len().saturating_sub(shown.len())
+}
+
+/// Positive and negative terms parsed from a search query.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueryTerms {
+    /// Terms that must all match.
+    pub include: Vec<String>,
+    /// Terms that must not match.
+    pub exclude: Vec<String>,
+}
+
+impl QueryTerms {
+    /// Whether the query contains no terms at all.
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Evaluate the terms against an entry using `matches_term` for a single term.
+    pub fn matches<F: Fn(&str) -> bool>(&self, matches_term: F) -> bool {
+        self.include.iter().all(|t| matches_term(t))
+            && !self.exclude.iter().any(|t| matches_term(t))
+    }
+}
+
+/// Split a search query into positive and negative terms.
+///
+/// Terms are separated by whitespace. A term starting with `-` is a negation
+/// (`-nologin` hides entries matching `nologin`). Double quotes group words and
+/// keep a leading dash literal: `"-x"` searches for `-x`, while `-"foo bar"`
+/// excludes the phrase `foo bar`. A lone `-` is treated as a literal term.
+pub fn parse_query_terms(query: &str) -> QueryTerms {
+    let mut terms = QueryTerms::default();
+    let mut chars = query.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        if chars.peek().is_none() {
+            break;
+        }
+        let mut negate = false;
+        if chars.peek() == Some(&'-') {
+            chars.next();
+            match chars.peek() {
+                None => {
+                    terms.include.push("-".to_string());
+                    break;
+                }
+                Some(c) if c.is_whitespace() => {
+                    terms.include.push("-".to_string());
+                    continue;
+                }
+                Some(_) => negate = true,
+            }
+        }
+        let mut term = String::new();
+        let mut in_quotes = false;
+        while let Some(&c) = chars.peek() {
+            if c == '"' {
+                in_quotes = !in_quotes;
+            } else if c.is_whitespace() && !in_quotes {
+                break;
+            } else {
+                term.push(c);
+            }
+            chars.next();
+        }
+        if term.is_empty() {
+            continue;
+        }
+        if negate {
+            terms.exclude.push(term);
+        } else {
+            terms.include.push(term);
+        }
+    }
+    terms
+}
+
+fn user_matches_term(u: &SystemUser, term: &str) -> bool {
+    u.name.to_lowercase().contains(term)
+        || u.full_name
+            .as_deref()
+            .unwrap_or("")
+            .to_lowercase()
+            .contains(term)
+        || u.home_dir.to_lowercase().contains(term)
+        || u.shell.to_lowercase().contains(term)
+        || u.uid.to_string().contains(term)
+        || u.primary_gid.to_string().contains(term)
+}
+
+fn group_matches_term(g: &SystemGroup, term: &str) -> bool {
+    g.name.to_lowercase().contains(term)
+        || g.gid.to_string().contains(term)
+        || g.members.iter().any(|m| m.to_lowercase().contains(term))
+}
+
+/// Byte ranges of `haystack` that `needle` matched, ignoring case.
+///
+/// A contiguous occurrence is preferred; otherwise the needle's characters are
+/// matched in order wherever they appear (`"alc"` in `"alice"` gives `a`, `l`
+/// and `c`). Adjacent characters are merged into one range. Returns nothing
+/// for an empty needle or when the characters do not all appear in order.
+pub fn match_spans(needle: &str, haystack: &str) -> Vec<(usize, usize)> {
+    let same = |a: char, b: char| a.to_lowercase().eq(b.to_lowercase());
+    let needle: Vec<char> = needle.chars().collect();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let chars: Vec<(usize, char)> = haystack.char_indices().collect();
+    let end_of = |i: usize| chars.get(i).map_or(haystack.len(), |&(b, _)| b);
+    for start in 0..chars.len().saturating_sub(needle.len() - 1) {
+        if needle
+            .iter()
+            .zip(&chars[start..])
+            .all(|(&n, &(_, c))| same(n, c))
+        {
+            return vec![(chars[start].0, end_of(start + needle.len()))];
+        }
     }
-    app.groups = groups_view;
-    app.selected_group_index = 0.min(app.groups.len().saturating_sub(1));
+    let mut spans: Vec<(usize, usize)> = Vec::new();
+    let mut wanted = needle.iter().peekable();
+    for (i, &(pos, c)) in chars.iter().enumerate() {
+        let Some(&&n) = wanted.peek() else { break };
+        if !same(n, c) {
+            continue;
+        }
+        wanted.next();
+        match spans.last_mut() {
+            Some(last) if last.1 == pos => last.1 = end_of(i + 1),
+            _ => spans.push((pos, end_of(i + 1))),
+        }
+    }
+    if wanted.peek().is_some() {
+        return Vec::new();
+    }
+    spans
+}
+
+/// Byte ranges of `haystack` matched by any positive term of `query`, sorted
+/// and merged. See [`match_spans`].
+pub fn query_match_spans(query: &str, haystack: &str) -> Vec<(usize, usize)> {
+    let mut spans: Vec<(usize, usize)> = parse_query_terms(query)
+        .include
+        .iter()
+        .flat_map(|t| match_spans(t, haystack))
+        .collect();
+    spans.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(spans.len());
+    for (s, e) in spans {
+        match merged.last_mut() {
+            Some(last) if s <= last.1 => last.1 = last.1.max(e),
+            _ => merged.push((s, e)),
+        }
+    }
+    merged
 }
 
 // Lightweight shadow status used for filters and details
@@ -150,43 +395,7 @@ fn read_shadow_status() -> ShadowMapResult {
             .duration_since(UNIX_EPOCH)
             .map(|d| (d.as_secs() / 86_400) as i64)
             .unwrap_or(0);
-        let mut map: ShadowMap = HashMap::new();
-        for line in contents.lines() {
-            if line.trim().is_empty() || line.starts_with('#') {
-                continue;
-            }
-            let parts: Vec<&str> = line.split(':').collect();
-            if parts.len() < 2 {
-                continue;
-            }
-            let name = parts[0].to_string();
-            let pw = parts[1];
-            let lastchg: i64 = parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(0);
-            let max: i64 = parts.get(4).and_then(|s| s.parse().ok()).unwrap_or(-1);
-            let expire_abs: i64 = parts.get(7).and_then(|s| s.parse().ok()).unwrap_or(-1);
-
-            let locked = pw.starts_with('!') || pw == "*" || pw == "!!";
-            let no_password = pw.is_empty();
-            let expired_by_max = max >= 0 && lastchg > 0 && (lastchg + max) <= today_days;
-            let expired_by_abs = expire_abs >= 0 && expire_abs <= today_days;
-            let expired = expired_by_max || expired_by_abs;
-
-            map.insert(
-                name,
-                ShadowStatus {
-                    locked,
-                    no_password,
-                    expired,
-                    last_change_days: if lastchg > 0 { Some(lastchg) } else { None },
-                    expire_abs_days: if expire_abs >= 0 {
-                        Some(expire_abs)
-                    } else {
-                        None
-                    },
-                },
-            );
-        }
-        Ok(map)
+        Ok(parse_shadow(&contents, today_days))
     }
 
     #[cfg(not(unix))]
@@ -199,7 +408,49 @@ fn read_shadow_status() -> ShadowMapResult {
     }
 }
 
-fn get_shadow_status() -> ShadowMapResult {
+/// Password status per user from the contents of a shadow file, with expiry
+/// judged against `today_days` (days since the epoch).
+pub fn parse_shadow(contents: &str, today_days: i64) -> HashMap<String, ShadowStatus> {
+    let mut map: ShadowMap = HashMap::new();
+    for line in contents.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parts: Vec<&str> = line.split(':').collect();
+        if parts.len() < 2 {
+            continue;
+        }
+        let name = parts[0].to_string();
+        let pw = parts[1];
+        let lastchg: i64 = parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(0);
+        let max: i64 = parts.get(4).and_then(|s| s.parse().ok()).unwrap_or(-1);
+        let expire_abs: i64 = parts.get(7).and_then(|s| s.parse().ok()).unwrap_or(-1);
+
+        let locked = pw.starts_with('!') || pw == "*" || pw == "!!";
+        let no_password = pw.is_empty();
+        let expired_by_max = max >= 0 && lastchg > 0 && (lastchg + max) <= today_days;
+        let expired_by_abs = expire_abs >= 0 && expire_abs <= today_days;
+        let expired = expired_by_max || expired_by_abs;
+
+        map.insert(
+            name,
+            ShadowStatus {
+                locked,
+                no_password,
+                expired,
+                last_change_days: if lastchg > 0 { Some(lastchg) } else { None },
+                expire_abs_days: if expire_abs >= 0 {
+                    Some(expire_abs)
+                } else {
+                    None
+                },
+            },
+        );
+    }
+    map
+}
+
+pub(crate) fn get_shadow_status() -> ShadowMapResult {
     if let Some(res) = SHADOW_PROVIDER.with(|p| p.borrow().as_ref().map(|f| f())) {
         return res;
     }
@@ -226,6 +477,19 @@ pub fn user_shadow_status(username: &str) -> Option<ShadowStatus> {
         .and_then(|m| m.get(username).cloned())
 }
 
+/// Names of the users whose password is locked, from one read of the shadow
+/// file; `None` when it cannot be read.
+pub fn locked_usernames() -> Option<HashSet<String>> {
+    let shadow = get_shadow_status().ok()?;
+    Some(
+        shadow
+            .into_iter()
+            .filter(|(_, s)| s.locked)
+            .map(|(name, _)| name)
+            .collect(),
+    )
+}
+
 thread_local! {
     static SHADOW_PROVIDER: std::cell::RefCell<Option<Box<ShadowProviderFn>>> = std::cell::RefCell::new(None);
 }
@@ -322,17 +586,53 @@ mod tests {
             _table_state: TableState::default(),
             input_mode: InputMode::Normal,
             search_query: String::new(),
+            search_cursor: 0,
             theme: Theme::dark(),
+            configured_theme: Theme::dark(),
             keymap: crate::app::keymap::Keymap::default(),
-            modal: None,
+            modal_stack: Vec::new(),
             users_focus: UsersFocus::UsersList,
             groups_focus: crate::app::GroupsFocus::GroupsList,
             sudo_password: None,
             users_filter: None,
             groups_filter: None,
             users_filter_chips: Default::default(),
+            groups_filter_chips: Default::default(),
+            filter_menu_selected: Default::default(),
             actions_context: None,
             show_keybinds: true,
+            sort_members: false,
+            pane_split: Default::default(),
+            id_radix: Default::default(),
+            live_stats: false,
+            modal_notice: None,
+            last_changed: None,
+            accounts_mtime: [None; 2],
+            accounts_stale: false,
+            running_as_root: None,
+            group_details_cache: None,
+            shells_cache: None,
+            effective_groups_cache: None,
+            locked_users_cache: None,
+            home_exists_cache: Default::default(),
+            membership_source: Default::default(),
+            layout_mode: Default::default(),
+            detail_verbosity: Default::default(),
+            privileged_groups: Default::default(),
+            empty_picker_enter: Default::default(),
+            members_include_primary: false,
+            min_password_length: 0,
+            user_columns: crate::app::UserColumn::ALL.to_vec(),
+            group_jump_origin: None,
+            compact_below: crate::app::DEFAULT_COMPACT_BELOW,
+            confirmations: crate::app::confirmconf::Confirmations::default(),
+            action_history: Default::default(),
+            queued_action: None,
+            pending_result: None,
+            lookup: None,
+            backend: std::sync::Arc::new(|_| panic!("search tests run no actions")),
+            #[cfg(not(feature = "read-only-build"))]
+            audit_log: None,
         }
     }
 
@@ -390,4 +690,66 @@ mod tests {
         assert_eq!(app.groups.len(), 1);
         assert_eq!(app.groups[0].name, "wheel");
     }
+
+    #[test]
+    fn parse_query_terms_splits_positive_and_negative() {
+        let t = parse_query_terms("home -nologin  \"-x\" -\"foo bar\" -");
+        assert_eq!(t.include, vec!["home", "-x", "-"]);
+        assert_eq!(t.exclude, vec!["nologin", "foo bar"]);
+        assert!(parse_query_terms("   ").is_empty());
+    }
+
+    #[test]
+    fn search_users_combines_positive_and_negative_terms() {
+        let users = vec![
+            mk_user(1000, "alice", 1000, None, "/home/alice", "/bin/zsh"),
+            mk_user(1001, "bob", 1001, None, "/home/bob", "/bin/bash"),
+            mk_user(2, "daemon", 2, None, "/sbin", "/usr/sbin/nologin"),
+            mk_user(1002, "al-x", 1002, None, "/home/al-x", "/usr/sbin/nologin"),
+        ];
+        let mut app = mk_app(users, vec![]);
+        app.input_mode = InputMode::SearchUsers;
+
+        app.search_query = "-nologin".to_string();
+        apply_filters_and_search(&mut app);
+        let names: Vec<_> = app.users.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(names, vec!["alice", "bob"]);
+
+        app.search_query = "home -bash".to_string();
+        apply_filters_and_search(&mut app);
+        let names: Vec<_> = app.users.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(names, vec!["alice", "al-x"]);
+
+        app.search_query = "\"-x\"".to_string();
+        apply_filters_and_search(&mut app);
+        assert_eq!(app.users.len(), 1);
+        assert_eq!(app.users[0].name, "al-x");
+    }
+
+    #[test]
+    fn search_groups_excludes_negated_members() {
+        let groups = vec![
+            mk_group(10, "wheel", &["root", "bob"]),
+            mk_group(100, "users", &["alice"]),
+            mk_group(1000, "devs", &["alice", "bob"]),
+        ];
+        let mut app = mk_app(vec![], groups);
+        app.input_mode = InputMode::SearchGroups;
+        app.search_query = "alice -bob".to_string();
+        apply_filters_and_search(&mut app);
+        assert_eq!(app.groups.len(), 1);
+        assert_eq!(app.groups[0].name, "users");
+    }
+
+    #[test]
+    fn match_spans_prefers_substring_then_scattered_characters() {
+        assert_eq!(match_spans("ICE", "alice"), vec![(2, 5)]);
+        // a-l-c in order, with a and l adjacent
+        assert_eq!(match_spans("alc", "alice"), vec![(0, 2), (3, 4)]);
+        assert!(match_spans("cla", "alice").is_empty());
+        assert!(match_spans("", "alice").is_empty());
+        // Multi-byte characters yield byte ranges on char boundaries
+        assert_eq!(match_spans("é", "josé"), vec![(3, 5)]);
+        assert_eq!(query_match_spans("al ic", "alice"), vec![(0, 4)]);
+    }
 }
@@ -0,0 +1,142 @@
+//! Recording backend used by unit tests in place of [`SystemAdapter`](super::SystemAdapter).
+
+use super::{SystemGroup, SystemUser, UserGroupBackend};
+use crate::error::{Result, auth_error, simple_error};
+use std::sync::{Arc, Mutex};
+
+/// Backend fake that records calls and serves canned account lists.
+///
+/// Every mutation is logged as a short string such as `"add alice wheel"`.
+/// Calls whose log line starts with `fail_on` return an error instead.
+/// Clones share the call log, so a test can keep one while the app runs
+/// actions against another on a worker thread.
+///
+/// With `accepts_password` set, mutations behave like sudo: unless
+/// `password` matches they fail with an authentication error and are not
+/// logged.
+#[derive(Clone, Default)]
+pub struct FakeBackend {
+    pub users: Vec<SystemUser>,
+    pub groups: Vec<SystemGroup>,
+    pub fail_on: Option<String>,
+    pub accepts_password: Option<String>,
+    pub password: Option<String>,
+    pub calls: Arc<Mutex<Vec<String>>>,
+}
+
+impl FakeBackend {
+    /// Calls recorded so far, in order.
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Log a mutation, after checking the password as sudo would.
+    fn run(&self, call: String) -> Result<()> {
+        if self.accepts_password.is_some() && self.password != self.accepts_password {
+            return Err(auth_error("sudo -v failed: incorrect password"));
+        }
+        self.record(call)
+    }
+
+    fn record(&self, call: String) -> Result<()> {
+        let fail = self
+            .fail_on
+            .as_deref()
+            .is_some_and(|prefix| call.starts_with(prefix));
+        self.calls.lock().unwrap().push(call.clone());
+        if fail {
+            Err(simple_error(format!("{} failed", call)))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl UserGroupBackend for FakeBackend {
+    fn list_users(&self) -> Result<Vec<SystemUser>> {
+        self.record("list_users".to_string())?;
+        Ok(self.users.clone())
+    }
+    fn list_groups(&self) -> Result<Vec<SystemGroup>> {
+        self.record("list_groups".to_string())?;
+        Ok(self.groups.clone())
+    }
+    fn effective_groups(&self, username: &str) -> Result<Vec<String>> {
+        self.record(format!("id {username}"))?;
+        let user = self
+            .users
+            .iter()
+            .find(|u| u.name == username)
+            .ok_or_else(|| simple_error(format!("id: '{username}': no such user")))?;
+        Ok(self
+            .groups
+            .iter()
+            .filter(|g| g.gid == user.primary_gid || g.members.contains(&user.name))
+            .map(|g| g.name.clone())
+            .collect())
+    }
+    fn read_sudoers_dropin(&self, username: &str) -> Result<Option<String>> {
+        self.run(format!("read_sudoers {username}"))?;
+        Ok(None)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn add_user_to_group(&self, username: &str, groupname: &str) -> Result<()> {
+        self.run(format!("add {username} {groupname}"))
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn remove_user_from_group(&self, username: &str, groupname: &str) -> Result<()> {
+        self.run(format!("remove {username} {groupname}"))
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn set_supplementary_groups(&self, username: &str, groups: &[&str]) -> Result<()> {
+        self.run(format!("set_groups {username} {}", groups.join(",")))
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn create_group(&self, groupname: &str) -> Result<()> {
+        self.run(format!("create_group {groupname}"))
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn delete_group(&self, groupname: &str) -> Result<()> {
+        self.run(format!("delete_group {groupname}"))
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn rename_group(&self, old_name: &str, new_name: &str) -> Result<()> {
+        self.run(format!("rename_group {old_name} {new_name}"))
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn create_user(&self, username: &str, create_home: bool) -> Result<()> {
+        self.run(format!("create_user {username} {create_home}"))
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn delete_user(&self, username: &str, delete_home: bool) -> Result<()> {
+        self.run(format!("delete_user {username} {delete_home}"))
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn change_user_shell(&self, username: &str, new_shell: &str) -> Result<()> {
+        self.run(format!("shell {username} {new_shell}"))
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn change_primary_group(&self, username: &str, groupname: &str) -> Result<()> {
+        self.run(format!("primary {username} {groupname}"))
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn change_user_fullname(&self, username: &str, new_fullname: &str) -> Result<()> {
+        self.run(format!("fullname {username} {new_fullname}"))
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn change_username(&self, old_username: &str, new_username: &str) -> Result<()> {
+        self.run(format!("username {old_username} {new_username}"))
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn set_user_password(&self, username: &str, _password: &str) -> Result<()> {
+        self.run(format!("set_password {username}"))
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn expire_user_password(&self, username: &str) -> Result<()> {
+        self.run(format!("expire_password {username}"))
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn create_sudoers_dropin(&self, username: &str) -> Result<()> {
+        self.run(format!("sudoers_dropin {username}"))
+    }
+}
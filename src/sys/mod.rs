@@ -4,11 +4,17 @@
 //! and a `SystemAdapter` that can list, create, delete, and modify accounts
 //! via standard Linux utilities. Many operations may require sudo.
 //!
+//! With the `read-only-build` feature the modifying methods are compiled out,
+//! leaving only the listing and lookup helpers.
+//!
 use crate::error::Result;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
+#[cfg(test)]
+pub mod fake;
+
 /// Representation of a system user (/etc/passwd).
 #[allow(dead_code)]
 #[derive(Clone, Debug)]
@@ -30,10 +36,132 @@ pub struct SystemGroup {
     pub members: Vec<String>,
 }
 
+/// UID and GID of the unprivileged `nobody`/`nogroup` accounts on most systems.
+pub const NOBODY_ID: u32 = 65534;
+
+/// Names used by more than one entry in `users`, sorted.
+///
+/// Tools like `usermod` act on the first such entry, so lookups by name alone
+/// are ambiguous for these.
+pub fn duplicate_user_names(users: &[SystemUser]) -> Vec<String> {
+    let mut seen = std::collections::BTreeSet::new();
+    let mut dups = std::collections::BTreeSet::new();
+    for u in users {
+        if !seen.insert(u.name.as_str()) {
+            dups.insert(u.name.clone());
+        }
+    }
+    dups.into_iter().collect()
+}
+
+/// Sort `users` by UID, breaking ties between duplicate UIDs by name so the
+/// order does not depend on where entries sit in `/etc/passwd`.
+pub fn sort_users(users: &mut [SystemUser]) {
+    users.sort_by(|a, b| (a.uid, &a.name).cmp(&(b.uid, &b.name)));
+}
+
+/// Sort `groups` by GID, breaking ties between duplicate GIDs by name.
+pub fn sort_groups(groups: &mut [SystemGroup]) {
+    groups.sort_by(|a, b| (a.gid, &a.name).cmp(&(b.gid, &b.name)));
+}
+
+impl SystemUser {
+    /// Whether this is the shared unprivileged `nobody` account, recognized by
+    /// name (UID 99 on older Red Hat systems) or by UID 65534.
+    pub fn is_nobody(&self) -> bool {
+        self.uid == NOBODY_ID || self.name == "nobody"
+    }
+}
+
+impl SystemGroup {
+    /// Whether this is the shared unprivileged `nogroup`/`nobody` group.
+    pub fn is_nobody(&self) -> bool {
+        self.gid == NOBODY_ID || self.name == "nogroup" || self.name == "nobody"
+    }
+}
+
+/// What a home directory path refers to, as shown in the user details.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HomeStatus {
+    /// Where the path points when it is itself a symlink.
+    pub link_target: Option<PathBuf>,
+    /// Whether the path (or the symlink's target) exists.
+    pub exists: bool,
+    /// Permission bits of the path or its target, on Unix.
+    pub mode: Option<u32>,
+}
+
+/// Total size in bytes of the files under `path`, for showing what deleting
+/// a home directory removes.
+///
+/// Symlinks inside are counted as links, not followed, so a link to `/` does
+/// not make the walk endless. `None` if `path` cannot be read; unreadable
+/// entries further down are skipped.
+pub fn home_dir_size(path: &Path) -> Option<u64> {
+    let mut total = 0;
+    let mut pending = vec![fs::read_dir(path).ok()?];
+    while let Some(entries) = pending.pop() {
+        for entry in entries.flatten() {
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                if let Ok(sub) = fs::read_dir(entry.path()) {
+                    pending.push(sub);
+                }
+            } else {
+                total += meta.len();
+            }
+        }
+    }
+    Some(total)
+}
+
+/// `bytes` with a binary unit, e.g. `512 B` or `1.5 MiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Inspect `home` without hiding a symlink behind its target.
+///
+/// `fs::metadata` alone follows links, so a dangling or redirected home would
+/// look like an ordinary (missing or present) directory.
+pub fn home_status(home: &str) -> HomeStatus {
+    let link_target = fs::symlink_metadata(home)
+        .ok()
+        .filter(|m| m.file_type().is_symlink())
+        .and_then(|_| fs::read_link(home).ok());
+    let target = fs::metadata(home).ok();
+    #[cfg(unix)]
+    let mode = {
+        use std::os::unix::fs::PermissionsExt;
+        target.as_ref().map(|m| m.permissions().mode() & 0o777)
+    };
+    #[cfg(not(unix))]
+    let mode = None;
+    HomeStatus {
+        link_target,
+        exists: target.is_some(),
+        mode,
+    }
+}
+
 /// Adapter that wraps privileged operations, optionally using a sudo password.
 #[allow(dead_code)]
 pub struct SystemAdapter {
     pub sudo_password: Option<String>,
+    /// Alternate filesystem root for reading account files (defaults to `/`).
+    root: Option<PathBuf>,
 }
 
 impl SystemAdapter {
@@ -41,6 +169,7 @@ impl SystemAdapter {
     pub fn new() -> Self {
         Self {
             sudo_password: None,
+            root: None,
         }
     }
 
@@ -48,30 +177,45 @@ impl SystemAdapter {
     pub fn with_sudo_password(password: Option<String>) -> Self {
         Self {
             sudo_password: password,
+            root: None,
         }
     }
 
+    /// Construct an adapter that reads `etc/passwd`, `etc/group` and
+    /// `etc/shells` below `root` instead of `/`, e.g. a chroot or a test fixture.
+    #[allow(dead_code)]
+    pub fn with_root<P: Into<PathBuf>>(root: P) -> Self {
+        Self {
+            sudo_password: None,
+            root: Some(root.into()),
+        }
+    }
+
+    /// Path of an account file under `/etc`, honoring the configured root.
+    fn etc_path(&self, name: &str) -> PathBuf {
+        let mut p = self.root.clone().unwrap_or_else(|| PathBuf::from("/"));
+        p.push("etc");
+        p.push(name);
+        p
+    }
+
     /// Read users from `/etc/passwd`.
     pub fn list_users(&self) -> Result<Vec<SystemUser>> {
-        parse_passwd("/etc/passwd")
+        parse_passwd(self.etc_path("passwd"))
     }
 
     /// Read groups from `/etc/group`.
     pub fn list_groups(&self) -> Result<Vec<SystemGroup>> {
-        parse_group("/etc/group")
+        parse_group(self.etc_path("group"))
     }
 
     /// Add a user to a group using `gpasswd -a`.
+    #[cfg(not(feature = "read-only-build"))]
     pub fn add_user_to_group(&self, username: &str, groupname: &str) -> Result<()> {
         // Prefer gpasswd for membership changes
         let output = self
             .run_privileged("gpasswd", &["-a", username, groupname])
-            .map_err(|e| {
-                crate::error::simple_error(format!(
-                    "failed to execute gpasswd -a {} {}: {}",
-                    username, groupname, e
-                ))
-            })?;
+            .map_err(|e| exec_failed(format!("gpasswd -a {} {}", username, groupname), e))?;
         if output.status.success() {
             Ok(())
         } else {
@@ -83,15 +227,11 @@ impl SystemAdapter {
     }
 
     /// Remove a user from a group using `gpasswd -d`.
+    #[cfg(not(feature = "read-only-build"))]
     pub fn remove_user_from_group(&self, username: &str, groupname: &str) -> Result<()> {
         let output = self
             .run_privileged("gpasswd", &["-d", username, groupname])
-            .map_err(|e| {
-                crate::error::simple_error(format!(
-                    "failed to execute gpasswd -d {} {}: {}",
-                    username, groupname, e
-                ))
-            })?;
+            .map_err(|e| exec_failed(format!("gpasswd -d {} {}", username, groupname), e))?;
         if output.status.success() {
             Ok(())
         } else {
@@ -102,11 +242,32 @@ impl SystemAdapter {
         }
     }
 
+    /// Replace a user's supplementary groups with `groups` via `usermod -G`.
+    ///
+    /// One call sets the whole list, so it cannot stop halfway like a series
+    /// of `gpasswd -d` calls.
+    #[cfg(not(feature = "read-only-build"))]
+    pub fn set_supplementary_groups(&self, username: &str, groups: &[&str]) -> Result<()> {
+        let list = groups.join(",");
+        let output = self
+            .run_privileged("usermod", &["-G", &list, username])
+            .map_err(|e| exec_failed(format!("usermod -G {} {}", list, username), e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(crate::error::simple_error(format_cli_error(
+                "usermod -G",
+                &output,
+            )))
+        }
+    }
+
     /// Create a group via `groupadd`.
+    #[cfg(not(feature = "read-only-build"))]
     pub fn create_group(&self, groupname: &str) -> Result<()> {
-        let output = self.run_privileged("groupadd", &[groupname]).map_err(|e| {
-            crate::error::simple_error(format!("failed to execute groupadd {}: {}", groupname, e))
-        })?;
+        let output = self
+            .run_privileged("groupadd", &[groupname])
+            .map_err(|e| exec_failed(format!("groupadd {}", groupname), e))?;
         if output.status.success() {
             Ok(())
         } else {
@@ -117,15 +278,13 @@ impl SystemAdapter {
     }
 
     /// Create a user via `useradd` (optionally with home `-m`).
+    #[cfg(not(feature = "read-only-build"))]
     pub fn create_user(&self, username: &str, create_home: bool) -> Result<()> {
-        let mut args: Vec<&str> = Vec::new();
-        if create_home {
-            args.push("-m");
-        }
-        args.push(username);
-        let output = self.run_privileged("useradd", &args).map_err(|e| {
-            crate::error::simple_error(format!("failed to execute useradd {}: {}", username, e))
-        })?;
+        let args = useradd_args(username, create_home);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = self
+            .run_privileged("useradd", &args)
+            .map_err(|e| exec_failed(format!("useradd {}", username), e))?;
         if output.status.success() {
             Ok(())
         } else {
@@ -136,6 +295,7 @@ impl SystemAdapter {
     }
 
     /// Delete a group via `groupdel` (idempotent if already absent).
+    #[cfg(not(feature = "read-only-build"))]
     pub fn delete_group(&self, groupname: &str) -> Result<()> {
         // If the group is already gone, treat as success (idempotent delete)
         if let Ok(groups) = self.list_groups()
@@ -143,9 +303,9 @@ impl SystemAdapter {
         {
             return Ok(());
         }
-        let output = self.run_privileged("groupdel", &[groupname]).map_err(|e| {
-            crate::error::simple_error(format!("failed to execute groupdel {}: {}", groupname, e))
-        })?;
+        let output = self
+            .run_privileged("groupdel", &[groupname])
+            .map_err(|e| exec_failed(format!("groupdel {}", groupname), e))?;
         if output.status.success() {
             Ok(())
         } else {
@@ -156,15 +316,11 @@ impl SystemAdapter {
     }
 
     /// Rename a group via `groupmod -n`.
+    #[cfg(not(feature = "read-only-build"))]
     pub fn rename_group(&self, old_name: &str, new_name: &str) -> Result<()> {
         let output = self
             .run_privileged("groupmod", &["-n", new_name, old_name])
-            .map_err(|e| {
-                crate::error::simple_error(format!(
-                    "failed to execute groupmod -n {} {}: {}",
-                    new_name, old_name, e
-                ))
-            })?;
+            .map_err(|e| exec_failed(format!("groupmod -n {} {}", new_name, old_name), e))?;
         if output.status.success() {
             Ok(())
         } else {
@@ -176,15 +332,16 @@ impl SystemAdapter {
     }
 
     /// Delete a user via `userdel` (optionally `-r` to remove home).
+    #[cfg(not(feature = "read-only-build"))]
     pub fn delete_user(&self, username: &str, delete_home: bool) -> Result<()> {
         let mut args: Vec<&str> = Vec::new();
         if delete_home {
             args.push("-r");
         }
         args.push(username);
-        let output = self.run_privileged("userdel", &args).map_err(|e| {
-            crate::error::simple_error(format!("failed to execute userdel {}: {}", username, e))
-        })?;
+        let output = self
+            .run_privileged("userdel", &args)
+            .map_err(|e| exec_failed(format!("userdel {}", username), e))?;
         if output.status.success() {
             Ok(())
         } else {
@@ -196,7 +353,7 @@ impl SystemAdapter {
 
     /// List available shells from `/etc/shells`.
     pub fn list_shells(&self) -> Result<Vec<String>> {
-        let contents = fs::read_to_string("/etc/shells")?;
+        let contents = fs::read_to_string(self.etc_path("shells"))?;
         let shells = contents
             .lines()
             .filter_map(|line| {
@@ -211,16 +368,25 @@ impl SystemAdapter {
         Ok(shells)
     }
 
+    /// Groups `username` is in as resolved through NSS, from `id -Gn`.
+    ///
+    /// Unlike [`list_groups`](Self::list_groups) this also sees memberships
+    /// served by LDAP or SSSD instead of `/etc/group`.
+    pub fn effective_groups(&self, username: &str) -> Result<Vec<String>> {
+        let output = Command::new("id").args(["-Gn", username]).output()?;
+        if output.status.success() {
+            Ok(parse_id_groups(&String::from_utf8_lossy(&output.stdout)))
+        } else {
+            Err(crate::error::simple_error(format_cli_error("id", &output)))
+        }
+    }
+
     /// Change a user's shell via `usermod -s`.
+    #[cfg(not(feature = "read-only-build"))]
     pub fn change_user_shell(&self, username: &str, new_shell: &str) -> Result<()> {
         let output = self
             .run_privileged("usermod", &["-s", new_shell, username])
-            .map_err(|e| {
-                crate::error::simple_error(format!(
-                    "failed to execute usermod -s {} {}: {}",
-                    new_shell, username, e
-                ))
-            })?;
+            .map_err(|e| exec_failed(format!("usermod -s {} {}", new_shell, username), e))?;
         if output.status.success() {
             Ok(())
         } else {
@@ -231,16 +397,28 @@ impl SystemAdapter {
         }
     }
 
+    /// Change a user's primary group via `usermod -g`.
+    #[cfg(not(feature = "read-only-build"))]
+    pub fn change_primary_group(&self, username: &str, groupname: &str) -> Result<()> {
+        let output = self
+            .run_privileged("usermod", &["-g", groupname, username])
+            .map_err(|e| exec_failed(format!("usermod -g {} {}", groupname, username), e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(crate::error::simple_error(format_cli_error(
+                "usermod -g",
+                &output,
+            )))
+        }
+    }
+
     /// Change a user's full name (GECOS) via `usermod -c`.
+    #[cfg(not(feature = "read-only-build"))]
     pub fn change_user_fullname(&self, username: &str, new_fullname: &str) -> Result<()> {
         let output = self
             .run_privileged("usermod", &["-c", new_fullname, username])
-            .map_err(|e| {
-                crate::error::simple_error(format!(
-                    "failed to execute usermod -c {} {}: {}",
-                    new_fullname, username, e
-                ))
-            })?;
+            .map_err(|e| exec_failed(format!("usermod -c {} {}", new_fullname, username), e))?;
         if output.status.success() {
             Ok(())
         } else {
@@ -252,15 +430,11 @@ impl SystemAdapter {
     }
 
     /// Rename a user via `usermod -l`.
+    #[cfg(not(feature = "read-only-build"))]
     pub fn change_username(&self, old_username: &str, new_username: &str) -> Result<()> {
         let output = self
             .run_privileged("usermod", &["-l", new_username, old_username])
-            .map_err(|e| {
-                crate::error::simple_error(format!(
-                    "failed to execute usermod -l {} {}: {}",
-                    new_username, old_username, e
-                ))
-            })?;
+            .map_err(|e| exec_failed(format!("usermod -l {} {}", new_username, old_username), e))?;
         if output.status.success() {
             Ok(())
         } else {
@@ -272,6 +446,7 @@ impl SystemAdapter {
     }
 
     /// Set a user's password via `chpasswd` (root) or `sudo` pipeline.
+    #[cfg(not(feature = "read-only-build"))]
     pub fn set_user_password(&self, username: &str, password: &str) -> Result<()> {
         use std::io::Write;
         if current_uid() == 0 {
@@ -295,11 +470,9 @@ impl SystemAdapter {
             }
         } else {
             // Non-root: avoid mixing sudo password and chpasswd input on the same stdin.
-            // If we don't yet have a sudo password, surface an explicit authentication error
-            // instead of attempting sudo with an empty line (which would count as a failed try).
-            if self.sudo_password.is_none() {
-                return Err(crate::error::simple_error("Authentication required"));
-            }
+            // Check the password on its own first, so a wrong one is reported as an
+            // authentication error rather than as chpasswd failing.
+            self.validate_sudo()?;
             // Use a bash -c pipeline so chpasswd reads from echo, while we send only the sudo password to sudo.
             fn escape_for_double_quotes(s: &str) -> String {
                 let mut out = String::with_capacity(s.len());
@@ -346,15 +519,11 @@ impl SystemAdapter {
     }
 
     /// Expire a user's password via `chage -d 0`.
+    #[cfg(not(feature = "read-only-build"))]
     pub fn expire_user_password(&self, username: &str) -> Result<()> {
         let output = self
             .run_privileged("chage", &["-d", "0", username])
-            .map_err(|e| {
-                crate::error::simple_error(format!(
-                    "failed to execute chage -d 0 {}: {}",
-                    username, e
-                ))
-            })?;
+            .map_err(|e| exec_failed(format!("chage -d 0 {}", username), e))?;
         if output.status.success() {
             Ok(())
         } else {
@@ -365,8 +534,103 @@ impl SystemAdapter {
         }
     }
 
+    /// Read the user's sudoers drop-in, or `None` if there is none.
+    ///
+    /// Drop-ins are usually readable by root only, so a failed direct read is
+    /// retried with `cat` through sudo.
+    pub fn read_sudoers_dropin(&self, username: &str) -> Result<Option<String>> {
+        let path = sudoers_dropin_path(username);
+        match fs::read_to_string(&path) {
+            Ok(contents) => return Ok(Some(contents)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(_) => {}
+        }
+        let path_str = path.to_string_lossy();
+        let exists = self.run_privileged("test", &["-e", &path_str])?;
+        if !exists.status.success() {
+            return Ok(None);
+        }
+        let output = self.run_privileged("cat", &[&path_str])?;
+        if output.status.success() {
+            Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+        } else {
+            Err(crate::error::simple_error(format_cli_error("cat", &output)))
+        }
+    }
+
+    /// Create the user's sudoers drop-in granting full sudo rights.
+    ///
+    /// The content is checked with `visudo -cf` on a temporary copy first and
+    /// then installed root-owned with mode 0440. The copy lives in a fresh
+    /// directory only the current user can enter, so no one else can plant or
+    /// swap it before it is installed. An existing drop-in is never
+    /// overwritten.
+    #[cfg(not(feature = "read-only-build"))]
+    pub fn create_sudoers_dropin(&self, username: &str) -> Result<()> {
+        let dest = sudoers_dropin_path(username);
+        if self.read_sudoers_dropin(username)?.is_some() {
+            return Err(crate::error::simple_error(format!(
+                "{} already exists",
+                dest.display()
+            )));
+        }
+        let dir = private_temp_dir("ugm_sudoers")?;
+        let tmp = dir.join("dropin");
+        let result = write_new_private_file(&tmp, &sudoers_dropin_content(username))
+            .and_then(|()| self.install_sudoers_dropin(&tmp, &dest));
+        let _ = fs::remove_dir_all(&dir);
+        result
+    }
+
+    /// Validate `tmp` with `visudo -cf` and install it as `dest`.
+    #[cfg(not(feature = "read-only-build"))]
+    fn install_sudoers_dropin(&self, tmp: &Path, dest: &Path) -> Result<()> {
+        let check = Command::new("visudo")
+            .args(["-c", "-f"])
+            .arg(tmp)
+            .stderr(Stdio::piped())
+            .output()
+            .map_err(|e| format!("failed to execute visudo: {}", e))?;
+        if !check.status.success() {
+            return Err(crate::error::simple_error(format_cli_error(
+                "visudo -cf",
+                &check,
+            )));
+        }
+        let tmp_str = tmp.to_string_lossy();
+        let dest_str = dest.to_string_lossy();
+        let output = self.run_privileged(
+            "install",
+            &[
+                "-m", "0440", "-o", "root", "-g", "root", &tmp_str, &dest_str,
+            ],
+        )?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(crate::error::simple_error(format_cli_error(
+                "install", &output,
+            )))
+        }
+    }
+
     /// Run a command with privileges using `sudo` if necessary.
     fn run_privileged(&self, cmd: &str, args: &[&str]) -> Result<std::process::Output> {
+        crate::logging::debug(format_args!("run_privileged: {}", command_line(cmd, args)));
+        let result = self.run_privileged_inner(cmd, args);
+        match &result {
+            Ok(out) if !out.status.success() => crate::logging::warn(format_args!(
+                "{cmd} exited with {}: {}",
+                out.status,
+                String::from_utf8_lossy(&out.stderr).trim()
+            )),
+            Ok(_) => crate::logging::debug(format_args!("{cmd} succeeded")),
+            Err(e) => crate::logging::warn(format_args!("{cmd} could not run: {e}")),
+        }
+        result
+    }
+
+    fn run_privileged_inner(&self, cmd: &str, args: &[&str]) -> Result<std::process::Output> {
         if current_uid() == 0 {
             return Command::new(cmd)
                 .args(args)
@@ -375,13 +639,31 @@ impl SystemAdapter {
                 .map_err(Into::into);
         }
 
+        // Step 1: validate sudo credentials to populate timestamp without mixing with command IO
+        self.validate_sudo()?;
+
+        // Step 2: run the actual command without reading from stdin (use -n to avoid prompting)
+        let output = Command::new("sudo")
+            .arg("-n")
+            .arg(cmd)
+            .args(args)
+            .stderr(Stdio::piped())
+            .output()?;
+        Ok(output)
+    }
+
+    /// Check the sudo password with `sudo -v`, which also refreshes the sudo
+    /// timestamp for the command that follows.
+    ///
+    /// Fails with an [`AuthError`](crate::error::AuthError), so callers can
+    /// tell a wrong password from a failing command.
+    fn validate_sudo(&self) -> Result<()> {
         // Without a sudo password, don't attempt sudo with a blank line.
         // Return a clear error so the UI can prompt first.
         if self.sudo_password.is_none() {
-            return Err(crate::error::simple_error("Authentication required"));
+            return Err(crate::error::auth_error("Authentication required"));
         }
 
-        // Step 1: validate sudo credentials to populate timestamp without mixing with command IO
         let mut validate = Command::new("sudo")
             .arg("-S")
             .arg("-p")
@@ -400,20 +682,160 @@ impl SystemAdapter {
         }
         let validate_out = validate.wait_with_output()?;
         if !validate_out.status.success() {
-            return Err(crate::error::simple_error(format_cli_error(
+            return Err(crate::error::auth_error(format_cli_error(
                 "sudo -v",
                 &validate_out,
             )));
         }
+        Ok(())
+    }
+}
 
-        // Step 2: run the actual command without reading from stdin (use -n to avoid prompting)
-        let output = Command::new("sudo")
-            .arg("-n")
-            .arg(cmd)
-            .args(args)
-            .stderr(Stdio::piped())
-            .output()?;
-        Ok(output)
+/// Account operations shared by the TUI and the command-line mode.
+///
+/// [`SystemAdapter`] is the real implementation; tests can substitute a fake
+/// that records calls instead of touching the system. Only the methods that
+/// read accounts exist in a `read-only-build`.
+pub trait UserGroupBackend {
+    /// Read all users.
+    fn list_users(&self) -> Result<Vec<SystemUser>>;
+    /// Read all groups.
+    fn list_groups(&self) -> Result<Vec<SystemGroup>>;
+    /// Groups `username` is in as resolved through NSS.
+    fn effective_groups(&self, username: &str) -> Result<Vec<String>>;
+    /// The user's sudoers drop-in, or `None` if there is none.
+    fn read_sudoers_dropin(&self, username: &str) -> Result<Option<String>>;
+    /// Add `username` to the supplementary group `groupname`.
+    #[cfg(not(feature = "read-only-build"))]
+    fn add_user_to_group(&self, username: &str, groupname: &str) -> Result<()>;
+    /// Remove `username` from the supplementary group `groupname`.
+    #[cfg(not(feature = "read-only-build"))]
+    fn remove_user_from_group(&self, username: &str, groupname: &str) -> Result<()>;
+    /// Replace `username`'s supplementary groups with exactly `groups`.
+    #[cfg(not(feature = "read-only-build"))]
+    fn set_supplementary_groups(&self, username: &str, groups: &[&str]) -> Result<()>;
+    /// Create an empty group.
+    #[cfg(not(feature = "read-only-build"))]
+    fn create_group(&self, groupname: &str) -> Result<()>;
+    /// Delete a group; deleting a missing group succeeds.
+    #[cfg(not(feature = "read-only-build"))]
+    fn delete_group(&self, groupname: &str) -> Result<()>;
+    /// Rename a group, keeping its GID.
+    #[cfg(not(feature = "read-only-build"))]
+    fn rename_group(&self, old_name: &str, new_name: &str) -> Result<()>;
+    /// Create a user, optionally with a home directory.
+    #[cfg(not(feature = "read-only-build"))]
+    fn create_user(&self, username: &str, create_home: bool) -> Result<()>;
+    /// Delete a user, optionally removing the home directory.
+    #[cfg(not(feature = "read-only-build"))]
+    fn delete_user(&self, username: &str, delete_home: bool) -> Result<()>;
+    /// Change a user's login shell.
+    #[cfg(not(feature = "read-only-build"))]
+    fn change_user_shell(&self, username: &str, new_shell: &str) -> Result<()>;
+    /// Change a user's primary group.
+    #[cfg(not(feature = "read-only-build"))]
+    fn change_primary_group(&self, username: &str, groupname: &str) -> Result<()>;
+    /// Change a user's full name (GECOS).
+    #[cfg(not(feature = "read-only-build"))]
+    fn change_user_fullname(&self, username: &str, new_fullname: &str) -> Result<()>;
+    /// Rename a user account.
+    #[cfg(not(feature = "read-only-build"))]
+    fn change_username(&self, old_username: &str, new_username: &str) -> Result<()>;
+    /// Set a user's password.
+    #[cfg(not(feature = "read-only-build"))]
+    fn set_user_password(&self, username: &str, password: &str) -> Result<()>;
+    /// Force a password change at next login.
+    #[cfg(not(feature = "read-only-build"))]
+    fn expire_user_password(&self, username: &str) -> Result<()>;
+    /// Create a validated per-user sudoers drop-in.
+    #[cfg(not(feature = "read-only-build"))]
+    fn create_sudoers_dropin(&self, username: &str) -> Result<()>;
+}
+
+/// Builds the backend actions run against, given the sudo password to use.
+///
+/// [`AppState`](crate::app::AppState) holds one so that tests can hand the
+/// event loop a fake instead of the real system.
+pub type BackendFactory =
+    std::sync::Arc<dyn Fn(Option<String>) -> Box<dyn UserGroupBackend + Send> + Send + Sync>;
+
+/// Factory for [`SystemAdapter`]s, the backend of a normal run.
+pub fn system_backend() -> BackendFactory {
+    std::sync::Arc::new(|password| Box::new(SystemAdapter::with_sudo_password(password)))
+}
+
+impl UserGroupBackend for SystemAdapter {
+    fn list_users(&self) -> Result<Vec<SystemUser>> {
+        SystemAdapter::list_users(self)
+    }
+    fn list_groups(&self) -> Result<Vec<SystemGroup>> {
+        SystemAdapter::list_groups(self)
+    }
+    fn effective_groups(&self, username: &str) -> Result<Vec<String>> {
+        SystemAdapter::effective_groups(self, username)
+    }
+    fn read_sudoers_dropin(&self, username: &str) -> Result<Option<String>> {
+        SystemAdapter::read_sudoers_dropin(self, username)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn add_user_to_group(&self, username: &str, groupname: &str) -> Result<()> {
+        SystemAdapter::add_user_to_group(self, username, groupname)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn remove_user_from_group(&self, username: &str, groupname: &str) -> Result<()> {
+        SystemAdapter::remove_user_from_group(self, username, groupname)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn set_supplementary_groups(&self, username: &str, groups: &[&str]) -> Result<()> {
+        SystemAdapter::set_supplementary_groups(self, username, groups)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn create_group(&self, groupname: &str) -> Result<()> {
+        SystemAdapter::create_group(self, groupname)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn delete_group(&self, groupname: &str) -> Result<()> {
+        SystemAdapter::delete_group(self, groupname)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn rename_group(&self, old_name: &str, new_name: &str) -> Result<()> {
+        SystemAdapter::rename_group(self, old_name, new_name)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn create_user(&self, username: &str, create_home: bool) -> Result<()> {
+        SystemAdapter::create_user(self, username, create_home)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn delete_user(&self, username: &str, delete_home: bool) -> Result<()> {
+        SystemAdapter::delete_user(self, username, delete_home)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn change_user_shell(&self, username: &str, new_shell: &str) -> Result<()> {
+        SystemAdapter::change_user_shell(self, username, new_shell)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn change_primary_group(&self, username: &str, groupname: &str) -> Result<()> {
+        SystemAdapter::change_primary_group(self, username, groupname)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn change_user_fullname(&self, username: &str, new_fullname: &str) -> Result<()> {
+        SystemAdapter::change_user_fullname(self, username, new_fullname)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn change_username(&self, old_username: &str, new_username: &str) -> Result<()> {
+        SystemAdapter::change_username(self, old_username, new_username)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn set_user_password(&self, username: &str, password: &str) -> Result<()> {
+        SystemAdapter::set_user_password(self, username, password)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn expire_user_password(&self, username: &str) -> Result<()> {
+        SystemAdapter::expire_user_password(self, username)
+    }
+    #[cfg(not(feature = "read-only-build"))]
+    fn create_sudoers_dropin(&self, username: &str) -> Result<()> {
+        SystemAdapter::create_sudoers_dropin(self, username)
     }
 }
 
@@ -488,6 +910,16 @@ fn parse_group<P: AsRef<Path>>(path: P) -> Result<Vec<SystemGroup>> {
 // Note: NSS enumeration is not used at the moment; parsing /etc/passwd and
 // /etc/group is the default approach and can be forced via the `file-parse` feature.
 
+/// Error for a command that could not be run at all, keeping `source` so an
+/// [`AuthError`](crate::error::AuthError) can still be told apart.
+#[cfg(not(feature = "read-only-build"))]
+fn exec_failed(command: String, source: crate::error::DynError) -> crate::error::DynError {
+    Box::new(crate::error::WithContextError {
+        context: format!("failed to execute {command}"),
+        source,
+    })
+}
+
 /// Format a helpful CLI error message from a process output.
 fn format_cli_error(cmd: &str, output: &std::process::Output) -> String {
     let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
@@ -498,6 +930,106 @@ fn format_cli_error(cmd: &str, output: &std::process::Output) -> String {
     }
 }
 
+/// Group names from the output of `id -Gn`, in the order printed.
+pub fn parse_id_groups(output: &str) -> Vec<String> {
+    output.split_whitespace().map(str::to_string).collect()
+}
+
+/// Location of the per-user sudoers drop-in for `user`.
+///
+/// sudo skips files in `/etc/sudoers.d` whose names contain a `.`, so dots in
+/// the username are replaced with `_`.
+pub fn sudoers_dropin_path(user: &str) -> PathBuf {
+    Path::new("/etc/sudoers.d").join(user.replace('.', "_"))
+}
+
+/// Create a new directory in the temp dir that only the current user can
+/// enter, as `mkdtemp` does. An existing path is never reused.
+#[cfg(not(feature = "read-only-build"))]
+fn private_temp_dir(prefix: &str) -> Result<PathBuf> {
+    use std::os::unix::fs::DirBuilderExt;
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    for attempt in 0..16u32 {
+        let dir = std::env::temp_dir().join(format!(
+            "{prefix}_{}_{:x}",
+            std::process::id(),
+            nanos.wrapping_add(attempt)
+        ));
+        match fs::DirBuilder::new().mode(0o700).create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Err(crate::error::simple_error(
+        "could not create a private temporary directory",
+    ))
+}
+
+/// Write `content` to `path` with mode 0600, failing if `path` already exists.
+#[cfg(not(feature = "read-only-build"))]
+fn write_new_private_file(path: &Path, content: &str) -> Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?
+        .write_all(content.as_bytes())?;
+    Ok(())
+}
+
+/// Drop-in content granting `user` full sudo rights.
+pub fn sudoers_dropin_content(user: &str) -> String {
+    format!("# Created by usrgrp-manager\n{user} ALL=(ALL) ALL\n")
+}
+
+/// Whether a sudoers file refers to `group` as `%group`, outside comments.
+pub fn sudoers_mentions_group(content: &str, group: &str) -> bool {
+    let needle = format!("%{group}");
+    content
+        .lines()
+        .map(str::trim_start)
+        .filter(|line| !line.starts_with('#'))
+        .any(|line| {
+            line.match_indices(&needle).any(|(i, _)| {
+                // `%dev` must not match `%devops`
+                !line[i + needle.len()..]
+                    .starts_with(|c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+            })
+        })
+}
+
+/// `/etc/sudoers` and the `/etc/sudoers.d` files sudo reads that name
+/// `group`. Files that cannot be read, as when not running as root, are
+/// skipped.
+pub fn sudoers_files_naming_group(group: &str) -> Vec<PathBuf> {
+    let mut files = vec![PathBuf::from("/etc/sudoers")];
+    if let Ok(entries) = fs::read_dir("/etc/sudoers.d") {
+        let mut dropins: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                // sudo ignores names with a `.` and editor backups ending in `~`
+                let name = e.file_name().to_string_lossy().into_owned();
+                !name.contains('.') && !name.ends_with('~')
+            })
+            .map(|e| e.path())
+            .collect();
+        dropins.sort();
+        files.extend(dropins);
+    }
+    files
+        .into_iter()
+        .filter(|path| {
+            fs::read_to_string(path).is_ok_and(|content| sudoers_mentions_group(&content, group))
+        })
+        .collect()
+}
+
 /// Best-effort current UID detection (Linux-specific).
 fn current_uid() -> u32 {
     // Linux: read from /proc; fallback to 0 if parsing fails
@@ -514,6 +1046,121 @@ fn current_uid() -> u32 {
     0
 }
 
+/// Arguments [`SystemAdapter::create_user`] passes to `useradd`.
+pub fn useradd_args(username: &str, create_home: bool) -> Vec<String> {
+    let mut args = Vec::new();
+    if create_home {
+        args.push("-m".to_string());
+    }
+    args.push(username.to_string());
+    args
+}
+
+/// The `useradd` command line shown before creating a user, built from
+/// [`useradd_args`] so the preview matches what runs.
+pub fn useradd_command_line(username: &str, create_home: bool) -> String {
+    command_line("useradd", &useradd_args(username, create_home))
+}
+
+/// Render `program` and `args` as a shell-style command line for display.
+///
+/// Arguments that are empty or contain characters the shell would treat
+/// specially are single-quoted, so a full name with spaces reads as one arg.
+pub fn command_line<S: AsRef<str>>(program: &str, args: &[S]) -> String {
+    let mut line = String::from(program);
+    for arg in args {
+        line.push(' ');
+        line.push_str(&shell_quote(arg.as_ref()));
+    }
+    line
+}
+
+/// `arg` quoted the way [`command_line`] quotes its arguments.
+pub(crate) fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_alphanumeric() || "-_./,:=@+%".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+type ProcessCountFn = dyn Fn(u32) -> usize;
+
+thread_local! {
+    static PROCESS_COUNT_PROVIDER: std::cell::RefCell<Option<Box<ProcessCountFn>>> =
+        std::cell::RefCell::new(None);
+}
+
+/// Number of running processes owned by `uid`, counted by scanning `/proc`.
+///
+/// Every process's `status` file is read, so this is costly on busy systems.
+pub fn process_count(uid: u32) -> usize {
+    if let Some(count) = PROCESS_COUNT_PROVIDER.with(|p| p.borrow().as_ref().map(|f| f(uid))) {
+        return count;
+    }
+    let uid = uid.to_string();
+    let mut count = 0usize;
+    if let Ok(entries) = fs::read_dir("/proc") {
+        for e in entries.flatten() {
+            if let Ok(name) = e.file_name().into_string()
+                && name.chars().all(|c| c.is_ascii_digit())
+            {
+                let mut status = e.path();
+                status.push("status");
+                if let Ok(s) = fs::read_to_string(status) {
+                    for line in s.lines() {
+                        if let Some(rest) = line.strip_prefix("Uid:") {
+                            if rest.split_whitespace().next() == Some(uid.as_str()) {
+                                count += 1;
+                            }
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    count
+}
+
+/// Replace [`process_count`] with `f` on this thread, for tests.
+#[allow(dead_code)]
+pub fn set_process_count_provider<F>(f: F)
+where
+    F: Fn(u32) -> usize + 'static,
+{
+    PROCESS_COUNT_PROVIDER.with(|p| *p.borrow_mut() = Some(Box::new(f)));
+}
+
+/// Restore the `/proc` scan in [`process_count`].
+#[allow(dead_code)]
+pub fn clear_process_count_provider() {
+    PROCESS_COUNT_PROVIDER.with(|p| *p.borrow_mut() = None);
+}
+
+/// Whether the process runs with UID 0, so privileged commands need no sudo.
+pub fn is_root() -> bool {
+    current_uid() == 0
+}
+
+/// Header label for a process running as root, naming the invoking user when
+/// started through sudo (`sudo_user` is the value of `SUDO_USER`).
+pub fn root_indicator(is_root: bool, sudo_user: Option<&str>) -> Option<String> {
+    if !is_root {
+        return None;
+    }
+    match sudo_user.map(str::trim) {
+        Some(user) if !user.is_empty() && user != "root" => {
+            Some(format!("running as root (as {user} via sudo)"))
+        }
+        _ => Some("running as root".to_string()),
+    }
+}
+
 /// Resolve the current username using the UID and `/etc/passwd`.
 pub fn current_username() -> Option<String> {
     let uid = current_uid();
@@ -543,6 +1190,144 @@ mod tests {
         p
     }
 
+    /// Root-owned user `name`, for test fixtures.
+    fn user(uid: u32, name: &str) -> SystemUser {
+        SystemUser {
+            uid,
+            name: name.to_string(),
+            primary_gid: 0,
+            full_name: None,
+            home_dir: "/root".to_string(),
+            shell: "/bin/sh".to_string(),
+        }
+    }
+
+    /// Group `name` listing `members`, for test fixtures.
+    fn group(gid: u32, name: &str, members: &[&str]) -> SystemGroup {
+        SystemGroup {
+            gid,
+            name: name.to_string(),
+            members: members.iter().map(|m| m.to_string()).collect(),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn home_dir_size_counts_files_without_following_links() {
+        let dir = tmp_path("size");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("a"), [0u8; 1000]).unwrap();
+        fs::write(dir.join("sub/b"), [0u8; 2000]).unwrap();
+        std::os::unix::fs::symlink("/", dir.join("root")).unwrap();
+
+        let size = home_dir_size(&dir).unwrap();
+        assert!((3000..3100).contains(&size), "{size}");
+        assert_eq!(home_dir_size(&dir.join("missing")), None);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 << 30), "3.0 GiB");
+    }
+
+    #[test]
+    fn home_status_reports_symlink_and_target_separately() {
+        let dir = tmp_path("home");
+        let data = dir.join("data");
+        fs::create_dir_all(&data).unwrap();
+        let link = dir.join("alice");
+        std::os::unix::fs::symlink(&data, &link).unwrap();
+        let link_str = link.to_str().unwrap();
+
+        let st = home_status(link_str);
+        assert_eq!(st.link_target.as_deref(), Some(data.as_path()));
+        assert!(st.exists);
+        assert!(st.mode.is_some());
+
+        let plain = home_status(data.to_str().unwrap());
+        assert_eq!(plain.link_target, None);
+        assert!(plain.exists);
+
+        fs::remove_dir(&data).unwrap();
+        let dangling = home_status(link_str);
+        assert_eq!(dangling.link_target.as_deref(), Some(data.as_path()));
+        assert!(!dangling.exists);
+        assert_eq!(dangling.mode, None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn useradd_preview_matches_adapter_argv() {
+        for create_home in [false, true] {
+            let argv = useradd_args("alice", create_home);
+            assert_eq!(
+                useradd_command_line("alice", create_home),
+                format!("useradd {}", argv.join(" "))
+            );
+        }
+        assert_eq!(useradd_args("alice", true), ["-m", "alice"]);
+        assert_eq!(useradd_command_line("alice", false), "useradd alice");
+    }
+
+    #[test]
+    fn root_indicator_names_the_sudo_user() {
+        assert_eq!(root_indicator(false, Some("alice")), None);
+        assert_eq!(
+            root_indicator(true, Some("alice")).as_deref(),
+            Some("running as root (as alice via sudo)")
+        );
+        // Logged in as root directly, or sudo invoked from a root shell
+        for sudo_user in [None, Some(""), Some("  "), Some("root")] {
+            assert_eq!(
+                root_indicator(true, sudo_user).as_deref(),
+                Some("running as root")
+            );
+        }
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn sudoers_temp_copy_is_private_and_never_reuses_a_path() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = super::private_temp_dir("ugm_test").unwrap();
+        let other = super::private_temp_dir("ugm_test").unwrap();
+        assert_ne!(dir, other);
+        let mode = |p: &Path| fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&dir), 0o700);
+
+        let file = dir.join("dropin");
+        super::write_new_private_file(&file, "alice ALL=(ALL) ALL\n").unwrap();
+        assert_eq!(mode(&file), 0o600);
+        // A file someone else put there first is refused, not written into
+        assert!(super::write_new_private_file(&file, "x").is_err());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "alice ALL=(ALL) ALL\n");
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(&other).unwrap();
+    }
+
+    #[test]
+    fn sudoers_dropin_path_and_content() {
+        assert_eq!(
+            sudoers_dropin_path("alice"),
+            PathBuf::from("/etc/sudoers.d/alice")
+        );
+        // sudo ignores drop-in names containing a dot
+        assert_eq!(
+            sudoers_dropin_path("john.doe"),
+            PathBuf::from("/etc/sudoers.d/john_doe")
+        );
+
+        let content = sudoers_dropin_content("john.doe");
+        assert!(content.ends_with('\n'));
+        assert_eq!(content.lines().last(), Some("john.doe ALL=(ALL) ALL"));
+        assert!(
+            content
+                .lines()
+                .filter(|l| !l.starts_with('#'))
+                .eq(["john.doe ALL=(ALL) ALL"])
+        );
+    }
+
     #[test]
     fn parse_passwd_basic() {
         let path = tmp_path("passwd");
@@ -590,6 +1375,63 @@ wheel:x:998:root,jdoe
         );
     }
 
+    #[test]
+    fn duplicate_ids_are_ordered_by_name() {
+        let mut users = vec![user(1000, "alice"), user(0, "toor"), user(0, "root")];
+        sort_users(&mut users);
+        let names: Vec<&str> = users.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(names, ["root", "toor", "alice"]);
+        // The file order of the duplicates does not matter
+        users.swap(0, 1);
+        sort_users(&mut users);
+        assert_eq!(users[0].name, "root");
+
+        let mut groups = vec![
+            group(100, "users", &[]),
+            group(100, "staff", &[]),
+            group(0, "root", &[]),
+        ];
+        sort_groups(&mut groups);
+        let names: Vec<&str> = groups.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, ["root", "staff", "users"]);
+    }
+
+    #[test]
+    fn parse_id_groups_splits_on_whitespace() {
+        assert_eq!(
+            parse_id_groups("alice wheel  ldap-devs\n"),
+            vec!["alice", "wheel", "ldap-devs"]
+        );
+        assert!(parse_id_groups("\n").is_empty());
+    }
+
+    #[test]
+    fn sudoers_group_references_match_whole_names_outside_comments() {
+        let sudoers = "# %devs ALL=(ALL) ALL\n%devops ALL=(ALL) ALL\n  %devs,%ops ALL=(ALL) ALL\n";
+        assert!(sudoers_mentions_group(sudoers, "devs"));
+        assert!(sudoers_mentions_group(sudoers, "ops"));
+        assert!(sudoers_mentions_group(sudoers, "devops"));
+        assert!(!sudoers_mentions_group(sudoers, "dev"));
+        assert!(!sudoers_mentions_group("# %wheel ALL=(ALL) ALL\n", "wheel"));
+    }
+
+    #[cfg(not(feature = "read-only-build"))]
+    #[test]
+    fn exec_failed_keeps_auth_errors_recognisable() {
+        use crate::error::{auth_error, is_auth_error, simple_error};
+        let e = super::exec_failed(
+            "groupadd devs".into(),
+            auth_error("Authentication required"),
+        );
+        assert_eq!(
+            e.to_string(),
+            "failed to execute groupadd devs: Authentication required"
+        );
+        assert!(is_auth_error(&*e));
+        let e = super::exec_failed("groupadd devs".into(), simple_error("no such file"));
+        assert!(!is_auth_error(&*e));
+    }
+
     #[test]
     #[cfg(unix)]
     fn format_cli_error_empty_and_nonempty_stderr() {
@@ -708,21 +1550,9 @@ empty:x:456:\n\
         let primary_gid = 100u32;
 
         let groups = [
-            SystemGroup {
-                gid: 100,
-                name: "users".to_string(),
-                members: vec!["bob".to_string()],
-            },
-            SystemGroup {
-                gid: 10,
-                name: "wheel".to_string(),
-                members: vec!["alice".to_string()],
-            },
-            SystemGroup {
-                gid: 50,
-                name: "dev".to_string(),
-                members: vec![],
-            },
+            group(100, "users", &["bob"]),
+            group(10, "wheel", &["alice"]),
+            group(50, "dev", &[]),
         ];
 
         let filtered: Vec<&SystemGroup> = groups
@@ -5,13 +5,91 @@
 //! modals and input dialogs.
 
 use ratatui::Frame;
-use ratatui::layout::Rect;
-use ratatui::style::{Modifier, Style};
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 
-use crate::app::{AppState, ModalState};
-use std::collections::{BTreeMap, BTreeSet};
+use crate::app::{AppState, ModalState, READ_ONLY};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Number of terminal columns `s` occupies; CJK characters and most emoji
+/// take two.
+pub fn text_width(s: &str) -> usize {
+    s.width()
+}
+
+/// Shorten `s` to at most `max` columns, ending in `…` when it was cut.
+pub fn truncate_to_width(s: &str, max: usize) -> String {
+    if text_width(s) <= max {
+        return s.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > max - 1 {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out.push('…');
+    out
+}
+
+/// The search query to highlight in `tab`'s table, if it is being searched.
+pub fn active_search_query(app: &AppState, tab: crate::app::ActiveTab) -> Option<&str> {
+    let searching = match tab {
+        crate::app::ActiveTab::Users => app.input_mode == crate::app::InputMode::SearchUsers,
+        crate::app::ActiveTab::Groups => app.input_mode == crate::app::InputMode::SearchGroups,
+    };
+    (searching && !app.search_query.is_empty()).then_some(app.search_query.as_str())
+}
+
+/// `text` as a line with the parts matched by `query` underlined and bold.
+pub fn highlight_matches(text: String, query: Option<&str>) -> Line<'static> {
+    let spans = query.map_or_else(Vec::new, |q| crate::search::query_match_spans(q, &text));
+    if spans.is_empty() {
+        return Line::from(text);
+    }
+    let hit = Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let mut parts = Vec::with_capacity(spans.len() * 2 + 1);
+    let mut at = 0;
+    for (start, end) in spans {
+        if start > at {
+            parts.push(Span::raw(text[at..start].to_string()));
+        }
+        parts.push(Span::styled(text[start..end].to_string(), hit));
+        at = end;
+    }
+    if at < text.len() {
+        parts.push(Span::raw(text[at..].to_string()));
+    }
+    Line::from(parts)
+}
+
+/// Bottom-border label for a multi-select picker, e.g. " 3 selected ".
+///
+/// Empty while nothing is checked so the border stays plain.
+pub fn selected_count_footer(count: usize) -> Line<'static> {
+    if count == 0 {
+        return Line::default();
+    }
+    Line::from(format!(" {count} selected ")).right_aligned()
+}
+
+/// Right-align `s` in a field `width` columns wide.
+pub fn pad_left_to_width(s: &str, width: usize) -> String {
+    let pad = width.saturating_sub(text_width(s));
+    format!("{}{}", " ".repeat(pad), s)
+}
+
+/// Frames of the status bar spinner shown while an action runs.
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
 
 /// Render the bottom status bar with current mode, counts, and active filters.
 ///
@@ -20,6 +98,8 @@ use std::collections::{BTreeMap, BTreeSet};
 /// - Number of visible users and groups
 /// - Items per page
 /// - Currently active filter chips
+/// - The command of a running action, behind a spinner
+/// - A reload hint once `/etc/passwd` or `/etc/group` changed on disk
 ///
 /// # Arguments
 ///
@@ -55,6 +135,24 @@ pub fn render_status_bar(f: &mut Frame, area: Rect, app: &AppState) {
     if app.users_filter_chips.expired {
         chips.push("expired");
     }
+    if app.users_filter_chips.no_primary_group {
+        chips.push("no_primary_group");
+    }
+    if app.groups_filter_chips.empty_only {
+        chips.push("empty_groups");
+    }
+    if app.groups_filter_chips.system_only {
+        chips.push("system_groups");
+    }
+    if app.groups_filter_chips.user_only {
+        chips.push("user_groups");
+    }
+    if app.groups_filter_chips.has_sudo_flag {
+        chips.push("sudo_group");
+    }
+    if app.groups_filter_chips.orphan_members {
+        chips.push("orphan_members");
+    }
     let chips_str = if chips.is_empty() {
         String::new()
     } else {
@@ -67,7 +165,46 @@ pub fn render_status_bar(f: &mut Frame, area: Rect, app: &AppState) {
         app.rows_per_page,
         chips_str
     );
-    let p = Paragraph::new(msg).style(
+    let running = app
+        .pending_result
+        .as_ref()
+        .map(|flight| (&flight.action, flight.started.elapsed().as_millis() / 100));
+    let working = running
+        .or(app.queued_action.as_ref().map(|action| (action, 0)))
+        .map(|(action, tick)| {
+            let command = crate::app::describe_command(action);
+            format!(
+                "{} Working: {}",
+                SPINNER[tick as usize % SPINNER.len()],
+                command.lines().next().unwrap_or_default()
+            )
+        });
+    let notice = working
+        .or_else(|| {
+            app.modal_notice
+                .clone()
+                .filter(|_| matches!(app.input_mode, crate::app::InputMode::Modal))
+        })
+        .or_else(|| {
+            app.accounts_stale.then(|| {
+                let key = app
+                    .keymap
+                    .key_for(crate::app::keymap::KeyAction::Reload)
+                    .unwrap_or_else(|| "Ctrl+r".to_string());
+                format!("Data changed on disk — press {key} to refresh")
+            })
+        });
+    let line = match notice {
+        Some(notice) => Line::from(vec![
+            Span::styled(
+                format!("{notice}  "),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(msg),
+        ]),
+        None => Line::from(msg),
+    };
+    let p = Paragraph::new(line).style(
         Style::default()
             .fg(app.theme.status_fg)
             .bg(app.theme.status_bg),
@@ -95,100 +232,36 @@ pub fn render_keybinds_panel(f: &mut Frame, area: Rect, app: &AppState) {
 
     // We'll collect styled lines instead of plain string body
 
-    // Build friendly maps of actions -> keys
-    let mut general: BTreeMap<&'static str, BTreeSet<String>> = BTreeMap::new();
-    let mut navigation: BTreeMap<&'static str, BTreeSet<String>> = BTreeMap::new();
-
-    for ((mods, code), action) in app.keymap.all_bindings().into_iter() {
-        // Normalize display for certain combos
-        let key = match code {
-            crossterm::event::KeyCode::BackTab => "Shift+Tab".to_string(),
-            crossterm::event::KeyCode::Tab
-                if mods.contains(crossterm::event::KeyModifiers::SHIFT) =>
-            {
-                "Shift+Tab".to_string()
-            }
-            _ => crate::app::keymap::Keymap::format_key(mods, code),
-        };
-
-        match action {
-            // General app commands
-            crate::app::keymap::KeyAction::Quit => {
-                general.entry("Quit").or_default().insert(key);
-            }
-            crate::app::keymap::KeyAction::SwitchTab => {
-                general.entry("Switch tab").or_default().insert(key);
-            }
-            crate::app::keymap::KeyAction::OpenFilterMenu => {
-                general.entry("Open filter menu").or_default().insert(key);
-            }
-            crate::app::keymap::KeyAction::StartSearch => {
-                general.entry("Search").or_default().insert(key);
-            }
-            crate::app::keymap::KeyAction::DeleteSelection => {
-                general.entry("Delete selection").or_default().insert(key);
-            }
-
-            // Navigation
-            crate::app::keymap::KeyAction::MoveUp => {
-                navigation.entry("Move up").or_default().insert(key);
-            }
-            crate::app::keymap::KeyAction::MoveDown => {
-                navigation.entry("Move down").or_default().insert(key);
-            }
-            crate::app::keymap::KeyAction::MoveLeftPage => {
-                navigation.entry("Move left").or_default().insert(key);
-            }
-            crate::app::keymap::KeyAction::MoveRightPage => {
-                navigation.entry("Move right").or_default().insert(key);
-            }
-            crate::app::keymap::KeyAction::PageUp => {
-                navigation.entry("Page up").or_default().insert(key);
-            }
-            crate::app::keymap::KeyAction::PageDown => {
-                navigation.entry("Page down").or_default().insert(key);
-            }
-
-            // Shown in contextual/tab sections below; skip in general list
-            crate::app::keymap::KeyAction::EnterAction
-            | crate::app::keymap::KeyAction::ToggleUsersFocus
-            | crate::app::keymap::KeyAction::ToggleGroupsFocus
-            | crate::app::keymap::KeyAction::ToggleKeybindsPane
-            | crate::app::keymap::KeyAction::OpenHelp
-            | crate::app::keymap::KeyAction::NewUser
-            | crate::app::keymap::KeyAction::Ignore => {}
-        }
-    }
+    let crate::app::keymap::BindingSections {
+        general,
+        navigation,
+    } = app.keymap.sections();
 
     // Compute column widths based on inner area and render rows with alignment
     let total_w = inner.width as usize;
     let sep = " │ ";
-    let sep_w = sep.chars().count();
+    let sep_w = text_width(sep);
 
     let mut max_label = 0usize;
     for k in general.keys() {
-        max_label = max_label.max(k.len());
+        max_label = max_label.max(text_width(k));
     }
     for k in navigation.keys() {
-        max_label = max_label.max(k.len());
+        max_label = max_label.max(text_width(k));
     }
-    max_label = max_label.max("Cancel / Close".len());
-    max_label = max_label.max("Toggle checkbox / multi-select".len());
-    max_label = max_label.max("Confirm / Apply".len());
-    max_label = max_label.max("Toggle pane".len());
-    max_label = max_label.max("New user".len());
-    max_label = max_label.max("New group".len());
+    max_label = max_label.max(text_width("Cancel / Close"));
+    max_label = max_label.max(text_width("Toggle checkbox / multi-select"));
+    max_label = max_label.max(text_width("Confirm / Apply"));
+    max_label = max_label.max(text_width("Toggle pane"));
+    max_label = max_label.max(text_width("New user"));
+    max_label = max_label.max(text_width("New group"));
 
     let col1_w = std::cmp::min(max_label, total_w.saturating_sub(sep_w + 8));
     let _col2_w = total_w.saturating_sub(sep_w + col1_w);
 
     let push_row = |label: &str, value: &str| -> (String, String) {
-        let mut lbl = label.to_string();
-        if lbl.chars().count() > col1_w {
-            // Truncate safely on char boundaries
-            lbl = lbl.chars().take(col1_w).collect();
-        }
-        let label_aligned = format!("{:>width$}", lbl, width = col1_w);
+        let lbl = truncate_to_width(label, col1_w);
+        let label_aligned = pad_left_to_width(&lbl, col1_w);
         let left = format!("  {}{}", label_aligned, sep);
         (left, value.to_string())
     };
@@ -234,7 +307,10 @@ pub fn render_keybinds_panel(f: &mut Frame, area: Rect, app: &AppState) {
     for (label, value) in [
         ("Cancel / Close", "Esc, Backspace"),
         ("Toggle checkbox / multi-select", "Space"),
+        ("Select all / clear in pickers", "Ctrl+A, Ctrl+D"),
+        ("Invert picker selection", "*"),
         ("Confirm / Apply", "Enter"),
+        ("Resize columns", "<, >, {, }"),
         ("Help", "?"),
     ] {
         let (left, right) = push_row(label, value);
@@ -252,11 +328,19 @@ pub fn render_keybinds_panel(f: &mut Frame, area: Rect, app: &AppState) {
                 "Users tab:",
                 Style::default().add_modifier(Modifier::BOLD),
             )));
+            let jump_key = app
+                .keymap
+                .key_for(crate::app::keymap::KeyAction::JumpToGroup)
+                .unwrap_or_default();
             for (label, value) in [
                 ("Toggle pane", "Shift+Tab"),
                 ("New user", "n"),
+                ("Go to Member of group", jump_key.as_str()),
                 ("Toggle keybindings", "Shift+K"),
             ] {
+                if READ_ONLY && label.starts_with("New ") {
+                    continue;
+                }
                 let (left, right) = push_row(label, value);
                 lines.push(Line::from(vec![
                     Span::raw(left),
@@ -269,11 +353,27 @@ pub fn render_keybinds_panel(f: &mut Frame, area: Rect, app: &AppState) {
                 "Groups tab:",
                 Style::default().add_modifier(Modifier::BOLD),
             )));
+            let sort_key = app
+                .keymap
+                .key_for(crate::app::keymap::KeyAction::ToggleMemberSort)
+                .unwrap_or_default();
+            let back_key = match &app.group_jump_origin {
+                Some(_) => app
+                    .keymap
+                    .key_for(crate::app::keymap::KeyAction::JumpToGroup)
+                    .unwrap_or_default(),
+                None => String::new(),
+            };
             for (label, value) in [
                 ("Toggle pane", "Shift+Tab"),
                 ("New group", "n"),
+                ("Sort members A-Z", sort_key.as_str()),
+                ("Back to user", back_key.as_str()),
                 ("Toggle keybindings", "Shift+K"),
             ] {
+                if (READ_ONLY && label.starts_with("New ")) || value.is_empty() {
+                    continue;
+                }
                 let (left, right) = push_row(label, value);
                 lines.push(Line::from(vec![
                     Span::raw(left),
@@ -288,6 +388,142 @@ pub fn render_keybinds_panel(f: &mut Frame, area: Rect, app: &AppState) {
     f.render_widget(p, inner);
 }
 
+/// Placeholder shown in an empty users or groups table.
+///
+/// Distinguishes a system without entries from a list that the active filters
+/// or search reduced to nothing, counting search matches the filters hide.
+/// Returns `None` when the table has rows.
+pub fn empty_table_message(app: &AppState, tab: crate::app::ActiveTab) -> Option<String> {
+    let (noun, shown, total) = match tab {
+        crate::app::ActiveTab::Users => ("users", app.users.len(), app.users_all.len()),
+        crate::app::ActiveTab::Groups => ("groups", app.groups.len(), app.groups_all.len()),
+    };
+    if shown > 0 {
+        return None;
+    }
+    if total == 0 {
+        return Some(format!("No {} found", noun));
+    }
+    let hint = app
+        .keymap
+        .key_for(crate::app::keymap::KeyAction::OpenFilterMenu)
+        .map(|k| format!(" (press {} to clear)", k))
+        .unwrap_or_default();
+    if !app.search_query.is_empty() {
+        let hidden = match tab {
+            crate::app::ActiveTab::Users => crate::search::hidden_user_matches(
+                &app.users_all,
+                &app.groups_all,
+                app.users_filter,
+                &app.users_filter_chips,
+                &app.search_query,
+            ),
+            crate::app::ActiveTab::Groups => crate::search::hidden_group_matches(
+                &app.groups_all,
+                &app.users_all,
+                app.groups_filter,
+                &app.groups_filter_chips,
+                &app.search_query,
+            ),
+        };
+        if hidden > 0 {
+            return Some(format!("0 matches — {} hidden by filters{}", hidden, hint));
+        }
+        return Some(format!("No {} match the search", noun));
+    }
+    Some(format!("No {} match the current filter{}", noun, hint))
+}
+
+/// Show the terminal cursor in a text input drawn inside the bordered `rect`.
+///
+/// `row` is the line within the box and `col` the display column where the
+/// cursor sits (prefix width plus the width of the input before the cursor).
+pub fn set_input_cursor(f: &mut Frame, rect: Rect, row: u16, col: usize) {
+    let inner_w = rect.width.saturating_sub(2);
+    if inner_w == 0 || row + 2 > rect.height {
+        return;
+    }
+    let x = rect.x + 1 + (col as u16).min(inner_w - 1);
+    f.set_cursor_position((x, rect.y + 1 + row));
+}
+
+/// Display width of the first `cursor` characters of `buf`, for placing the
+/// terminal cursor in a text input.
+pub fn width_before_cursor(buf: &str, cursor: usize) -> usize {
+    buf.chars()
+        .take(cursor)
+        .map(|c| c.width().unwrap_or(0))
+        .sum()
+}
+
+/// Draw `message` centered in the body of a bordered table with a header row.
+pub fn render_empty_table_message(f: &mut Frame, table_area: Rect, message: &str) {
+    let body = Rect {
+        x: table_area.x.saturating_add(1),
+        y: table_area.y.saturating_add(2),
+        width: table_area.width.saturating_sub(2),
+        height: table_area.height.saturating_sub(3),
+    };
+    if body.width == 0 || body.height == 0 {
+        return;
+    }
+    // Narrow panes wrap the hint instead of cutting it off mid-word.
+    let lines = (text_width(message) as u16)
+        .div_ceil(body.width)
+        .clamp(1, body.height);
+    let top = (body.height - lines) / 2;
+    let line = Rect {
+        y: body.y + top,
+        height: body.height - top,
+        ..body
+    };
+    let p = Paragraph::new(message.to_string())
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .style(
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::ITALIC),
+        );
+    f.render_widget(p, line);
+}
+
+/// "Runs:" block listing the commands `action` will execute, one per line,
+/// for the bottom of a confirm dialog. Returns the text and its line count.
+pub fn command_preview(action: &crate::app::PendingAction) -> (String, u16) {
+    let mut text = String::new();
+    let mut count = 0;
+    for (idx, line) in crate::app::describe_command(action).lines().enumerate() {
+        let label = if idx == 0 { "Runs: " } else { "      " };
+        text.push_str(&format!("\n  {label}{line}"));
+        count += 1;
+    }
+    (text, count)
+}
+
+/// Strength meter shown under a password's confirm field: a colored bar and
+/// the [`password_strength`](crate::app::password_strength) label. Blank
+/// while nothing is typed.
+pub fn password_strength_line(password: &str) -> Line<'static> {
+    if password.is_empty() {
+        return Line::default();
+    }
+    let (score, label) = crate::app::password_strength(password);
+    let color = match label {
+        "weak" => Color::Red,
+        "ok" => Color::Yellow,
+        _ => Color::Green,
+    };
+    let filled = usize::from(score.max(1));
+    let empty = usize::from(crate::app::PASSWORD_STRENGTH_MAX) - filled;
+    Line::from(vec![
+        Span::raw("  Strength: "),
+        Span::styled("█".repeat(filled), Style::default().fg(color)),
+        Span::styled("░".repeat(empty), Style::default().fg(Color::DarkGray)),
+        Span::styled(format!(" {label}"), Style::default().fg(color)),
+    ])
+}
+
 /// Compute a rectangle centered within `area` with a maximum size.
 pub fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     let x = area.x + area.width.saturating_sub(width) / 2;
@@ -323,12 +559,40 @@ pub fn render_info_modal(f: &mut Frame, area: Rect, app: &AppState, state: &Moda
     }
 }
 
-/// Render the help modal with important usage information and key tips.
-pub fn render_help_modal(f: &mut Frame, area: Rect, app: &AppState, scroll: u16) {
-    let width = 80u16.min(area.width.saturating_sub(4)).max(60);
-    let height = 22u16.min(area.height.saturating_sub(4)).max(14);
+/// Render `lines` in a centered modal titled `title`, sized to fit them
+/// where the screen allows and wrapping what does not fit.
+pub fn render_details_modal(
+    f: &mut Frame,
+    area: Rect,
+    app: &AppState,
+    title: &str,
+    lines: Vec<Line<'static>>,
+) {
+    let longest = lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16;
+    let width = longest
+        .saturating_add(4)
+        .max(text_width(title) as u16 + 4)
+        .min(area.width.saturating_sub(4));
+    let height = (lines.len() as u16)
+        .saturating_add(2)
+        .min(area.height.saturating_sub(2));
     let rect = centered_rect(width, height, area);
+    let p = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .style(Style::default().fg(app.theme.text))
+        .block(
+            Block::default()
+                .title(title.to_string())
+                .title_bottom(Line::from(" Esc to close ").right_aligned())
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border)),
+        );
+    f.render_widget(Clear, rect);
+    f.render_widget(p, rect);
+}
 
+/// Lines of the help modal: usage information and key tips.
+pub fn help_lines() -> Vec<Line<'static>> {
     let mut lines: Vec<Line> = vec![
         Line::from(Span::styled(
             "Help",
@@ -343,6 +607,22 @@ pub fn render_help_modal(f: &mut Frame, area: Rect, app: &AppState, scroll: u16)
             Style::default().add_modifier(Modifier::ITALIC),
         ),
     ]));
+    lines.push(Line::from(vec![
+        Span::raw("First / last entry of a list or picker: "),
+        Span::styled(
+            "Home / End",
+            Style::default().add_modifier(Modifier::ITALIC),
+        ),
+        Span::raw(" ("),
+        Span::styled("G", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw(" also goes to the last, but there is no "),
+        Span::styled("gg", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw(" since "),
+        Span::styled("g", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw(" jumps to a group; "),
+        Span::styled("g G", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw(" in pickers)"),
+    ]));
     lines.push(Line::from(vec![
         Span::raw("Search: "),
         Span::styled("/", Style::default().add_modifier(Modifier::ITALIC)),
@@ -365,10 +645,61 @@ pub fn render_help_modal(f: &mut Frame, area: Rect, app: &AppState, scroll: u16)
         Span::styled("Shift+K", Style::default().add_modifier(Modifier::ITALIC)),
         Span::raw(" (toggle)"),
     ]));
+    lines.push(Line::from(vec![
+        Span::raw("Show UIDs/GIDs in decimal, hex or octal: "),
+        Span::styled("x", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw(" (cycle)"),
+    ]));
+    lines.push(Line::from(vec![
+        Span::raw("Show home, password, SSH key and process stats in details: "),
+        Span::styled("L", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw(" (toggle)"),
+    ]));
+    lines.push(Line::from(vec![
+        Span::raw("Resize columns: "),
+        Span::styled("< >", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw(" table/details, "),
+        Span::styled("{ }", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw(" details/keybindings"),
+    ]));
+    lines.push(Line::from(vec![
+        Span::raw("Reload users, groups and shells from disk: "),
+        Span::styled("Ctrl+r", Style::default().add_modifier(Modifier::ITALIC)),
+    ]));
+    lines.push(Line::from(vec![
+        Span::raw("Copy the shell command for an action on the selection: "),
+        Span::styled("y", Style::default().add_modifier(Modifier::ITALIC)),
+    ]));
     lines.push(Line::from(vec![
         Span::raw("Open this help: "),
         Span::styled("?", Style::default().add_modifier(Modifier::ITALIC)),
     ]));
+    lines.push(Line::from(vec![
+        Span::raw("Details of the selection (narrow terminals hide the column): "),
+        Span::styled("i", Style::default().add_modifier(Modifier::ITALIC)),
+    ]));
+    lines.push(Line::from(vec![
+        Span::raw("Compact or full user details: "),
+        Span::styled("v", Style::default().add_modifier(Modifier::ITALIC)),
+    ]));
+    lines.push(Line::from(vec![
+        Span::raw("Actions performed this session: "),
+        Span::styled("H", Style::default().add_modifier(Modifier::ITALIC)),
+    ]));
+    if !READ_ONLY {
+        lines.push(Line::from(vec![
+            Span::raw("Create users from a CSV file: "),
+            Span::styled("I", Style::default().add_modifier(Modifier::ITALIC)),
+        ]));
+    }
+    lines.push(Line::from(vec![
+        Span::raw("Save theme, keybindings and filters to their config files: "),
+        Span::styled("Ctrl+s", Style::default().add_modifier(Modifier::ITALIC)),
+    ]));
+    lines.push(Line::from(vec![
+        Span::raw("Save this key list to keybinds_reference.txt: "),
+        Span::styled("E", Style::default().add_modifier(Modifier::ITALIC)),
+    ]));
     lines.push(Line::from(vec![
         Span::raw("Quit: "),
         Span::styled("q", Style::default().add_modifier(Modifier::ITALIC)),
@@ -378,36 +709,76 @@ pub fn render_help_modal(f: &mut Frame, area: Rect, app: &AppState, scroll: u16)
         "Users tab",
         Style::default().add_modifier(Modifier::BOLD),
     )));
+    if !READ_ONLY {
+        lines.push(Line::from(vec![
+            Span::raw("Open actions / modify: "),
+            Span::styled("Enter", Style::default().add_modifier(Modifier::ITALIC)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::raw("Create user: "),
+            Span::styled("n", Style::default().add_modifier(Modifier::ITALIC)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::raw("Delete user / remove from group: "),
+            Span::styled("Delete", Style::default().add_modifier(Modifier::ITALIC)),
+        ]));
+    }
     lines.push(Line::from(vec![
-        Span::raw("Open actions / modify: "),
-        Span::styled("Enter", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw("Show the selected Member of group on the Groups tab: "),
+        Span::styled("g", Style::default().add_modifier(Modifier::ITALIC)),
     ]));
     lines.push(Line::from(vec![
-        Span::raw("Create user: "),
-        Span::styled("n", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw("In the details popup, copy the home path / open it in $FILE_MANAGER: "),
+        Span::styled("c / o", Style::default().add_modifier(Modifier::ITALIC)),
     ]));
     lines.push(Line::from(vec![
-        Span::raw("Delete user / remove from group: "),
-        Span::styled("Delete", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw("Switch Member of between /etc/group and id -Gn (LDAP, SSSD): "),
+        Span::styled("M", Style::default().add_modifier(Modifier::ITALIC)),
     ]));
     lines.push(Line::raw(""));
     lines.push(Line::from(Span::styled(
         "Groups tab",
         Style::default().add_modifier(Modifier::BOLD),
     )));
+    if !READ_ONLY {
+        lines.push(Line::from(vec![
+            Span::raw("Open actions: "),
+            Span::styled("Enter", Style::default().add_modifier(Modifier::ITALIC)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::raw("Create group: "),
+            Span::styled("n", Style::default().add_modifier(Modifier::ITALIC)),
+            Span::raw(" (Down after the name picks its first members)"),
+        ]));
+        lines.push(Line::from(vec![
+            Span::raw("Delete group / remove member: "),
+            Span::styled("Delete", Style::default().add_modifier(Modifier::ITALIC)),
+        ]));
+    }
+    lines.push(Line::from(vec![
+        Span::raw("Back to the user you came from: "),
+        Span::styled("g", Style::default().add_modifier(Modifier::ITALIC)),
+    ]));
     lines.push(Line::from(vec![
-        Span::raw("Open actions: "),
-        Span::styled("Enter", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw("Sort members alphabetically: "),
+        Span::styled("s", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw(" (toggle)"),
     ]));
+    lines.push(Line::raw(""));
     lines.push(Line::from(vec![
-        Span::raw("Create group: "),
+        Span::raw("Search help: "),
+        Span::styled("/", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw(" then type; "),
         Span::styled("n", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw(" for the next match"),
     ]));
     lines.push(Line::from(vec![
-        Span::raw("Delete group: "),
-        Span::styled("Delete", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw("Scroll help: "),
+        Span::styled(
+            "Up / Down / PgUp / PgDn / Home / End",
+            Style::default().add_modifier(Modifier::ITALIC),
+        ),
     ]));
-    lines.push(Line::raw(""));
     lines.push(Line::from(vec![
         Span::raw("Close help: "),
         Span::styled(
@@ -415,13 +786,63 @@ pub fn render_help_modal(f: &mut Frame, area: Rect, app: &AppState, scroll: u16)
             Style::default().add_modifier(Modifier::ITALIC),
         ),
     ]));
+    lines
+}
+
+/// Furthest the help can scroll: its last line at the top of the modal.
+pub fn help_max_scroll() -> u16 {
+    help_lines().len().saturating_sub(1) as u16
+}
+
+/// Indices of the help lines containing `query`, ignoring case.
+///
+/// An empty query matches nothing.
+pub fn help_matches(query: &str) -> Vec<u16> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let query = query.to_lowercase();
+    help_lines()
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.to_string().to_lowercase().contains(&query))
+        .map(|(i, _)| i as u16)
+        .collect()
+}
+
+/// Render the help modal, highlighting the lines that match `query`.
+pub fn render_help_modal(
+    f: &mut Frame,
+    area: Rect,
+    app: &AppState,
+    scroll: u16,
+    query: &str,
+    searching: bool,
+) {
+    let width = 80u16.min(area.width.saturating_sub(4)).max(60);
+    let height = 22u16.min(area.height.saturating_sub(4)).max(14);
+    let rect = centered_rect(width, height, area);
+
+    let mut lines = help_lines();
+    let matches = help_matches(query);
+    for &i in &matches {
+        let line = std::mem::take(&mut lines[i as usize]);
+        lines[i as usize] = line.patch_style(Style::default().fg(Color::Yellow));
+    }
+    let title = if searching {
+        format!("Help - search: {query}")
+    } else if !query.is_empty() {
+        format!("Help - '{query}': {} matches (n: next)", matches.len())
+    } else {
+        "Help (/ to search)".to_string()
+    };
 
     let p = Paragraph::new(lines)
         .wrap(Wrap { trim: false })
         .scroll((scroll, 0))
         .block(
             Block::default()
-                .title("Help")
+                .title(title)
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(app.theme.border)),
         );
@@ -432,7 +853,10 @@ pub fn render_help_modal(f: &mut Frame, area: Rect, app: &AppState, scroll: u16)
 /// Render the sudo password prompt modal.
 pub fn render_sudo_modal(f: &mut Frame, area: Rect, app: &AppState, state: &ModalState) {
     if let ModalState::SudoPrompt {
-        password, error, ..
+        password,
+        error,
+        attempts,
+        ..
     } = state
     {
         let width = 50u16.min(area.width.saturating_sub(4)).max(40);
@@ -454,15 +878,175 @@ pub fn render_sudo_modal(f: &mut Frame, area: Rect, app: &AppState, state: &Moda
         }
         let p = Paragraph::new(body).wrap(Wrap { trim: false }).block(
             Block::default()
-                .title("Authentication required")
+                .title(if *attempts > 0 {
+                    format!(
+                        "Authentication required (attempt {} of {})",
+                        attempts + 1,
+                        crate::app::MAX_SUDO_ATTEMPTS
+                    )
+                } else {
+                    "Authentication required".to_string()
+                })
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border)),
+        );
+        f.render_widget(Clear, rect);
+        f.render_widget(p, rect);
+    }
+}
+
+/// Short age of an event `elapsed` ago, such as "42s" or "3m".
+fn format_age(elapsed: std::time::Duration) -> String {
+    let secs = elapsed.as_secs();
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m", secs / 60),
+        _ => format!("{}h", secs / 3600),
+    }
+}
+
+/// Render the performed actions, newest first, with their outcome and age.
+pub fn render_action_history_modal(f: &mut Frame, area: Rect, app: &AppState, scroll: u16) {
+    let width = 90u16.min(area.width.saturating_sub(4));
+    let height = 20u16.min(area.height.saturating_sub(4));
+    let rect = centered_rect(width, height, area);
+    let mut lines: Vec<Line> = Vec::new();
+    if app.action_history.is_empty() {
+        lines.push(Line::raw("No actions performed yet."));
+    }
+    for record in app.action_history.iter().rev() {
+        let (status, color) = match &record.outcome {
+            Ok(()) => ("ok    ".to_string(), app.theme.text),
+            Err(_) => ("failed".to_string(), Color::Red),
+        };
+        lines.push(Line::from(vec![
+            Span::raw(format!("{:>4} ago  ", format_age(record.at.elapsed()))),
+            Span::styled(status, Style::default().fg(color)),
+            Span::raw(format!("  {}", record.description)),
+        ]));
+        if let Err(e) = &record.outcome {
+            lines.push(Line::raw(format!("                  {e}")));
+        }
+    }
+    let p = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0))
+        .block(
+            Block::default()
+                .title(format!("Action history ({})", app.action_history.len()))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border)),
+        );
+    f.render_widget(Clear, rect);
+    f.render_widget(p, rect);
+}
+
+/// Render the Yes/No dialog `confirmations.conf` adds in front of an action.
+pub fn render_confirm_action_modal(f: &mut Frame, area: Rect, app: &AppState, state: &ModalState) {
+    if let ModalState::ConfirmAction {
+        selected,
+        next,
+        warning,
+    } = state
+    {
+        let yes = if *selected == 0 { "[Yes]" } else { " Yes " };
+        let no = if *selected == 1 { "[No]" } else { " No  " };
+        let (runs, lines) = command_preview(next);
+        let (caveat, extra) = match warning {
+            // Room for the wrapped warning plus a blank line
+            Some(w) => {
+                let caveat = format!("Warning: {w}");
+                let rows = (text_width(&caveat) as u16).div_ceil(50).max(1);
+                (format!("{caveat}\n\n"), rows + 1)
+            }
+            None => (String::new(), 0),
+        };
+        let body = format!("{caveat}Run this action?\n\n  {yes}    {no}\n{runs}");
+        let rect = centered_rect(60, 6 + lines + extra, area);
+        let p = Paragraph::new(body).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .title("Confirm")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border)),
+        );
+        f.render_widget(Clear, rect);
+        f.render_widget(p, rect);
+    }
+}
+
+/// Render the per-account list of a membership change before it runs.
+pub fn render_membership_preview_modal(
+    f: &mut Frame,
+    area: Rect,
+    app: &AppState,
+    state: &ModalState,
+) {
+    if let ModalState::MembershipPreview { next, scroll } = state {
+        let lines = crate::app::membership_preview(next);
+        let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(4).max(5));
+        let rect = centered_rect(50, height, area);
+        let p = Paragraph::new(lines.join("\n")).scroll((*scroll, 0)).block(
+            Block::default()
+                .title(format!("Apply {} changes?", lines.len()))
+                .title_bottom(Line::from(" Enter apply · Esc back ").right_aligned())
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border)),
+        );
+        f.render_widget(Clear, rect);
+        f.render_widget(p, rect);
+    }
+}
+
+/// Render the prompt for typing the affected name before an action runs.
+pub fn render_typed_confirm_modal(f: &mut Frame, area: Rect, app: &AppState, state: &ModalState) {
+    if let ModalState::TypedConfirm {
+        next,
+        keyword,
+        input,
+        cursor,
+    } = state
+    {
+        let (runs, lines) = command_preview(next);
+        let body = format!("Type '{keyword}' to confirm:\n{input}\n{runs}");
+        let rect = centered_rect(60, 5 + lines, area);
+        let p = Paragraph::new(body).block(
+            Block::default()
+                .title("Confirm by typing")
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(app.theme.border)),
         );
         f.render_widget(Clear, rect);
         f.render_widget(p, rect);
+        set_input_cursor(f, rect, 1, width_before_cursor(input, *cursor));
     }
 }
 
+/// Render the copy-command menu: the actions for the current selection and
+/// the command the focused one copies.
+pub fn render_copy_command_modal(f: &mut Frame, area: Rect, app: &AppState, selected: usize) {
+    let choices = crate::app::copy_command_choices(app);
+    let mut body = String::new();
+    for (idx, (label, _)) in choices.iter().enumerate() {
+        let marker = if idx == selected { "▶" } else { " " };
+        body.push_str(&format!("{} {}\n", marker, label));
+    }
+    let command = choices
+        .get(selected)
+        .map(|(_, action)| crate::app::ready_to_run_command(action))
+        .unwrap_or_default();
+    body.push_str(&format!("\n{}\n", command));
+    let height = choices.len() as u16 + command.lines().count() as u16 + 3;
+    let rect = centered_rect(64, height, area);
+    let p = Paragraph::new(body).block(
+        Block::default()
+            .title("Copy shell command")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(app.theme.border)),
+    );
+    f.render_widget(Clear, rect);
+    f.render_widget(p, rect);
+}
+
 /// Render filter selection modal depending on active tab.
 pub fn render_filter_modal(f: &mut Frame, area: Rect, app: &AppState, state: &ModalState) {
     if let ModalState::FilterMenu { selected } = state {
@@ -471,7 +1055,7 @@ pub fn render_filter_modal(f: &mut Frame, area: Rect, app: &AppState, state: &Mo
                 let width = 64u16.min(area.width.saturating_sub(4)).max(44);
                 let height = 14u16.min(area.height.saturating_sub(4)).max(10);
                 let rect = centered_rect(width, height, area);
-                let opts: [&str; 8] = [
+                let opts: [&str; 9] = [
                     "Show all",
                     "Human users only (uid >= 1000)",
                     "System users only (uid < 1000)",
@@ -480,6 +1064,7 @@ pub fn render_filter_modal(f: &mut Frame, area: Rect, app: &AppState, state: &Mo
                     "Locked account",
                     "No password set",
                     "Password expired",
+                    "Primary group missing",
                 ];
                 let mut text = String::new();
                 for (idx, label) in opts.iter().enumerate() {
@@ -494,6 +1079,7 @@ pub fn render_filter_modal(f: &mut Frame, area: Rect, app: &AppState, state: &Mo
                             5 => app.users_filter_chips.locked,
                             6 => app.users_filter_chips.no_password,
                             7 => app.users_filter_chips.expired,
+                            8 => app.users_filter_chips.no_primary_group,
                             _ => false,
                         };
                         if checked { "[x] " } else { "[ ] " }
@@ -513,20 +1099,36 @@ pub fn render_filter_modal(f: &mut Frame, area: Rect, app: &AppState, state: &Mo
             }
             crate::app::ActiveTab::Groups => {
                 let width = 56u16.min(area.width.saturating_sub(4)).max(40);
-                let height = 9u16;
+                let height = 13u16.min(area.height.saturating_sub(4)).max(10);
                 let rect = centered_rect(width, height, area);
-                let options: [&str; 3] = [
+                let options: [&str; 8] = [
                     "Show all",
                     "Only show User GIDs (>=1000)",
                     "Only show System GIDs (<1000)",
+                    "Empty groups",
+                    "System groups only (gid < 1000)",
+                    "User groups only (gid >= 1000)",
+                    "Sudo group",
+                    "Members missing from passwd",
                 ];
                 let mut text = String::new();
                 for (idx, label) in options.iter().enumerate() {
-                    if idx == *selected {
-                        text.push_str(&format!("▶ {}\n", label));
+                    let marker = if idx == *selected { "▶" } else { " " };
+                    // Entries after the top-level filters are chips with a checkbox
+                    let checkbox = if idx >= 3 {
+                        let checked = match idx {
+                            3 => app.groups_filter_chips.empty_only,
+                            4 => app.groups_filter_chips.system_only,
+                            5 => app.groups_filter_chips.user_only,
+                            6 => app.groups_filter_chips.has_sudo_flag,
+                            7 => app.groups_filter_chips.orphan_members,
+                            _ => false,
+                        };
+                        if checked { "[x] " } else { "[ ] " }
                     } else {
-                        text.push_str(&format!("  {}\n", label));
-                    }
+                        ""
+                    };
+                    text.push_str(&format!("{} {}{}\n", marker, checkbox, label));
                 }
                 let p = Paragraph::new(text).block(
                     Block::default()
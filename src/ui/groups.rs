@@ -6,9 +6,10 @@
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Rect};
 use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
 use ratatui::widgets::{Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Table};
 
-use crate::app::{AppState, GroupsFocus, ModalState};
+use crate::app::{ActiveTab, AppState, GroupsFocus, ModalState, PendingAction, format_id};
 
 /// Render the groups table and manage selection/pagination state.
 ///
@@ -31,6 +32,7 @@ pub fn render_groups_table(f: &mut Frame, area: Rect, app: &mut AppState) {
     let start = (app.selected_group_index / app.rows_per_page) * app.rows_per_page;
     let end = (start + app.rows_per_page).min(app.groups.len());
     let slice = &app.groups[start..end];
+    let query = crate::ui::components::active_search_query(app, ActiveTab::Groups);
 
     let rows = slice.iter().enumerate().map(|(i, g)| {
         let absolute_index = start + i;
@@ -41,12 +43,26 @@ pub fn render_groups_table(f: &mut Frame, area: Rect, app: &mut AppState) {
         } else {
             Style::default()
         };
+        let style = if app.is_recently_changed(ActiveTab::Groups, &g.name) {
+            style.bg(Color::Green).fg(Color::Black)
+        } else {
+            style
+        };
+        let marker = if g.is_nobody() {
+            crate::app::NOBODY_MARKER
+        } else {
+            ""
+        };
         let name_text = if absolute_index == app.selected_group_index {
-            format!("[{}]", g.name)
+            format!("[{}]{}", g.name, marker)
         } else {
-            g.name.clone()
+            format!("{}{}", g.name, marker)
         };
-        Row::new(vec![Cell::from(g.gid.to_string()), Cell::from(name_text)]).style(style)
+        Row::new(vec![
+            Cell::from(format_id(g.gid, app.id_radix)),
+            Cell::from(crate::ui::components::highlight_matches(name_text, query)),
+        ])
+        .style(style)
     });
 
     let widths = [Constraint::Length(8), Constraint::Percentage(100)];
@@ -79,189 +95,61 @@ pub fn render_groups_table(f: &mut Frame, area: Rect, app: &mut AppState) {
         .column_spacing(1);
 
     f.render_widget(table, area);
+    if let Some(msg) = crate::ui::components::empty_table_message(app, ActiveTab::Groups) {
+        crate::ui::components::render_empty_table_message(f, area, &msg);
+    }
 }
 
-/// Render the selected group's summary details.
+/// Text of the details panel for the selected group.
 ///
-/// This panel displays comprehensive information about the currently selected group, including:
+/// Covers comprehensive information about the currently selected group, including:
 /// - Classification (GID, system vs. user group)
 /// - Membership counts (primary members, secondary members)
 /// - Member preview (list of top members)
 /// - Orphan detection (secondary members not in user database)
 /// - Distributions (shell interactivity, UID class, account status)
-/// - Privilege (whether this is the sudo-capable group)
+/// - Privilege (what membership grants, for groups in `privileges.conf` or the sudo group)
 /// - Change indicator (last modification time of `/etc/group`)
 ///
-/// # Arguments
-///
-/// * `f` - The frame to render into.
-/// * `area` - The rectangle area where the details panel will be drawn.
-/// * `app` - The application state containing group and user data.
-pub fn render_group_details(f: &mut Frame, area: Rect, app: &AppState) {
-    let group = app.groups.get(app.selected_group_index);
-    let (
-        name,
-        gid,
-        members,
-        is_system,
-        primary_count,
-        _secondary_count,
-        sudo_flag,
-        members_preview,
-        shell_interactive,
-        shell_noninteractive,
-        uid_system_count,
-        uid_user_count,
-        locked_count,
-        nopass_count,
-        expired_count,
-        orphan_count,
-        group_mtime_days,
-    ) = match group {
+/// Takes `app` mutably to refresh the cached member statistics.
+pub fn group_details_text(app: &mut AppState) -> String {
+    // /etc/group mtime: cache key for the member statistics, and shown in
+    // days since epoch as a proxy for the last membership change
+    let group_mtime = std::fs::metadata("/etc/group")
+        .and_then(|m| m.modified())
+        .ok();
+    let group_mtime_days = group_mtime
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or(0);
+    let stats = crate::app::selected_group_details(app, group_mtime).unwrap_or_default();
+    let (name, gid, members, class, privilege) = match app.groups.get(app.selected_group_index) {
         Some(g) => {
-            let is_system = g.gid < 1000;
-
-            // Build lookup for users by name and collect primary members
-            let mut user_by_name: std::collections::HashMap<&str, &crate::sys::SystemUser> =
-                std::collections::HashMap::new();
-            for u in &app.users_all {
-                user_by_name.insert(&u.name, u);
-            }
-            let primary_usernames: Vec<&str> = app
-                .users_all
-                .iter()
-                .filter(|u| u.primary_gid == g.gid)
-                .map(|u| u.name.as_str())
-                .collect();
-            let primaries = primary_usernames.len();
-
-            // Secondary members are from the group membership list
-            let secondary_count = g.members.len();
-
-            // Configurable sudo flag
-            let sudo_group = crate::app::sudo_group_name();
-            let sudo_flag = if g.name == sudo_group { "sudo" } else { "-" };
-
-            // Combined member set (primary + secondary) for distributions
-            let mut member_set: std::collections::BTreeSet<String> =
-                std::collections::BTreeSet::new();
-            for n in &g.members {
-                member_set.insert(n.clone());
-            }
-            for n in primary_usernames {
-                member_set.insert(n.to_string());
-            }
-
-            // Shell distribution and UID class counts
-            let mut shell_interactive = 0usize;
-            let mut shell_noninteractive = 0usize;
-            let mut uid_system_count = 0usize;
-            let mut uid_user_count = 0usize;
-            let mut orphan_count = 0usize;
-            for name in member_set.iter() {
-                if let Some(u) = user_by_name.get(name.as_str()) {
-                    let sh = u.shell.as_str();
-                    let non = sh.ends_with("/nologin") || sh.ends_with("/false");
-                    if non {
-                        shell_noninteractive += 1;
-                    } else {
-                        shell_interactive += 1;
-                    }
-                    if u.uid < 1000 {
-                        uid_system_count += 1;
-                    } else {
-                        uid_user_count += 1;
-                    }
-                } else {
-                    orphan_count += 1;
-                }
-            }
-
-            // Shadow status counts (best-effort)
-            let mut locked_count = 0usize;
-            let mut nopass_count = 0usize;
-            let mut expired_count = 0usize;
-            for name in member_set.iter() {
-                if let Some(sh) = crate::search::user_shadow_status(name) {
-                    if sh.locked {
-                        locked_count += 1;
-                    }
-                    if sh.no_password {
-                        nopass_count += 1;
-                    }
-                    if sh.expired {
-                        expired_count += 1;
-                    }
-                }
-            }
-
-            // Alphabetical top-N preview of member names (secondary list only)
-            let mut names = g.members.clone();
-            names.sort_by_key(|a| a.to_lowercase());
-            let n: usize = 10;
-            let total = names.len();
-            let shown: Vec<String> = names.into_iter().take(n).collect();
-            let mut preview = if shown.is_empty() {
-                "-".to_string()
-            } else {
-                shown.join(", ")
+            // The configured sudo group counts even when privileges.conf omits it
+            let privilege = match app.privileged_groups.label(&g.name) {
+                Some(what) => what.to_string(),
+                None if g.name == crate::app::sudo_group_name() => "sudo".to_string(),
+                None => "-".to_string(),
             };
-            if total > n {
-                let more = total - n;
-                preview.push_str(&format!(" (+{} more)", more));
-            }
-
-            // /etc/group mtime in days since epoch (proxy for membership change)
-            let group_mtime_days = std::fs::metadata("/etc/group")
-                .and_then(|m| m.modified())
-                .ok()
-                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                .map(|d| d.as_secs() / 86_400)
-                .unwrap_or(0);
-
             (
                 g.name.clone(),
                 g.gid,
                 g.members.len(),
-                is_system,
-                primaries,
-                secondary_count,
-                sudo_flag.to_string(),
-                preview,
-                shell_interactive,
-                shell_noninteractive,
-                uid_system_count,
-                uid_user_count,
-                locked_count,
-                nopass_count,
-                expired_count,
-                orphan_count,
-                group_mtime_days,
+                if g.is_nobody() {
+                    "nobody"
+                } else if g.gid < 1000 {
+                    "system"
+                } else {
+                    "user"
+                },
+                privilege,
             )
         }
-        None => (
-            String::new(),
-            0,
-            0,
-            false,
-            0,
-            0,
-            String::new(),
-            String::new(),
-            0,
-            0,
-            0,
-            0,
-            0,
-            0,
-            0,
-            0,
-            0,
-        ),
+        None => (String::new(), 0, 0, "user", String::new()),
     };
-    let text = format!(
-        "Group: {name}\nGID: {gid} ({})\nMembers (secondary): {members}\nPrimary members: {primary_count}\nPrivilege: {sudo_flag}\nMembers preview: {members_preview}\nShells: interactive={}, noninteractive={}\nUID class: system={}, user={}\nAccounts: locked={}, no_password={}, expired={}\nOrphan secondary members: {}\n/etc/group mtime (days since epoch): {}",
-        if is_system { "system" } else { "user" },
+    let crate::app::GroupDetailsStats {
+        primary_count,
+        members_preview,
         shell_interactive,
         shell_noninteractive,
         uid_system_count,
@@ -270,9 +158,37 @@ pub fn render_group_details(f: &mut Frame, area: Rect, app: &AppState) {
         nopass_count,
         expired_count,
         orphan_count,
+    } = stats;
+    let gid = format_id(gid, app.id_radix);
+    let accounts = if app.live_stats {
+        format!("locked={locked_count}, no_password={nopass_count}, expired={expired_count}")
+    } else {
+        let key = app
+            .keymap
+            .key_for(crate::app::keymap::KeyAction::ToggleLiveStats)
+            .unwrap_or_default();
+        format!("press {key} for live stats")
+    };
+    format!(
+        "Group: {name}\nGID: {gid} ({class})\nMembers (secondary): {members}\nPrimary members: {primary_count}\nPrivilege: {privilege}\nMembers preview: {members_preview}\nShells: interactive={}, noninteractive={}\nUID class: system={}, user={}\nAccounts: {accounts}\nOrphan secondary members: {}\n/etc/group mtime (days since epoch): {}",
+        shell_interactive,
+        shell_noninteractive,
+        uid_system_count,
+        uid_user_count,
+        orphan_count,
         group_mtime_days,
-    );
-    let p = Paragraph::new(text)
+    )
+}
+
+/// Render the selected group's summary details.
+///
+/// # Arguments
+///
+/// * `f` - The frame to render into.
+/// * `area` - The rectangle area where the details panel will be drawn.
+/// * `app` - The application state containing group and user data.
+pub fn render_group_details(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let p = Paragraph::new(group_details_text(app))
         .style(Style::default().fg(app.theme.text))
         .block(
             Block::default()
@@ -283,6 +199,34 @@ pub fn render_group_details(f: &mut Frame, area: Rect, app: &AppState) {
     f.render_widget(p, area);
 }
 
+/// Render the selected group's details in a centered modal, for layouts
+/// without a details column.
+pub fn render_group_details_modal(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let name = app
+        .groups
+        .get(app.selected_group_index)
+        .map(|g| g.name.clone())
+        .unwrap_or_default();
+    let mut lines: Vec<Line<'static>> = group_details_text(app)
+        .lines()
+        .map(|l| Line::from(l.to_string()))
+        .collect();
+    let has_orphans = app
+        .groups
+        .get(app.selected_group_index)
+        .is_some_and(|g| !crate::app::orphan_members(g, &app.users_all).is_empty());
+    if has_orphans {
+        lines.push(Line::from("Press o to review orphan members"));
+    }
+    crate::ui::components::render_details_modal(
+        f,
+        area,
+        app,
+        &format!("Group details - {name}"),
+        lines,
+    );
+}
+
 /// Render the selected group's members list.
 ///
 /// This panel displays the members of the currently selected group. It supports
@@ -294,11 +238,7 @@ pub fn render_group_details(f: &mut Frame, area: Rect, app: &AppState) {
 /// * `area` - The rectangle area where the members list will be drawn.
 /// * `app` - The application state containing group and user data.
 pub fn render_group_members(f: &mut Frame, area: Rect, app: &mut AppState) {
-    let members = app
-        .groups
-        .get(app.selected_group_index)
-        .map(|g| g.members.clone())
-        .unwrap_or_default();
+    let members = crate::app::selected_group_member_rows(app);
 
     let body_height = area.height.saturating_sub(3) as usize;
     if body_height > 0 {
@@ -308,16 +248,17 @@ pub fn render_group_members(f: &mut Frame, area: Rect, app: &mut AppState) {
     let end = members.len().min(app.rows_per_page);
     let slice = &members[start..end];
 
-    let rows = slice.iter().enumerate().map(|(i, m)| {
+    let rows = slice.iter().enumerate().map(|(i, (m, primary))| {
         let absolute_index = start + i;
         let mut style = Style::default();
         if absolute_index == app.selected_group_member_index {
             style = style.fg(Color::Yellow).add_modifier(Modifier::BOLD);
         }
+        let marker = if *primary { " [primary]" } else { "" };
         let text = if absolute_index == app.selected_group_member_index {
-            format!("[{}]", m)
+            format!("[{}]{}", m, marker)
         } else {
-            m.clone()
+            format!("{}{}", m, marker)
         };
         Row::new(vec![Cell::from(text)]).style(style)
     });
@@ -332,11 +273,17 @@ pub fn render_group_members(f: &mut Frame, area: Rect, app: &mut AppState) {
         .header(header)
         .block(
             Block::default()
-                .title(if matches!(app.groups_focus, GroupsFocus::Members) {
-                    "[Group Members]"
-                } else {
-                    "Group Members"
-                })
+                .title(
+                    match (
+                        matches!(app.groups_focus, GroupsFocus::Members),
+                        app.sort_members,
+                    ) {
+                        (true, true) => "[Group Members (A-Z)]",
+                        (true, false) => "[Group Members]",
+                        (false, true) => "Group Members (A-Z)",
+                        (false, false) => "Group Members",
+                    },
+                )
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(app.theme.border)),
         )
@@ -351,7 +298,7 @@ pub fn render_group_modal(f: &mut Frame, area: Rect, app: &mut AppState, state:
             selected,
             target_gid,
         } => {
-            let rect = crate::ui::components::centered_rect(36, 8, area);
+            let rect = crate::ui::components::centered_rect(36, 9, area);
             let (options, title) = if let Some(gid) = target_gid {
                 let name = app
                     .groups
@@ -360,12 +307,25 @@ pub fn render_group_modal(f: &mut Frame, area: Rect, app: &mut AppState, state:
                     .map(|g| g.name.clone())
                     .unwrap_or_else(|| "<unknown>".to_string());
                 (
-                    ["Modify group", "Remove group"].as_slice(),
+                    [
+                        "Modify group",
+                        "Remove group",
+                        "Clone group",
+                        "Merge into another group",
+                    ]
+                    .as_slice(),
                     &*format!("Group actions - {}", name),
                 )
             } else {
                 (
-                    ["Add group", "Remove group", "Modify group (members)"].as_slice(),
+                    [
+                        "Add group",
+                        "Remove group",
+                        "Modify group (members)",
+                        "Clone group",
+                        "Merge into another group",
+                    ]
+                    .as_slice(),
                     "Group actions",
                 )
             };
@@ -386,43 +346,152 @@ pub fn render_group_modal(f: &mut Frame, area: Rect, app: &mut AppState, state:
             f.render_widget(Clear, rect);
             f.render_widget(p, rect);
         }
-        ModalState::GroupAddInput { name } => {
+        ModalState::GroupAddInput { name, cursor } => {
             let rect = crate::ui::components::centered_rect(48, 7, area);
             let msg = format!("New group name:\n{}", name);
             let p = Paragraph::new(msg).block(
                 Block::default()
                     .title("Create group")
+                    .title_bottom(Line::from(" Enter create · Down add members ").right_aligned())
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(app.theme.border)),
             );
             f.render_widget(Clear, rect);
             f.render_widget(p, rect);
+            let col = crate::ui::components::width_before_cursor(&name, cursor);
+            crate::ui::components::set_input_cursor(f, rect, 1, col);
+        }
+        ModalState::GroupDeleteReassign {
+            selected,
+            target_gid,
+            ..
+        } => {
+            let name = app
+                .groups_all
+                .iter()
+                .find(|g| g.gid == target_gid)
+                .map(|g| g.name.clone())
+                .unwrap_or_default();
+            let affected = crate::app::primary_group_users(&app.users_all, target_gid);
+            let targets = crate::app::reassign_targets(&app.groups_all, target_gid);
+            let width = (area.width.saturating_sub(10)).clamp(40, 60);
+            let height = (area.height.saturating_sub(6)).clamp(10, 20);
+            let rect = crate::ui::components::centered_rect(width, height, area);
+            // Two lines for the affected users and a blank separator.
+            let visible_capacity = rect.height.saturating_sub(4) as usize;
+            let total = targets.len();
+            let max_offset = total.saturating_sub(visible_capacity);
+            let start = selected
+                .saturating_sub(visible_capacity / 2)
+                .min(max_offset)
+                .min(total);
+            let end = (start + visible_capacity).min(total);
+            let mut items: Vec<ListItem> = Vec::with_capacity(end - start + 2);
+            items.push(ListItem::new(format!(
+                "Primary group of: {}",
+                affected.join(", ")
+            )));
+            items.push(ListItem::new(""));
+            for (i, g) in targets[start..end].iter().enumerate() {
+                let focus = if start + i == selected { "▶ " } else { "  " };
+                items.push(ListItem::new(format!("{}{} ({})", focus, g.name, g.gid)));
+            }
+            if targets.is_empty() {
+                items.push(ListItem::new("  No other group to move them to"));
+            }
+            let list = List::new(items).block(
+                Block::default()
+                    .title(format!("Delete '{}': move its users to", name))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(app.theme.border)),
+            );
+            f.render_widget(Clear, rect);
+            f.render_widget(list, rect);
+        }
+        ModalState::MergeGroup {
+            selected,
+            source_gid,
+            ..
+        } => {
+            let source = app.groups_all.iter().find(|g| g.gid == source_gid);
+            let name = source.map(|g| g.name.clone()).unwrap_or_default();
+            let member_count = source.map(|g| g.members.len()).unwrap_or(0);
+            let primary = crate::app::primary_group_users(&app.users_all, source_gid);
+            let targets = crate::app::reassign_targets(&app.groups_all, source_gid);
+            let width = (area.width.saturating_sub(10)).clamp(40, 60);
+            let height = (area.height.saturating_sub(6)).clamp(10, 20);
+            let rect = crate::ui::components::centered_rect(width, height, area);
+            // Two lines for the summary and a blank separator.
+            let visible_capacity = rect.height.saturating_sub(4) as usize;
+            let total = targets.len();
+            let max_offset = total.saturating_sub(visible_capacity);
+            let start = selected
+                .saturating_sub(visible_capacity / 2)
+                .min(max_offset)
+                .min(total);
+            let end = (start + visible_capacity).min(total);
+            let mut items: Vec<ListItem> = Vec::with_capacity(end - start + 2);
+            items.push(ListItem::new(format!(
+                "{} member(s), primary group of {} user(s)",
+                member_count,
+                primary.len()
+            )));
+            items.push(ListItem::new(""));
+            for (i, g) in targets[start..end].iter().enumerate() {
+                let focus = if start + i == selected { "▶ " } else { "  " };
+                items.push(ListItem::new(format!("{}{} ({})", focus, g.name, g.gid)));
+            }
+            if targets.is_empty() {
+                items.push(ListItem::new("  No other group to merge into"));
+            }
+            let list = List::new(items).block(
+                Block::default()
+                    .title(format!("Merge '{}' into", name))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(app.theme.border)),
+            );
+            f.render_widget(Clear, rect);
+            f.render_widget(list, rect);
         }
         ModalState::GroupDeleteConfirm {
             selected,
             target_gid,
+            reassign_to,
         } => {
-            let rect = crate::ui::components::centered_rect(50, 7, area);
-            let (name, gid) = if let Some(tgid) = target_gid {
-                app.groups
-                    .iter()
-                    .find(|g| g.gid == tgid)
-                    .map(|g| (g.name.clone(), g.gid))
-                    .unwrap_or_else(|| (String::new(), 0))
+            let group = if let Some(tgid) = target_gid {
+                app.groups.iter().find(|g| g.gid == tgid)
             } else {
-                app.groups
-                    .get(app.selected_group_index)
-                    .map(|g| (g.name.clone(), g.gid))
-                    .unwrap_or_else(|| (String::new(), 0))
+                app.groups.get(app.selected_group_index)
             };
+            let (name, gid) = group
+                .map(|g| (g.name.clone(), g.gid))
+                .unwrap_or_else(|| (String::new(), 0));
+            let mut height = 6;
             let mut body = format!("Delete group '{}' ?\n\n", name);
+            if let Some(fallback) = &reassign_to {
+                body.push_str(&format!(
+                    "Its primary users move to '{}' first.\n\n",
+                    fallback
+                ));
+                height += 2;
+            }
             // Show a caution if this looks like a system group
             if gid < 1000 && gid != 0 {
                 body.push_str(&format!("WARNING: '{}' appears to be a system group (GID {}).\nDeleting may break the system.\n\n", name, gid));
+                height += 3;
             }
             let yes = if selected == 0 { "[Yes]" } else { " Yes " };
             let no = if selected == 1 { "[No]" } else { " No  " };
-            body.push_str(&format!("  {}    {}", yes, no));
+            body.push_str(&format!("  {}    {}\n", yes, no));
+            let pending = match group {
+                Some(g) => {
+                    crate::app::group_delete_action(&app.users_all, g, reassign_to.as_deref())
+                }
+                None => PendingAction::DeleteGroup { groupname: name },
+            };
+            let (runs, lines) = crate::ui::components::command_preview(&pending);
+            body.push_str(&runs);
+            let rect = crate::ui::components::centered_rect(60, height + lines, area);
             let p = Paragraph::new(body).block(
                 Block::default()
                     .title("Confirm delete")
@@ -467,7 +536,11 @@ pub fn render_group_modal(f: &mut Frame, area: Rect, app: &mut AppState, state:
             f.render_widget(Clear, rect);
             f.render_widget(p, rect);
         }
-        ModalState::GroupRenameInput { name, target_gid } => {
+        ModalState::GroupRenameInput {
+            name,
+            cursor,
+            target_gid,
+        } => {
             let rect = crate::ui::components::centered_rect(48, 7, area);
             let current = if let Some(gid) = target_gid {
                 app.groups
@@ -481,7 +554,8 @@ pub fn render_group_modal(f: &mut Frame, area: Rect, app: &mut AppState, state:
                     .map(|g| g.name.clone())
                     .unwrap_or_default()
             };
-            let msg = format!("Current: {}\nNew name: {}", current, name);
+            let prefix = "New name: ";
+            let msg = format!("Current: {}\n{}{}", current, prefix, name);
             let p = Paragraph::new(msg).block(
                 Block::default()
                     .title("Rename group")
@@ -490,11 +564,42 @@ pub fn render_group_modal(f: &mut Frame, area: Rect, app: &mut AppState, state:
             );
             f.render_widget(Clear, rect);
             f.render_widget(p, rect);
+            let col = prefix.len() + crate::ui::components::width_before_cursor(&name, cursor);
+            crate::ui::components::set_input_cursor(f, rect, 1, col);
+        }
+        ModalState::GroupCloneInput {
+            source,
+            name,
+            cursor,
+        } => {
+            let rect = crate::ui::components::centered_rect(48, 7, area);
+            let members = app
+                .groups_all
+                .iter()
+                .find(|g| g.name == source)
+                .map(|g| g.members.len())
+                .unwrap_or(0);
+            let prefix = "New name: ";
+            let msg = format!(
+                "Copy of: {} ({} members)\n{}{}",
+                source, members, prefix, name
+            );
+            let p = Paragraph::new(msg).block(
+                Block::default()
+                    .title("Clone group")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(app.theme.border)),
+            );
+            f.render_widget(Clear, rect);
+            f.render_widget(p, rect);
+            let col = prefix.len() + crate::ui::components::width_before_cursor(&name, cursor);
+            crate::ui::components::set_input_cursor(f, rect, 1, col);
         }
         ModalState::GroupModifyAddMembers {
             selected,
             offset: _,
             selected_multi,
+            new_group,
             ..
         } => {
             let users = &app.users_all;
@@ -515,7 +620,7 @@ pub fn render_group_modal(f: &mut Frame, area: Rect, app: &mut AppState, state:
             for (i, u) in slice.iter().enumerate() {
                 let abs_index = start + i;
                 let focus = if abs_index == selected { "▶ " } else { "  " };
-                let checked = if selected_multi.contains(&abs_index) {
+                let checked = if selected_multi.contains(&u.name) {
                     "[x] "
                 } else {
                     "[ ] "
@@ -528,7 +633,13 @@ pub fn render_group_modal(f: &mut Frame, area: Rect, app: &mut AppState, state:
             let list = List::new(items)
                 .block(
                     Block::default()
-                        .title("Add member to group")
+                        .title(match &new_group {
+                            Some(name) => format!("Members of new group '{}'", name),
+                            None => "Add member to group".to_string(),
+                        })
+                        .title_bottom(crate::ui::components::selected_count_footer(
+                            selected_multi.len(),
+                        ))
                         .borders(Borders::ALL)
                         .border_style(Style::default().fg(app.theme.border)),
                 )
@@ -573,7 +684,7 @@ pub fn render_group_modal(f: &mut Frame, area: Rect, app: &mut AppState, state:
             for (i, m) in slice.iter().enumerate() {
                 let abs_index = start + i;
                 let focus = if abs_index == selected { "▶ " } else { "  " };
-                let checked = if selected_multi.contains(&abs_index) {
+                let checked = if selected_multi.contains(m) {
                     "[x] "
                 } else {
                     "[ ] "
@@ -584,6 +695,9 @@ pub fn render_group_modal(f: &mut Frame, area: Rect, app: &mut AppState, state:
                 .block(
                     Block::default()
                         .title(format!("Remove member from '{}'", name))
+                        .title_bottom(crate::ui::components::selected_count_footer(
+                            selected_multi.len(),
+                        ))
                         .borders(Borders::ALL)
                         .border_style(Style::default().fg(app.theme.border)),
                 )
@@ -595,6 +709,110 @@ pub fn render_group_modal(f: &mut Frame, area: Rect, app: &mut AppState, state:
             f.render_widget(Clear, rect);
             f.render_widget(list, rect);
         }
+        ModalState::MoveMemberPicker {
+            selected,
+            username,
+            from_group,
+            ..
+        } => {
+            let targets = crate::app::move_member_targets(
+                &app.groups_all,
+                &app.users_all,
+                &username,
+                &from_group,
+            );
+            let width = (area.width.saturating_sub(10)).clamp(40, 60);
+            let height = (area.height.saturating_sub(6)).clamp(8, 20);
+            let rect = crate::ui::components::centered_rect(width, height, area);
+            let visible_capacity = rect.height.saturating_sub(2) as usize;
+            let total = targets.len();
+            let max_offset = total.saturating_sub(visible_capacity);
+            let start = selected
+                .saturating_sub(visible_capacity / 2)
+                .min(max_offset)
+                .min(total);
+            let end = (start + visible_capacity).min(total);
+            let mut items: Vec<ListItem> = Vec::with_capacity(end - start);
+            for (i, g) in targets[start..end].iter().enumerate() {
+                let focus = if start + i == selected { "▶ " } else { "  " };
+                items.push(ListItem::new(format!("{}{} ({})", focus, g.name, g.gid)));
+            }
+            if items.is_empty() {
+                items.push(ListItem::new("  No other group to move to"));
+            }
+            let list = List::new(items).block(
+                Block::default()
+                    .title(format!("Move '{}' from '{}' to", username, from_group))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(app.theme.border)),
+            );
+            f.render_widget(Clear, rect);
+            f.render_widget(list, rect);
+        }
+        ModalState::OrphanMembers {
+            selected,
+            group_name,
+            orphans,
+        } => {
+            let width = (area.width.saturating_sub(10)).clamp(40, 60);
+            let height = (area.height.saturating_sub(6)).clamp(8, 20);
+            let rect = crate::ui::components::centered_rect(width, height, area);
+            let visible_capacity = rect.height.saturating_sub(2) as usize;
+            let total = orphans.len();
+            let max_offset = total.saturating_sub(visible_capacity);
+            let start = selected
+                .saturating_sub(visible_capacity / 2)
+                .min(max_offset)
+                .min(total);
+            let end = (start + visible_capacity).min(total);
+            let mut items: Vec<ListItem> = Vec::with_capacity(end - start);
+            for (i, name) in orphans[start..end].iter().enumerate() {
+                let focus = if start + i == selected { "▶ " } else { "  " };
+                items.push(ListItem::new(format!("{}{}", focus, name)));
+            }
+            if items.is_empty() {
+                items.push(ListItem::new("  Every member has an account"));
+            }
+            let list = List::new(items).block(
+                Block::default()
+                    .title(format!("Members of '{}' without an account", group_name))
+                    .title_bottom(Line::from(" Enter to remove from group ").right_aligned())
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(app.theme.border)),
+            );
+            f.render_widget(Clear, rect);
+            f.render_widget(list, rect);
+        }
+        ModalState::MoveMemberConfirm {
+            selected,
+            username,
+            from_group,
+            to_group,
+        } => {
+            let mut body = format!(
+                "Move '{}' from '{}' to '{}' ?\n\n",
+                username, from_group, to_group
+            );
+            let yes = if selected == 0 { "[Yes]" } else { " Yes " };
+            let no = if selected == 1 { "[No]" } else { " No  " };
+            body.push_str(&format!("  {}    {}\n", yes, no));
+            let (runs, lines) =
+                crate::ui::components::command_preview(&PendingAction::MoveUserBetweenGroups {
+                    username,
+                    from_group,
+                    to_group,
+                });
+            body.push_str(&runs);
+            let rect = crate::ui::components::centered_rect(60, 6 + lines, area);
+            let p = Paragraph::new(body).block(
+                Block::default()
+                    .title("Confirm move")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(app.theme.border)),
+            );
+            f.render_widget(Clear, rect);
+            f.render_widget(p, rect);
+        }
         _ => {}
     }
 }
@@ -27,83 +27,126 @@ pub fn render(f: &mut Frame, app: &mut AppState) {
             .as_ref(),
         )
         .split(f.area());
-    let body = if app.show_keybinds {
+    // Narrow terminals drop the keybindings panel first, then the details
+    let compact = app.is_compact(f.area().width);
+    let show_keybinds = app.shows_keybinds(f.area().width);
+    let split = app.pane_split;
+    let body = if compact {
+        // Narrow terminal: the table gets the whole body, details open in a popup
+        Layout::default()
+            .constraints([Constraint::Percentage(100)].as_ref())
+            .split(root[1])
+    } else if show_keybinds {
         Layout::default()
             .direction(Direction::Horizontal)
             .constraints(
                 [
-                    Constraint::Percentage(41), // main table
-                    Constraint::Percentage(34), // details/members
-                    Constraint::Percentage(25), // keybinds panel
+                    Constraint::Percentage(split.table),    // main table
+                    Constraint::Percentage(split.details),  // details/members
+                    Constraint::Percentage(split.keybinds), // keybinds panel
                 ]
                 .as_ref(),
             )
             .split(root[1])
     } else {
+        let table = split.table_without_keybinds();
         Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .constraints(
+                [
+                    Constraint::Percentage(table),
+                    Constraint::Percentage(100 - table),
+                ]
+                .as_ref(),
+            )
             .split(root[1])
     };
-    let right = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Length(16), Constraint::Min(5)].as_ref())
-        .split(body[1]);
 
     let who = crate::sys::current_username().unwrap_or_else(|| "unknown".to_string());
     let tabs = match app.active_tab {
         ActiveTab::Users => "[Users]  Groups",
         ActiveTab::Groups => "Users  [Groups]",
     };
-    let prompt = match app.input_mode {
-        crate::app::InputMode::Normal => String::new(),
-        crate::app::InputMode::SearchUsers => format!("  Search users: {}", app.search_query),
-        crate::app::InputMode::SearchGroups => format!("  Search groups: {}", app.search_query),
-        crate::app::InputMode::Modal => String::new(),
+    let prompt_label = match app.input_mode {
+        crate::app::InputMode::SearchUsers => Some("  Search users: "),
+        crate::app::InputMode::SearchGroups => Some("  Search groups: "),
+        crate::app::InputMode::Normal | crate::app::InputMode::Modal => None,
     };
+    let heading = format!("usrgrp-manager ({who})  {tabs}");
+    let prompt = prompt_label
+        .map(|label| format!("{label}{}", app.search_query))
+        .unwrap_or_default();
     // Inline key hints removed; dedicated keybinds panel is shown on the right now.
+    let root_note = app
+        .running_as_root
+        .as_ref()
+        .map(|label| format!("  [{label}]"))
+        .unwrap_or_default();
     let p = Paragraph::new(format!(
-        "usrgrp-manager ({who})  {tabs}{prompt}\nusers:{}  groups:{}",
+        "{heading}{prompt}\nusers:{}  groups:{}{root_note}",
         app.users.len(),
         app.groups.len()
     ))
     .block(
         Block::default()
-            .title("usrgrp-manager")
+            .title(if crate::app::READ_ONLY {
+                "usrgrp-manager (read-only)"
+            } else {
+                "usrgrp-manager"
+            })
             .borders(Borders::ALL)
             .border_style(Style::default().fg(app.theme.border)),
     )
     .style(Style::default().fg(app.theme.header_fg));
     f.render_widget(p, root[0]);
+    if let Some(label) = prompt_label {
+        let col = components::text_width(&heading)
+            + label.len()
+            + components::width_before_cursor(&app.search_query, app.search_cursor);
+        components::set_input_cursor(f, root[0], 0, col);
+    }
 
     match app.active_tab {
-        ActiveTab::Users => {
-            users::render_users_table(f, body[0], app);
-            users::render_user_details(f, right[0], app);
-            users::render_user_groups(f, right[1], app);
-        }
-        ActiveTab::Groups => {
-            groups::render_groups_table(f, body[0], app);
-            groups::render_group_details(f, right[0], app);
-            groups::render_group_members(f, right[1], app);
-        }
+        ActiveTab::Users => users::render_users_table(f, body[0], app),
+        ActiveTab::Groups => groups::render_groups_table(f, body[0], app),
     }
-
-    // Keybindings panel on the far right (if enabled)
-    if app.show_keybinds {
-        components::render_keybinds_panel(f, body[2], app);
+    if !compact {
+        render_details(f, body[1], app);
+        // Keybindings panel on the far right (if enabled)
+        if show_keybinds {
+            components::render_keybinds_panel(f, body[2], app);
+        }
     }
 
     components::render_status_bar(f, root[2], app);
 
-    if app.modal.is_some() {
+    if app.modal().is_some() {
         render_modal(f, f.area(), app);
     }
 }
 
+/// Render the details pane and member list of the active tab's selection,
+/// stacked in `area`.
+fn render_details(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(16), Constraint::Min(5)].as_ref())
+        .split(area);
+    match app.active_tab {
+        ActiveTab::Users => {
+            users::render_user_details(f, parts[0], app);
+            users::render_user_groups(f, parts[1], app);
+        }
+        ActiveTab::Groups => {
+            groups::render_group_details(f, parts[0], app);
+            groups::render_group_members(f, parts[1], app);
+        }
+    }
+}
+
 /// Route modal rendering to the appropriate submodule.
 fn render_modal(f: &mut Frame, area: Rect, app: &mut AppState) {
-    if let Some(state) = app.modal.clone() {
+    if let Some(state) = app.modal().cloned() {
         match state.clone() {
             ModalState::Actions { .. }
             | ModalState::ModifyMenu { .. }
@@ -112,19 +155,29 @@ fn render_modal(f: &mut Frame, area: Rect, app: &mut AppState) {
             | ModalState::ModifyDetailsMenu { .. }
             | ModalState::ModifyShell { .. }
             | ModalState::ModifyTextInput { .. }
+            | ModalState::EditGecos { .. }
+            | ModalState::ImportPathInput { .. }
             | ModalState::DeleteConfirm { .. }
             | ModalState::UserAddInput { .. }
+            | ModalState::CloneUser { .. }
             | ModalState::ModifyPasswordMenu { .. }
+            | ModalState::SudoersDropin { .. }
             | ModalState::ChangePassword { .. } => {
                 users::render_user_modal(f, area, app, &state);
             }
             ModalState::GroupsActions { .. }
             | ModalState::GroupAddInput { .. }
+            | ModalState::GroupDeleteReassign { .. }
             | ModalState::GroupDeleteConfirm { .. }
             | ModalState::GroupModifyMenu { .. }
             | ModalState::GroupModifyAddMembers { .. }
             | ModalState::GroupModifyRemoveMembers { .. }
-            | ModalState::GroupRenameInput { .. } => {
+            | ModalState::GroupRenameInput { .. }
+            | ModalState::GroupCloneInput { .. }
+            | ModalState::MergeGroup { .. }
+            | ModalState::MoveMemberPicker { .. }
+            | ModalState::MoveMemberConfirm { .. }
+            | ModalState::OrphanMembers { .. } => {
                 groups::render_group_modal(f, area, app, &state);
             }
             ModalState::ConfirmRemoveUserFromGroup { .. } => {
@@ -133,8 +186,26 @@ fn render_modal(f: &mut Frame, area: Rect, app: &mut AppState) {
             ModalState::Info { .. } => {
                 components::render_info_modal(f, area, app, &state);
             }
-            ModalState::Help { scroll } => {
-                components::render_help_modal(f, area, app, scroll);
+            ModalState::UserDetails => users::render_user_details_modal(f, area, app),
+            ModalState::GroupDetails => groups::render_group_details_modal(f, area, app),
+            ModalState::ActionHistory { scroll } => {
+                components::render_action_history_modal(f, area, app, scroll);
+            }
+            ModalState::Help {
+                scroll,
+                query,
+                searching,
+            } => {
+                components::render_help_modal(f, area, app, scroll, &query, searching);
+            }
+            ModalState::ConfirmAction { .. } => {
+                components::render_confirm_action_modal(f, area, app, &state);
+            }
+            ModalState::MembershipPreview { .. } => {
+                components::render_membership_preview_modal(f, area, app, &state);
+            }
+            ModalState::TypedConfirm { .. } => {
+                components::render_typed_confirm_modal(f, area, app, &state);
             }
             ModalState::SudoPrompt { .. } => {
                 components::render_sudo_modal(f, area, app, &state);
@@ -142,6 +213,9 @@ fn render_modal(f: &mut Frame, area: Rect, app: &mut AppState) {
             ModalState::FilterMenu { .. } => {
                 components::render_filter_modal(f, area, app, &state);
             }
+            ModalState::CopyCommandMenu { selected } => {
+                components::render_copy_command_modal(f, area, app, selected);
+            }
         }
     }
 }
@@ -7,15 +7,47 @@
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Rect};
 use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Table};
 
-use crate::app::{AppState, ModalState, ModifyField, UsersFocus};
+use crate::app::{
+    ActiveTab, AppState, DetailVerbosity, GroupEligibility, ModalState, ModifyField, PendingAction,
+    UserColumn, UsersFocus, format_id,
+};
+use crate::ui::components::truncate_to_width;
+
+/// Width of the USER column in the users table, in terminal columns.
+const USER_COLUMN_WIDTH: usize = 24;
+
+/// Header labels and width constraints for the visible users table columns.
+///
+/// IDs and names get fixed widths; HOME and SHELL split 80% of the table
+/// between them, so hiding one widens the other.
+pub fn user_table_layout(columns: &[UserColumn]) -> (Vec<&'static str>, Vec<Constraint>) {
+    let paths = columns
+        .iter()
+        .filter(|c| matches!(c, UserColumn::Home | UserColumn::Shell))
+        .count()
+        .max(1) as u16;
+    let widths = columns
+        .iter()
+        .map(|c| match c {
+            UserColumn::Uid | UserColumn::Gid => Constraint::Length(8),
+            UserColumn::HomeExists => Constraint::Length(3),
+            UserColumn::Name => Constraint::Length(USER_COLUMN_WIDTH as u16),
+            UserColumn::Home | UserColumn::Shell => Constraint::Percentage(80 / paths),
+        })
+        .collect();
+    (columns.iter().map(|c| c.header()).collect(), widths)
+}
 
 /// Render the users table and manage selection/pagination state.
 ///
-/// Displays a table of users (UID, name, GID, home directory, shell) with
-/// the currently selected user highlighted. This function also calculates
-/// pagination and updates the rows-per-page based on available space.
+/// Displays a table of users (by default UID, name, GID, whether the home
+/// directory exists, home directory and shell; see `user_columns` in
+/// `ui.conf`) with the currently selected user highlighted. This function
+/// also calculates pagination and updates the rows-per-page based on
+/// available space.
 ///
 /// # Arguments
 ///
@@ -28,10 +60,26 @@ pub fn render_users_table(f: &mut Frame, area: Rect, app: &mut AppState) {
     if body_height > 0 {
         app.rows_per_page = body_height;
     }
+    // Shadow is read once per reload, not once per frame
+    let locked = crate::app::locked_users(app).clone();
 
     let start = (app.selected_user_index / app.rows_per_page) * app.rows_per_page;
     let end = (start + app.rows_per_page).min(app.users.len());
+    // Each home is stat'ed once per reload, and only for rows on this page
+    let home_exists: Vec<bool> = if app.user_columns.contains(&UserColumn::HomeExists) {
+        app.users[start..end]
+            .iter()
+            .map(|u| {
+                *app.home_exists_cache
+                    .entry(u.home_dir.clone())
+                    .or_insert_with(|| std::fs::metadata(&u.home_dir).is_ok())
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
     let slice = &app.users[start..end];
+    let query = crate::ui::components::active_search_query(app, ActiveTab::Users);
 
     let rows = slice.iter().enumerate().map(|(i, u)| {
         let absolute_index = start + i;
@@ -42,30 +90,48 @@ pub fn render_users_table(f: &mut Frame, area: Rect, app: &mut AppState) {
         } else {
             Style::default()
         };
+        let style = if app.is_recently_changed(ActiveTab::Users, &u.name) {
+            style.bg(Color::Green).fg(Color::Black)
+        } else {
+            style
+        };
+        let marker = if u.is_nobody() {
+            crate::app::NOBODY_MARKER
+        } else {
+            ""
+        };
+        let lock = if locked.contains(&u.name) {
+            crate::app::LOCKED_MARKER
+        } else {
+            ""
+        };
+        let room = USER_COLUMN_WIDTH - marker.len() - crate::ui::components::text_width(lock);
         let name_text = if absolute_index == app.selected_user_index {
-            format!("[{}]", u.name)
+            let name = truncate_to_width(&u.name, room - 2);
+            format!("{lock}[{}]{}", name, marker)
         } else {
-            u.name.clone()
+            let name = truncate_to_width(&u.name, room);
+            format!("{lock}{}{}", name, marker)
         };
-        Row::new(vec![
-            Cell::from(u.uid.to_string()),
-            Cell::from(name_text),
-            Cell::from(u.primary_gid.to_string()),
-            Cell::from(u.home_dir.clone()),
-            Cell::from(u.shell.clone()),
-        ])
+        Row::new(app.user_columns.iter().map(|c| match c {
+            UserColumn::Uid => Cell::from(format_id(u.uid, app.id_radix)),
+            UserColumn::Name => Cell::from(crate::ui::components::highlight_matches(
+                name_text.clone(),
+                query,
+            )),
+            UserColumn::Gid => Cell::from(format_id(u.primary_gid, app.id_radix)),
+            UserColumn::HomeExists => {
+                let (exists, missing) = crate::app::HOME_EXISTS_MARKERS;
+                Cell::from(if home_exists[i] { exists } else { missing })
+            }
+            UserColumn::Home => Cell::from(u.home_dir.clone()),
+            UserColumn::Shell => Cell::from(u.shell.clone()),
+        }))
         .style(style)
     });
 
-    let widths = [
-        Constraint::Length(8),
-        Constraint::Length(24),
-        Constraint::Length(8),
-        Constraint::Percentage(40),
-        Constraint::Percentage(40),
-    ];
-
-    let header = Row::new(vec!["UID", "USER", "GID", "HOME", "SHELL"]).style(
+    let (headers, widths) = user_table_layout(&app.user_columns);
+    let header = Row::new(headers).style(
         Style::default()
             .fg(app.theme.title)
             .add_modifier(Modifier::BOLD),
@@ -104,11 +170,14 @@ pub fn render_users_table(f: &mut Frame, area: Rect, app: &mut AppState) {
         .column_spacing(1);
 
     f.render_widget(table, area);
+    if let Some(msg) = crate::ui::components::empty_table_message(app, ActiveTab::Users) {
+        crate::ui::components::render_empty_table_message(f, area, &msg);
+    }
 }
 
-/// Render the details panel for the selected user.
+/// Lines of the details panel for the selected user.
 ///
-/// This panel displays comprehensive information about the currently selected user, including:
+/// Covers comprehensive information about the currently selected user, including:
 /// - Identity (UID, GID, primary group name)
 /// - Home directory (path, existence, permissions)
 /// - Shell (validity, interactivity)
@@ -117,19 +186,43 @@ pub fn render_users_table(f: &mut Frame, area: Rect, app: &mut AppState) {
 /// - SSH keys (count from `~/.ssh/authorized_keys`)
 /// - Processes (current count owned by the user)
 ///
-/// # Arguments
-///
-/// * `f` - The frame to render into.
-/// * `area` - The rectangle area where the details panel will be drawn.
-/// * `app` - The application state containing user data.
-pub fn render_user_details(f: &mut Frame, area: Rect, app: &AppState) {
+/// In [`DetailVerbosity::Compact`] only the name, UID, primary group and
+/// shell are shown, and none of the lookups above are made.
+pub fn user_details_lines(app: &mut AppState) -> Vec<Line<'static>> {
+    if app.detail_verbosity == DetailVerbosity::Compact {
+        let user = app.users.get(app.selected_user_index);
+        return vec![
+            Line::from(format!(
+                "Username: {}",
+                user.map(|u| u.name.as_str()).unwrap_or_default()
+            )),
+            Line::from(format!(
+                "UID: {}",
+                format_id(user.map(|u| u.uid).unwrap_or(0), app.id_radix)
+            )),
+            primary_group_line(app, user),
+            Line::from(format!(
+                "Shell: {}",
+                user.map(|u| u.shell.as_str()).unwrap_or_default()
+            )),
+        ];
+    }
+    crate::app::known_shells(app, &crate::sys::SystemAdapter::new());
+    if app.live_stats
+        && let Some(name) = app
+            .users
+            .get(app.selected_user_index)
+            .map(|u| u.name.clone())
+    {
+        crate::app::effective_groups(app, &name);
+    }
+    let app: &AppState = app;
     let user = app.users.get(app.selected_user_index);
-    let (username, fullname, uid, gid, home, shell) = match user {
+    let (username, fullname, uid, home, shell) = match user {
         Some(u) => (
             u.name.clone(),
             u.full_name.clone().unwrap_or_default(),
             u.uid,
-            u.primary_gid,
             u.home_dir.clone(),
             u.shell.clone(),
         ),
@@ -137,76 +230,57 @@ pub fn render_user_details(f: &mut Frame, area: Rect, app: &AppState) {
             String::new(),
             String::new(),
             0,
-            0,
             String::new(),
             String::new(),
         ),
     };
 
-    let primary_group_name = app
-        .groups_all
-        .iter()
-        .find(|g| g.gid == gid)
-        .map(|g| g.name.clone())
-        .unwrap_or_else(|| "-".to_string());
-
-    // Home directory existence and permissions (octal)
-    let (home_exists, home_perms): (bool, String) = match std::fs::metadata(&home) {
-        Ok(meta) => {
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                let mode = meta.permissions().mode() & 0o777;
-                (true, format!("{:03o}", mode))
-            }
-            #[cfg(not(unix))]
-            {
-                let _ = meta; // Use meta to avoid unused variable warning
-                (true, "-".to_string())
+    // Home directory: symlink target, existence and permissions (octal)
+    let home_line = match app.live_stats.then(|| crate::sys::home_status(&home)) {
+        Some(st) => {
+            let perms = st
+                .mode
+                .map(|m| format!("{:03o}", m))
+                .unwrap_or_else(|| "-".to_string());
+            match st.link_target {
+                Some(target) => format!(
+                    "Home directory: {home} -> {} (symlink; target exists: {}, perms: {perms})",
+                    target.display(),
+                    st.exists
+                ),
+                None => format!(
+                    "Home directory: {home} (exists: {}, perms: {perms})",
+                    st.exists
+                ),
             }
         }
-        Err(_) => (false, "-".to_string()),
+        None => format!("Home directory: {home}"),
     };
 
-    // Shell validity and interactivity with cached /etc/shells
+    // Shell validity and interactivity against the cached /etc/shells
     let (shell_valid, shell_interactive) = {
-        use std::sync::OnceLock;
-        static SHELLS: OnceLock<Vec<String>> = OnceLock::new();
-        let shells = SHELLS.get_or_init(|| {
-            if let Ok(contents) = std::fs::read_to_string("/etc/shells") {
-                contents
-                    .lines()
-                    .filter_map(|raw| {
-                        let line = raw.trim();
-                        if line.is_empty() || line.starts_with('#') {
-                            None
-                        } else {
-                            Some(line.to_string())
-                        }
-                    })
-                    .collect()
-            } else {
-                Vec::new()
-            }
-        });
+        let shells = app.shells_cache.as_deref().unwrap_or_default();
         let valid = shells.iter().any(|s| s == &shell);
         let interactive = !(shell.ends_with("/nologin") || shell.ends_with("/false"));
         (valid, interactive)
     };
 
     // Password/account status from /etc/shadow (best effort)
-    let (locked, no_password, expired, last_change, expire_abs) =
-        if let Some(sh) = crate::search::user_shadow_status(&username) {
-            (
-                sh.locked,
-                sh.no_password,
-                sh.expired,
-                sh.last_change_days,
-                sh.expire_abs_days,
-            )
-        } else {
-            (false, false, false, None, None)
-        };
+    let (locked, no_password, expired, last_change, expire_abs) = if let Some(sh) = app
+        .live_stats
+        .then(|| crate::search::user_shadow_status(&username))
+        .flatten()
+    {
+        (
+            sh.locked,
+            sh.no_password,
+            sh.expired,
+            sh.last_change_days,
+            sh.expire_abs_days,
+        )
+    } else {
+        (false, false, false, None, None)
+    };
 
     // Best-effort date representation: keep days since epoch as string
     fn fmt_days(d: Option<i64>) -> String {
@@ -214,7 +288,7 @@ pub fn render_user_details(f: &mut Frame, area: Rect, app: &AppState) {
     }
 
     // SSH authorized_keys count
-    let ssh_keys_count = {
+    let ssh_keys_count = if app.live_stats {
         let mut p = std::path::PathBuf::from(&home);
         p.push(".ssh");
         p.push("authorized_keys");
@@ -228,33 +302,15 @@ pub fn render_user_details(f: &mut Frame, area: Rect, app: &AppState) {
                 .count(),
             Err(_) => 0,
         }
+    } else {
+        0
     };
 
     // Process count owned by the user (best-effort via /proc)
-    let process_count = {
-        let mut count = 0usize;
-        if let Ok(entries) = std::fs::read_dir("/proc") {
-            for e in entries.flatten() {
-                if let Ok(name) = e.file_name().into_string()
-                    && name.chars().all(|c| c.is_ascii_digit())
-                {
-                    let mut status = e.path();
-                    status.push("status");
-                    if let Ok(s) = std::fs::read_to_string(status) {
-                        for line in s.lines() {
-                            if let Some(rest) = line.strip_prefix("Uid:") {
-                                let first = rest.split_whitespace().next().unwrap_or("");
-                                if first == uid.to_string() {
-                                    count += 1;
-                                }
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        count
+    let process_count = if app.live_stats && user.is_some() {
+        crate::sys::process_count(uid)
+    } else {
+        0
     };
 
     // Sudo membership (configurable group name via sudo_group_name())
@@ -266,19 +322,90 @@ pub fn render_user_details(f: &mut Frame, area: Rect, app: &AppState) {
         .map(|g| g.members.iter().any(|m| m == &username))
         .unwrap_or(false);
 
-    let text = format!(
-        "Username: {username}\nFullname: {fullname}\nUID: {uid}\nPrimary group: {gid} ({primary_group_name})\nHome directory: {home} (exists: {home_exists}, perms: {home_perms})\nShell: {shell} (valid: {shell_valid}, interactive: {shell_interactive})\nPassword: locked={locked}, no_password={no_password}, expired={expired}\nLast change (days since epoch): {}\nExpiry (days since epoch): {}\nSudo: {}\nSSH keys: {}\nProcesses: {}",
-        fmt_days(last_change),
-        fmt_days(expire_abs),
-        if in_wheel {
-            "member of sudo group"
-        } else {
-            "no"
-        },
-        ssh_keys_count,
-        process_count,
-    );
-    let p = Paragraph::new(text)
+    // Groups `id -Gn` resolves, shown only when NSS (LDAP, SSSD) disagrees
+    // with the account files
+    let effective = user.filter(|_| app.live_stats).and_then(|u| {
+        let (name, groups) = app.effective_groups_cache.as_ref()?;
+        let groups = groups.as_ref().filter(|_| *name == u.name)?;
+        let from_id: std::collections::BTreeSet<&String> = groups.iter().collect();
+        let from_files = crate::app::file_groups(u, &app.groups_all);
+        (from_files.iter().collect::<std::collections::BTreeSet<_>>() != from_id)
+            .then(|| groups.join(", "))
+    });
+
+    let uid = format_id(uid, app.id_radix);
+    let primary_group = primary_group_line(app, user);
+    let nobody = if user.is_some_and(|u| u.is_nobody()) {
+        crate::app::NOBODY_MARKER
+    } else {
+        ""
+    };
+    let head = format!("Username: {username}\nFullname: {fullname}\nUID: {uid}{nobody}");
+    let sudo = if in_wheel {
+        "member of sudo group"
+    } else {
+        "no"
+    };
+    let tail = if app.live_stats {
+        format!(
+            "{home_line}\nShell: {shell} (valid: {shell_valid}, interactive: {shell_interactive})\nPassword: locked={locked}, no_password={no_password}, expired={expired}\nLast change (days since epoch): {}\nExpiry (days since epoch): {}\nSudo: {sudo}\nSSH keys: {ssh_keys_count}\nProcesses: {process_count}",
+            fmt_days(last_change),
+            fmt_days(expire_abs),
+        )
+    } else {
+        let key = app
+            .keymap
+            .key_for(crate::app::keymap::KeyAction::ToggleLiveStats)
+            .unwrap_or_default();
+        format!(
+            "{home_line}\nShell: {shell} (valid: {shell_valid}, interactive: {shell_interactive})\nSudo: {sudo}\nPassword, SSH keys, processes: press {key} for live stats"
+        )
+    };
+    let mut lines: Vec<Line> = head.lines().map(|l| Line::from(l.to_string())).collect();
+    lines.push(primary_group);
+    lines.extend(tail.lines().map(|l| Line::from(l.to_string())));
+    if let Some(effective) = effective {
+        lines.push(Line::from(format!("Effective groups (id): {effective}")));
+    }
+    lines
+}
+
+/// `Primary group:` line of the user details.
+///
+/// A primary gid with no entry in /etc/group is a misconfiguration worth
+/// flagging.
+fn primary_group_line(app: &AppState, user: Option<&crate::sys::SystemUser>) -> Line<'static> {
+    let gid = user.map(|u| u.primary_gid).unwrap_or(0);
+    let name = app
+        .groups_all
+        .iter()
+        .find(|g| g.gid == gid)
+        .map(|g| g.name.clone());
+    let gid = format_id(gid, app.id_radix);
+    match (name, user) {
+        (Some(name), _) => Line::from(format!("Primary group: {gid} ({name})")),
+        (None, Some(_)) => Line::from(vec![
+            Span::raw(format!("Primary group: {gid} ")),
+            Span::styled(
+                "(MISSING)",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        (None, None) => Line::from(format!("Primary group: {gid} (-)")),
+    }
+}
+
+/// Render the details panel for the selected user.
+///
+/// # Arguments
+///
+/// * `f` - The frame to render into.
+/// * `area` - The rectangle area where the details panel will be drawn.
+/// * `app` - The application state containing user data.
+pub fn render_user_details(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let p = Paragraph::new(user_details_lines(app))
         .style(Style::default().fg(app.theme.text))
         .block(
             Block::default()
@@ -289,6 +416,29 @@ pub fn render_user_details(f: &mut Frame, area: Rect, app: &AppState) {
     f.render_widget(p, area);
 }
 
+/// Render the selected user's details in a centered modal, for layouts
+/// without a details column.
+pub fn render_user_details_modal(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let name = app
+        .users
+        .get(app.selected_user_index)
+        .map(|u| u.name.clone())
+        .unwrap_or_default();
+    let mut lines = user_details_lines(app);
+    if !name.is_empty() {
+        lines.push(Line::from(
+            "Press c to copy the home path, o to open it in $FILE_MANAGER",
+        ));
+    }
+    crate::ui::components::render_details_modal(
+        f,
+        area,
+        app,
+        &format!("User details - {name}"),
+        lines,
+    );
+}
+
 /// Render the list of groups the selected user belongs to.
 ///
 /// This panel displays the groups to which the currently selected user belongs,
@@ -301,17 +451,18 @@ pub fn render_user_details(f: &mut Frame, area: Rect, app: &AppState) {
 /// * `area` - The rectangle area where the groups panel will be drawn.
 /// * `app` - The application state containing user and group data.
 pub fn render_user_groups(f: &mut Frame, area: Rect, app: &mut AppState) {
-    let groups = if let Some(u) = app.users.get(app.selected_user_index) {
-        let name = u.name.clone();
-        let pgid = u.primary_gid;
-        app.groups
-            .iter()
-            .filter(|g| g.gid == pgid || g.members.iter().any(|m| m == &name))
-            .cloned()
-            .collect::<Vec<_>>()
-    } else {
-        Vec::new()
-    };
+    let by_id = app.membership_source == crate::app::MembershipSource::Id;
+    if by_id
+        && let Some(name) = app
+            .users
+            .get(app.selected_user_index)
+            .map(|u| u.name.clone())
+    {
+        crate::app::effective_groups(app, &name);
+    }
+    let groups = crate::app::member_of_groups(app);
+    // Groups only NSS knows about are listed after the others but cannot be selected
+    let nss_only = crate::app::nss_only_groups(app);
 
     if !groups.is_empty() {
         if app.selected_group_index >= groups.len() {
@@ -329,6 +480,10 @@ pub fn render_user_groups(f: &mut Frame, area: Rect, app: &mut AppState) {
     let end = (start + app.rows_per_page).min(groups.len());
     let slice = &groups[start..end];
 
+    let nss_rows = nss_only.iter().map(|name| {
+        Row::new(vec![Cell::from("-"), Cell::from(format!("{name} (NSS)"))])
+            .style(Style::default().fg(Color::DarkGray))
+    });
     let rows = slice.iter().enumerate().map(|(i, g)| {
         let absolute_index = start + i;
         let style = if absolute_index == app.selected_group_index {
@@ -343,8 +498,13 @@ pub fn render_user_groups(f: &mut Frame, area: Rect, app: &mut AppState) {
         } else {
             g.name.clone()
         };
-        Row::new(vec![Cell::from(g.gid.to_string()), Cell::from(name_text)]).style(style)
+        Row::new(vec![
+            Cell::from(format_id(g.gid, app.id_radix)),
+            Cell::from(name_text),
+        ])
+        .style(style)
     });
+    let rows = rows.chain(nss_rows.take(app.rows_per_page.saturating_sub(slice.len())));
 
     let widths = [Constraint::Length(8), Constraint::Percentage(100)];
     let header = Row::new(vec!["GID", "Name"]).style(
@@ -354,10 +514,11 @@ pub fn render_user_groups(f: &mut Frame, area: Rect, app: &mut AppState) {
     );
 
     let groups_title = {
-        let base = if app.users_focus == UsersFocus::MemberOf {
-            "[Member of]"
-        } else {
-            "Member of"
+        let base = match (app.users_focus == UsersFocus::MemberOf, by_id) {
+            (true, false) => "[Member of]",
+            (false, false) => "Member of",
+            (true, true) => "[Member of (id -Gn)]",
+            (false, true) => "Member of (id -Gn)",
         };
         if app.users_focus == UsersFocus::MemberOf {
             if let Some(g) = groups.get(app.selected_group_index) {
@@ -388,13 +549,14 @@ pub fn render_user_modal(f: &mut Frame, area: Rect, app: &mut AppState, state: &
             let width = 30u16;
             let height = 7u16;
             let rect = crate::ui::components::centered_rect(width, height, area);
-            let options = if let Some(crate::app::ActionsContext::GroupMemberRemoval { .. }) =
-                &app.actions_context
-            {
-                ["Modify", "Remove from group"]
-            } else {
-                ["Modify", "Delete"]
-            };
+            let options: &[&str] =
+                if let Some(crate::app::ActionsContext::GroupMemberRemoval { .. }) =
+                    &app.actions_context
+                {
+                    &["Modify", "Remove from group", "Move to another group"]
+                } else {
+                    &["Modify", "Delete", "Create from template"]
+                };
             let mut text = String::new();
             for (idx, label) in options.iter().enumerate() {
                 if idx == selected {
@@ -413,8 +575,14 @@ pub fn render_user_modal(f: &mut Frame, area: Rect, app: &mut AppState, state: &
             f.render_widget(p, rect);
         }
         ModalState::ModifyMenu { selected } => {
-            let rect = crate::ui::components::centered_rect(36, 9, area);
-            let options = ["Add group", "Remove group", "Modify details", "Password"];
+            let rect = crate::ui::components::centered_rect(36, 10, area);
+            let options = [
+                "Add group",
+                "Remove group",
+                "Modify details",
+                "Password",
+                "Sudoers drop-in",
+            ];
             let mut text = String::new();
             for (idx, label) in options.iter().enumerate() {
                 if idx == selected {
@@ -493,7 +661,8 @@ pub fn render_user_modal(f: &mut Frame, area: Rect, app: &mut AppState, state: &
                 ),
                 format!("{} Submit", if selected == 3 { "▶" } else { " " }),
             ];
-            let body = lines.join("\n");
+            let mut body: Vec<Line> = lines.into_iter().map(Line::from).collect();
+            body.insert(2, crate::ui::components::password_strength_line(&password));
             let p = Paragraph::new(body).block(
                 Block::default()
                     .title("Set password")
@@ -505,7 +674,7 @@ pub fn render_user_modal(f: &mut Frame, area: Rect, app: &mut AppState, state: &
         }
         ModalState::ModifyDetailsMenu { selected } => {
             let rect = crate::ui::components::centered_rect(34, 8, area);
-            let options = ["Username", "Fullname", "Shell"];
+            let options = ["Username", "Fullname", "Shell", "GECOS fields"];
             let mut text = String::new();
             for (idx, label) in options.iter().enumerate() {
                 if idx == selected {
@@ -532,24 +701,44 @@ pub fn render_user_modal(f: &mut Frame, area: Rect, app: &mut AppState, state: &
             selected,
             offset,
             shells,
+            filter,
         } => {
             let width = (area.width.saturating_sub(10)).clamp(40, 60);
             let height = (area.height.saturating_sub(6)).clamp(8, 20);
             let rect = crate::ui::components::centered_rect(width, height, area);
-            let visible_capacity = rect.height.saturating_sub(2) as usize;
-            let start = offset.min(shells.len());
-            let end = (start + visible_capacity).min(shells.len());
-            let slice = &shells[start..end];
-            let mut items: Vec<ListItem> = Vec::with_capacity(slice.len());
-            for (i, sh) in slice.iter().enumerate() {
+            let mut entries: Vec<&str> = crate::app::matching_shells(&shells, &filter)
+                .into_iter()
+                .map(String::as_str)
+                .collect();
+            let no_match = entries.is_empty();
+            entries.push("Other…");
+            let mut items: Vec<ListItem> = Vec::new();
+            if no_match {
+                items.push(ListItem::new("  (no matching shell)"));
+            }
+            let visible_capacity = (rect.height.saturating_sub(2) as usize)
+                .saturating_sub(items.len())
+                .max(1);
+            // Scroll just far enough to keep the selection in view
+            let start = offset
+                .min(selected)
+                .max((selected + 1).saturating_sub(visible_capacity))
+                .min(entries.len());
+            let end = (start + visible_capacity).min(entries.len());
+            for (i, sh) in entries[start..end].iter().enumerate() {
                 let abs_index = start + i;
                 let marker = if abs_index == selected { "▶ " } else { "  " };
                 items.push(ListItem::new(format!("{}{}", marker, sh)));
             }
+            let title = if filter.is_empty() {
+                "Select shell (type to filter)".to_string()
+            } else {
+                format!("Select shell - filter: {}", filter)
+            };
             let list = List::new(items)
                 .block(
                     Block::default()
-                        .title("Select shell")
+                        .title(title)
                         .borders(Borders::ALL)
                         .border_style(Style::default().fg(app.theme.border)),
                 )
@@ -561,11 +750,16 @@ pub fn render_user_modal(f: &mut Frame, area: Rect, app: &mut AppState, state: &
             f.render_widget(Clear, rect);
             f.render_widget(list, rect);
         }
-        ModalState::ModifyTextInput { field, value } => {
+        ModalState::ModifyTextInput {
+            field,
+            value,
+            cursor,
+        } => {
             let rect = crate::ui::components::centered_rect(50, 7, area);
             let title = match field {
                 ModifyField::Username => "Change username",
                 ModifyField::Fullname => "Change full name",
+                ModifyField::Shell => "Shell path",
             };
             let msg = format!("{}:\n{}", title, value);
             let p = Paragraph::new(msg).block(
@@ -576,29 +770,81 @@ pub fn render_user_modal(f: &mut Frame, area: Rect, app: &mut AppState, state: &
             );
             f.render_widget(Clear, rect);
             f.render_widget(p, rect);
+            let col = crate::ui::components::width_before_cursor(&value, cursor);
+            crate::ui::components::set_input_cursor(f, rect, 1, col);
+        }
+        ModalState::ImportPathInput { path, cursor } => {
+            let rect = crate::ui::components::centered_rect(60, 7, area);
+            let msg = format!(
+                "CSV file (username,fullname,shell,groups,create_home):\n{}",
+                path
+            );
+            let p = Paragraph::new(msg).block(
+                Block::default()
+                    .title("Import users")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(app.theme.border)),
+            );
+            f.render_widget(Clear, rect);
+            f.render_widget(p, rect);
+            let col = crate::ui::components::width_before_cursor(&path, cursor);
+            crate::ui::components::set_input_cursor(f, rect, 1, col);
+        }
+        ModalState::EditGecos {
+            selected,
+            fields,
+            cursor,
+            ..
+        } => {
+            let rect = crate::ui::components::centered_rect(56, 8, area);
+            let width = crate::app::GECOS_LABELS
+                .iter()
+                .map(|l| l.len())
+                .max()
+                .unwrap_or(0);
+            let lines: Vec<Line> = crate::app::GECOS_LABELS
+                .iter()
+                .zip(fields.iter())
+                .enumerate()
+                .map(|(i, (label, value))| {
+                    let marker = if i == selected { "▶" } else { " " };
+                    Line::from(format!("{} {:<width$}  {}", marker, label, value))
+                })
+                .collect();
+            let p = Paragraph::new(lines).block(
+                Block::default()
+                    .title("Edit GECOS")
+                    .title_bottom(Line::from(" Up/Down field · Enter save ").right_aligned())
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(app.theme.border)),
+            );
+            f.render_widget(Clear, rect);
+            f.render_widget(p, rect);
+            crate::ui::components::set_input_cursor(
+                f,
+                rect,
+                selected as u16,
+                width + 4 + crate::ui::components::width_before_cursor(&fields[selected], cursor),
+            );
         }
         ModalState::ModifyGroupsAdd {
             selected,
             offset: _,
             selected_multi,
+            show_all,
         } => {
-            // Build list of eligible groups (not already a member of, and not the primary group)
-            let (username, primary_gid) = if let Some(u) = app.users.get(app.selected_user_index) {
-                (u.name.clone(), u.primary_gid)
-            } else {
-                (String::new(), 0)
-            };
-            let eligible: Vec<&crate::sys::SystemGroup> = app
-                .groups_all
-                .iter()
-                .filter(|g| g.gid != primary_gid && !g.members.iter().any(|m| m == &username))
-                .collect();
+            // Eligible groups only, or every group annotated with why it is excluded
+            let candidates = crate::app::add_group_candidates(
+                &app.groups_all,
+                app.users.get(app.selected_user_index),
+                show_all,
+            );
 
             let width = (area.width.saturating_sub(10)).clamp(40, 60);
             let height = (area.height.saturating_sub(6)).clamp(8, 20);
             let rect = crate::ui::components::centered_rect(width, height, area);
             let visible_capacity = rect.height.saturating_sub(2) as usize;
-            let total = eligible.len();
+            let total = candidates.len();
             let max_offset = total.saturating_sub(visible_capacity);
             let mut off = selected.saturating_sub(visible_capacity / 2);
             if off > max_offset {
@@ -606,25 +852,46 @@ pub fn render_user_modal(f: &mut Frame, area: Rect, app: &mut AppState, state: &
             }
             let start = off.min(total);
             let end = (start + visible_capacity).min(total);
-            let slice = &eligible[start..end];
+            let slice = &candidates[start..end];
             let mut items: Vec<ListItem> = Vec::with_capacity(slice.len());
-            for (i, g) in slice.iter().enumerate() {
+            for (i, (g, eligibility)) in slice.iter().enumerate() {
                 let abs_index = start + i;
                 let focus = if abs_index == selected { "▶ " } else { "  " };
-                let checked = if selected_multi.contains(&abs_index) {
-                    "[x] "
+                if *eligibility == GroupEligibility::Eligible {
+                    let checked = if selected_multi.contains(&g.name) {
+                        "[x] "
+                    } else {
+                        "[ ] "
+                    };
+                    items.push(ListItem::new(format!(
+                        "{}{}{} ({})",
+                        focus, checked, g.name, g.gid
+                    )));
                 } else {
-                    "[ ] "
-                };
-                items.push(ListItem::new(format!(
-                    "{}{}{} ({})",
-                    focus, checked, g.name, g.gid
-                )));
+                    items.push(
+                        ListItem::new(format!(
+                            "{}    {} ({}) [{}]",
+                            focus,
+                            g.name,
+                            g.gid,
+                            eligibility.marker()
+                        ))
+                        .style(Style::default().fg(app.theme._muted)),
+                    );
+                }
             }
+            let title = if show_all {
+                "Group to add (a: only eligible)"
+            } else {
+                "Group to add (a: show all)"
+            };
             let list = List::new(items)
                 .block(
                     Block::default()
-                        .title("Group to add")
+                        .title(title)
+                        .title_bottom(crate::ui::components::selected_count_footer(
+                            selected_multi.len(),
+                        ))
                         .borders(Borders::ALL)
                         .border_style(Style::default().fg(app.theme.border)),
                 )
@@ -669,7 +936,7 @@ pub fn render_user_modal(f: &mut Frame, area: Rect, app: &mut AppState, state: &
             for (i, g) in slice.iter().enumerate() {
                 let abs_index = start + i;
                 let focus = if abs_index == selected { "▶ " } else { "  " };
-                let checked = if selected_multi.contains(&abs_index) {
+                let checked = if selected_multi.contains(&g.name) {
                     "[x] "
                 } else {
                     "[ ] "
@@ -683,6 +950,9 @@ pub fn render_user_modal(f: &mut Frame, area: Rect, app: &mut AppState, state: &
                 .block(
                     Block::default()
                         .title("Remove from group")
+                        .title_bottom(crate::ui::components::selected_count_footer(
+                            selected_multi.len(),
+                        ))
                         .borders(Borders::ALL)
                         .border_style(Style::default().fg(app.theme.border)),
                 )
@@ -698,25 +968,46 @@ pub fn render_user_modal(f: &mut Frame, area: Rect, app: &mut AppState, state: &
             selected,
             allowed,
             delete_home,
+            home_size,
+            measuring,
         } => {
-            let rect = crate::ui::components::centered_rect(50, 7, area);
-            let (name, uid) = if let Some(u) = app.users.get(app.selected_user_index) {
-                (u.name.clone(), u.uid)
+            let (name, uid, home) = if let Some(u) = app.users.get(app.selected_user_index) {
+                (u.name.clone(), u.uid, u.home_dir.clone())
             } else {
-                (String::new(), 0)
+                (String::new(), 0, String::new())
             };
+            let mut height = 7;
             let mut body = format!("Delete user '{name}' (uid {uid})?\n\n");
             if allowed {
                 let yes = if selected == 0 { "[Yes]" } else { " Yes " };
                 let no = if selected == 1 { "[No]" } else { " No  " };
                 let checkbox = if delete_home { "[x]" } else { "[ ]" };
                 body.push_str(&format!(
-                    "  {}    {}\n\n{} Also delete home (Space)",
+                    "  {}    {}\n\n{} Also delete home (Space)\n",
                     yes, no, checkbox
                 ));
+                if delete_home {
+                    let size = if measuring {
+                        "calculating…".to_string()
+                    } else {
+                        home_size
+                            .map(crate::sys::format_size)
+                            .unwrap_or_else(|| "size unknown".to_string())
+                    };
+                    body.push_str(&format!("    removes {home} ({size})\n"));
+                    height += 1;
+                }
+                let (runs, lines) =
+                    crate::ui::components::command_preview(&PendingAction::DeleteUser {
+                        username: name.clone(),
+                        delete_home,
+                    });
+                body.push_str(&runs);
+                height += 1 + lines;
             } else {
                 body.push_str("Deletion not allowed (only UID 1000-1999 allowed). Press Esc.");
             }
+            let rect = crate::ui::components::centered_rect(60, height, area);
             let p = Paragraph::new(body).block(
                 Block::default()
                     .title("Confirm delete")
@@ -730,11 +1021,22 @@ pub fn render_user_modal(f: &mut Frame, area: Rect, app: &mut AppState, state: &
             selected,
             group_name,
         } => {
-            let rect = crate::ui::components::centered_rect(54, 7, area);
+            let username = app
+                .users
+                .get(app.selected_user_index)
+                .map(|u| u.name.clone())
+                .unwrap_or_default();
             let mut body = format!("Remove user from group '{}' ?\n\n", group_name);
             let yes = if selected == 0 { "[Yes]" } else { " Yes " };
             let no = if selected == 1 { "[No]" } else { " No  " };
-            body.push_str(&format!("  {}    {}", yes, no));
+            body.push_str(&format!("  {}    {}\n", yes, no));
+            let (runs, lines) =
+                crate::ui::components::command_preview(&PendingAction::RemoveUserFromGroup {
+                    username,
+                    groupname: group_name.clone(),
+                });
+            body.push_str(&runs);
+            let rect = crate::ui::components::centered_rect(60, 6 + lines, area);
             let p = Paragraph::new(body).block(
                 Block::default()
                     .title("Confirm removal")
@@ -749,12 +1051,20 @@ pub fn render_user_modal(f: &mut Frame, area: Rect, app: &mut AppState, state: &
             name,
             password,
             confirm,
+            cursor,
             create_home,
             add_to_wheel,
         } => {
-            let rect = crate::ui::components::centered_rect(64, 13, area);
-            let pw_mask = "*".repeat(password.len());
-            let cf_mask = "*".repeat(confirm.len());
+            let (runs, runs_lines) =
+                crate::ui::components::command_preview(&PendingAction::CreateUserWithOptions {
+                    username: name.trim().to_string(),
+                    password: (!password.is_empty()).then(|| password.clone()),
+                    create_home,
+                    add_to_wheel,
+                });
+            let rect = crate::ui::components::centered_rect(64, 13 + runs_lines, area);
+            let pw_mask = "*".repeat(password.chars().count());
+            let cf_mask = "*".repeat(confirm.chars().count());
             let ch = if create_home { "[x]" } else { "[ ]" };
             let wh = if add_to_wheel { "[x]" } else { "[ ]" };
             let lines = [
@@ -780,13 +1090,20 @@ pub fn render_user_modal(f: &mut Frame, area: Rect, app: &mut AppState, state: &
                     ch
                 ),
                 format!(
-                    "{} {} Add to wheel (sudo) group (Space)",
+                    "{} {} Add to {} (sudo) group (Space)",
                     if selected == 4 { "▶" } else { " " },
-                    wh
+                    wh,
+                    crate::app::sudo_group_name()
                 ),
                 format!("{} Submit", if selected == 5 { "▶" } else { " " }),
+                runs,
             ];
-            let body = lines.join("\n");
+            let mut body: Vec<Line> = lines
+                .iter()
+                .flat_map(|l| l.lines())
+                .map(|l| Line::from(l.to_string()))
+                .collect();
+            body.insert(4, crate::ui::components::password_strength_line(&password));
             let p = Paragraph::new(body).block(
                 Block::default()
                     .title("New user")
@@ -795,6 +1112,122 @@ pub fn render_user_modal(f: &mut Frame, area: Rect, app: &mut AppState, state: &
             );
             f.render_widget(Clear, rect);
             f.render_widget(p, rect);
+            if selected < 3 {
+                // Text rows follow the heading; each label is "▶ Xxxxxxxx: " wide
+                let field = [&name, &pw_mask, &cf_mask][selected];
+                let prefix = crate::ui::components::text_width(&lines[selected + 1])
+                    - crate::ui::components::text_width(field);
+                crate::ui::components::set_input_cursor(
+                    f,
+                    rect,
+                    selected as u16 + 1,
+                    prefix + crate::ui::components::width_before_cursor(field, cursor),
+                );
+            }
+        }
+        ModalState::CloneUser {
+            template,
+            shell,
+            groups,
+            selected,
+            name,
+            password,
+            confirm,
+            cursor,
+            create_home,
+        } => {
+            let (runs, runs_lines) =
+                crate::ui::components::command_preview(&PendingAction::CloneUser {
+                    template: template.clone(),
+                    username: name.trim().to_string(),
+                    password: (!password.is_empty()).then(|| password.clone()),
+                    create_home,
+                    shell: shell.clone(),
+                    groupnames: groups.clone(),
+                });
+            let rect = crate::ui::components::centered_rect(64, 13 + runs_lines, area);
+            let pw_mask = "*".repeat(password.chars().count());
+            let cf_mask = "*".repeat(confirm.chars().count());
+            let ch = if create_home { "[x]" } else { "[ ]" };
+            let marker = |row: usize| if selected == row { "▶" } else { " " };
+            let group_list = if groups.is_empty() {
+                "(none)".to_string()
+            } else {
+                groups.join(", ")
+            };
+            let lines = [
+                format!("Copying shell and groups from '{}'", template),
+                format!("{} Username: {}", marker(0), name),
+                format!("{} Password: {}", marker(1), pw_mask),
+                format!("{} Confirm:  {}", marker(2), cf_mask),
+                format!("{} {} Create home directory (Space)", marker(3), ch),
+                format!("{} Submit", marker(4)),
+                format!("  Shell:  {}", shell),
+                format!("  Groups: {}", group_list),
+                runs,
+            ];
+            let mut body: Vec<Line> = lines
+                .iter()
+                .flat_map(|l| l.lines())
+                .map(|l| Line::from(l.to_string()))
+                .collect();
+            body.insert(4, crate::ui::components::password_strength_line(&password));
+            let p = Paragraph::new(body)
+                .wrap(ratatui::widgets::Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .title("New user from template")
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(app.theme.border)),
+                );
+            f.render_widget(Clear, rect);
+            f.render_widget(p, rect);
+            if selected < 3 {
+                let field = [&name, &pw_mask, &cf_mask][selected];
+                let prefix = crate::ui::components::text_width(&lines[selected + 1])
+                    - crate::ui::components::text_width(field);
+                crate::ui::components::set_input_cursor(
+                    f,
+                    rect,
+                    selected as u16 + 1,
+                    prefix + crate::ui::components::width_before_cursor(field, cursor),
+                );
+            }
+        }
+        ModalState::SudoersDropin {
+            username,
+            loading,
+            content,
+            error,
+        } => {
+            let path = crate::sys::sudoers_dropin_path(&username);
+            let template = crate::sys::sudoers_dropin_content(&username);
+            let rule = template.lines().last().unwrap_or_default();
+            let body = match (content, error) {
+                _ if loading => format!("Reading {}…", path.display()),
+                (Some(text), _) => text,
+                (None, Some(err)) => format!(
+                    "Could not read {}:\n{}\n\nc: create '{}'",
+                    path.display(),
+                    err,
+                    rule
+                ),
+                (None, None) => format!(
+                    "No drop-in for '{}'.\n\nc: create '{}'\n(validated with visudo before writing)",
+                    username, rule
+                ),
+            };
+            let rect = crate::ui::components::centered_rect(64, 12, area);
+            let p = Paragraph::new(body)
+                .wrap(ratatui::widgets::Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .title(path.display().to_string())
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(app.theme.border)),
+                );
+            f.render_widget(Clear, rect);
+            f.render_widget(p, rect);
         }
         ModalState::Info { .. } => { /* routed to components */ }
         ModalState::SudoPrompt { .. } => { /* routed to components */ }
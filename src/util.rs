@@ -0,0 +1,62 @@
+//! Small helpers for handing paths over to desktop programs.
+
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Program that opens directories when `$FILE_MANAGER` is not set.
+pub const FALLBACK_OPENER: &str = "xdg-open";
+
+/// Command line used to open a path: `file_manager` split on whitespace, or
+/// [`FALLBACK_OPENER`] if it is found in `path_var`. `None` when neither is
+/// available.
+pub fn resolve_launcher(
+    file_manager: Option<&str>,
+    path_var: Option<&OsStr>,
+) -> Option<Vec<String>> {
+    if let Some(words) = file_manager
+        .map(|fm| {
+            fm.split_whitespace()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .filter(|words| !words.is_empty())
+    {
+        return Some(words);
+    }
+    std::env::split_paths(path_var?)
+        .any(|dir| dir.join(FALLBACK_OPENER).is_file())
+        .then(|| vec![FALLBACK_OPENER.to_string()])
+}
+
+/// Open `path` in `$FILE_MANAGER` (or [`FALLBACK_OPENER`]) without waiting for
+/// it, returning the program that was started.
+///
+/// Fails if `path` does not exist, if no launcher is configured, or if it
+/// cannot be started. The launcher's output is discarded so it cannot draw
+/// over the TUI.
+pub fn open_path(path: &Path) -> crate::Result<String> {
+    if !path.exists() {
+        return Err(format!("{} does not exist.", path.display()).into());
+    }
+    let file_manager = std::env::var("FILE_MANAGER").ok();
+    let path_var = std::env::var_os("PATH");
+    let Some(launcher) = resolve_launcher(file_manager.as_deref(), path_var.as_deref()) else {
+        return Err(format!(
+            "No file manager configured: set $FILE_MANAGER or install {}.",
+            FALLBACK_OPENER
+        )
+        .into());
+    };
+    let mut child = Command::new(&launcher[0])
+        .args(&launcher[1..])
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Could not start {}: {}", launcher[0], e))?;
+    // Reap the launcher once it exits instead of leaving a zombie behind
+    std::thread::spawn(move || child.wait());
+    Ok(launcher[0].clone())
+}
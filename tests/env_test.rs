@@ -0,0 +1,29 @@
+// Tests that set environment variables read by AppState::new. They live in
+// their own binary so the other test binaries never see the variables.
+
+#[test]
+fn settings_come_from_the_environment() {
+    use usrgrp_manager::app::confirmconf::sudo_grantees;
+    use usrgrp_manager::app::{ActiveTab, AppState, GroupsFocus, PendingAction, describe_command};
+
+    // SAFETY: this is the only test in this binary
+    unsafe {
+        std::env::set_var("UGM_DEFAULT_TAB", "groups");
+        std::env::set_var("UGM_DEFAULT_FOCUS", "members");
+        std::env::set_var("UGM_SUDO_GROUP", "sudo");
+    }
+    let app = AppState::new();
+    assert_eq!(app.active_tab, ActiveTab::Groups);
+    assert_eq!(app.groups_focus, GroupsFocus::Members);
+
+    // The sudo checkbox adds the user to the configured group, which is also
+    // the group the confirmation level looks at
+    let create = PendingAction::CreateUserWithOptions {
+        username: "carol".into(),
+        password: None,
+        create_home: false,
+        add_to_wheel: true,
+    };
+    assert!(describe_command(&create).ends_with("gpasswd -a carol sudo"));
+    assert_eq!(sudo_grantees(&create), ["carol"]);
+}
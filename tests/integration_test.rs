@@ -112,17 +112,53 @@ fn search_applies_filters_across_users_and_groups() {
         _table_state: TableState::default(),
         input_mode: InputMode::Normal,
         search_query: String::new(),
+        search_cursor: 0,
         theme: Theme::mocha(),
+        configured_theme: Theme::mocha(),
         keymap: usrgrp_manager::app::keymap::Keymap::default(),
-        modal: None,
+        modal_stack: Vec::new(),
         users_focus: UsersFocus::UsersList,
         groups_focus: usrgrp_manager::app::GroupsFocus::GroupsList,
         sudo_password: None,
         users_filter: Some(UsersFilter::OnlyUserIds),
         groups_filter: Some(GroupsFilter::OnlyUserGids),
         users_filter_chips: Default::default(),
+        groups_filter_chips: Default::default(),
+        filter_menu_selected: Default::default(),
         actions_context: None,
         show_keybinds: true,
+        sort_members: false,
+        pane_split: Default::default(),
+        id_radix: Default::default(),
+        live_stats: false,
+        modal_notice: None,
+        last_changed: None,
+        accounts_mtime: [None; 2],
+        accounts_stale: false,
+        running_as_root: None,
+        group_details_cache: None,
+        shells_cache: None,
+        effective_groups_cache: None,
+        locked_users_cache: None,
+        home_exists_cache: Default::default(),
+        membership_source: Default::default(),
+        layout_mode: Default::default(),
+        detail_verbosity: Default::default(),
+        privileged_groups: Default::default(),
+        empty_picker_enter: Default::default(),
+        members_include_primary: false,
+        min_password_length: 0,
+        user_columns: usrgrp_manager::app::UserColumn::ALL.to_vec(),
+        group_jump_origin: None,
+        compact_below: usrgrp_manager::app::DEFAULT_COMPACT_BELOW,
+        confirmations: usrgrp_manager::app::confirmconf::Confirmations::default(),
+        action_history: Default::default(),
+        queued_action: None,
+        pending_result: None,
+        lookup: None,
+        backend: std::sync::Arc::new(|_| panic!("these tests run no actions")),
+        #[cfg(not(feature = "read-only-build"))]
+        audit_log: None,
     };
 
     // Users search
@@ -143,7 +179,7 @@ fn search_applies_filters_across_users_and_groups() {
     assert_eq!(app.selected_group_index, 0);
 }
 
-#[cfg(unix)]
+#[cfg(all(unix, not(feature = "read-only-build")))]
 fn is_root() -> bool {
     if let Ok(s) = std::fs::read_to_string("/proc/self/status") {
         for line in s.lines() {
@@ -159,7 +195,7 @@ fn is_root() -> bool {
 
 // 3) Privileged ops require authentication when not root
 #[test]
-#[cfg(unix)]
+#[cfg(all(unix, not(feature = "read-only-build")))]
 fn privileged_ops_require_auth_without_sudo_password() {
     use usrgrp_manager::sys::SystemAdapter;
 
@@ -188,7 +224,7 @@ fn privileged_ops_require_auth_without_sudo_password() {
 
 // 4) delete_group is idempotent when group is already missing
 #[test]
-#[cfg(unix)]
+#[cfg(all(unix, not(feature = "read-only-build")))]
 fn delete_group_is_idempotent_without_sudo_when_missing() {
     use usrgrp_manager::sys::SystemAdapter;
 
@@ -253,17 +289,53 @@ fn search_mode_gating_leaves_lists_unchanged() {
         _table_state: TableState::default(),
         input_mode: InputMode::Normal,
         search_query: "alice".into(),
+        search_cursor: 0,
         theme: Theme::mocha(),
+        configured_theme: Theme::mocha(),
         keymap: usrgrp_manager::app::keymap::Keymap::default(),
-        modal: None,
+        modal_stack: Vec::new(),
         users_focus: UsersFocus::UsersList,
         groups_focus: usrgrp_manager::app::GroupsFocus::GroupsList,
         sudo_password: None,
         users_filter: None,
         groups_filter: None,
         users_filter_chips: Default::default(),
+        groups_filter_chips: Default::default(),
+        filter_menu_selected: Default::default(),
         actions_context: None,
         show_keybinds: true,
+        sort_members: false,
+        pane_split: Default::default(),
+        id_radix: Default::default(),
+        live_stats: false,
+        modal_notice: None,
+        last_changed: None,
+        accounts_mtime: [None; 2],
+        accounts_stale: false,
+        running_as_root: None,
+        group_details_cache: None,
+        shells_cache: None,
+        effective_groups_cache: None,
+        locked_users_cache: None,
+        home_exists_cache: Default::default(),
+        membership_source: Default::default(),
+        layout_mode: Default::default(),
+        detail_verbosity: Default::default(),
+        privileged_groups: Default::default(),
+        empty_picker_enter: Default::default(),
+        members_include_primary: false,
+        min_password_length: 0,
+        user_columns: usrgrp_manager::app::UserColumn::ALL.to_vec(),
+        group_jump_origin: None,
+        compact_below: usrgrp_manager::app::DEFAULT_COMPACT_BELOW,
+        confirmations: usrgrp_manager::app::confirmconf::Confirmations::default(),
+        action_history: Default::default(),
+        queued_action: None,
+        pending_result: None,
+        lookup: None,
+        backend: std::sync::Arc::new(|_| panic!("these tests run no actions")),
+        #[cfg(not(feature = "read-only-build"))]
+        audit_log: None,
     };
 
     apply_filters_and_search(&mut app);
@@ -325,17 +397,53 @@ fn search_numeric_matching_users_and_groups() {
         _table_state: TableState::default(),
         input_mode: InputMode::SearchUsers,
         search_query: "1000".into(),
+        search_cursor: 0,
         theme: Theme::mocha(),
+        configured_theme: Theme::mocha(),
         keymap: usrgrp_manager::app::keymap::Keymap::default(),
-        modal: None,
+        modal_stack: Vec::new(),
         users_focus: UsersFocus::UsersList,
         groups_focus: usrgrp_manager::app::GroupsFocus::GroupsList,
         sudo_password: None,
         users_filter: None,
         groups_filter: None,
         users_filter_chips: Default::default(),
+        groups_filter_chips: Default::default(),
+        filter_menu_selected: Default::default(),
         actions_context: None,
         show_keybinds: true,
+        sort_members: false,
+        pane_split: Default::default(),
+        id_radix: Default::default(),
+        live_stats: false,
+        modal_notice: None,
+        last_changed: None,
+        accounts_mtime: [None; 2],
+        accounts_stale: false,
+        running_as_root: None,
+        group_details_cache: None,
+        shells_cache: None,
+        effective_groups_cache: None,
+        locked_users_cache: None,
+        home_exists_cache: Default::default(),
+        membership_source: Default::default(),
+        layout_mode: Default::default(),
+        detail_verbosity: Default::default(),
+        privileged_groups: Default::default(),
+        empty_picker_enter: Default::default(),
+        members_include_primary: false,
+        min_password_length: 0,
+        user_columns: usrgrp_manager::app::UserColumn::ALL.to_vec(),
+        group_jump_origin: None,
+        compact_below: usrgrp_manager::app::DEFAULT_COMPACT_BELOW,
+        confirmations: usrgrp_manager::app::confirmconf::Confirmations::default(),
+        action_history: Default::default(),
+        queued_action: None,
+        pending_result: None,
+        lookup: None,
+        backend: std::sync::Arc::new(|_| panic!("these tests run no actions")),
+        #[cfg(not(feature = "read-only-build"))]
+        audit_log: None,
     };
 
     apply_filters_and_search(&mut app);
@@ -404,17 +512,53 @@ fn filters_apply_with_empty_query() {
         _table_state: TableState::default(),
         input_mode: InputMode::SearchUsers,
         search_query: String::new(),
+        search_cursor: 0,
         theme: Theme::mocha(),
+        configured_theme: Theme::mocha(),
         keymap: usrgrp_manager::app::keymap::Keymap::default(),
-        modal: None,
+        modal_stack: Vec::new(),
         users_focus: UsersFocus::UsersList,
         groups_focus: usrgrp_manager::app::GroupsFocus::GroupsList,
         sudo_password: None,
         users_filter: Some(UsersFilter::OnlySystemIds),
         groups_filter: Some(GroupsFilter::OnlySystemGids),
         users_filter_chips: Default::default(),
+        groups_filter_chips: Default::default(),
+        filter_menu_selected: Default::default(),
         actions_context: None,
         show_keybinds: true,
+        sort_members: false,
+        pane_split: Default::default(),
+        id_radix: Default::default(),
+        live_stats: false,
+        modal_notice: None,
+        last_changed: None,
+        accounts_mtime: [None; 2],
+        accounts_stale: false,
+        running_as_root: None,
+        group_details_cache: None,
+        shells_cache: None,
+        effective_groups_cache: None,
+        locked_users_cache: None,
+        home_exists_cache: Default::default(),
+        membership_source: Default::default(),
+        layout_mode: Default::default(),
+        detail_verbosity: Default::default(),
+        privileged_groups: Default::default(),
+        empty_picker_enter: Default::default(),
+        members_include_primary: false,
+        min_password_length: 0,
+        user_columns: usrgrp_manager::app::UserColumn::ALL.to_vec(),
+        group_jump_origin: None,
+        compact_below: usrgrp_manager::app::DEFAULT_COMPACT_BELOW,
+        confirmations: usrgrp_manager::app::confirmconf::Confirmations::default(),
+        action_history: Default::default(),
+        queued_action: None,
+        pending_result: None,
+        lookup: None,
+        backend: std::sync::Arc::new(|_| panic!("these tests run no actions")),
+        #[cfg(not(feature = "read-only-build"))]
+        audit_log: None,
     };
 
     apply_filters_and_search(&mut app);
@@ -482,7 +626,7 @@ unknown_key = #abcdef
 
 // 9) Extended auth coverage for more ops (non-root)
 #[test]
-#[cfg(unix)]
+#[cfg(all(unix, not(feature = "read-only-build")))]
 fn privileged_ops_auth_required_extended_when_not_root() {
     use usrgrp_manager::sys::SystemAdapter;
     if is_root() {
@@ -508,7 +652,7 @@ fn privileged_ops_auth_required_extended_when_not_root() {
 
 // 10) Idempotent delete_group for multiple distinct missing names
 #[test]
-#[cfg(unix)]
+#[cfg(all(unix, not(feature = "read-only-build")))]
 fn delete_group_is_idempotent_for_multiple_missing_names() {
     use usrgrp_manager::sys::SystemAdapter;
     let adapter = SystemAdapter::with_sudo_password(None);
@@ -564,3 +708,41 @@ fn theme_write_includes_header_and_all_keys_once() {
 
     let _ = std::fs::remove_file(&p);
 }
+
+// 11) A read-only build has no account-modifying adapter methods
+#[test]
+#[cfg(feature = "read-only-build")]
+fn read_only_build_compiles_out_mutating_methods() {
+    use usrgrp_manager::sys::SystemAdapter;
+
+    // Inherent methods win over trait methods, so each call below only resolves
+    // to `Absent` (and only type-checks) if the adapter lacks the real method.
+    trait Absent {
+        fn add_user_to_group(&self, _: &str, _: &str) -> &'static str {
+            "absent"
+        }
+        fn create_group(&self, _: &str) -> &'static str {
+            "absent"
+        }
+        fn delete_user(&self, _: &str, _: bool) -> &'static str {
+            "absent"
+        }
+        fn set_user_password(&self, _: &str, _: &str) -> &'static str {
+            "absent"
+        }
+        fn create_sudoers_dropin(&self, _: &str) -> &'static str {
+            "absent"
+        }
+    }
+    impl Absent for SystemAdapter {}
+
+    let adapter = SystemAdapter::new();
+    assert_eq!(adapter.add_user_to_group("root", "root"), "absent");
+    assert_eq!(adapter.create_group("ugm_test"), "absent");
+    assert_eq!(adapter.delete_user("root", false), "absent");
+    assert_eq!(adapter.set_user_password("root", "x"), "absent");
+    assert_eq!(adapter.create_sudoers_dropin("root"), "absent");
+
+    assert!(usrgrp_manager::cli::parse_args(["add-to-group", "alice", "devs"]).is_err());
+    assert!(adapter.list_groups().is_ok());
+}
@@ -1,6 +1,29 @@
 // Unit tests for usrgrp-manager
 // These tests work with the public API without modifying the main codebase
 
+use usrgrp_manager::sys::{SystemGroup, SystemUser};
+
+/// User `name` whose primary group has the same id, shared by the modules below.
+fn create_test_user(name: &str, uid: u32) -> SystemUser {
+    SystemUser {
+        uid,
+        name: name.to_string(),
+        primary_gid: uid,
+        full_name: Some(format!("{} User", name)),
+        home_dir: format!("/home/{}", name),
+        shell: "/bin/bash".to_string(),
+    }
+}
+
+/// Group `name` listing `members`, shared by the modules below.
+fn create_test_group(name: &str, gid: u32, members: Vec<String>) -> SystemGroup {
+    SystemGroup {
+        gid,
+        name: name.to_string(),
+        members,
+    }
+}
+
 #[cfg(test)]
 mod sys_tests {
     use usrgrp_manager::sys::{SystemAdapter, SystemGroup, SystemUser};
@@ -82,15 +105,17 @@ mod sys_tests {
 
 #[cfg(test)]
 mod search_tests {
+    use super::{create_test_group, create_test_user};
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
     use ratatui::widgets::TableState;
     use usrgrp_manager::app::keymap::KeyAction;
     use usrgrp_manager::app::keymap::Keymap;
     use usrgrp_manager::app::{ActiveTab, AppState, InputMode, Theme, UsersFocus};
     use usrgrp_manager::search::{
-        apply_filters_and_search, clear_shadow_provider, set_shadow_provider,
+        apply_filters_and_search, clear_shadow_provider, hidden_group_matches, hidden_user_matches,
+        set_shadow_provider,
     };
-    use usrgrp_manager::sys::{SystemGroup, SystemUser};
+    use usrgrp_manager::sys::SystemUser;
 
     fn create_test_app() -> AppState {
         AppState {
@@ -107,36 +132,53 @@ mod search_tests {
             _table_state: TableState::default(),
             input_mode: InputMode::Normal,
             search_query: String::new(),
+            search_cursor: 0,
             theme: Theme::dark(),
+            configured_theme: Theme::dark(),
             keymap: Keymap::default(),
-            modal: None,
+            modal_stack: Vec::new(),
             users_focus: UsersFocus::UsersList,
             groups_focus: usrgrp_manager::app::GroupsFocus::GroupsList,
             sudo_password: None,
             users_filter: None,
             groups_filter: None,
             users_filter_chips: Default::default(),
+            groups_filter_chips: Default::default(),
+            filter_menu_selected: Default::default(),
             actions_context: None,
             show_keybinds: true,
-        }
-    }
-
-    fn create_test_user(name: &str, uid: u32) -> SystemUser {
-        SystemUser {
-            uid,
-            name: name.to_string(),
-            primary_gid: uid,
-            full_name: Some(format!("{} User", name)),
-            home_dir: format!("/home/{}", name),
-            shell: "/bin/bash".to_string(),
-        }
-    }
-
-    fn create_test_group(name: &str, gid: u32, members: Vec<String>) -> SystemGroup {
-        SystemGroup {
-            gid,
-            name: name.to_string(),
-            members,
+            sort_members: false,
+            pane_split: Default::default(),
+            id_radix: Default::default(),
+            live_stats: false,
+            modal_notice: None,
+            last_changed: None,
+            accounts_mtime: [None; 2],
+            accounts_stale: false,
+            running_as_root: None,
+            group_details_cache: None,
+            shells_cache: None,
+            effective_groups_cache: None,
+            locked_users_cache: None,
+            home_exists_cache: Default::default(),
+            membership_source: Default::default(),
+            layout_mode: Default::default(),
+            detail_verbosity: Default::default(),
+            privileged_groups: Default::default(),
+            empty_picker_enter: Default::default(),
+            members_include_primary: false,
+            min_password_length: 0,
+            user_columns: usrgrp_manager::app::UserColumn::ALL.to_vec(),
+            group_jump_origin: None,
+            compact_below: usrgrp_manager::app::DEFAULT_COMPACT_BELOW,
+            confirmations: usrgrp_manager::app::confirmconf::Confirmations::default(),
+            action_history: Default::default(),
+            queued_action: None,
+            pending_result: None,
+            lookup: None,
+            backend: std::sync::Arc::new(|_| panic!("these tests run no actions")),
+            #[cfg(not(feature = "read-only-build"))]
+            audit_log: None,
         }
     }
 
@@ -354,6 +396,58 @@ mod search_tests {
         clear_shadow_provider();
     }
 
+    #[test]
+    fn group_details_are_cached_until_selection_or_mtime_changes() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+        use std::time::{Duration, UNIX_EPOCH};
+        use usrgrp_manager::app::selected_group_details;
+
+        let mut app = create_test_app();
+        app.live_stats = true;
+        app.users_all = vec![
+            create_test_user("alice", 1000),
+            create_test_user("bob", 1001),
+        ];
+        app.groups = vec![
+            create_test_group("devs", 2000, vec!["alice".into(), "bob".into()]),
+            create_test_group("ops", 2001, vec!["bob".into()]),
+        ];
+        let reads = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&reads);
+        set_shadow_provider(move || {
+            counter.set(counter.get() + 1);
+            let mut m = std::collections::HashMap::new();
+            m.insert(
+                "alice".to_string(),
+                usrgrp_manager::search::make_shadow_status(true, false, false),
+            );
+            Ok(m)
+        });
+        let mtime = Some(UNIX_EPOCH + Duration::from_secs(86_400));
+
+        let first = selected_group_details(&mut app, mtime).unwrap();
+        assert_eq!(first.locked_count, 1);
+        assert_eq!(first.members_preview, "alice, bob");
+        assert_eq!(reads.get(), 1);
+
+        // Same group and mtime: repeated renders reuse the cached figures
+        for _ in 0..3 {
+            assert_eq!(selected_group_details(&mut app, mtime).unwrap(), first);
+        }
+        assert_eq!(reads.get(), 1);
+
+        let touched = Some(UNIX_EPOCH + Duration::from_secs(2 * 86_400));
+        selected_group_details(&mut app, touched).unwrap();
+        assert_eq!(reads.get(), 2);
+
+        app.selected_group_index = 1;
+        let ops = selected_group_details(&mut app, touched).unwrap();
+        assert_eq!(ops.members_preview, "bob");
+        assert_eq!(reads.get(), 3);
+        clear_shadow_provider();
+    }
+
     #[test]
     fn shadow_filters_include_exclude_as_expected() {
         // alice locked, bob expired, carol no_password
@@ -408,6 +502,158 @@ mod search_tests {
         clear_shadow_provider();
     }
 
+    #[test]
+    fn no_primary_group_chip_shows_only_dangling_gids() {
+        let mut app = create_test_app();
+        let mut dangling = create_test_user("bob", 1001);
+        dangling.primary_gid = 4242;
+        app.users_all = vec![create_test_user("alice", 1000), dangling];
+        app.groups_all = vec![create_test_group("alice", 1000, vec![])];
+
+        app.users_filter_chips.no_primary_group = true;
+        apply_filters_and_search(&mut app);
+        assert_eq!(app.users.len(), 1);
+        assert_eq!(app.users[0].name, "bob");
+
+        // Persisted alongside the other chips in filter.conf
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("filter.conf");
+        let path = path.to_str().unwrap();
+        usrgrp_manager::app::filterconf::FiltersConfig::save_from_app(&app, path).unwrap();
+        let loaded = usrgrp_manager::app::filterconf::FiltersConfig::from_file(path).unwrap();
+        assert!(loaded.no_primary_group);
+    }
+
+    #[test]
+    fn groups_filter_chips_combine_and_persist() {
+        let mut app = create_test_app();
+        let sudo = usrgrp_manager::app::sudo_group_name();
+        app.users_all = vec![create_test_user("alice", 1000)];
+        app.groups_all = vec![
+            create_test_group(&sudo, 10, vec!["alice".into()]),
+            create_test_group("audio", 29, vec![]),
+            create_test_group("alice", 1000, vec![]),
+            create_test_group("devs", 2000, vec!["alice".into(), "ghost".into()]),
+            create_test_group("empty", 2001, vec![]),
+        ];
+        let names = |app: &usrgrp_manager::app::AppState| -> Vec<String> {
+            app.groups.iter().map(|g| g.name.clone()).collect()
+        };
+
+        // "alice" has no members but is alice's primary group, so it is not empty
+        app.groups_filter_chips.empty_only = true;
+        apply_filters_and_search(&mut app);
+        assert_eq!(names(&app), ["audio", "empty"]);
+
+        app.groups_filter_chips.user_only = true;
+        apply_filters_and_search(&mut app);
+        assert_eq!(names(&app), ["empty"]);
+
+        app.groups_filter_chips = Default::default();
+        app.groups_filter_chips.orphan_members = true;
+        apply_filters_and_search(&mut app);
+        assert_eq!(names(&app), ["devs"]);
+
+        app.groups_filter_chips = Default::default();
+        app.groups_filter_chips.has_sudo_flag = true;
+        app.groups_filter_chips.system_only = true;
+        apply_filters_and_search(&mut app);
+        assert_eq!(names(&app), [sudo.as_str()]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("filter.conf");
+        let path = path.to_str().unwrap();
+        usrgrp_manager::app::filterconf::FiltersConfig::save_from_app(&app, path).unwrap();
+        let mut restored = create_test_app();
+        usrgrp_manager::app::filterconf::FiltersConfig::from_file(path)
+            .unwrap()
+            .apply_to(&mut restored);
+        assert!(restored.groups_filter_chips.has_sudo_flag);
+        assert!(restored.groups_filter_chips.system_only);
+        assert!(!restored.groups_filter_chips.orphan_members);
+        // Group chips use their own keys and leave the users chips alone
+        assert!(!restored.users_filter_chips.system_only);
+    }
+
+    #[test]
+    fn hide_system_users_default_starts_with_only_human_users() {
+        use usrgrp_manager::app::filterconf::FiltersConfig;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("filter.conf");
+        let path = path.to_str().unwrap();
+        std::fs::write(
+            path,
+            "system_only = true\nhide_system_users_default = true\n",
+        )
+        .unwrap();
+
+        let mut app = create_test_app();
+        app.users_all = vec![
+            create_test_user("root", 0),
+            create_test_user("daemon", 1),
+            create_test_user("alice", 1000),
+            create_test_user("bob", 1001),
+        ];
+        FiltersConfig::from_file(path).unwrap().apply_to(&mut app);
+        apply_filters_and_search(&mut app);
+        assert!(app.users_filter_chips.human_only);
+        assert!(!app.users_filter_chips.system_only);
+        let names: Vec<&str> = app.users.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(names, ["alice", "bob"]);
+
+        // Toggling chips rewrites the file but keeps the startup flag
+        app.users_filter_chips.human_only = false;
+        FiltersConfig::save_from_app(&app, path).unwrap();
+        let saved = FiltersConfig::from_file(path).unwrap();
+        assert!(saved.hide_system_users_default);
+        assert!(!saved.human_only);
+    }
+
+    #[test]
+    fn hidden_matches_count_entries_excluded_by_filters() {
+        let users = vec![
+            create_test_user("svc-backup", 120),
+            create_test_user("svc-web", 121),
+            create_test_user("svc-mail", 1005),
+            create_test_user("alice", 1000),
+        ];
+        let mut chips = usrgrp_manager::app::UsersFilterChips {
+            human_only: true,
+            ..Default::default()
+        };
+        // Two system accounts match "svc" but the human-only chip hides them
+        assert_eq!(hidden_user_matches(&users, &[], None, &chips, "SVC"), 2);
+        assert_eq!(hidden_user_matches(&users, &[], None, &chips, "bob"), 0);
+
+        chips.human_only = false;
+        assert_eq!(hidden_user_matches(&users, &[], None, &chips, "svc"), 0);
+        assert_eq!(
+            hidden_user_matches(
+                &users,
+                &[],
+                Some(usrgrp_manager::app::UsersFilter::OnlySystemIds),
+                &chips,
+                "svc"
+            ),
+            1
+        );
+
+        let groups = vec![
+            create_test_group("docker", 998, vec![]),
+            create_test_group("devs", 1001, vec![]),
+        ];
+        assert_eq!(
+            hidden_group_matches(
+                &groups,
+                &[],
+                Some(usrgrp_manager::app::GroupsFilter::OnlyUserGids),
+                &Default::default(),
+                "d"
+            ),
+            1
+        );
+    }
+
     #[test]
     fn no_home_filter_includes_only_nonexistent_paths() {
         use std::time::{SystemTime, UNIX_EPOCH};
@@ -510,11 +756,127 @@ mod error_handling_tests {
     }
 }
 
+#[cfg(test)]
+mod import_tests {
+    use usrgrp_manager::app::import::{parse_csv, valid_username};
+    use usrgrp_manager::sys::{SystemGroup, SystemUser};
+
+    #[test]
+    fn test_valid_import_usernames() {
+        assert!(valid_username("alice"));
+        assert!(valid_username("_svc-01"));
+        assert!(valid_username("host$"));
+        assert!(!valid_username("Alice"));
+        assert!(!valid_username("1user"));
+        assert!(!valid_username("a b"));
+        assert!(!valid_username(&"a".repeat(33)));
+    }
+
+    #[test]
+    fn test_parse_import_csv_skips_bad_rows() {
+        let users = vec![SystemUser {
+            uid: 1000,
+            name: "alice".to_string(),
+            primary_gid: 1000,
+            full_name: None,
+            home_dir: "/home/alice".to_string(),
+            shell: "/bin/bash".to_string(),
+        }];
+        let groups = vec![SystemGroup {
+            gid: 100,
+            name: "users".to_string(),
+            members: vec![],
+        }];
+        let csv = "# new hires\n\
+                   username,fullname,shell,groups,create_home\n\
+                   bob,\"Bob \"\"B\"\", Jr\",/bin/bash,users;wheel,no\n\
+                   carol, Carol ,,users,\n\
+                   alice\n\
+                   Dave\n\
+                   erin,,bash\n\
+                   frank,,,,maybe\n\
+                   carol\n";
+        let groups = [
+            groups,
+            vec![SystemGroup {
+                gid: 10,
+                name: "wheel".to_string(),
+                members: vec![],
+            }],
+        ]
+        .concat();
+        let (rows, skipped) = parse_csv(csv, &users, &groups);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].full_name, "Bob \"B\", Jr");
+        assert_eq!(rows[0].groups, ["users", "wheel"]);
+        assert!(!rows[0].create_home);
+        assert_eq!(rows[1].full_name, "Carol");
+        assert!(rows[1].shell.is_empty());
+        assert!(rows[1].create_home);
+        assert_eq!(
+            skipped,
+            [
+                "line 5: user 'alice' already exists",
+                "line 6: invalid username 'Dave'",
+                "line 7: shell 'bash' is not an absolute path",
+                "line 8: create_home 'maybe' is not yes or no",
+                "line 9: user 'carol' already exists",
+            ]
+        );
+    }
+}
+
 #[cfg(test)]
 mod app_state_tests {
+    use super::{create_test_group, create_test_user};
     use usrgrp_manager::app::{
-        ActiveTab, AppState, InputMode, ModalState, ModifyField, PendingAction, Theme, UsersFocus,
+        ActiveTab, AppState, GroupEligibility, IdRadix, InputMode, ModalState, ModifyField,
+        PaneSplit, PendingAction, RECENT_CHANGE_HIGHLIGHT, Theme, UsersFocus,
+        account_files_advanced, add_group_candidates, change_highlight_active, checked_items,
+        deletion_blockers, describe_command, displayed_members, format_id, group_eligibility,
+        invert_selection, join_gecos, split_gecos,
     };
+    use usrgrp_manager::sys::SystemUser;
+
+    #[test]
+    fn test_unknown_start_view_values_are_ignored() {
+        let mut app = AppState::default();
+        let tab = app.active_tab;
+        app.apply_start_view(Some("sideways"), Some("nowhere"));
+        assert_eq!(app.active_tab, tab);
+        assert_eq!(app.users_focus, UsersFocus::UsersList);
+        app.apply_start_view(Some(" Groups "), Some("members"));
+        assert_eq!(app.active_tab, ActiveTab::Groups);
+        assert_eq!(app.users_focus, UsersFocus::MemberOf);
+    }
+
+    #[test]
+    fn test_account_files_advanced_only_when_newer() {
+        use std::time::{Duration, UNIX_EPOCH};
+        let t = |secs| Some(UNIX_EPOCH + Duration::from_secs(secs));
+        assert!(!account_files_advanced(&[t(10), t(20)], &[t(10), t(20)]));
+        assert!(account_files_advanced(&[t(10), t(20)], &[t(10), t(21)]));
+        assert!(account_files_advanced(&[None, t(20)], &[t(5), t(20)]));
+        // An older or unreadable file is not a new change
+        assert!(!account_files_advanced(&[t(10), t(20)], &[t(9), None]));
+    }
+
+    #[test]
+    fn test_gecos_split_and_join() {
+        let (fields, other) = split_gecos("John Doe,,,");
+        assert_eq!(fields, ["John Doe", "", "", ""]);
+        assert_eq!(other, "");
+        assert_eq!(join_gecos(&fields, &other), "John Doe");
+
+        // Missing trailing fields are empty, extra ones are kept
+        assert_eq!(split_gecos("").0, ["", "", "", ""]);
+        assert_eq!(split_gecos("Ann,B12").0, ["Ann", "B12", "", ""]);
+        let (fields, other) = split_gecos("Ann,B12,1,2,ann@example.com");
+        assert_eq!(other, "ann@example.com");
+        assert_eq!(join_gecos(&fields, &other), "Ann,B12,1,2,ann@example.com");
+        let fields = [String::new(), String::new(), "3".into(), String::new()];
+        assert_eq!(join_gecos(&fields, ""), ",,3");
+    }
 
     #[test]
     fn test_app_state_creation() {
@@ -596,6 +958,7 @@ mod app_state_tests {
             name: String::new(),
             password: String::new(),
             confirm: String::new(),
+            cursor: 0,
             create_home: true,
             add_to_wheel: false,
         };
@@ -635,6 +998,503 @@ mod app_state_tests {
         };
         assert!(matches!(action, PendingAction::CreateGroup { .. }));
     }
+
+    #[test]
+    fn test_recent_change_highlight_window() {
+        use std::time::{Duration, Instant};
+
+        assert!(change_highlight_active(Duration::ZERO));
+        assert!(change_highlight_active(
+            RECENT_CHANGE_HIGHLIGHT - Duration::from_millis(1)
+        ));
+        assert!(!change_highlight_active(RECENT_CHANGE_HIGHLIGHT));
+        assert!(!change_highlight_active(Duration::from_secs(60)));
+
+        let mut app = AppState::new();
+        app.last_changed = Some((ActiveTab::Users, "alice".to_string(), Instant::now()));
+        assert!(app.is_recently_changed(ActiveTab::Users, "alice"));
+        assert!(!app.is_recently_changed(ActiveTab::Groups, "alice"));
+        assert!(!app.is_recently_changed(ActiveTab::Users, "bob"));
+
+        // Only stale once the window has passed
+        if let Some(stale) = Instant::now().checked_sub(RECENT_CHANGE_HIGHLIGHT) {
+            app.last_changed = Some((ActiveTab::Users, "alice".to_string(), stale));
+            assert!(!app.is_recently_changed(ActiveTab::Users, "alice"));
+        }
+    }
+
+    #[test]
+    fn test_group_eligibility_annotations() {
+        let user = create_test_user("alice", 1000);
+        let groups = vec![
+            create_test_group("wheel", 10, vec!["root".into(), "alice".into()]),
+            create_test_group("users", 100, vec![]),
+            create_test_group("alice", 1000, vec![]),
+        ];
+
+        assert_eq!(
+            group_eligibility(&user, &groups[0]),
+            GroupEligibility::AlreadyMember
+        );
+        assert_eq!(
+            group_eligibility(&user, &groups[1]),
+            GroupEligibility::Eligible
+        );
+        assert_eq!(
+            group_eligibility(&user, &groups[2]),
+            GroupEligibility::Primary
+        );
+        assert_eq!(GroupEligibility::Primary.marker(), "primary");
+
+        let eligible = add_group_candidates(&groups, Some(&user), false);
+        assert_eq!(eligible.len(), 1);
+        assert_eq!(eligible[0].0.name, "users");
+
+        let all = add_group_candidates(&groups, Some(&user), true);
+        let marked: Vec<_> = all.iter().map(|(g, e)| (g.name.as_str(), *e)).collect();
+        assert_eq!(
+            marked,
+            vec![
+                ("wheel", GroupEligibility::AlreadyMember),
+                ("users", GroupEligibility::Eligible),
+                ("alice", GroupEligibility::Primary),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pane_split_shift_clamps_at_minimums() {
+        let mut split = PaneSplit::default();
+        split.shift(0, PaneSplit::STEP);
+        assert_eq!((split.table, split.details, split.keybinds), (46, 29, 25));
+
+        // Shrinking the details column below its minimum stops at the minimum
+        split.shift(0, 50);
+        assert_eq!((split.table, split.details), (55, PaneSplit::MIN[1]));
+        split.shift(1, 50);
+        assert_eq!((split.details, split.keybinds), (30, PaneSplit::MIN[2]));
+
+        // Same for the table and details when moving borders the other way
+        split.shift(0, -100);
+        assert_eq!((split.table, split.details), (PaneSplit::MIN[0], 65));
+        split.shift(1, -100);
+        assert_eq!((split.details, split.keybinds), (PaneSplit::MIN[1], 60));
+        assert_eq!(split.table + split.details + split.keybinds, 100);
+
+        // Stored widths that break the rules fall back to the default
+        assert_eq!(PaneSplit::new(70, 20, 10), PaneSplit::default());
+        assert_eq!(PaneSplit::new(50, 30, 30), PaneSplit::default());
+        assert_eq!(PaneSplit::new(50, 30, 20).table_without_keybinds(), 62);
+    }
+
+    #[test]
+    fn test_ui_conf_roundtrips_pane_split() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ui.conf");
+        let path = path.to_str().unwrap();
+        let cfg = usrgrp_manager::app::uiconf::UiConfig {
+            pane_split: PaneSplit::new(30, 45, 25),
+            live_stats: true,
+            empty_selection_enter: usrgrp_manager::app::EmptyPickerEnter::RequireChecked,
+            ..Default::default()
+        };
+        cfg.write_file(path).unwrap();
+        let loaded = usrgrp_manager::app::uiconf::UiConfig::from_file(path).unwrap();
+        assert_eq!(loaded.pane_split, PaneSplit::new(30, 45, 25));
+        assert!(loaded.live_stats);
+        assert_eq!(
+            loaded.empty_selection_enter,
+            usrgrp_manager::app::EmptyPickerEnter::RequireChecked
+        );
+    }
+
+    #[test]
+    fn test_privileges_conf_labels_configured_groups() {
+        use usrgrp_manager::app::privconf::PrivilegedGroups;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("privileges.conf");
+        let path = path.to_str().unwrap();
+
+        let defaults = PrivilegedGroups::load_or_init(path);
+        assert_eq!(
+            defaults.label("docker"),
+            Some("root-equivalent (Docker daemon)")
+        );
+        assert_eq!(PrivilegedGroups::from_file(path).unwrap(), defaults);
+
+        std::fs::write(path, "# custom\ndocker = root via dockerd\nbackup =\n").unwrap();
+        let cfg = PrivilegedGroups::from_file(path).unwrap();
+        assert_eq!(cfg.label("docker"), Some("root via dockerd"));
+        assert_eq!(cfg.label("backup"), Some("privileged"));
+        assert_eq!(cfg.label("wheel"), None);
+        assert_eq!(cfg.label("users"), None);
+    }
+
+    #[test]
+    fn test_ui_conf_hidden_home_column_drops_header_and_width() {
+        use ratatui::layout::Constraint;
+        use usrgrp_manager::app::UserColumn;
+        use usrgrp_manager::app::uiconf::UiConfig;
+        use usrgrp_manager::ui::users::user_table_layout;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ui.conf");
+        let path = path.to_str().unwrap();
+
+        let (headers, widths) = user_table_layout(&UiConfig::default().user_columns);
+        assert_eq!(headers, vec!["UID", "USER", "GID", "DIR", "HOME", "SHELL"]);
+        assert_eq!(widths.len(), 6);
+        assert_eq!(widths[3], Constraint::Length(3));
+
+        std::fs::write(path, "user_columns = uid, user, gid, shell\n").unwrap();
+        let cfg = UiConfig::from_file(path).unwrap();
+        let (headers, widths) = user_table_layout(&cfg.user_columns);
+        assert_eq!(headers, vec!["UID", "USER", "GID", "SHELL"]);
+        assert_eq!(widths.len(), headers.len());
+        assert_eq!(widths[3], Constraint::Percentage(80));
+
+        // USER cannot be hidden; unknown names are skipped
+        assert_eq!(
+            UserColumn::parse_list("shell, bogus, shell"),
+            vec![UserColumn::Name, UserColumn::Shell]
+        );
+    }
+
+    #[test]
+    fn test_keybinds_reference_lists_custom_bindings() {
+        use usrgrp_manager::app::keymap::{Keymap, export_keybinds};
+        let dir = tempfile::tempdir().unwrap();
+        let conf = dir.path().join("keybinds.conf");
+        let reference = dir.path().join("keybinds_reference.txt");
+        std::fs::write(&conf, "Quit = Ctrl+q\nCopyCommand = Y\n").unwrap();
+        let keymap = Keymap::from_file(conf.to_str().unwrap()).unwrap();
+
+        export_keybinds(&keymap, reference.to_str().unwrap()).unwrap();
+        let text = std::fs::read_to_string(&reference).unwrap();
+        let general = text.find("General:").unwrap();
+        let navigation = text.find("Navigation:").unwrap();
+        // Added keys join the defaults under the panel's labels
+        assert!(text.contains("  Quit: Ctrl+q, q\n"));
+        assert!(text.contains("  Copy shell command: Y, y\n"));
+        assert!(text.contains("  Move down: Down, j\n"));
+        assert!(general < text.find("  Quit:").unwrap());
+        assert!(navigation < text.find("  Move down:").unwrap());
+    }
+
+    #[test]
+    fn test_saving_keybinds_keeps_custom_bindings() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        use usrgrp_manager::app::keymap::{KeyAction, Keymap};
+        let dir = tempfile::tempdir().unwrap();
+        let conf = dir.path().join("keybinds.conf");
+        let conf = conf.to_str().unwrap();
+
+        Keymap::default().write_file(conf).unwrap();
+        assert!(!std::fs::read_to_string(conf).unwrap().contains("# Custom"));
+
+        std::fs::write(conf, "Quit = Ctrl+q\nSaveConfig = W\n").unwrap();
+        Keymap::from_file(conf).unwrap().write_file(conf).unwrap();
+        let text = std::fs::read_to_string(conf).unwrap();
+        assert!(
+            text.contains("# Custom\nQuit = Ctrl+q\nSaveConfig = W\n"),
+            "{text}"
+        );
+
+        let saved = Keymap::from_file(conf).unwrap();
+        let ctrl_q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL);
+        assert_eq!(saved.resolve(&ctrl_q), Some(KeyAction::Quit));
+        let w = KeyEvent::new(KeyCode::Char('W'), KeyModifiers::NONE);
+        assert_eq!(saved.resolve(&w), Some(KeyAction::SaveConfig));
+    }
+
+    #[test]
+    fn test_confirmations_conf_overrides_listed_categories() {
+        use usrgrp_manager::app::confirmconf::{ConfirmLevel, Confirmations};
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("confirmations.conf");
+        let path = path.to_str().unwrap();
+
+        // A missing file is created with the defaults
+        let cfg = Confirmations::load_or_init(path);
+        assert_eq!(cfg, Confirmations::default());
+        assert_eq!(Confirmations::from_file(path).unwrap(), cfg);
+
+        std::fs::write(
+            path,
+            "delete_user = typed\nset_password = YesNo\ndelete_group = maybe\n",
+        )
+        .unwrap();
+        let cfg = Confirmations::from_file(path).unwrap();
+        assert_eq!(cfg.delete_user, ConfirmLevel::Typed);
+        assert_eq!(cfg.set_password, ConfirmLevel::YesNo);
+        assert_eq!(cfg.delete_group, ConfirmLevel::YesNo);
+        assert_eq!(cfg.remove_from_sudo, ConfirmLevel::YesNo);
+        assert_eq!(cfg.grant_sudo, ConfirmLevel::YesNo);
+
+        std::fs::write(path, "grant_sudo = none\n").unwrap();
+        assert_eq!(
+            Confirmations::from_file(path).unwrap().grant_sudo,
+            ConfirmLevel::None
+        );
+    }
+
+    #[test]
+    fn test_ui_conf_default_tab_falls_back_to_users() {
+        use usrgrp_manager::app::uiconf::UiConfig;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ui.conf");
+        let path = path.to_str().unwrap();
+
+        std::fs::write(path, "default_tab = groups\n").unwrap();
+        let cfg = UiConfig::from_file(path).unwrap();
+        assert_eq!(cfg.default_tab, ActiveTab::Groups);
+        let mut app = AppState::default();
+        cfg.apply_to(&mut app);
+        assert_eq!(app.active_tab, ActiveTab::Groups);
+
+        for bad in ["default_tab = both", "default_tab =", "default_tab = 2"] {
+            std::fs::write(path, bad).unwrap();
+            assert_eq!(
+                UiConfig::from_file(path).unwrap().default_tab,
+                ActiveTab::Users,
+                "{bad}"
+            );
+        }
+
+        // Saving new column widths leaves the startup tab alone
+        std::fs::write(path, "default_tab = Groups\n").unwrap();
+        UiConfig::save_from_app(&app, path).unwrap();
+        assert_eq!(
+            UiConfig::from_file(path).unwrap().default_tab,
+            ActiveTab::Groups
+        );
+    }
+
+    #[test]
+    fn test_format_id_in_each_radix() {
+        assert_eq!(format_id(1000, IdRadix::Decimal), "1000");
+        assert_eq!(format_id(1000, IdRadix::Hex), "0x3e8");
+        assert_eq!(format_id(1000, IdRadix::Octal), "0o1750");
+        assert_eq!(format_id(0, IdRadix::Hex), "0x0");
+        assert_eq!(format_id(4096, IdRadix::Hex), "0x1000");
+        assert_eq!(format_id(65534, IdRadix::Octal), "0o177776");
+
+        assert_eq!(IdRadix::default(), IdRadix::Decimal);
+        assert_eq!(IdRadix::Octal.next(), IdRadix::Decimal);
+        for radix in [IdRadix::Decimal, IdRadix::Hex, IdRadix::Octal] {
+            assert_eq!(IdRadix::parse(radix.as_str()), Some(radix));
+        }
+        assert_eq!(IdRadix::parse("binary"), None);
+    }
+
+    #[test]
+    fn test_describe_command_maps_actions_to_commands() {
+        assert_eq!(
+            describe_command(&PendingAction::AddUserToGroup {
+                username: "alice".into(),
+                groupname: "devs".into(),
+            }),
+            "gpasswd -a alice devs"
+        );
+        assert_eq!(
+            describe_command(&PendingAction::DeleteUser {
+                username: "alice".into(),
+                delete_home: true,
+            }),
+            "userdel -r alice"
+        );
+        assert_eq!(
+            describe_command(&PendingAction::ChangeFullname {
+                username: "alice".into(),
+                new_fullname: "Alice O'Neil".into(),
+            }),
+            "usermod -c 'Alice O'\\''Neil' alice"
+        );
+        assert_eq!(
+            describe_command(&PendingAction::MoveUserBetweenGroups {
+                username: "bob".into(),
+                from_group: "devs".into(),
+                to_group: "ops".into(),
+            }),
+            "gpasswd -d bob devs\ngpasswd -a bob ops"
+        );
+        let remove = PendingAction::RemoveUserFromGroups {
+            username: "bob".into(),
+            groupnames: vec!["devs".into(), "ops".into()],
+        };
+        assert_eq!(
+            describe_command(&remove),
+            "usermod -G <remaining groups> bob\n\
+             # fallback: gpasswd -d bob devs\n\
+             # fallback: gpasswd -d bob ops"
+        );
+        assert_eq!(
+            usrgrp_manager::app::ready_to_run_command(&remove)
+                .lines()
+                .next(),
+            Some("sudo usermod -G <remaining groups> bob")
+        );
+        assert!(
+            usrgrp_manager::app::ready_to_run_command(&remove)
+                .ends_with("\n# fallback: gpasswd -d bob ops")
+        );
+        assert_eq!(
+            describe_command(&PendingAction::RenameGroup {
+                old_name: "devs".into(),
+                new_name: "eng".into(),
+            }),
+            "groupmod -n eng devs"
+        );
+
+        let create = describe_command(&PendingAction::CreateUserWithOptions {
+            username: "carol".into(),
+            password: Some("hunter2".into()),
+            create_home: true,
+            add_to_wheel: true,
+        });
+        assert_eq!(
+            create.lines().collect::<Vec<_>>(),
+            [
+                "useradd -m carol",
+                "chpasswd  # carol:<password> on stdin",
+                "gpasswd -a carol wheel",
+            ]
+        );
+        let set = describe_command(&PendingAction::SetPassword {
+            username: "carol".into(),
+            password: "hunter2".into(),
+            must_change: true,
+        });
+        assert!(!set.contains("hunter2"));
+        assert!(set.ends_with("chage -d 0 carol"));
+    }
+
+    #[test]
+    fn test_deletion_blockers_lists_every_reason() {
+        use usrgrp_manager::sys::{clear_process_count_provider, set_process_count_provider};
+        let bob = SystemUser {
+            primary_gid: 500,
+            ..create_test_user("bob", 1001)
+        };
+        let app = AppState {
+            users_all: vec![create_test_user("svc", 500), bob],
+            groups_all: vec![create_test_group("svc", 500, vec![])],
+            ..AppState::default()
+        };
+
+        set_process_count_provider(|uid| if uid == 500 { 3 } else { 0 });
+        let blockers = deletion_blockers(&app, &app.users_all[0]);
+        let clean = deletion_blockers(&app, &create_test_user("carol", 1002));
+        clear_process_count_provider();
+
+        assert_eq!(
+            blockers,
+            vec![
+                "UID outside 1000-1999".to_string(),
+                "user owns 3 processes".to_string(),
+                "group 'svc' is the primary group of bob".to_string(),
+            ]
+        );
+        assert!(clean.is_empty());
+    }
+
+    #[test]
+    fn test_invert_selection() {
+        let mut sel = vec![3, 0];
+        invert_selection(&mut sel, 5);
+        assert_eq!(sel, vec![1, 2, 4]);
+        invert_selection(&mut sel, 5);
+        assert_eq!(sel, vec![0, 3]);
+
+        let mut none: Vec<usize> = vec![];
+        invert_selection(&mut none, 3);
+        assert_eq!(none, vec![0, 1, 2]);
+        invert_selection(&mut none, 3);
+        assert!(none.is_empty());
+
+        let mut empty_list: Vec<usize> = vec![];
+        invert_selection(&mut empty_list, 0);
+        assert!(empty_list.is_empty());
+    }
+
+    #[test]
+    fn test_checked_items_follow_list_order_not_positions() {
+        let selection = std::collections::BTreeSet::from(["bob".to_string(), "zed".to_string()]);
+        let listed = ["carol", "bob", "alice"];
+        assert_eq!(checked_items(listed, &selection), ["bob"]);
+        let resorted = ["alice", "bob", "carol"];
+        assert_eq!(checked_items(resorted, &selection), ["bob"]);
+    }
+
+    #[test]
+    fn test_displayed_members_sorts_a_copy_case_insensitively() {
+        let members: Vec<String> = ["carol", "Bob", "alice", "bob"]
+            .iter()
+            .map(|m| m.to_string())
+            .collect();
+
+        assert_eq!(displayed_members(&members, false), members);
+        assert_eq!(
+            displayed_members(&members, true),
+            vec!["alice", "Bob", "bob", "carol"]
+        );
+        // The group's own list keeps its file order
+        assert_eq!(members[0], "carol");
+    }
+
+    #[test]
+    fn test_password_strength_scores_length_and_variety() {
+        use usrgrp_manager::app::{PASSWORD_STRENGTH_MAX, password_strength};
+
+        assert_eq!(password_strength(""), (0, "weak"));
+        assert_eq!(password_strength("password"), (1, "weak"));
+        // Short passwords stay weak however varied they are
+        assert_eq!(password_strength("Ab1!"), (2, "weak"));
+        assert_eq!(password_strength("Password1"), (3, "ok"));
+        assert_eq!(password_strength("correcthorsebatterystaple"), (3, "ok"));
+        assert_eq!(password_strength("Tr0ub4dor&3x"), (5, "strong"));
+        assert_eq!(
+            password_strength("Correct-Horse-Battery-9"),
+            (PASSWORD_STRENGTH_MAX, "strong")
+        );
+    }
+
+    #[test]
+    fn test_known_shells_cached_until_invalidated() {
+        use usrgrp_manager::app::known_shells;
+        use usrgrp_manager::sys::SystemAdapter;
+
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("etc")).unwrap();
+        let shells = root.path().join("etc/shells");
+        std::fs::write(&shells, "# comment\n/bin/sh\n/bin/bash\n").unwrap();
+        let adapter = SystemAdapter::with_root(root.path());
+        let mut app = AppState::new();
+
+        assert_eq!(known_shells(&mut app, &adapter), ["/bin/sh", "/bin/bash"]);
+
+        // A shell installed mid-session is not seen until the cache is dropped
+        std::fs::write(&shells, "/bin/sh\n/bin/bash\n/usr/bin/fish\n").unwrap();
+        assert_eq!(known_shells(&mut app, &adapter).len(), 2);
+
+        app.shells_cache = None;
+        assert_eq!(
+            known_shells(&mut app, &adapter),
+            ["/bin/sh", "/bin/bash", "/usr/bin/fish"]
+        );
+    }
+
+    #[test]
+    fn test_file_groups_are_primary_and_listed_memberships() {
+        use usrgrp_manager::app::file_groups;
+
+        let alice = create_test_user("alice", 1000);
+        let groups = [
+            create_test_group("wheel", 10, vec!["alice".into()]),
+            create_test_group("audio", 20, vec!["bob".into()]),
+            create_test_group("alice", 1000, vec![]),
+        ];
+        assert_eq!(file_groups(&alice, &groups), ["wheel", "alice"]);
+    }
 }
 
 #[cfg(test)]
@@ -678,10 +1538,113 @@ mod username_validation_tests {
     }
 }
 
+#[cfg(test)]
+mod logging_tests {
+    use usrgrp_manager::logging::{Level, Logger};
+
+    #[test]
+    fn test_warn_level_suppresses_debug_lines() {
+        assert_eq!(Level::parse("WARN"), Some(Level::Warn));
+        assert_eq!(Level::parse("debug"), Some(Level::Debug));
+        assert_eq!(Level::parse("trace"), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ugm.log");
+        let logger = Logger::new(Level::Warn, &path);
+        logger.log(Level::Debug, format_args!("running groupadd"));
+        logger.log(Level::Info, format_args!("performing groupadd"));
+        logger.log(Level::Warn, format_args!("groupadd exited with 9"));
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("WARN"));
+        assert!(lines[0].ends_with("groupadd exited with 9"));
+    }
+}
+
+#[cfg(all(test, not(feature = "read-only-build")))]
+mod audit_tests {
+    use usrgrp_manager::audit::{AuditLog, format_entry, utc_timestamp};
+
+    #[test]
+    fn test_audit_entries_record_outcome_and_rotate() {
+        assert_eq!(utc_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(utc_timestamp(1_709_294_400), "2024-03-01T12:00:00Z");
+        assert_eq!(
+            format_entry(0, "alice", "groupadd devs", Err("exit 9\nno")),
+            "1970-01-01T00:00:00Z alice FAILED groupadd devs -- exit 9; no\n"
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::new(dir.path().join("audit.log"), 80);
+        log.append("alice", "groupadd devs", Ok(())).unwrap();
+        log.append("alice", "gpasswd -a bob devs", Err("denied"))
+            .unwrap();
+        let text = std::fs::read_to_string(log.path()).unwrap();
+        assert!(
+            text.lines()
+                .next()
+                .unwrap()
+                .ends_with(" alice ok groupadd devs")
+        );
+        assert!(
+            text.lines()
+                .nth(1)
+                .unwrap()
+                .ends_with("FAILED gpasswd -a bob devs -- denied")
+        );
+
+        // Past the cap the old entries move to audit.log.1
+        log.append("alice", "groupdel devs", Ok(())).unwrap();
+        assert_eq!(std::fs::read_to_string(log.rotated_path()).unwrap(), text);
+        let text = std::fs::read_to_string(log.path()).unwrap();
+        assert_eq!(text.lines().count(), 1);
+        assert!(text.ends_with("ok groupdel devs\n"));
+    }
+}
+
+#[cfg(test)]
+mod util_tests {
+    use std::ffi::OsStr;
+    use std::path::Path;
+    use usrgrp_manager::util::{FALLBACK_OPENER, open_path, resolve_launcher};
+
+    #[test]
+    fn test_file_manager_wins_over_the_fallback_opener() {
+        let dir = std::env::temp_dir().join(format!("usrgrp-opener-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(FALLBACK_OPENER), "").unwrap();
+        let path = dir.as_os_str();
+
+        assert_eq!(
+            resolve_launcher(Some("thunar --new-window"), Some(path)),
+            Some(vec!["thunar".to_string(), "--new-window".to_string()])
+        );
+        assert_eq!(
+            resolve_launcher(Some("  "), Some(path)),
+            Some(vec![FALLBACK_OPENER.to_string()])
+        );
+        assert_eq!(resolve_launcher(None, Some(OsStr::new(""))), None);
+        assert_eq!(resolve_launcher(None, None), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_open_path_refuses_missing_paths() {
+        let err = open_path(Path::new("/nonexistent/usrgrp-home")).unwrap_err();
+        assert!(err.to_string().contains("does not exist"), "{err}");
+    }
+}
+
 #[cfg(test)]
 mod integration_tests {
+    use super::{create_test_group, create_test_user};
     use ratatui::{Terminal, backend::TestBackend};
-    use usrgrp_manager::app::AppState;
+    use usrgrp_manager::app::keymap::Keymap;
+    use usrgrp_manager::app::{ActiveTab, AppState, ModalState, UsersFilter};
+    use usrgrp_manager::sys::{SystemGroup, SystemUser};
+    use usrgrp_manager::ui::components::{pad_left_to_width, text_width, truncate_to_width};
     use usrgrp_manager::ui::render;
 
     #[test]
@@ -714,4 +1677,441 @@ mod integration_tests {
             })
             .expect("render frame with empty data");
     }
+
+    fn buffer_text(terminal: &Terminal<TestBackend>) -> String {
+        let buf = terminal.backend().buffer();
+        let mut out = String::new();
+        for y in 0..buf.area.height {
+            for x in 0..buf.area.width {
+                out.push_str(buf[(x, y)].symbol());
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    #[test]
+    fn test_live_stats_off_skips_process_scan() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+        use usrgrp_manager::sys::{clear_process_count_provider, set_process_count_provider};
+
+        let scans = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&scans);
+        set_process_count_provider(move |_uid| {
+            counter.set(counter.get() + 1);
+            7
+        });
+        let user = SystemUser {
+            uid: 1000,
+            name: "alice".to_string(),
+            primary_gid: 1000,
+            full_name: None,
+            home_dir: "/home/alice".to_string(),
+            shell: "/bin/bash".to_string(),
+        };
+        let mut app = AppState {
+            users_all: vec![user.clone()],
+            users: vec![user],
+            ..AppState::new()
+        };
+        app.keymap = Keymap::new_defaults();
+        app.active_tab = ActiveTab::Users;
+        app.live_stats = false;
+        // Live stats also ask `id -Gn`; a cached answer keeps it from running
+        app.effective_groups_cache = Some(("alice".to_string(), None));
+        app.backend = std::sync::Arc::new(|_| panic!("this test runs no commands"));
+
+        let mut terminal = Terminal::new(TestBackend::new(240, 30)).unwrap();
+        for _ in 0..3 {
+            terminal.draw(|f| render(f, &mut app)).unwrap();
+        }
+        assert_eq!(scans.get(), 0);
+        assert!(buffer_text(&terminal).contains("press L for live stats"));
+
+        app.live_stats = true;
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert_eq!(scans.get(), 1);
+        assert!(buffer_text(&terminal).contains("Processes: 7"));
+        clear_process_count_provider();
+    }
+
+    fn compact_layout_app() -> AppState {
+        let user = SystemUser {
+            uid: 1000,
+            name: "alice".to_string(),
+            primary_gid: 1000,
+            full_name: None,
+            home_dir: "/home/alice".to_string(),
+            shell: "/bin/bash".to_string(),
+        };
+        AppState {
+            users_all: vec![user.clone()],
+            users: vec![user],
+            groups_all: vec![],
+            groups: vec![],
+            keymap: Keymap::new_defaults(),
+            modal_stack: vec![],
+            // The keybindings panel mentions "Member of" too
+            show_keybinds: false,
+            compact_below: 100,
+            ..AppState::new()
+        }
+    }
+
+    #[test]
+    fn test_narrow_terminal_hides_details_column() {
+        let mut app = compact_layout_app();
+        let mut terminal = Terminal::new(TestBackend::new(60, 30)).unwrap();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        let text = buffer_text(&terminal);
+        assert!(text.contains("alice"));
+        assert!(!text.contains("Member of"));
+
+        // The details open as a modal instead
+        app.open_modal(ModalState::UserDetails);
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        let text = buffer_text(&terminal);
+        assert!(text.contains("User details - alice"));
+        assert!(text.contains("Username: alice"));
+    }
+
+    #[test]
+    fn test_group_details_modal_shows_panel_content() {
+        let mut app = compact_layout_app();
+        app.groups_all = vec![SystemGroup {
+            gid: 1000,
+            name: "alice".to_string(),
+            members: vec![],
+        }];
+        app.groups = app.groups_all.clone();
+        app.active_tab = ActiveTab::Groups;
+        app.open_modal(ModalState::GroupDetails);
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 30)).unwrap();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        let text = buffer_text(&terminal);
+        assert!(text.contains("Group details - alice"));
+        assert!(text.contains("GID: 1000 (user)"));
+        assert!(text.contains("Primary members: 1"));
+    }
+
+    #[test]
+    fn test_wide_terminal_keeps_details_column() {
+        let mut app = compact_layout_app();
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        let text = buffer_text(&terminal);
+        assert!(text.contains("alice"));
+        assert!(text.contains("Member of"));
+
+        // A threshold of 0 never switches to the compact layout
+        app.compact_below = 0;
+        let mut terminal = Terminal::new(TestBackend::new(60, 30)).unwrap();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(buffer_text(&terminal).contains("Member of"));
+    }
+
+    #[test]
+    fn test_keybinds_panel_dropped_before_details_on_narrow_terminals() {
+        let mut app = compact_layout_app();
+        app.show_keybinds = true;
+        let mut terminal = Terminal::new(TestBackend::new(110, 30)).unwrap();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        let text = buffer_text(&terminal);
+        assert!(text.contains("Member of"));
+        assert!(!text.contains("Keybindings"));
+        // The setting itself is kept for wider terminals
+        assert!(app.show_keybinds);
+
+        let mut terminal = Terminal::new(TestBackend::new(160, 30)).unwrap();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(buffer_text(&terminal).contains("Keybindings"));
+    }
+
+    #[test]
+    fn test_compact_layout_toggle_applies_at_any_width() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        use usrgrp_manager::app::LayoutMode;
+        use usrgrp_manager::app::keymap::KeyAction;
+
+        let mut app = compact_layout_app();
+        app.show_keybinds = true;
+        assert_eq!(
+            app.keymap
+                .resolve(&KeyEvent::new(KeyCode::Char('z'), KeyModifiers::NONE)),
+            Some(KeyAction::ToggleLayout)
+        );
+        app.layout_mode = LayoutMode::Compact;
+        let mut terminal = Terminal::new(TestBackend::new(160, 30)).unwrap();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        let text = buffer_text(&terminal);
+        assert!(text.contains("alice"));
+        // Neither the details nor the keybindings panel is drawn
+        assert!(!text.contains("Member of"));
+        assert!(!text.contains("Keybindings"));
+
+        app.layout_mode = LayoutMode::Expanded;
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(buffer_text(&terminal).contains("Member of"));
+    }
+
+    #[test]
+    fn test_status_bar_shows_queued_action_as_working() {
+        let mut app = compact_layout_app();
+        app.queued_action = Some(usrgrp_manager::app::PendingAction::CreateGroup {
+            groupname: "devs".to_string(),
+        });
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(buffer_text(&terminal).contains("Working: groupadd devs"));
+
+        app.queued_action = None;
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(!buffer_text(&terminal).contains("Working"));
+    }
+
+    #[test]
+    fn test_locked_accounts_are_marked_in_users_table() {
+        use std::collections::HashMap;
+        use usrgrp_manager::search::{
+            clear_shadow_provider, make_shadow_status, set_shadow_provider,
+        };
+        set_shadow_provider(|| {
+            let mut m = HashMap::new();
+            m.insert("bob".to_string(), make_shadow_status(true, false, false));
+            m.insert("alice".to_string(), make_shadow_status(false, false, false));
+            Ok(m)
+        });
+        let mut app = compact_layout_app();
+        app.users = vec![
+            create_test_user("alice", 1000),
+            create_test_user("bob", 1001),
+        ];
+        app.users_all = app.users.clone();
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        let text = buffer_text(&terminal);
+        let row = |name: &str| {
+            text.lines()
+                .find(|l| l.contains(&format!(" {name} ")) || l.contains(&format!("[{name}]")))
+                .unwrap()
+                .to_string()
+        };
+        assert!(row("bob").contains('🔒'));
+        assert!(!row("alice").contains('🔒'));
+
+        // Shadow is not read again on the next frame
+        set_shadow_provider(|| Err(std::io::Error::other("denied")));
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(buffer_text(&terminal).contains('🔒'));
+
+        // Unreadable shadow after a reload: nothing is marked
+        app.locked_users_cache = None;
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(!buffer_text(&terminal).contains('🔒'));
+        clear_shadow_provider();
+    }
+
+    #[test]
+    fn test_users_table_marks_missing_home_directories() {
+        let home = tempfile::tempdir().unwrap();
+        let mut app = compact_layout_app();
+        app.users = vec![
+            SystemUser {
+                home_dir: home.path().display().to_string(),
+                ..create_test_user("alice", 1000)
+            },
+            SystemUser {
+                home_dir: "/nonexistent/bob".to_string(),
+                ..create_test_user("bob", 1001)
+            },
+        ];
+        app.users_all = app.users.clone();
+        app.user_columns = usrgrp_manager::app::UserColumn::ALL.to_vec();
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        let text = buffer_text(&terminal);
+        assert!(text.contains("DIR"));
+        // The details pane shares the lines, so match the table cells only
+        let row = |cell: &str| text.lines().find(|l| l.contains(cell)).unwrap().to_string();
+        assert!(row("[alice]").contains('✓'));
+        assert!(row(" bob ").contains('✗'));
+
+        // Checked once per reload, not on every frame
+        home.close().unwrap();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(buffer_text(&terminal).contains('✓'));
+        app.home_exists_cache.clear();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(!buffer_text(&terminal).contains('✓'));
+    }
+
+    #[test]
+    fn test_empty_tables_distinguish_no_data_from_filtered() {
+        let user = SystemUser {
+            uid: 1000,
+            name: "alice".to_string(),
+            primary_gid: 1000,
+            full_name: None,
+            home_dir: "/home/alice".to_string(),
+            shell: "/bin/bash".to_string(),
+        };
+        let mut app = AppState {
+            users_all: vec![],
+            users: vec![],
+            groups_all: vec![],
+            groups: vec![],
+            ..AppState::new()
+        };
+        app.keymap = Keymap::new_defaults();
+
+        let mut terminal = Terminal::new(TestBackend::new(240, 30)).unwrap();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(buffer_text(&terminal).contains("No users found"));
+
+        app.users_all = vec![user];
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        let text = buffer_text(&terminal);
+        assert!(text.contains("No users match the current filter (press f to clear)"));
+        assert!(!text.contains("No users found"));
+
+        // A search that only comes up empty because of the filters says so
+        app.users_filter = Some(UsersFilter::OnlySystemIds);
+        app.search_query = "ali".to_string();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(buffer_text(&terminal).contains("0 matches — 1 hidden by filters (press f"));
+        app.search_query = "bob".to_string();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(buffer_text(&terminal).contains("No users match the search"));
+        app.search_query.clear();
+        app.users_filter = None;
+
+        app.active_tab = ActiveTab::Groups;
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(buffer_text(&terminal).contains("No groups found"));
+    }
+
+    #[test]
+    fn test_user_details_flag_missing_primary_group() {
+        let user = SystemUser {
+            uid: 1000,
+            name: "alice".to_string(),
+            primary_gid: 4242,
+            full_name: None,
+            home_dir: "/home/alice".to_string(),
+            shell: "/bin/bash".to_string(),
+        };
+        let group = SystemGroup {
+            gid: 1000,
+            name: "alice".to_string(),
+            members: vec![],
+        };
+        let mut app = AppState {
+            users_all: vec![user.clone()],
+            users: vec![user],
+            groups_all: vec![group.clone()],
+            groups: vec![group],
+            ..AppState::new()
+        };
+
+        let mut terminal = Terminal::new(TestBackend::new(240, 30)).unwrap();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(buffer_text(&terminal).contains("Primary group: 4242 (MISSING)"));
+
+        app.users_all[0].primary_gid = 1000;
+        app.users[0].primary_gid = 1000;
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        let text = buffer_text(&terminal);
+        assert!(text.contains("Primary group: 1000 (alice)"));
+        assert!(!text.contains("MISSING"));
+    }
+
+    #[test]
+    fn test_multi_select_picker_shows_checked_count() {
+        let groups = vec![
+            create_test_group("audio", 10, vec![]),
+            create_test_group("video", 20, vec![]),
+            create_test_group("games", 30, vec![]),
+        ];
+        let user = create_test_user("alice", 1000);
+        let picker = |checked: &[&str]| ModalState::ModifyGroupsAdd {
+            selected: 0,
+            offset: 0,
+            selected_multi: checked.iter().map(|s| s.to_string()).collect(),
+            show_all: false,
+        };
+        let mut app = AppState {
+            users_all: vec![user.clone()],
+            users: vec![user],
+            groups_all: groups.clone(),
+            groups,
+            ..AppState::new()
+        };
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+
+        app.open_modal(picker(&[]));
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(!buffer_text(&terminal).contains("selected "));
+
+        app.modal_stack = vec![picker(&["audio", "games"])];
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert!(buffer_text(&terminal).contains(" 2 selected "));
+    }
+
+    #[test]
+    fn test_members_list_can_include_primary_group_users() {
+        let alice = SystemUser {
+            primary_gid: 2000,
+            ..create_test_user("alice", 1000)
+        };
+        let users = vec![alice, create_test_user("bob", 1001)];
+        let group = create_test_group("devs", 2000, vec!["bob".to_string()]);
+        let mut app = AppState {
+            users_all: users.clone(),
+            users,
+            groups_all: vec![group.clone()],
+            groups: vec![group],
+            active_tab: ActiveTab::Groups,
+            ..AppState::new()
+        };
+
+        app.members_include_primary = false;
+        assert_eq!(
+            usrgrp_manager::app::selected_group_member_rows(&app),
+            vec![("bob".to_string(), false)]
+        );
+
+        app.members_include_primary = true;
+        assert_eq!(
+            usrgrp_manager::app::selected_group_member_rows(&app),
+            vec![("bob".to_string(), false), ("alice".to_string(), true)]
+        );
+        let mut terminal = Terminal::new(TestBackend::new(240, 30)).unwrap();
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        let text = buffer_text(&terminal);
+        assert!(text.contains("alice [primary]"));
+        assert!(!text.contains("bob [primary]"));
+    }
+
+    #[test]
+    fn test_text_width_counts_wide_characters_twice() {
+        assert_eq!(text_width("alice"), 5);
+        assert_eq!(text_width("山田太郎"), 8);
+        assert_eq!(text_width("😀 ok"), 5);
+        assert_eq!(text_width("José"), 4);
+
+        // Truncation never splits a wide character across the limit
+        assert_eq!(truncate_to_width("山田太郎", 8), "山田太郎");
+        assert_eq!(truncate_to_width("山田太郎", 6), "山田…");
+        assert_eq!(truncate_to_width("山田太郎", 5), "山田…");
+        assert_eq!(truncate_to_width("😀😀😀", 4), "😀…");
+        assert_eq!(truncate_to_width("abc", 0), "");
+
+        assert_eq!(pad_left_to_width("山田", 6), "  山田");
+        assert_eq!(text_width(&pad_left_to_width("😀", 5)), 5);
+    }
 }